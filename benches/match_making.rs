@@ -0,0 +1,244 @@
+#![allow(clippy::needless_return)]
+
+use std::collections::{HashMap, HashSet};
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use dating_simulation::{AgeConfig, AttributeSchema, Gender, Individual, MatchingAlgorithm, Orientation, PersonId, RatingDistribution, Sample, SampleConfig, ScoreCacheMode, ThresholdDistribution};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// a seeded population without progress bars, so the numbers are not skewed by terminal output
+fn population(size: usize) -> Sample {
+	return Sample::generate(&SampleConfig::builder().population(size).complexity(3).seed(42).build().unwrap()).unwrap();
+}
+
+/// drawing a population of each size
+fn generation(c: &mut Criterion) {
+	let mut group = c.benchmark_group("generation");
+	group.sample_size(10);
+
+	for size in [1_000, 10_000, 100_000] {
+		group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| b.iter(|| population(size)));
+	}
+
+	group.finish();
+}
+
+/// drawing 100k individuals of 50 attributes with weights shared by everyone and with random weights,
+/// the shared weights are borrowed and only copied once into each individual
+fn wide_generation(c: &mut Criterion) {
+	let mut group = c.benchmark_group("generation_100k_complexity_50");
+	group.sample_size(10);
+
+	let random = SampleConfig::builder().population(100_000).complexity(50).seed(42).build().unwrap();
+	let mut shared = random.clone();
+	shared.predefined_weights = Some(vec![0.5; 50]);
+
+	group.bench_function("predefined_weights", |b| b.iter(|| Sample::generate(&shared).unwrap()));
+	group.bench_function("random_weights", |b| b.iter(|| Sample::generate(&random).unwrap()));
+
+	group.finish();
+}
+
+/// drawing a million individuals of 10 attributes as generation did before, with `StdRng` and one draw per weight,
+/// and with `Sample::generate`. the two are within the noise of each other, the generator is a few percent of the time.
+/// the baseline gives everyone a gender in turn rather than drawing it
+fn large_generation(c: &mut Criterion) {
+	let mut group = c.benchmark_group("generation_1m_complexity_10");
+	group.sample_size(10);
+
+	let config = SampleConfig::builder().population(1_000_000).complexity(10).seed(42).build().unwrap();
+	group.bench_function("std_rng_baseline", |b| b.iter(|| {
+		let mut rng = StdRng::seed_from_u64(42);
+		let population: Vec<Individual> = (0..config.population_size)
+			.map(|position| {
+				let gender = if position % 2 == 0 { Gender::Male } else { Gender::Female };
+				let weights: Vec<f32> = (0..config.attribute_schema.len()).map(|_| rng.r#gen::<f32>()).collect();
+
+				Individual::new(&config.attribute_schema, Some(&weights), gender, Orientation::Heterosexual, &ThresholdDistribution::default(), &config.age, &mut rng).unwrap()
+			})
+			.collect();
+
+		Sample::from_individuals(population)
+	}));
+	group.bench_function("random_weights", |b| b.iter(|| Sample::generate(&config).unwrap()));
+
+	group.finish();
+}
+
+/// the first round of every algorithm on a fresh population
+fn one_round(c: &mut Criterion) {
+	let mut group = c.benchmark_group("one_round");
+	group.sample_size(10);
+
+	for algorithm in [MatchingAlgorithm::Greedy, MatchingAlgorithm::DeferredAcceptance, MatchingAlgorithm::ParallelProposal] {
+		for size in [1_000, 10_000] {
+			group.bench_with_input(BenchmarkId::new(algorithm.to_string(), size), &size, |b, &size| b.iter_batched(
+				|| {
+					let mut sample = population(size);
+					sample.matching_config.algorithm = algorithm;
+
+					sample
+				},
+				|mut sample| sample.match_making().unwrap(),
+				BatchSize::LargeInput
+			));
+		}
+	}
+
+	group.finish();
+}
+
+/// ten greedy rounds on a 10k population, the blacklist lookups dominate the later rounds
+fn ten_rounds(c: &mut Criterion) {
+	let mut group = c.benchmark_group("match_making");
+	group.sample_size(10);
+
+	group.bench_function("greedy_10k_10_rounds", |b| b.iter_batched(
+		|| population(10_000),
+		|mut sample| sample.run(10).unwrap(),
+		BatchSize::LargeInput
+	));
+
+	group.finish();
+}
+
+/// the first and the 50th greedy round on a 10k population, the later one with the matched left out and without
+fn late_round(c: &mut Criterion) {
+	let mut group = c.benchmark_group("greedy_10k");
+	group.sample_size(10);
+
+	let mut fresh = population(10_000);
+	fresh.matching_config.only_unmatched = true;
+	group.bench_function("round_1", |b| b.iter_batched(|| fresh.clone(), |mut sample| sample.match_making().unwrap(), BatchSize::LargeInput));
+
+	let mut settled = fresh.clone();
+	for _ in 1..50 {
+		settled.match_making().unwrap();
+	}
+	group.bench_function("round_50_only_unmatched", |b| b.iter_batched(|| settled.clone(), |mut sample| sample.match_making().unwrap(), BatchSize::LargeInput));
+	settled.matching_config.only_unmatched = false;
+	group.bench_function("round_50", |b| b.iter_batched(|| settled.clone(), |mut sample| sample.match_making().unwrap(), BatchSize::LargeInput));
+
+	group.finish();
+}
+
+/// ten rounds of deferred acceptance on 2k individuals of 400 attributes without and with the score cache.
+/// deferred acceptance scores every admissible pair again each round, where the greedy scan
+/// blacklists most pairs after their first score
+fn cached_rounds(c: &mut Criterion) {
+	let mut group = c.benchmark_group("score_cache");
+	group.sample_size(10);
+
+	let config = SampleConfig::builder().population(2_000).complexity(400).seed(42).build().unwrap();
+	for mode in [ScoreCacheMode::Off, ScoreCacheMode::Lazy, ScoreCacheMode::Eager] {
+		group.bench_function(BenchmarkId::new("deferred_acceptance_2k_10_rounds", mode), |b| b.iter_batched(
+			|| {
+				let mut sample = Sample::generate(&config).unwrap();
+				sample.matching_config.algorithm = MatchingAlgorithm::DeferredAcceptance;
+				sample.matching_config.score_cache = mode;
+
+				sample
+			},
+			|mut sample| sample.run(10).unwrap(),
+			BatchSize::LargeInput
+		));
+	}
+
+	group.finish();
+}
+
+/// scoring one individual against another
+fn score(c: &mut Criterion) {
+	let schema = AttributeSchema::anonymous(3, RatingDistribution::default());
+	let mut rng = StdRng::seed_from_u64(42);
+	let rater = Individual::new(&schema, None, Gender::Male, Orientation::Heterosexual, &ThresholdDistribution::default(), &AgeConfig::default(), &mut rng).unwrap();
+	let rated = Individual::new(&schema, None, Gender::Female, Orientation::Heterosexual, &ThresholdDistribution::default(), &AgeConfig::default(), &mut rng).unwrap();
+
+	c.bench_function("score", |b| b.iter(|| std::hint::black_box(&rater).score(std::hint::black_box(&rated)).unwrap()));
+}
+
+/// scoring at 64 attributes with the plain loop of before and with `Individual::score`,
+/// which adds up such wide vectors in chunks of 8
+fn wide_score(c: &mut Criterion) {
+	let schema = AttributeSchema::anonymous(64, RatingDistribution::default());
+	let mut rng = StdRng::seed_from_u64(42);
+	let rater = Individual::new(&schema, None, Gender::Male, Orientation::Heterosexual, &ThresholdDistribution::default(), &AgeConfig::default(), &mut rng).unwrap();
+	let rated = Individual::new(&schema, None, Gender::Female, Orientation::Heterosexual, &ThresholdDistribution::default(), &AgeConfig::default(), &mut rng).unwrap();
+
+	let mut group = c.benchmark_group("score_complexity_64");
+	group.bench_function("scalar", |b| b.iter(|| {
+		let (rater, rated) = (std::hint::black_box(&rater), std::hint::black_box(&rated));
+
+		rater.preference_weights.iter().zip(&rated.ratings).map(|(weight, rating)| weight * rating).sum::<f32>()
+	}));
+	group.bench_function("chunked", |b| b.iter(|| std::hint::black_box(&rater).score(std::hint::black_box(&rated)).unwrap()));
+
+	group.finish();
+}
+
+/// the lookups behind every proposal of a round on 10k individuals, where the reviewer sits in the population
+/// and whether they blacklisted the proposer, keyed by the uuid strings of before and by `PersonId`
+fn identity_lookups(c: &mut Criterion) {
+	let sample = population(10_000);
+	let size = sample.population.len();
+	// everyone blacklisted the 20 after them
+	let blacklisted = |position: usize| (1..=20).map(move |offset| (position + offset) % size);
+	// 50 reviewers scattered over the population for every proposer
+	let proposals = || (0..size).flat_map(move |proposer| (1..=50).map(move |offset| (proposer, (proposer + offset * 97) % size)));
+
+	let strings: Vec<String> = sample.population.iter().map(|individual| individual.identity.to_string()).collect();
+	let string_index: HashMap<String, usize> = strings.iter().cloned().enumerate().map(|(position, identity)| (identity, position)).collect();
+	let string_blacklists: Vec<HashMap<String, u32>> = (0..size).map(|position| blacklisted(position).map(|other| (strings[other].clone(), 1)).collect()).collect();
+
+	let ids: Vec<PersonId> = sample.population.iter().map(|individual| individual.identity).collect();
+	let id_index: HashMap<PersonId, usize> = ids.iter().copied().enumerate().map(|(position, identity)| (identity, position)).collect();
+	let id_blacklists: Vec<HashMap<PersonId, u32>> = (0..size).map(|position| blacklisted(position).map(|other| (ids[other], 1)).collect()).collect();
+
+	let mut group = c.benchmark_group("identity_lookups_10k");
+	group.sample_size(10);
+	group.bench_function("strings", |b| b.iter(|| proposals()
+		.filter(|&(proposer, reviewer)| string_blacklists[string_index[&strings[reviewer]]].contains_key(&strings[proposer]))
+		.count()));
+	group.bench_function("person_id", |b| b.iter(|| proposals()
+		.filter(|&(proposer, reviewer)| id_blacklists[id_index[&ids[reviewer]]].contains_key(&ids[proposer]))
+		.count()));
+
+	group.finish();
+}
+
+/// one individual's blacklist checked against 10k proposers as a `Vec`, as blacklists were kept before,
+/// and as the `HashSet` and the `HashMap` of the round each entry was added they became.
+/// the blacklist grows by the rejections of every round, so it is measured at several lengths
+fn blacklist_lookups(c: &mut Criterion) {
+	let ids: Vec<PersonId> = population(10_000).population.iter().map(|individual| individual.identity).collect();
+	let mut group = c.benchmark_group("blacklist_lookups_10k");
+	group.sample_size(10);
+
+	for length in [10, 100, 1_000] {
+		// every other proposer up to the length is blacklisted, in the order they were rejected.
+		// the lookups check expiry as `has_blacklisted` does, in round 5 with a duration of 10
+		let blacklisted: Vec<PersonId> = ids.iter().copied().step_by(2).take(length).collect();
+		let vec: Vec<(PersonId, u32)> = blacklisted.iter().map(|&identity| (identity, 1)).collect();
+		let set: HashSet<PersonId> = blacklisted.iter().copied().collect();
+		let map: HashMap<PersonId, u32> = blacklisted.iter().map(|&identity| (identity, 1)).collect();
+
+		group.bench_with_input(BenchmarkId::new("vec", length), &vec, |b, vec| b.iter(|| ids
+			.iter()
+			.filter(|&&identity| vec.iter().any(|&(blacklisted, added)| blacklisted == identity && 5 < added + 10))
+			.count()));
+		group.bench_with_input(BenchmarkId::new("hash_set", length), &set, |b, set| b.iter(|| ids
+			.iter()
+			.filter(|identity| set.contains(identity))
+			.count()));
+		group.bench_with_input(BenchmarkId::new("hash_map", length), &map, |b, map| b.iter(|| ids
+			.iter()
+			.filter(|identity| map.get(identity).is_some_and(|&added| 5 < added + 10))
+			.count()));
+	}
+
+	group.finish();
+}
+
+criterion_group!(benches, generation, wide_generation, large_generation, one_round, ten_rounds, late_round, cached_rounds, score, wide_score, identity_lookups, blacklist_lookups);
+criterion_main!(benches);
@@ -0,0 +1,15 @@
+/// hands `git describe` of the checkout to `RunMetadata`, builds outside of git go without it
+fn main() {
+	println!("cargo:rerun-if-changed=.git/HEAD");
+	println!("cargo:rerun-if-changed=.git/refs");
+
+	let output = std::process::Command::new("git")
+		.args(["describe", "--always", "--dirty", "--tags"])
+		.output();
+	if let Ok(output) = output {
+		let describe = String::from_utf8_lossy(&output.stdout).trim().to_string();
+		if output.status.success() && !describe.is_empty() {
+			println!("cargo:rustc-env=DATING_SIMULATION_GIT_DESCRIBE={}", describe);
+		}
+	}
+}
@@ -0,0 +1,103 @@
+"""The parquet exports against pyarrow, which the crate itself never links.
+
+Runs with `cargo build --release --features python,arrow && python -m pytest pytests`, skipped without pyarrow.
+It has not run yet: pyarrow was not available where the exports were written.
+"""
+
+import json
+import sys
+from pathlib import Path
+
+sys.path.insert(0, str(Path(__file__).resolve().parents[1] / "python"))
+
+import pytest
+
+pa = pytest.importorskip("pyarrow")
+pq = pytest.importorskip("pyarrow.parquet")
+
+from dating_simulation import PySample, SimulationError, read_parquet
+
+PAIRS_COLUMNS = ["proposer_id", "reviewer_id", "reviewer_score", "proposer_score", "matched_in_round", "retired"]
+
+
+def test_pyarrow_reads_the_pairs_export(tmp_path):
+    sample = PySample({"population_size": 200, "preference_complexity": 3, "seed": 72})
+    for _ in range(3):
+        sample.match_making()
+    pairs = sample.pairs()
+    assert pairs
+
+    path = tmp_path / "pairs.parquet"
+    sample.export_pairs_parquet(path)
+    table = pq.read_table(path)
+
+    assert table.schema.names == PAIRS_COLUMNS
+    assert [field.type for field in table.schema] == [pa.string(), pa.string(), pa.float32(), pa.float32(), pa.uint32(), pa.bool_()]
+    assert [field.nullable for field in table.schema] == [False, False, False, False, True, False]
+    assert table.num_rows == len(pairs)
+
+    rows = table.to_pylist()
+    assert [(row["proposer_id"], row["reviewer_id"]) for row in rows] == [(pair[0], pair[1]) for pair in pairs]
+    # `pairs()` prints the float32 scores in their shortest form, pyarrow widens them
+    assert [row["reviewer_score"] for row in rows] == pytest.approx([pair[2] for pair in pairs], rel=1e-7)
+    assert not any(row["retired"] for row in rows)
+
+    metadata = pq.read_metadata(path).metadata
+    assert json.loads(metadata[b"dating_simulation.metadata"])["seed"] == 72
+
+
+def _pyarrow_table():
+    return pa.table({
+        "id": pa.array(["a", None, "ccc", "", "é"], pa.string()),
+        "round": pa.array([1, 2, None, 4, 4294967295], pa.uint32()),
+        # values a float32 holds exactly
+        "score": pa.array([0.5, None, -1.25, 2.0 ** 100, 0.0], pa.float32()),
+        "retired": pa.array([True, False, None, True, True], pa.bool_()),
+    })
+
+
+@pytest.mark.parametrize("data_page_size", [1024 * 1024, 8])
+def test_a_plain_uncompressed_file_written_by_pyarrow_is_read(tmp_path, data_page_size):
+    path = tmp_path / "pyarrow.parquet"
+    pq.write_table(
+        _pyarrow_table(),
+        path,
+        compression="none",
+        use_dictionary=False,
+        column_encoding="PLAIN",
+        data_page_version="1.0",
+        # a small size splits every column into several pages
+        data_page_size=data_page_size,
+        write_batch_size=1,
+    )
+
+    columns, metadata = read_parquet(path)
+
+    assert list(columns) == ["id", "round", "score", "retired"]
+    assert columns["id"] == ["a", None, "ccc", "", "é"]
+    assert columns["round"] == [1, 2, None, 4, 4294967295]
+    assert columns["score"] == [0.5, None, -1.25, 2.0 ** 100, 0.0]
+    assert columns["retired"] == [True, False, None, True, True]
+    # pyarrow keeps its own schema in the metadata
+    assert "ARROW:schema" in metadata
+
+
+def test_a_file_the_reader_does_not_cover_is_refused(tmp_path):
+    path = tmp_path / "snappy.parquet"
+    pq.write_table(_pyarrow_table(), path, compression="snappy", use_dictionary=False)
+
+    with pytest.raises(SimulationError, match="compressed"):
+        read_parquet(path)
+
+
+def test_the_pairs_round_trip_through_pyarrow(tmp_path):
+    sample = PySample({"population_size": 100, "seed": 7})
+    sample.match_making()
+    ours = tmp_path / "ours.parquet"
+    sample.export_pairs_parquet(ours)
+
+    # rewritten by pyarrow, then read back by the crate
+    theirs = tmp_path / "theirs.parquet"
+    pq.write_table(pq.read_table(ours), theirs, compression="none", use_dictionary=False, column_encoding="PLAIN", data_page_version="1.0")
+
+    assert read_parquet(theirs)[0] == read_parquet(ours)[0]
@@ -0,0 +1,121 @@
+"""Runs with `cargo build --release --features python && python -m pytest pytests`."""
+
+import json
+import resource
+import sys
+import threading
+import time
+from pathlib import Path
+
+sys.path.insert(0, str(Path(__file__).resolve().parents[1] / "python"))
+
+import pytest
+
+from dating_simulation import PySample, SimulationError, _get_library
+
+
+def test_a_population_of_1000_runs_end_to_end():
+    sample = PySample({"population_size": 1000, "preference_complexity": 3, "seed": 42})
+    for _ in range(3):
+        sample.match_making()
+
+    statistics = sample.statistics()
+    assert statistics["total_population"] == 1000
+    assert statistics["seed"] == 42
+
+    pairs = sample.pairs()
+    assert pairs
+    assert all(len(pair) == 4 and isinstance(pair[2], float) for pair in pairs)
+    matched = statistics["matched_males"] + statistics["matched_females"] + statistics["matched_non_binary"]
+    assert len(pairs) * 2 == matched
+
+    exported = json.loads(sample.to_json())
+    assert exported["metadata"]["seed"] == 42
+    assert exported["round"] == 3
+    assert len(exported["population"]) == 1000
+
+
+def _ticks_per_second_while(action):
+    """how fast a loop on another python thread counts while `action` runs on this one, and how long it ran"""
+    ticks = 0
+    done = threading.Event()
+
+    def count():
+        nonlocal ticks
+        while not done.is_set():
+            ticks += 1
+
+    counter = threading.Thread(target=count)
+    counter.start()
+    while ticks == 0:
+        time.sleep(0.001)
+    # only what it counts during the call, a thread holding the gil still lets it run for a switch interval on either side
+    before, start = ticks, time.perf_counter()
+    action()
+    counted, elapsed = ticks - before, time.perf_counter() - start
+    done.set()
+    counter.join()
+    return counted / elapsed, elapsed
+
+
+def test_python_threads_keep_running_while_a_round_runs():
+    sample = PySample({"population_size": 10000, "preference_complexity": 3, "seed": 42})
+
+    running, elapsed = _ticks_per_second_while(sample.match_making)
+    idle, _ = _ticks_per_second_while(lambda: time.sleep(elapsed))
+    # long enough that the switch intervals around the call count for little
+    assert elapsed > 0.2
+    # holding the gil the counter would stand still for the whole round, it only shares the cores with it
+    assert running > idle * 0.2, (running, idle)
+
+
+def test_a_refused_config_raises():
+    with pytest.raises(SimulationError, match="population size"):
+        PySample({"population_size": 0})
+
+    with pytest.raises(SimulationError, match="unknown field"):
+        PySample({"no_such_key": 1})
+
+
+def _peak_resident_bytes():
+    # kilobytes on linux, bytes on macos
+    peak = resource.getrusage(resource.RUSAGE_SELF).ru_maxrss
+    return peak if sys.platform == "darwin" else peak * 1024
+
+
+def test_a_null_sample_is_refused_rather_than_dereferenced():
+    library = _get_library()
+
+    assert library.dating_simulation_match_making(None) == -1
+    assert library.dating_simulation_last_error() == b"the sample is null"
+    for name in ["dating_simulation_statistics", "dating_simulation_pairs", "dating_simulation_to_json"]:
+        assert getattr(library, name)(None) is None
+        assert library.dating_simulation_last_error() == b"the sample is null"
+
+    assert library.dating_simulation_sample_new(None) is None
+    assert library.dating_simulation_last_error() == b"the config is null"
+
+    # freeing null does nothing
+    library.dating_simulation_sample_free(None)
+    library.dating_simulation_string_free(None)
+
+
+def test_error_strings_and_returned_strings_are_freed():
+    # a message of about a megabyte, 200 of them would be noticed if the old ones were kept
+    config = {"x" * (1 << 20): 1}
+    with pytest.raises(SimulationError, match="unknown field"):
+        PySample(config)
+    before = _peak_resident_bytes()
+    for _ in range(200):
+        with pytest.raises(SimulationError, match="unknown field"):
+            PySample(config)
+    assert _peak_resident_bytes() - before < 50 << 20
+
+    # each export is about a megabyte too, freed once it is copied into python
+    sample = PySample({"population_size": 2000, "seed": 7})
+    exported = len(sample.to_json())
+    assert exported > 1 << 19
+    before = _peak_resident_bytes()
+    for _ in range(200):
+        assert len(sample.to_json()) == exported
+    assert _peak_resident_bytes() - before < 50 << 20
@@ -0,0 +1,145 @@
+"""The simulation from python, through the c interface of the `python` feature.
+
+Build the library first with `cargo build --release --features python`, `python,arrow` for parquet,
+it is looked up in
+`target/release` of this checkout unless `DATING_SIMULATION_LIBRARY` gives its path.
+ctypes releases the gil during every call, so `match_making` leaves other threads running.
+"""
+
+import ctypes
+import json
+import os
+import sys
+from pathlib import Path
+
+__all__ = ["PySample", "SimulationError", "read_parquet"]
+
+
+class SimulationError(Exception):
+    """A config the simulation refuses or a round that failed."""
+
+
+def _library_path():
+    if "DATING_SIMULATION_LIBRARY" in os.environ:
+        return Path(os.environ["DATING_SIMULATION_LIBRARY"])
+
+    if sys.platform == "darwin":
+        name = "libdating_simulation.dylib"
+    elif sys.platform == "win32":
+        name = "dating_simulation.dll"
+    else:
+        name = "libdating_simulation.so"
+    return Path(__file__).resolve().parents[2] / "target" / "release" / name
+
+
+def _load():
+    library = ctypes.CDLL(str(_library_path()))
+
+    library.dating_simulation_last_error.restype = ctypes.c_char_p
+    library.dating_simulation_sample_new.argtypes = [ctypes.c_char_p]
+    library.dating_simulation_sample_new.restype = ctypes.c_void_p
+    library.dating_simulation_sample_free.argtypes = [ctypes.c_void_p]
+    library.dating_simulation_match_making.argtypes = [ctypes.c_void_p]
+    library.dating_simulation_match_making.restype = ctypes.c_int
+    for name in ["dating_simulation_statistics", "dating_simulation_pairs", "dating_simulation_to_json"]:
+        function = getattr(library, name)
+        function.argtypes = [ctypes.c_void_p]
+        # a plain pointer, a `c_char_p` would be copied and never freed
+        function.restype = ctypes.c_void_p
+    library.dating_simulation_string_free.argtypes = [ctypes.c_void_p]
+    # only built with the `arrow` feature
+    if hasattr(library, "dating_simulation_read_parquet"):
+        library.dating_simulation_export_pairs_parquet.argtypes = [ctypes.c_void_p, ctypes.c_char_p]
+        library.dating_simulation_export_pairs_parquet.restype = ctypes.c_int
+        library.dating_simulation_read_parquet.argtypes = [ctypes.c_char_p]
+        library.dating_simulation_read_parquet.restype = ctypes.c_void_p
+
+    return library
+
+
+_library = None
+
+
+def _get_library():
+    global _library
+    if _library is None:
+        _library = _load()
+    return _library
+
+
+def _error(library):
+    message = library.dating_simulation_last_error()
+    return SimulationError(message.decode("utf-8") if message else "unknown error")
+
+
+def _taken_string(library, pointer):
+    if not pointer:
+        raise _error(library)
+    try:
+        return ctypes.string_at(pointer).decode("utf-8")
+    finally:
+        library.dating_simulation_string_free(pointer)
+
+
+def _parquet_library():
+    library = _get_library()
+    if not hasattr(library, "dating_simulation_read_parquet"):
+        raise SimulationError("the library was built without the `arrow` feature")
+    return library
+
+
+def read_parquet(path):
+    """A parquet file as the exports write it, as `(columns, metadata)`.
+
+    `columns` maps the names to lists of values in the order of the schema, with `None` for nulls,
+    and `metadata` the `key_value_metadata` of the file. Compressed or dictionary encoded files are refused.
+    """
+    library = _parquet_library()
+    table = json.loads(_taken_string(library, library.dating_simulation_read_parquet(os.fsencode(path))))
+    return dict(table["columns"]), table["metadata"]
+
+
+class PySample:
+    """A generated population and its matching state.
+
+    `config` holds the keys of the toml config, e.g.
+    `{"population_size": 1000, "seed": 42, "matching": {"algorithm": "deferred-acceptance"}}`,
+    anything left out keeps its default.
+    """
+
+    def __init__(self, config=None):
+        self._library = _get_library()
+        self._sample = self._library.dating_simulation_sample_new(json.dumps(config or {}).encode("utf-8"))
+        if not self._sample:
+            raise _error(self._library)
+
+    def __del__(self):
+        sample, self._sample = getattr(self, "_sample", None), None
+        if sample:
+            self._library.dating_simulation_sample_free(sample)
+
+    def _string(self, function):
+        return _taken_string(self._library, function(self._sample))
+
+    def match_making(self):
+        """Run one round."""
+        if self._library.dating_simulation_match_making(self._sample) != 0:
+            raise _error(self._library)
+
+    def statistics(self):
+        """The statistics of the current state as a dict."""
+        return json.loads(self._string(self._library.dating_simulation_statistics))
+
+    def pairs(self):
+        """The mutual pairs as `(proposer_id, reviewer_id, reviewer_score, proposer_score)` tuples."""
+        return [tuple(pair) for pair in json.loads(self._string(self._library.dating_simulation_pairs))]
+
+    def to_json(self):
+        """The whole sample as the json export writes it."""
+        return self._string(self._library.dating_simulation_to_json)
+
+    def export_pairs_parquet(self, path):
+        """Write the pairs, the retired ones included, as the `--pairs-parquet` flag does."""
+        library = _parquet_library()
+        if library.dating_simulation_export_pairs_parquet(self._sample, os.fsencode(path)) != 0:
+            raise _error(library)
@@ -0,0 +1,102 @@
+/// the column every row is assigned to such that the weights of the assignment sum to the most,
+/// at most one row per column. `None` weights can not be assigned, and a row stays unassigned
+/// rather than taking a weight below 0. the hungarian algorithm in O(rows² × (rows + columns))
+pub(crate) fn max_weight_assignment(weights: &[Vec<Option<f64>>]) -> Vec<Option<usize>> {
+	let rows = weights.len();
+	let columns = weights.first().map_or(0, |row| row.len());
+
+	// the smaller side is assigned to the larger one, the running time grows with its square
+	if rows > columns {
+		let transposed: Vec<Vec<Option<f64>>> = (0..columns)
+			.map(|column| (0..rows).map(|row| weights[row][column]).collect())
+			.collect();
+
+		let mut assignment = vec![None; rows];
+		for (column, row) in max_weight_assignment(&transposed).into_iter().enumerate() {
+			if let Some(row) = row {
+				assignment[row] = Some(column);
+			}
+		}
+
+		return assignment;
+	}
+
+	// a minimum cost assignment where every row has a column of its own past the real ones
+	// that stands for staying unassigned at a cost of 0. 1-based, column 0 is where a row starts from
+	let cost = |row: usize, column: usize| -> f64 {
+		if column > columns {
+			return if column - columns == row { 0.0 } else { f64::INFINITY };
+		}
+
+		return weights[row - 1][column - 1].map_or(f64::INFINITY, |weight| -weight);
+	};
+	let width = columns + rows;
+
+	// the potentials of the rows and columns, and the row each column is assigned to
+	let mut row_potential = vec![0.0; rows + 1];
+	let mut column_potential = vec![0.0; width + 1];
+	let mut assigned_row = vec![0; width + 1];
+	// the column before each column on the path of the current augmentation
+	let mut previous = vec![0; width + 1];
+
+	for row in 1..=rows {
+		assigned_row[0] = row;
+		let mut column = 0;
+		let mut min_slack = vec![f64::INFINITY; width + 1];
+		let mut visited = vec![false; width + 1];
+
+		// grow the tree of tight edges until it reaches an unassigned column
+		loop {
+			visited[column] = true;
+			let tree_row = assigned_row[column];
+			let mut delta = f64::INFINITY;
+			let mut next_column = 0;
+
+			for candidate in 1..=width {
+				if visited[candidate] {
+					continue;
+				}
+
+				let slack = cost(tree_row, candidate) - row_potential[tree_row] - column_potential[candidate];
+				if slack < min_slack[candidate] {
+					min_slack[candidate] = slack;
+					previous[candidate] = column;
+				}
+				if min_slack[candidate] < delta {
+					delta = min_slack[candidate];
+					next_column = candidate;
+				}
+			}
+
+			for candidate in 0..=width {
+				if visited[candidate] {
+					row_potential[assigned_row[candidate]] += delta;
+					column_potential[candidate] -= delta;
+				} else {
+					min_slack[candidate] -= delta;
+				}
+			}
+
+			column = next_column;
+			if assigned_row[column] == 0 {
+				break;
+			}
+		}
+
+		// flip the assignments along the path back to the row
+		while column != 0 {
+			let previous_column = previous[column];
+			assigned_row[column] = assigned_row[previous_column];
+			column = previous_column;
+		}
+	}
+
+	let mut assignment = vec![None; rows];
+	for column in 1..=columns {
+		if assigned_row[column] != 0 {
+			assignment[assigned_row[column] - 1] = Some(column - 1);
+		}
+	}
+
+	return assignment;
+}
@@ -0,0 +1,552 @@
+use rand::Rng;
+use rand_distr::StandardNormal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::individual::{self, Individual, RatingDistribution};
+
+/// a problem with an attribute schema or with values that should follow it
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaError {
+	// a schema needs at least one attribute
+	Empty,
+	// the attribute's range is not finite or has `min` above `max`
+	InvalidRange { name: String, min: f32, max: f32 },
+	// the attribute's distribution cannot be drawn from, or draws outside of its range
+	InvalidDistribution { name: String, reason: String },
+	// predefined weights were given for a different number of attributes
+	WeightCountMismatch { attributes: usize, weights: usize },
+	// the chance of a dealbreaker on the attribute is not within 0 to 1
+	InvalidDealbreakerProbability { name: String, probability: f64 },
+	// the correlation matrix is not a valid one, or not one for the attributes of the schema
+	InvalidCorrelation { reason: String },
+	// the categorical attribute has no categories, frequencies or a compatibility matrix that do not fit them
+	InvalidCategories { name: String, reason: String }
+}
+
+impl std::fmt::Display for SchemaError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return match self {
+			SchemaError::Empty => write!(
+				f, "the attribute schema must have at least one attribute"
+			),
+			SchemaError::InvalidRange { name, min, max } => write!(
+				f, "attribute {} needs a finite range with min <= max, got {} to {}", name, min, max
+			),
+			SchemaError::InvalidDistribution { name, reason } => write!(
+				f, "attribute {} has an invalid distribution: {}", name, reason
+			),
+			SchemaError::WeightCountMismatch { attributes, weights } => write!(
+				f, "{} predefined weights were given for {} attributes, they must match", weights, attributes
+			),
+			SchemaError::InvalidDealbreakerProbability { name, probability } => write!(
+				f, "attribute {} needs a dealbreaker probability between 0 and 1, got {}", name, probability
+			),
+			SchemaError::InvalidCorrelation { reason } => write!(
+				f, "invalid attribute correlation: {}", reason
+			),
+			SchemaError::InvalidCategories { name, reason } => write!(
+				f, "categorical attribute {} is invalid: {}", name, reason
+			)
+		};
+	}
+}
+
+impl std::error::Error for SchemaError {}
+
+/// one attribute that every individual rates and weights
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AttributeSpec {
+	pub name: String,
+	#[serde(default = "default_min")]
+	pub min: f32,
+	#[serde(default = "default_max")]
+	pub max: f32,
+	// ratings drawn outside of `min..=max` are clamped to it
+	#[serde(default)]
+	pub distribution: RatingDistribution,
+	// chance that an individual refuses everyone rated below a minimum on this attribute,
+	// the minimum is drawn like a rating
+	#[serde(default)]
+	pub dealbreaker_probability: f64
+}
+
+fn default_min() -> f32 {
+	return Individual::MIN_RATING;
+}
+
+fn default_max() -> f32 {
+	return Individual::MAX_RATING;
+}
+
+impl AttributeSpec {
+	/// an attribute rated within `Individual::MIN_RATING..=Individual::MAX_RATING`
+	pub fn new(name: &str, distribution: RatingDistribution) -> Self {
+		return AttributeSpec {
+			name: name.to_string(),
+			min: Individual::MIN_RATING,
+			max: Individual::MAX_RATING,
+			distribution: distribution,
+			dealbreaker_probability: 0.0
+		};
+	}
+
+	pub fn validate(&self) -> Result<(), SchemaError> {
+		if !self.min.is_finite() || !self.max.is_finite() || self.min > self.max {
+			return Err(SchemaError::InvalidRange {
+				name: self.name.clone(),
+				min: self.min,
+				max: self.max
+			});
+		}
+
+		self.distribution.validate().map_err(|error| SchemaError::InvalidDistribution {
+			name: self.name.clone(),
+			reason: error.to_string()
+		})?;
+
+		if !(0.0..=1.0).contains(&self.dealbreaker_probability) {
+			return Err(SchemaError::InvalidDealbreakerProbability {
+				name: self.name.clone(),
+				probability: self.dealbreaker_probability
+			});
+		}
+
+		if let RatingDistribution::Uniform { min, max } = self.distribution {
+			if min < self.min || max > self.max {
+				return Err(SchemaError::InvalidDistribution {
+					name: self.name.clone(),
+					reason: format!("uniform range {} to {} exceeds the attribute range {} to {}", min, max, self.min, self.max)
+				});
+			}
+		}
+
+		return Ok(());
+	}
+
+	/// draw one rating of this attribute
+	pub fn sample(&self, rng: &mut impl Rng) -> f32 {
+		return self.distribution
+			.sample(rng)
+			.clamp(self.min, self.max);
+	}
+
+	/// the rating at the quantile of the distribution that `z` is of the standard normal:
+	/// a normal distribution is shifted and scaled to it, a uniform one rescaled through the normal cdf
+	pub fn from_standard_normal(&self, z: f64) -> f32 {
+		let rating = match self.distribution {
+			RatingDistribution::Uniform { min, max } => min + (max - min) * standard_normal_cdf(z) as f32,
+			RatingDistribution::Normal { mean, std_dev } => mean + std_dev * z as f32
+		};
+
+		return rating.clamp(self.min, self.max);
+	}
+
+	/// draw whether an individual has a dealbreaker on this attribute and its minimum rating,
+	/// the rng is left untouched when `dealbreaker_probability` is 0
+	pub fn sample_dealbreaker(&self, rng: &mut impl Rng) -> Option<f32> {
+		if self.dealbreaker_probability <= 0.0 || !rng.gen_bool(self.dealbreaker_probability) {
+			return None;
+		}
+
+		return Some(self.sample(rng));
+	}
+}
+
+/// the normal cdf, from the approximation 7.1.26 of abramowitz and stegun, within 1e-7 of it
+fn standard_normal_cdf(z: f64) -> f64 {
+	let x = z.abs() / std::f64::consts::SQRT_2;
+	let t = 1.0 / (1.0 + 0.327_591_1 * x);
+	let polynomial = t * (0.254_829_592 + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+	let erf = 1.0 - polynomial * (-x * x).exp();
+
+	return match z >= 0.0 {
+		true => 0.5 * (1.0 + erf),
+		false => 0.5 * (1.0 - erf)
+	};
+}
+
+/// what a rater's own category and the category of the rated individual add to the score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum CategoryPreference {
+	// `bonus` for the rater's own category, `penalty` is subtracted for any other,
+	// a large penalty makes a mismatch all but a dealbreaker
+	SameCategoryBonus {
+		#[serde(default)]
+		bonus: f32,
+		#[serde(default)]
+		penalty: f32
+	},
+	// one row per category of the rater and one column per category of the rated individual
+	Matrix { compatibility: Vec<Vec<f32>> }
+}
+
+/// an attribute everyone falls into one category of, e.g. smoker or non-smoker,
+/// scored by `preference` rather than weighted and rated
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CategoricalAttribute {
+	pub name: String,
+	pub categories: Vec<String>,
+	// how common each category is relative to the others, all are equally common when empty
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub frequencies: Vec<f64>,
+	pub preference: CategoryPreference
+}
+
+impl CategoricalAttribute {
+	/// equally common categories
+	pub fn new(name: &str, categories: &[&str], preference: CategoryPreference) -> Self {
+		return CategoricalAttribute {
+			name: name.to_string(),
+			categories: categories.iter().map(|category| category.to_string()).collect(),
+			frequencies: Vec::new(),
+			preference: preference
+		};
+	}
+
+	pub fn validate(&self) -> Result<(), SchemaError> {
+		let invalid = |reason: String| SchemaError::InvalidCategories { name: self.name.clone(), reason: reason };
+		let count = self.categories.len();
+
+		if count == 0 {
+			return Err(invalid(String::from("it needs at least one category")));
+		}
+		if !self.frequencies.is_empty() {
+			if self.frequencies.len() != count {
+				return Err(invalid(format!("{} frequencies were given for {} categories", self.frequencies.len(), count)));
+			}
+			if self.frequencies.iter().any(|frequency| !frequency.is_finite() || *frequency < 0.0) || self.frequencies.iter().sum::<f64>() <= 0.0 {
+				return Err(invalid(String::from("frequencies must be finite, not negative and not all 0")));
+			}
+		}
+
+		return match &self.preference {
+			CategoryPreference::SameCategoryBonus { bonus, penalty } => match bonus.is_finite() && penalty.is_finite() {
+				true => Ok(()),
+				false => Err(invalid(String::from("the bonus and the penalty must be finite")))
+			},
+			CategoryPreference::Matrix { compatibility } => {
+				if compatibility.len() != count || compatibility.iter().any(|row| row.len() != count) {
+					return Err(invalid(format!("the compatibility matrix must have {} rows of {} entries", count, count)));
+				}
+				if compatibility.iter().flatten().any(|value| !value.is_finite()) {
+					return Err(invalid(String::from("the compatibilities must be finite")));
+				}
+
+				Ok(())
+			}
+		};
+	}
+
+	/// draw the index of one category by the frequencies
+	pub fn sample(&self, rng: &mut impl Rng) -> usize {
+		let frequencies: Vec<(usize, f64)> = match self.frequencies.is_empty() {
+			true => (0..self.categories.len()).map(|category| (category, 1.0)).collect(),
+			false => self.frequencies.iter().copied().enumerate().collect()
+		};
+
+		return individual::draw(&frequencies, rng);
+	}
+
+	/// what a rater of category `rater` adds to the score of someone of category `rated`,
+	/// 0 for categories outside of the attribute
+	pub fn score(&self, rater: usize, rated: usize) -> f32 {
+		return match &self.preference {
+			CategoryPreference::SameCategoryBonus { bonus, penalty } => match rater == rated {
+				true => *bonus,
+				false => -penalty
+			},
+			CategoryPreference::Matrix { compatibility } => compatibility
+				.get(rater)
+				.and_then(|row| row.get(rated))
+				.copied()
+				.unwrap_or_default()
+		};
+	}
+
+	/// the name of the category at `category`, `?` outside of the attribute
+	pub fn category(&self, category: usize) -> &str {
+		return self.categories.get(category).map_or("?", String::as_str);
+	}
+}
+
+/// how the ratings of the attributes of a schema correlate, one row per attribute.
+/// it is checked to be symmetric and positive semi-definite with ones on the diagonal
+/// and factorized once when it is built, written as its rows
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<Vec<f64>>", into = "Vec<Vec<f64>>")]
+pub struct CorrelationMatrix {
+	rows: Vec<Vec<f64>>,
+	// the lower triangular cholesky factor of `rows`
+	cholesky: Vec<Vec<f64>>
+}
+
+impl CorrelationMatrix {
+	// how far the matrix may be from symmetric, and a pivot below 0, before it is refused
+	const TOLERANCE: f64 = 1e-9;
+
+	pub fn new(rows: Vec<Vec<f64>>) -> Result<Self, SchemaError> {
+		let invalid = |reason: String| SchemaError::InvalidCorrelation { reason: reason };
+		let size = rows.len();
+
+		for (i, row) in rows.iter().enumerate() {
+			if row.len() != size {
+				return Err(invalid(format!("row {} has {} entries, a matrix of {} rows needs {}", i + 1, row.len(), size, size)));
+			}
+			if row[i] != 1.0 {
+				return Err(invalid(format!("the diagonal must be 1, row {} has {}", i + 1, row[i])));
+			}
+			for (j, &value) in row.iter().enumerate() {
+				if !(-1.0..=1.0).contains(&value) {
+					return Err(invalid(format!("correlations must be between -1 and 1, row {} has {}", i + 1, value)));
+				}
+				if (value - rows[j][i]).abs() > CorrelationMatrix::TOLERANCE {
+					return Err(invalid(format!("the matrix must be symmetric, {} in row {} against {} in row {}", value, i + 1, rows[j][i], j + 1)));
+				}
+			}
+		}
+
+		// a pivot of 0 leaves its column at 0, the attribute then follows the earlier ones
+		let mut cholesky = vec![vec![0.0; size]; size];
+		for i in 0..size {
+			for j in 0..=i {
+				let sum: f64 = (0..j).map(|k| cholesky[i][k] * cholesky[j][k]).sum();
+				if i == j {
+					let pivot = rows[i][i] - sum;
+					if pivot < -CorrelationMatrix::TOLERANCE {
+						return Err(invalid(String::from("the matrix must be positive semi-definite")));
+					}
+					cholesky[i][i] = pivot.max(0.0).sqrt();
+				} else if cholesky[j][j] > 0.0 {
+					cholesky[i][j] = (rows[i][j] - sum) / cholesky[j][j];
+				} else if (rows[i][j] - sum).abs() > CorrelationMatrix::TOLERANCE {
+					return Err(invalid(String::from("the matrix must be positive semi-definite")));
+				}
+			}
+		}
+
+		return Ok(CorrelationMatrix { rows: rows, cholesky: cholesky });
+	}
+
+	pub fn rows(&self) -> &[Vec<f64>] {
+		return &self.rows;
+	}
+
+	pub fn len(&self) -> usize {
+		return self.rows.len();
+	}
+
+	pub fn is_empty(&self) -> bool {
+		return self.rows.is_empty();
+	}
+
+	/// one draw of standard normals that correlate like the matrix says
+	pub fn sample(&self, rng: &mut impl Rng) -> Vec<f64> {
+		let independent: Vec<f64> = (0..self.len()).map(|_| rng.sample(StandardNormal)).collect();
+
+		return self.cholesky
+			.iter()
+			.map(|row| row.iter().zip(&independent).map(|(factor, z)| factor * z).sum())
+			.collect();
+	}
+}
+
+impl TryFrom<Vec<Vec<f64>>> for CorrelationMatrix {
+	type Error = SchemaError;
+
+	fn try_from(rows: Vec<Vec<f64>>) -> Result<Self, Self::Error> {
+		return CorrelationMatrix::new(rows);
+	}
+}
+
+impl From<CorrelationMatrix> for Vec<Vec<f64>> {
+	fn from(matrix: CorrelationMatrix) -> Self {
+		return matrix.rows;
+	}
+}
+
+/// the attributes behind `Individual::preference_weights` and `Individual::ratings`,
+/// in the same order, and the categorical ones behind `Individual::categories`.
+/// in a toml config it is a list of `[[attributes]]` tables. a schema with a `correlation`
+/// or `categorical` attributes is written as a table of `attributes`, `correlation` and `categorical` instead
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AttributeSchema {
+	pub attributes: Vec<AttributeSpec>,
+	// ratings are drawn independently without one
+	pub correlation: Option<CorrelationMatrix>,
+	pub categorical: Vec<CategoricalAttribute>
+}
+
+// the table form of a schema
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SchemaTable {
+	#[serde(default)]
+	attributes: Vec<AttributeSpec>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	correlation: Option<CorrelationMatrix>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	categorical: Vec<CategoricalAttribute>
+}
+
+impl Serialize for AttributeSchema {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		if self.correlation.is_none() && self.categorical.is_empty() {
+			return self.attributes.serialize(serializer);
+		}
+
+		return SchemaTable {
+			attributes: self.attributes.clone(),
+			correlation: self.correlation.clone(),
+			categorical: self.categorical.clone()
+		}.serialize(serializer);
+	}
+}
+
+impl<'de> Deserialize<'de> for AttributeSchema {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct SchemaVisitor;
+
+		impl<'de> serde::de::Visitor<'de> for SchemaVisitor {
+			type Value = AttributeSchema;
+
+			fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				return f.write_str("a list of attributes or a table of `attributes`, `correlation` and `categorical`");
+			}
+
+			fn visit_seq<A: serde::de::SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+				let attributes = Vec::deserialize(serde::de::value::SeqAccessDeserializer::new(seq))?;
+
+				return Ok(AttributeSchema { attributes: attributes, correlation: None, categorical: Vec::new() });
+			}
+
+			fn visit_map<A: serde::de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+				let schema = SchemaTable::deserialize(serde::de::value::MapAccessDeserializer::new(map))?;
+
+				return Ok(AttributeSchema { attributes: schema.attributes, correlation: schema.correlation, categorical: schema.categorical });
+			}
+		}
+
+		return deserializer.deserialize_any(SchemaVisitor);
+	}
+}
+
+impl AttributeSchema {
+	/// whether any attribute can become a dealbreaker
+	pub fn has_dealbreakers(&self) -> bool {
+		return self.attributes
+			.iter()
+			.any(|attribute| attribute.dealbreaker_probability > 0.0);
+	}
+
+	/// `count` attributes named `attribute_1`, `attribute_2`, ..., all drawn from `distribution`
+	pub fn anonymous(count: usize, distribution: RatingDistribution) -> Self {
+		return AttributeSchema {
+			attributes: (0..count)
+				.map(|position| AttributeSpec::new(&anonymous_name(position), distribution))
+				.collect(),
+			correlation: None,
+			categorical: Vec::new()
+		};
+	}
+
+	pub fn len(&self) -> usize {
+		return self.attributes.len();
+	}
+
+	pub fn is_empty(&self) -> bool {
+		return self.attributes.is_empty();
+	}
+
+	/// the name of the attribute at `position`, positions beyond the schema get an anonymous name
+	pub fn name(&self, position: usize) -> String {
+		return self.attributes
+			.get(position)
+			.map(|attribute| attribute.name.clone())
+			.unwrap_or_else(|| anonymous_name(position));
+	}
+
+	/// label every value by its attribute, e.g. `looks: 7.30, humor: 4.10`
+	pub fn label(&self, values: &[f32]) -> String {
+		return values
+			.iter()
+			.enumerate()
+			.map(|(position, value)| format!("{}: {:.2}", self.name(position), value))
+			.collect::<Vec<String>>()
+			.join(", ");
+	}
+
+	pub fn validate(&self) -> Result<(), SchemaError> {
+		if self.attributes.is_empty() {
+			return Err(SchemaError::Empty);
+		}
+
+		for attribute in &self.attributes {
+			attribute.validate()?;
+		}
+
+		if let Some(correlation) = self.correlation.as_ref().filter(|correlation| correlation.len() != self.attributes.len()) {
+			return Err(SchemaError::InvalidCorrelation {
+				reason: format!("a matrix of {} rows was given for {} attributes", correlation.len(), self.attributes.len())
+			});
+		}
+
+		for attribute in &self.categorical {
+			attribute.validate()?;
+		}
+
+		return Ok(());
+	}
+
+	/// draw one rating of every attribute, independently or from the multivariate normal of `correlation`
+	/// mapped onto every attribute's distribution with `AttributeSpec::from_standard_normal`
+	pub fn sample_ratings(&self, rng: &mut impl Rng) -> Vec<f32> {
+		return match &self.correlation {
+			None => self.attributes
+				.iter()
+				.map(|attribute| attribute.sample(rng))
+				.collect(),
+			Some(correlation) => self.attributes
+				.iter()
+				.zip(correlation.sample(rng))
+				.map(|(attribute, z)| attribute.from_standard_normal(z))
+				.collect()
+		};
+	}
+
+	/// draw one category of every categorical attribute, the rng is left untouched without any
+	pub fn sample_categories(&self, rng: &mut impl Rng) -> Vec<usize> {
+		return self.categorical
+			.iter()
+			.map(|attribute| attribute.sample(rng))
+			.collect();
+	}
+
+	/// what the categories of `rated` add to the score `rater` gives them, 0 without categorical attributes
+	pub fn category_score(&self, rater: &Individual, rated: &Individual) -> f32 {
+		return self.categorical
+			.iter()
+			.zip(rater.extras().categories.iter().zip(&rated.extras().categories))
+			.map(|(attribute, (&rater, &rated))| attribute.score(rater, rated))
+			.sum();
+	}
+
+	/// name every category by its attribute, e.g. `smoker: no, religion: none`
+	pub fn label_categories(&self, categories: &[usize]) -> String {
+		return categories
+			.iter()
+			.enumerate()
+			.map(|(position, &category)| match self.categorical.get(position) {
+				Some(attribute) => format!("{}: {}", attribute.name, attribute.category(category)),
+				None => format!("category_{}: {}", position + 1, category)
+			})
+			.collect::<Vec<String>>()
+			.join(", ");
+	}
+}
+
+fn anonymous_name(position: usize) -> String {
+	return format!("attribute_{}", position + 1);
+}
@@ -0,0 +1,25 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// asks a running `Sample::run_until` to stop after the round in progress,
+/// clones share the flag so one can be handed to a signal handler or another thread
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+	cancelled: Arc<AtomicBool>
+}
+
+impl CancellationToken {
+	pub fn new() -> Self {
+		return CancellationToken::default();
+	}
+
+	/// stop the run once the current round is over
+	pub fn cancel(&self) {
+		self.cancelled.store(true, Ordering::SeqCst);
+	}
+
+	/// whether `cancel` was called on this token or one of its clones
+	pub fn is_cancelled(&self) -> bool {
+		return self.cancelled.load(Ordering::SeqCst);
+	}
+}
@@ -0,0 +1,409 @@
+use serde::{Deserialize, Serialize};
+
+use crate::attributes::{AttributeSchema, CategoricalAttribute, CorrelationMatrix};
+use crate::error::SimulationError;
+use crate::drift::PreferenceDrift;
+use crate::individual::{AgeConfig, GenderCounts, GenderDistribution, OrientationDistribution, RatingDistribution, ThresholdDistribution, WeightDistribution};
+use crate::matching::MatchingConfig;
+use crate::preset::Preset;
+use crate::sample::{ChurnConfig, GenerationParameters, ReproductionConfig, Sample, SelectivityConfig};
+
+/// parameters of a whole simulation run,
+/// usually loaded from a toml file and then overridden by command-line flags
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimulationConfig {
+	pub population_size: usize,
+	// number of anonymous attributes, only used when `attributes` is empty
+	pub preference_complexity: usize,
+	pub rounds: u32,
+	// stop before `rounds` once the pairs stayed the same for this many rounds in a row
+	pub convergence_rounds: Option<u32>,
+	// weights shared by every individual, random weights are generated when absent
+	pub predefined_weights: Option<Vec<f32>>,
+	// the `[weight_distribution]` table of the random weights,
+	// e.g. `kind = "dirichlet"`, `concentration = [0.5, 0.5, 0.5]`
+	pub weight_distribution: WeightDistribution,
+	pub seed: Option<u64>,
+	// the `[gender_distribution]` table
+	pub gender_distribution: GenderDistribution,
+	// the `[gender_counts]` table, e.g. `male = 4000`, `female = 6000`, to draw exactly that many.
+	// they must add up to `population_size`
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub gender_counts: Option<GenderCounts>,
+	// the `[orientation_distribution]` table
+	pub orientation_distribution: OrientationDistribution,
+	// the `[rating_distribution]` table of the anonymous attributes, 
+	// e.g. `kind = "normal"`, `mean = 5.5`, `std_dev = 1.5`
+	pub rating_distribution: RatingDistribution,
+	// chance of a dealbreaker on each anonymous attribute, 
+	// named attributes set their own `dealbreaker_probability`
+	pub dealbreaker_probability: f64,
+	// the `[min_acceptable_score]` table, e.g. `kind = "own-score"`, `factor = 0.8`
+	pub min_acceptable_score: ThresholdDistribution,
+	// the `[age]` table, e.g. `step = 0.25` with `distribution = { kind = "normal", mean = 30, std_dev = 8 }`
+	pub age: AgeConfig,
+	// the `[churn]` table, e.g. `arrivals_per_round = 100`, `exit_on_match_probability = 0.5`, `breakup_probability = 0.1`
+	pub churn: ChurnConfig,
+	// the `[selectivity]` table, e.g. `initial = 1.5`, `decay = 0.9`, `floor = 0.5`
+	pub selectivity: SelectivityConfig,
+	// the `[drift]` table, e.g. `random_walk = 0.02`, `toward_rejecters = 0.1`, `renormalize = true`
+	pub drift: PreferenceDrift,
+	// the `[reproduction]` table, e.g. `births_per_pair = 1`, `every_rounds = 5`, `mutation_std_dev = 0.3`, `maturity_rounds = 10`
+	pub reproduction: ReproductionConfig,
+	// the `[matching]` table
+	pub matching: MatchingConfig,
+	// named attributes as `[[attributes]]` tables, they replace `preference_complexity`
+	#[serde(skip_serializing_if = "AttributeSchema::is_empty")]
+	pub attributes: AttributeSchema,
+	// how the ratings of the attributes, named or anonymous, correlate, one row per attribute,
+	// e.g. `attribute_correlation = [[1.0, 0.8], [0.8, 1.0]]`. they are drawn independently without it
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub attribute_correlation: Option<CorrelationMatrix>,
+	// attributes everyone falls into a category of as `[[categorical_attributes]]` tables,
+	// scored on top of the rated ones
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub categorical_attributes: Vec<CategoricalAttribute>
+}
+
+impl Default for SimulationConfig {
+	fn default() -> Self {
+		return SimulationConfig {
+			population_size: 10000,
+			preference_complexity: 3,
+			rounds: 100,
+			convergence_rounds: None,
+			predefined_weights: None,
+			weight_distribution: WeightDistribution::default(),
+			seed: None,
+			gender_distribution: GenderDistribution::default(),
+			gender_counts: None,
+			orientation_distribution: OrientationDistribution::default(),
+			rating_distribution: RatingDistribution::default(),
+			dealbreaker_probability: 0.0,
+			min_acceptable_score: ThresholdDistribution::default(),
+			age: AgeConfig::default(),
+			churn: ChurnConfig::default(),
+			selectivity: SelectivityConfig::default(),
+			drift: PreferenceDrift::default(),
+			reproduction: ReproductionConfig::default(),
+			matching: MatchingConfig::default(),
+			attributes: AttributeSchema::default(),
+			attribute_correlation: None,
+			categorical_attributes: Vec::new()
+		};
+	}
+}
+
+impl SimulationConfig {
+	/// read a config from a toml file,
+	/// keys that are not part of the config are rejected
+	#[cfg(feature = "fs")]
+	pub fn from_toml_file(
+		path: &std::path::Path
+	) -> Result<Self, SimulationError> {
+		let content = std::fs::read_to_string(path)?;
+		let config: SimulationConfig = toml::from_str(&content)
+			.map_err(|error| SimulationError::Toml { path: path.to_path_buf(), source: error })?;
+
+		return Ok(config);
+	}
+
+	/// render the config as toml, e.g. to archive the parameters of a run
+	pub fn to_toml_string(&self) -> Result<String, SimulationError> {
+		return Ok(toml::to_string(self)?);
+	}
+
+	/// the named `attributes`, or `preference_complexity` anonymous attributes 
+	/// drawn from `rating_distribution` when none are listed, correlated by `attribute_correlation`,
+	/// along with the `categorical_attributes`
+	pub fn attribute_schema(&self) -> AttributeSchema {
+		let mut attribute_schema = match self.attributes.is_empty() {
+			false => self.attributes.clone(),
+			true => {
+				let mut attribute_schema = AttributeSchema::anonymous(
+					self.preference_complexity, 
+					self.rating_distribution
+				);
+				for attribute in &mut attribute_schema.attributes {
+					attribute.dealbreaker_probability = self.dealbreaker_probability;
+				}
+
+				attribute_schema
+			}
+		};
+		if let Some(correlation) = &self.attribute_correlation {
+			attribute_schema.correlation = Some(correlation.clone());
+		}
+		attribute_schema.categorical.extend(self.categorical_attributes.iter().cloned());
+
+		return attribute_schema;
+	}
+
+	/// the parameters of the population `Sample::generate` draws for this run
+	pub fn sample_config(&self) -> SampleConfig {
+		return SampleConfig {
+			population_size: self.population_size,
+			attribute_schema: self.attribute_schema(),
+			predefined_weights: self.predefined_weights.clone(),
+			weight_distribution: self.weight_distribution.clone(),
+			gender_distribution: self.gender_distribution,
+			orientation_distribution: self.orientation_distribution,
+			threshold_distribution: self.min_acceptable_score,
+			age: self.age,
+			gender_counts: self.gender_counts,
+			seed: self.seed,
+			show_progress: false
+		};
+	}
+
+	/// draw the population of this run and give it the matching, convergence, churn, selectivity, drift and reproduction settings, 
+	/// ready for `Sample::run` with `rounds`
+	pub fn generate_sample(&self) -> Result<Sample, SimulationError> {
+		return self.generate_sample_with_rng(&mut rand::rngs::OsRng);
+	}
+
+	/// `SimulationConfig::generate_sample` with the seed picked from `rng` when there is none, 
+	/// see `Sample::generate_with_rng`
+	pub fn generate_sample_with_rng(&self, rng: &mut impl rand::RngCore) -> Result<Sample, SimulationError> {
+		let mut sample = Sample::generate_with_rng(&self.sample_config(), rng)?;
+		sample.matching_config = self.matching.clone();
+		sample.convergence_rounds = self.convergence_rounds;
+		sample.churn = self.churn;
+		sample.set_selectivity(self.selectivity);
+		sample.drift = self.drift;
+		sample.reproduction = self.reproduction;
+
+		return Ok(sample);
+	}
+
+	/// check the values that would otherwise make `Sample::generate` misbehave
+	pub fn validate(&self) -> Result<(), SimulationError> {
+		if self.population_size < 1 {
+			return Err(
+				SimulationError::InvalidConfig(format!("population size must be at least 1, got {}", self.population_size))
+			);
+		}
+
+		if self.attributes.is_empty() && self.preference_complexity < 1 {
+			return Err(
+				SimulationError::InvalidConfig(format!("preference complexity must be at least 1, got {}", self.preference_complexity))
+			);
+		}
+
+		self.sample_config().validate()?;
+		self.churn.validate()?;
+		self.selectivity.validate()?;
+		self.drift.validate()?;
+		self.reproduction.validate()?;
+		self.matching.validate()?;
+
+		return Ok(());
+	}
+}
+
+/// the parameters `Sample::generate` draws a population from, usually put together with `SampleConfig::builder`.
+/// a generated sample keeps it in `Sample::sample_config`, with the seed that was used, to tell where it came from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SampleConfig {
+	pub population_size: usize,
+	pub attribute_schema: AttributeSchema,
+	// weights shared by everyone, random weights are drawn when absent
+	pub predefined_weights: Option<Vec<f32>>,
+	// how the random weights are drawn, unused with `predefined_weights`
+	pub weight_distribution: WeightDistribution,
+	pub gender_distribution: GenderDistribution,
+	pub orientation_distribution: OrientationDistribution,
+	pub threshold_distribution: ThresholdDistribution,
+	pub age: AgeConfig,
+	// exactly this many males and females instead of drawing the genders, they add up to `population_size`.
+	// arrivals and children still draw theirs from `gender_distribution`, which `SampleConfigBuilder::gender_counts`
+	// and `--gender-counts` set to the same ratio
+	pub gender_counts: Option<GenderCounts>,
+	// a random seed is picked when absent
+	pub seed: Option<u64>,
+	// whether to draw a progress bar while generating, it does not change the population
+	#[serde(skip)]
+	pub show_progress: bool
+}
+
+impl Default for SampleConfig {
+	fn default() -> Self {
+		return SampleConfig {
+			population_size: 10000,
+			attribute_schema: AttributeSchema::anonymous(3, RatingDistribution::default()),
+			predefined_weights: None,
+			weight_distribution: WeightDistribution::default(),
+			gender_distribution: GenderDistribution::default(),
+			orientation_distribution: OrientationDistribution::default(),
+			threshold_distribution: ThresholdDistribution::default(),
+			age: AgeConfig::default(),
+			gender_counts: None,
+			seed: None,
+			show_progress: false
+		};
+	}
+}
+
+impl SampleConfig {
+	pub fn builder() -> SampleConfigBuilder {
+		return SampleConfigBuilder::default();
+	}
+
+	/// the default config with the attributes, rating distributions and weights of `preset`
+	pub fn preset(preset: Preset) -> SampleConfig {
+		return SampleConfig {
+			attribute_schema: preset.attribute_schema(),
+			weight_distribution: preset.weight_distribution(),
+			..SampleConfig::default()
+		};
+	}
+
+	/// the distributions every individual is drawn from, as the sample keeps them for arrivals
+	pub fn generation(&self) -> GenerationParameters {
+		return GenerationParameters {
+			predefined_weights: self.predefined_weights.clone(),
+			weight_distribution: self.weight_distribution.clone(),
+			gender_distribution: self.gender_distribution,
+			orientation_distribution: self.orientation_distribution,
+			threshold_distribution: self.threshold_distribution
+		};
+	}
+
+	/// check the values that would otherwise make `Sample::generate` misbehave
+	pub fn validate(&self) -> Result<(), SimulationError> {
+		if self.population_size < 1 {
+			return Err(
+				SimulationError::InvalidConfig(format!("population size must be at least 1, got {}", self.population_size))
+			);
+		}
+
+		self.attribute_schema.validate()?;
+
+		if let Some(predefined_weights) = &self.predefined_weights {
+			if predefined_weights.len() != self.attribute_schema.len() {
+				return Err(SimulationError::WeightRatingLengthMismatch {
+					expected: self.attribute_schema.len(),
+					actual: predefined_weights.len()
+				});
+			}
+		} else {
+			self.weight_distribution.validate(self.attribute_schema.len())?;
+		}
+
+		self.gender_distribution.validate()?;
+		if let Some(counts) = self.gender_counts.filter(|counts| counts.total() != self.population_size) {
+			return Err(
+				SimulationError::InvalidConfig(format!(
+					"{} males and {} females make {} individuals, not the population size of {}",
+					counts.male,
+					counts.female,
+					counts.total(),
+					self.population_size
+				))
+			);
+		}
+		self.orientation_distribution.validate()?;
+		self.threshold_distribution.validate()?;
+		self.age.validate()?;
+
+		return Ok(());
+	}
+}
+
+/// put together a `SampleConfig`, e.g.
+/// `SampleConfig::builder().population(10_000).complexity(3).seed(42).build()`.
+/// anything that is not set keeps the value of `SampleConfig::default`
+#[derive(Debug, Clone, Default)]
+pub struct SampleConfigBuilder {
+	config: SampleConfig
+}
+
+impl SampleConfigBuilder {
+	pub fn population(mut self, population_size: usize) -> Self {
+		self.config.population_size = population_size;
+
+		return self;
+	}
+
+	/// `complexity` anonymous attributes with the default rating distribution
+	pub fn complexity(mut self, complexity: usize) -> Self {
+		self.config.attribute_schema = AttributeSchema::anonymous(complexity, RatingDistribution::default());
+
+		return self;
+	}
+
+	pub fn attribute_schema(mut self, attribute_schema: AttributeSchema) -> Self {
+		self.config.attribute_schema = attribute_schema;
+
+		return self;
+	}
+
+	pub fn predefined_weights(mut self, predefined_weights: Vec<f32>) -> Self {
+		self.config.predefined_weights = Some(predefined_weights);
+
+		return self;
+	}
+
+	pub fn weight_distribution(mut self, weight_distribution: WeightDistribution) -> Self {
+		self.config.weight_distribution = weight_distribution;
+
+		return self;
+	}
+
+	pub fn gender_distribution(mut self, gender_distribution: GenderDistribution) -> Self {
+		self.config.gender_distribution = gender_distribution;
+
+		return self;
+	}
+
+	/// exactly `males` males and `females` females, a population of the two together.
+	/// arrivals draw their gender in the same ratio
+	pub fn gender_counts(mut self, males: usize, females: usize) -> Self {
+		self.config.gender_counts = Some(GenderCounts { male: males, female: females });
+		self.config.population_size = males + females;
+		if males + females > 0 {
+			self.config.gender_distribution = GenderCounts { male: males, female: females }.distribution();
+		}
+
+		return self;
+	}
+
+	pub fn orientation_distribution(mut self, orientation_distribution: OrientationDistribution) -> Self {
+		self.config.orientation_distribution = orientation_distribution;
+
+		return self;
+	}
+
+	pub fn threshold_distribution(mut self, threshold_distribution: ThresholdDistribution) -> Self {
+		self.config.threshold_distribution = threshold_distribution;
+
+		return self;
+	}
+
+	pub fn age(mut self, age: AgeConfig) -> Self {
+		self.config.age = age;
+
+		return self;
+	}
+
+	pub fn seed(mut self, seed: u64) -> Self {
+		self.config.seed = Some(seed);
+
+		return self;
+	}
+
+	pub fn show_progress(mut self, show_progress: bool) -> Self {
+		self.config.show_progress = show_progress;
+
+		return self;
+	}
+
+	/// the config, once it passes `SampleConfig::validate`
+	pub fn build(self) -> Result<SampleConfig, SimulationError> {
+		self.config.validate()?;
+
+		return Ok(self.config);
+	}
+}
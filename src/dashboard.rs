@@ -0,0 +1,290 @@
+use std::collections::VecDeque;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::error::SimulationError;
+use crate::event::{EventSink, SimEvent};
+use crate::statistics::RoundStats;
+
+/// whether a `Dashboard` holds the alternate screen of stderr, so that `Dashboard::restore` knows to give it back
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+const ENTER_ALTERNATE_SCREEN: &str = "\x1b[?1049h";
+const LEAVE_ALTERNATE_SCREEN: &str = "\x1b[?1049l";
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+// the cursor to the top left, then everything below it cleared
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[J";
+
+/// the least time between two frames
+const REDRAW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// the lines of the event log kept on screen
+const LOG_LINES: usize = 10;
+
+const GAUGE_WIDTH: usize = 30;
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// where a `Dashboard` draws its frames
+struct Screen {
+	writer: Box<dyn Write + Send + Sync>,
+	// `None` takes the width of the terminal on stderr before every frame
+	width: Option<usize>,
+	// stderr is entered through `ACTIVE`, so that `Dashboard::restore` can give it back
+	stderr: bool,
+	entered: bool
+}
+
+impl Screen {
+	/// whether the alternate screen still has to be entered
+	fn enter(&mut self) -> bool {
+		if self.stderr {
+			return !ACTIVE.swap(true, Ordering::SeqCst);
+		}
+
+		return !std::mem::replace(&mut self.entered, true);
+	}
+
+	/// leave the alternate screen and show the cursor again, if they were taken
+	fn leave(&mut self) -> Result<(), SimulationError> {
+		if self.stderr {
+			Dashboard::restore();
+		} else if std::mem::replace(&mut self.entered, false) {
+			write!(self.writer, "{}{}", LEAVE_ALTERNATE_SCREEN, SHOW_CURSOR)?;
+		}
+
+		return Ok(());
+	}
+
+	fn width(&self) -> usize {
+		return self.width.unwrap_or_else(|| console::Term::stderr().size().1 as usize);
+	}
+}
+
+impl std::fmt::Debug for Screen {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return f.debug_struct("Screen").field("width", &self.width).field("stderr", &self.stderr).finish();
+	}
+}
+
+/// a live view of a run on stderr, taking the place of the statistics of every round:
+/// the round, the match rate of every gender as a gauge, a sparkline of the mean candidate score
+/// over the rounds and a log of new best pairs and breakups.
+/// drawn with plain escape codes on the alternate screen rather than through ratatui, which a frame
+/// of a few lines does not need. the final frame stays on stderr once the run ends
+#[derive(Debug)]
+pub struct Dashboard {
+	// `None` renders frames without drawing them
+	screen: Option<Screen>,
+	// the rounds the run was given, 0 when open ended
+	rounds: u32,
+	round: u32,
+	population: usize,
+	latest: Option<RoundStats>,
+	// the mean candidate score after every round, 0 while nobody is matched
+	mean_scores: Vec<f32>,
+	log: VecDeque<String>,
+	// the highest sum of the two scores of a pair yet
+	best_pair: Option<f32>,
+	last_draw: Option<Instant>
+}
+
+impl Dashboard {
+	/// a dashboard that draws nothing, its frames come from `Dashboard::render`
+	pub fn new(rounds: u32) -> Self {
+		return Dashboard {
+			screen: None,
+			rounds: rounds,
+			round: 0,
+			population: 0,
+			latest: None,
+			mean_scores: Vec::new(),
+			log: VecDeque::with_capacity(LOG_LINES),
+			best_pair: None,
+			last_draw: None
+		};
+	}
+
+	/// a dashboard drawing on stderr, `None` when stderr is not a terminal
+	pub fn stderr(rounds: u32) -> Option<Self> {
+		let term = console::Term::stderr();
+		if !term.is_term() {
+			return None;
+		}
+
+		let mut dashboard = Dashboard::new(rounds);
+		dashboard.screen = Some(Screen { writer: Box::new(term), width: None, stderr: true, entered: false });
+
+		return Some(dashboard);
+	}
+
+	/// a dashboard drawing its frames `width` columns wide into `writer`, escape codes and all,
+	/// as it would on a terminal
+	pub fn to_writer(rounds: u32, writer: impl Write + Send + Sync + 'static, width: usize) -> Self {
+		let mut dashboard = Dashboard::new(rounds);
+		dashboard.screen = Some(Screen { writer: Box::new(writer), width: Some(width), stderr: false, entered: false });
+
+		return dashboard;
+	}
+
+	/// leave the alternate screen and show the cursor again if a dashboard holds them,
+	/// for a panic or an exit that skips `EventSink::finish`
+	pub fn restore() {
+		if ACTIVE.swap(false, Ordering::SeqCst) {
+			let _ = write!(std::io::stderr(), "{}{}", LEAVE_ALTERNATE_SCREEN, SHOW_CURSOR);
+		}
+	}
+
+	/// the current frame, `width` columns wide at most
+	pub fn render(&self, width: usize) -> String {
+		let mut frame = String::new();
+
+		let rounds = if self.rounds > 0 { format!(" of {}", self.rounds) } else { String::new() };
+		frame.push_str(&format!("Dating simulation, round {}{}, population {}\n\n", self.round, rounds, self.population));
+
+		if let Some(stats) = &self.latest {
+			let genders = [
+				("Males", stats.matched_males, stats.unmatched_males),
+				("Females", stats.matched_females, stats.unmatched_females),
+				("Non-binary", stats.matched_non_binary, stats.unmatched_non_binary)
+			];
+			for (name, matched, unmatched) in genders {
+				if matched + unmatched == 0 {
+					continue;
+				}
+
+				let rate = matched as f64 / (matched + unmatched) as f64;
+				let filled = (rate * GAUGE_WIDTH as f64).round() as usize;
+				frame.push_str(&format!(
+					"{:<10} {}{} {:>6.2}% matched\n",
+					name,
+					"█".repeat(filled),
+					"░".repeat(GAUGE_WIDTH - filled),
+					rate * 100.0
+				));
+			}
+		}
+
+		let label = "Mean score ";
+		let scores = &self.mean_scores[self.mean_scores.len().saturating_sub(width.saturating_sub(label.len() + 10).max(1))..];
+		let current = self.latest
+			.as_ref()
+			.and_then(|stats| stats.mean_candidate_score)
+			.map(|score| format!(" {:.3}", score))
+			.unwrap_or_default();
+		frame.push_str(&format!("\n{}{}{}\n\nEvents\n", label, sparkline(scores), current));
+
+		for line in &self.log {
+			frame.push_str(&console::truncate_str(line, width, "…"));
+			frame.push('\n');
+		}
+
+		return frame;
+	}
+
+	fn push_log(&mut self, line: String) {
+		if self.log.len() == LOG_LINES {
+			self.log.pop_front();
+		}
+		self.log.push_back(line);
+	}
+
+	/// draw the current frame unless the last one is more recent than `REDRAW_INTERVAL`
+	fn draw(&mut self) -> Result<(), SimulationError> {
+		let Some(width) = self.screen.as_ref().map(Screen::width) else {
+			return Ok(());
+		};
+		if self.last_draw.is_some_and(|last_draw| last_draw.elapsed() < REDRAW_INTERVAL) {
+			return Ok(());
+		}
+
+		let frame = self.render(width);
+		let screen = self.screen.as_mut().expect("the width came from the screen");
+		if screen.enter() {
+			write!(screen.writer, "{}{}", ENTER_ALTERNATE_SCREEN, HIDE_CURSOR)?;
+		}
+		write!(screen.writer, "{}{}", CLEAR_SCREEN, frame)?;
+		screen.writer.flush()?;
+		self.last_draw = Some(Instant::now());
+
+		return Ok(());
+	}
+}
+
+impl EventSink for Dashboard {
+	fn emit(&mut self, event: &SimEvent) -> Result<(), SimulationError> {
+		match event {
+			SimEvent::RoundStarted { round, population } => {
+				self.round = *round;
+				self.population = *population;
+
+				return self.draw();
+			},
+			SimEvent::MatchFormed { round, proposer, reviewer, reviewer_score, proposer_score } => {
+				let sum = reviewer_score + proposer_score;
+				if self.best_pair.is_none_or(|best| sum > best) {
+					self.best_pair = Some(sum);
+					self.push_log(format!(
+						"round {}: best pair yet, {} and {} scoring each other {:.3} and {:.3}",
+						round,
+						proposer,
+						reviewer,
+						proposer_score,
+						reviewer_score
+					));
+				}
+			},
+			SimEvent::RoundCompleted { round, stats } => {
+				if stats.breakups > 0 {
+					self.push_log(format!("round {}: {} pairs broke up", round, stats.breakups));
+				}
+				self.mean_scores.push(stats.mean_candidate_score.unwrap_or_default());
+				self.latest = Some(stats.clone());
+
+				return self.draw();
+			},
+			SimEvent::Proposal { .. } | SimEvent::Rejection { .. } | SimEvent::MatchBroken { .. } => {}
+		}
+
+		return Ok(());
+	}
+
+	/// give the terminal back and leave the final frame on stderr
+	fn finish(&mut self) -> Result<(), SimulationError> {
+		let Some(width) = self.screen.as_ref().map(Screen::width) else {
+			return Ok(());
+		};
+
+		let frame = self.render(width);
+		let screen = self.screen.as_mut().expect("the width came from the screen");
+		screen.leave()?;
+		screen.writer.write_all(frame.as_bytes())?;
+		screen.writer.flush()?;
+
+		return Ok(());
+	}
+}
+
+impl Drop for Dashboard {
+	fn drop(&mut self) {
+		if let Some(screen) = &mut self.screen {
+			let _ = screen.leave();
+		}
+	}
+}
+
+/// `values` as one bar each, from the lowest of them to the highest
+fn sparkline(values: &[f32]) -> String {
+	let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+	let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+
+	return values
+		.iter()
+		.map(|&value| {
+			let level = if max > min { (value - min) / (max - min) * (SPARKS.len() - 1) as f32 } else { 0.0 };
+
+			return SPARKS[(level.round() as usize).min(SPARKS.len() - 1)];
+		})
+		.collect();
+}
@@ -0,0 +1,248 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::SimulationError;
+use crate::matching::MatchPair;
+use crate::metadata::RunMetadata;
+use crate::person_id::PersonId;
+use crate::sample::Sample;
+use crate::statistics::Statistics;
+
+// the pairs and partner changes `RunDiff` prints of each kind, the json has all of them
+const DISPLAY_LIMIT: usize = 20;
+
+/// a run read back from a json export or a checkpoint
+#[derive(Debug, Clone)]
+pub struct RunExport {
+	// `None` for files written before exports carried their metadata
+	pub metadata: Option<RunMetadata>,
+	pub sample: Sample
+}
+
+// only the metadata of a file, the sample is read on its own
+#[derive(Deserialize)]
+struct MetadataOf {
+	#[serde(default)]
+	metadata: Option<RunMetadata>
+}
+
+impl RunExport {
+	/// the sample as it would be exported now
+	pub fn from_sample(sample: Sample) -> Self {
+		return RunExport { metadata: Some(sample.metadata()), sample: sample };
+	}
+
+	/// read the json of `Sample::to_json_writer` from `reader`, checked like `Sample::from_json_reader`
+	pub fn from_json_reader(mut reader: impl std::io::Read) -> Result<Self, SimulationError> {
+		let mut json = Vec::new();
+		reader.read_to_end(&mut json)?;
+		let sample = Sample::from_json_reader(json.as_slice())?;
+		let metadata: MetadataOf = serde_json::from_slice(&json)?;
+
+		return Ok(RunExport { metadata: metadata.metadata, sample: sample });
+	}
+
+	/// read a file written by `Sample::to_json_file` or `Sample::checkpoint`
+	#[cfg(feature = "fs")]
+	pub fn from_json_file(path: &std::path::Path) -> Result<Self, SimulationError> {
+		let file = std::fs::File::open(path)?;
+
+		return RunExport::from_json_reader(std::io::BufReader::new(file));
+	}
+}
+
+/// the partner someone has in either run, `None` while single
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PartnerChange {
+	pub identity: PersonId,
+	pub in_a: Option<PersonId>,
+	pub in_b: Option<PersonId>
+}
+
+/// a headline statistic of both runs, `None` where the run has none, e.g. the mean score without pairs
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatisticDelta {
+	pub name: String,
+	pub a: Option<f64>,
+	pub b: Option<f64>
+}
+
+impl StatisticDelta {
+	fn new(name: &str, a: Option<f64>, b: Option<f64>) -> Self {
+		return StatisticDelta { name: name.to_string(), a: a, b: b };
+	}
+
+	/// b minus a, `None` unless both runs have the statistic
+	pub fn delta(&self) -> Option<f64> {
+		return Some(self.b? - self.a?);
+	}
+
+	pub fn differs(&self) -> bool {
+		return self.a != self.b;
+	}
+}
+
+/// what changed from run a to run b, see `diff_runs`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunDiff {
+	pub pairs_in_a: usize,
+	pub pairs_in_b: usize,
+	// pairs of a whose two individuals are not paired in b, and the other way round
+	pub only_in_a: Vec<MatchPair>,
+	pub only_in_b: Vec<MatchPair>,
+	// individuals of both runs whose partner is not the same, ordered by identity
+	pub partner_changes: Vec<PartnerChange>,
+	// individuals only one of the runs has, none for two runs of the same seed and config
+	pub individuals_only_in_a: usize,
+	pub individuals_only_in_b: usize,
+	// every headline statistic, whether it changed or not
+	pub statistics: Vec<StatisticDelta>
+}
+
+impl RunDiff {
+	/// whether the two runs ended the same
+	pub fn is_empty(&self) -> bool {
+		return self.only_in_a.is_empty()
+			&& self.only_in_b.is_empty()
+			&& self.partner_changes.is_empty()
+			&& self.individuals_only_in_a == 0
+			&& self.individuals_only_in_b == 0
+			&& !self.statistics.iter().any(StatisticDelta::differs);
+	}
+}
+
+/// the two individuals of a pair, in the same order whichever of them proposed
+fn pair_key(pair: &MatchPair) -> (PersonId, PersonId) {
+	return (pair.proposer_id.min(pair.reviewer_id), pair.proposer_id.max(pair.reviewer_id));
+}
+
+fn partners(pairs: &[MatchPair]) -> BTreeMap<PersonId, PersonId> {
+	return pairs
+		.iter()
+		.flat_map(|pair| [(pair.proposer_id, pair.reviewer_id), (pair.reviewer_id, pair.proposer_id)])
+		.collect();
+}
+
+fn headline_statistics(a: &RunExport, b: &RunExport) -> Vec<StatisticDelta> {
+	let (statistics_a, statistics_b) = (a.sample.statistics_with_blocking_pairs(), b.sample.statistics_with_blocking_pairs());
+	let both = |name: &str, statistic: &dyn Fn(&Statistics) -> Option<f64>| {
+		return StatisticDelta::new(name, statistic(&statistics_a), statistic(&statistics_b));
+	};
+
+	return vec![
+		StatisticDelta::new("rounds", Some(a.sample.round as f64), Some(b.sample.round as f64)),
+		both("matched", &|statistics| Some((statistics.matched_males + statistics.matched_females + statistics.matched_non_binary) as f64)),
+		both("unmatched_percentage", &|statistics| Some(statistics.unmatched_percentage)),
+		both("mean_candidate_score", &|statistics| statistics.mean_candidate_score.map(f64::from)),
+		both("median_candidate_score", &|statistics| statistics.median_candidate_score.map(f64::from)),
+		both("total_welfare", &|statistics| Some(statistics.total_welfare)),
+		both("blocking_pairs", &|statistics| statistics.blocking_pairs),
+		both("retired_pairs", &|statistics| Some(statistics.retired_pairs as f64)),
+		both("breakups", &|statistics| Some(statistics.breakups as f64)),
+		both("divorces", &|statistics| Some(statistics.divorces as f64))
+	];
+}
+
+/// compare the final pairs and statistics of two runs, e.g. of the same seed before and after a change
+/// to the matching. two pairs are the same when they pair the same two individuals, whatever their scores
+pub fn diff_runs(a: &RunExport, b: &RunExport) -> RunDiff {
+	let (pairs_a, pairs_b) = (a.sample.pairs(), b.sample.pairs());
+	let keys_a: BTreeSet<_> = pairs_a.iter().map(pair_key).collect();
+	let keys_b: BTreeSet<_> = pairs_b.iter().map(pair_key).collect();
+
+	let identities_a: BTreeSet<PersonId> = a.sample.population.iter().map(|individual| individual.identity).collect();
+	let identities_b: BTreeSet<PersonId> = b.sample.population.iter().map(|individual| individual.identity).collect();
+	let (partners_a, partners_b) = (partners(&pairs_a), partners(&pairs_b));
+	let partner_changes = identities_a
+		.intersection(&identities_b)
+		.map(|&identity| PartnerChange {
+			identity: identity,
+			in_a: partners_a.get(&identity).copied(),
+			in_b: partners_b.get(&identity).copied()
+		})
+		.filter(|change| change.in_a != change.in_b)
+		.collect();
+
+	return RunDiff {
+		pairs_in_a: pairs_a.len(),
+		pairs_in_b: pairs_b.len(),
+		only_in_a: pairs_a.iter().filter(|pair| !keys_b.contains(&pair_key(pair))).cloned().collect(),
+		only_in_b: pairs_b.iter().filter(|pair| !keys_a.contains(&pair_key(pair))).cloned().collect(),
+		partner_changes: partner_changes,
+		individuals_only_in_a: identities_a.difference(&identities_b).count(),
+		individuals_only_in_b: identities_b.difference(&identities_a).count(),
+		statistics: headline_statistics(a, b)
+	};
+}
+
+// counts without decimals
+fn format_statistic(value: Option<f64>) -> String {
+	return match value {
+		None => String::from("-"),
+		Some(value) if value.fract() == 0.0 => format!("{}", value),
+		Some(value) => format!("{:.4}", value)
+	};
+}
+
+fn format_partner(partner: Option<PersonId>) -> String {
+	return partner.map_or(String::from("single"), |partner| partner.short_id().to_string());
+}
+
+fn write_pairs(f: &mut std::fmt::Formatter<'_>, title: &str, pairs: &[MatchPair]) -> std::fmt::Result {
+	if pairs.is_empty() {
+		return Ok(());
+	}
+
+	writeln!(f, "{} ({}):", title, pairs.len())?;
+	for pair in pairs.iter().take(DISPLAY_LIMIT) {
+		writeln!(f, "  {} & {}  {:.4} / {:.4}", pair.proposer_id.short_id(), pair.reviewer_id.short_id(), pair.reviewer_score, pair.proposer_score)?;
+	}
+	if pairs.len() > DISPLAY_LIMIT {
+		writeln!(f, "  ... and {} more", pairs.len() - DISPLAY_LIMIT)?;
+	}
+
+	return Ok(());
+}
+
+impl std::fmt::Display for RunDiff {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.is_empty() {
+			true => writeln!(f, "The runs ended the same, {} pairs each", self.pairs_in_a)?,
+			false => writeln!(f, "The runs differ: {} pairs in a, {} in b", self.pairs_in_a, self.pairs_in_b)?
+		}
+		if self.individuals_only_in_a > 0 || self.individuals_only_in_b > 0 {
+			writeln!(f, "Individuals only in a: {}, only in b: {}", self.individuals_only_in_a, self.individuals_only_in_b)?;
+		}
+
+		write_pairs(f, "Pairs only in a", &self.only_in_a)?;
+		write_pairs(f, "Pairs only in b", &self.only_in_b)?;
+
+		if !self.partner_changes.is_empty() {
+			writeln!(f, "Partner changes ({}):", self.partner_changes.len())?;
+			for change in self.partner_changes.iter().take(DISPLAY_LIMIT) {
+				writeln!(f, "  {}: {} -> {}", change.identity.short_id(), format_partner(change.in_a), format_partner(change.in_b))?;
+			}
+			if self.partner_changes.len() > DISPLAY_LIMIT {
+				writeln!(f, "  ... and {} more", self.partner_changes.len() - DISPLAY_LIMIT)?;
+			}
+		}
+
+		let width = self.statistics.iter().map(|statistic| statistic.name.len()).max().unwrap_or_default();
+		writeln!(f, "{:<width$}  {:>12}  {:>12}  {:>12}", "Statistic", "a", "b", "delta", width = width)?;
+		for statistic in &self.statistics {
+			writeln!(
+				f,
+				"{:<width$}  {:>12}  {:>12}  {:>12}{}",
+				statistic.name,
+				format_statistic(statistic.a),
+				format_statistic(statistic.b),
+				format_statistic(statistic.delta()),
+				if statistic.differs() { "  *" } else { "" },
+				width = width
+			)?;
+		}
+
+		return Ok(());
+	}
+}
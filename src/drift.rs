@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand_distr::Distribution;
+use serde::{Deserialize, Serialize};
+
+use crate::error::SimulationError;
+use crate::individual::Individual;
+use crate::person_id::PersonId;
+
+/// how the preference weights of the population change between the rounds of `Sample::run`,
+/// see `Sample::set_preference_update`. `round` is the 1-based round that just ended and
+/// `rng` is seeded from the sample's seed and the round, so a seeded run drifts the same every time
+pub trait PreferenceUpdate: std::fmt::Debug + Send + Sync {
+	fn update(&self, population: &mut [Individual], round: u32, rng: &mut StdRng);
+}
+
+/// leaves every weight as it is, the update of a `Sample` without drift
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoOp;
+
+impl PreferenceUpdate for NoOp {
+	fn update(&self, _population: &mut [Individual], _round: u32, _rng: &mut StdRng) {}
+}
+
+/// the built-in `PreferenceUpdate`, the `[drift]` table. the default changes nothing
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PreferenceDrift {
+	// standard deviation of the normal step every weight takes after a round, 0 keeps them
+	pub random_walk: f32,
+	// how far the weights of someone turned down in a round move toward the ratings of those who
+	// turned them down, from 0 for not at all to 1 for all the way. "turned down" is what the blacklist
+	// of the round holds, so a breakup under `blacklist_on_breakup` counts too
+	pub toward_rejecters: f32,
+	// scale the weights to add up to 1 in absolute value after they moved
+	pub renormalize: bool,
+	// every weight is clamped to these after it moved and was renormalized
+	pub min_weight: f32,
+	pub max_weight: f32
+}
+
+impl Default for PreferenceDrift {
+	fn default() -> Self {
+		return PreferenceDrift {
+			random_walk: 0.0,
+			toward_rejecters: 0.0,
+			renormalize: false,
+			min_weight: 0.0,
+			max_weight: 1.0
+		};
+	}
+}
+
+impl PreferenceDrift {
+	pub fn validate(&self) -> Result<(), SimulationError> {
+		if !self.random_walk.is_finite() || self.random_walk < 0.0 {
+			return Err(
+				SimulationError::InvalidConfig(format!("the random walk of the drift must be a non-negative number, got {}", self.random_walk))
+			);
+		}
+		if !(0.0..=1.0).contains(&self.toward_rejecters) {
+			return Err(
+				SimulationError::InvalidConfig(format!("the drift toward rejecters must be between 0 and 1, got {}", self.toward_rejecters))
+			);
+		}
+		if !self.min_weight.is_finite() || !self.max_weight.is_finite() || self.min_weight > self.max_weight {
+			return Err(
+				SimulationError::InvalidConfig(format!("the drift needs finite weight bounds with min <= max, got {} to {}", self.min_weight, self.max_weight))
+			);
+		}
+
+		return Ok(());
+	}
+
+	/// whether the weights move at all
+	pub fn is_active(&self) -> bool {
+		return self.random_walk > 0.0 || self.toward_rejecters > 0.0;
+	}
+
+	/// the mean ratings of those who turned `individual` down in `round`,
+	/// scaled to the absolute sum of their weights. `None` when nobody did
+	fn rejecter_profile(
+		individual: &Individual,
+		population: &[Individual],
+		index: &HashMap<PersonId, usize>,
+		round: u32
+	) -> Option<Vec<f32>> {
+		// in population order, the blacklist is a hash map and adding up in its order would not repeat
+		let mut positions: Vec<usize> = individual.extras().blacklist
+			.iter()
+			.filter(|(_, &blacklisted_in)| blacklisted_in == round)
+			.filter_map(|(identity, _)| index.get(identity).copied())
+			.collect();
+		positions.sort_unstable();
+		let rejecters: Vec<&Individual> = positions
+			.into_iter()
+			.map(|position| &population[position])
+			.filter(|rejecter| rejecter.ratings.len() == individual.preference_weights.len())
+			.collect();
+		if rejecters.is_empty() {
+			return None;
+		}
+
+		let mut profile = vec![0.0; individual.preference_weights.len()];
+		for rejecter in &rejecters {
+			for (mean, rating) in profile.iter_mut().zip(&rejecter.ratings) {
+				*mean += rating / rejecters.len() as f32;
+			}
+		}
+		let (profile_sum, weight_sum) = (
+			profile.iter().map(|rating| rating.abs()).sum::<f32>(),
+			individual.preference_weights.iter().map(|weight| weight.abs()).sum::<f32>()
+		);
+		if profile_sum <= 0.0 {
+			return None;
+		}
+
+		return Some(profile.into_iter().map(|rating| rating / profile_sum * weight_sum).collect());
+	}
+}
+
+impl PreferenceUpdate for PreferenceDrift {
+	/// first toward the rejecters, then the random walk, then renormalized and clamped
+	fn update(&self, population: &mut [Individual], round: u32, rng: &mut StdRng) {
+		if !self.is_active() {
+			return;
+		}
+
+		// the blacklists are read before anyone moves, so the order of the population does not matter
+		let profiles: Vec<Option<Vec<f32>>> = match self.toward_rejecters > 0.0 {
+			true => {
+				let index: HashMap<PersonId, usize> = population
+					.iter()
+					.enumerate()
+					.map(|(position, individual)| (individual.identity, position))
+					.collect();
+
+				population
+					.iter()
+					.map(|individual| PreferenceDrift::rejecter_profile(individual, population, &index, round))
+					.collect()
+			},
+			false => vec![None; population.len()]
+		};
+		let step = rand_distr::Normal::new(0.0, self.random_walk).unwrap();
+
+		for (individual, profile) in population.iter_mut().zip(profiles) {
+			if let Some(profile) = profile {
+				for (weight, target) in individual.preference_weights.iter_mut().zip(profile) {
+					*weight += (target - *weight) * self.toward_rejecters;
+				}
+			}
+			if self.random_walk > 0.0 {
+				for weight in &mut individual.preference_weights {
+					*weight += step.sample(rng);
+				}
+			}
+			if self.renormalize {
+				let sum: f32 = individual.preference_weights.iter().map(|weight| weight.abs()).sum();
+				if sum > 0.0 {
+					for weight in &mut individual.preference_weights {
+						*weight /= sum;
+					}
+				}
+			}
+			for weight in &mut individual.preference_weights {
+				*weight = weight.clamp(self.min_weight, self.max_weight);
+			}
+		}
+	}
+}
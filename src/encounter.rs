@@ -0,0 +1,151 @@
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::individual::Individual;
+use crate::person_id::PersonId;
+
+/// who a proposer comes across when `MatchingConfig::max_evaluations_per_round` only lets them
+/// evaluate a few of the reviewers they could propose to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Encounters {
+	// every admissible reviewer is as likely to be evaluated as any other
+	#[default]
+	Uniform,
+	// reviewers are seen in proportion to their desirability under the mean weights of the population,
+	// the ones at or below 0 are never seen
+	Desirability
+}
+
+impl std::fmt::Display for Encounters {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			Encounters::Uniform => "uniform",
+			Encounters::Desirability => "desirability"
+		};
+
+		return write!(f, "{}", name);
+	}
+}
+
+impl std::str::FromStr for Encounters {
+	type Err = String;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		return match name {
+			"uniform" => Ok(Encounters::Uniform),
+			"desirability" => Ok(Encounters::Desirability),
+			_ => Err(format!("unknown encounters `{}`, expected `uniform` or `desirability`", name))
+		};
+	}
+}
+
+/// draws positions in proportion to their weights in constant time, vose's alias method
+#[derive(Debug, Clone)]
+pub(crate) struct AliasTable {
+	// the chance of keeping the drawn column rather than taking its alias
+	probabilities: Vec<f64>,
+	aliases: Vec<usize>
+}
+
+impl AliasTable {
+	/// `None` when no weight is above 0, weights below 0 count as 0
+	pub fn new(weights: &[f64]) -> Option<Self> {
+		let total: f64 = weights.iter().map(|weight| weight.max(0.0)).sum();
+		if total <= 0.0 || !total.is_finite() {
+			return None;
+		}
+
+		// every weight scaled so that they average 1
+		let mut scaled: Vec<f64> = weights
+			.iter()
+			.map(|weight| weight.max(0.0) * weights.len() as f64 / total)
+			.collect();
+		let mut probabilities = vec![1.0; weights.len()];
+		let mut aliases: Vec<usize> = (0..weights.len()).collect();
+
+		let (mut small, mut large): (Vec<usize>, Vec<usize>) = (0..weights.len()).partition(|&position| scaled[position] < 1.0);
+		while let (Some(&under), Some(&over)) = (small.last(), large.last()) {
+			small.pop();
+			probabilities[under] = scaled[under];
+			aliases[under] = over;
+
+			// the large column gives away what fills up the small one
+			scaled[over] -= 1.0 - scaled[under];
+			if scaled[over] < 1.0 {
+				large.pop();
+				small.push(over);
+			}
+		}
+		// what is left over is 1 up to rounding errors
+
+		return Some(AliasTable { probabilities: probabilities, aliases: aliases });
+	}
+
+	/// a position drawn with the chance of its weight
+	pub fn sample(&self, rng: &mut impl Rng) -> usize {
+		let column = rng.gen_range(0..self.probabilities.len());
+
+		return if rng.r#gen::<f64>() < self.probabilities[column] { column } else { self.aliases[column] };
+	}
+}
+
+/// the `AliasTable` of `Encounters::Desirability` over a population,
+/// kept between rounds until the population changes
+#[derive(Debug, Clone)]
+pub(crate) struct VisibilityTable {
+	// the population order the table was built for
+	identities: Vec<PersonId>,
+	pub table: AliasTable
+}
+
+impl VisibilityTable {
+	/// `None` when nobody in `population` is desirable at all
+	pub fn new(population: &[Individual], mean_weights: &[f32]) -> Option<Self> {
+		let weights: Vec<f64> = population
+			.iter()
+			.map(|individual| individual.desirability(mean_weights) as f64)
+			.collect();
+
+		return Some(VisibilityTable {
+			identities: population.iter().map(|individual| individual.identity).collect(),
+			table: AliasTable::new(&weights)?
+		});
+	}
+
+	/// whether the table was built for `population` in this order
+	pub fn fits(&self, population: &[Individual]) -> bool {
+		return self.identities.len() == population.len() &&
+			self.identities.iter().zip(population).all(|(identity, individual)| *identity == individual.identity);
+	}
+}
+
+/// what came of an `Encounter`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EncounterOutcome {
+	// the reviewer took the proposer, for the time being
+	Accepted,
+	// the reviewer turned the proposer down, their score fell below the candidate the reviewer holds or the reviewer's minimum
+	RejectedBelowCandidate,
+	// the proposer's score came within `MatchingConfig::score_epsilon` of the candidate the reviewer holds,
+	// who stays. neither blacklists the other
+	Tied,
+	// one of the two fails a dealbreaker of the other, neither scored
+	Dealbreaker,
+	// one of the two still has the other on their blacklist, neither scored
+	Blacklisted
+}
+
+/// one proposal in the `Individual::history` of both sides, recorded under `MatchingConfig::record_history`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Encounter {
+	// 1-based
+	pub round: u32,
+	pub other: PersonId,
+	// whether the individual whose history this is proposed, otherwise `other` proposed to them
+	pub proposed: bool,
+	// the reviewer's score of the proposer, `None` when it never came to scoring
+	pub score: Option<f32>,
+	pub outcome: EncounterOutcome
+}
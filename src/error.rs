@@ -0,0 +1,88 @@
+use crate::attributes::SchemaError;
+use crate::individual::Gender;
+use crate::scoring::ScoreError;
+use crate::validation::ValidationError;
+
+/// everything the public api of the simulation can fail with
+#[derive(Debug, thiserror::Error)]
+pub enum SimulationError {
+	// preference weights were given, or are held, for a different number of attributes than there are ratings
+	#[error("{actual} preference weights were given for {expected} ratings, they must match")]
+	WeightRatingLengthMismatch { expected: usize, actual: usize },
+	// a config or distribution value the simulation cannot run with
+	#[error("{0}")]
+	InvalidConfig(String),
+	// an identity that is referenced, e.g. as a candidate, but not part of the sample
+	#[error("{0} is not part of the sample")]
+	UnknownIdentity(String),
+	#[error(transparent)]
+	Schema(SchemaError),
+	// a round checked with `MatchingConfig::validate_rounds` broke the invariants of `Sample::validate`
+	#[error("round {round} left the sample inconsistent: {}", join(.errors))]
+	InconsistentRound { round: u32, errors: Vec<ValidationError> },
+	// `Sample::match_making` in a pool where no proposer is compatible with anyone they could propose to,
+	// `gender` is the one missing when the pool is of a single gender
+	#[error("{}", empty_population(.gender))]
+	EmptyPopulation { gender: Option<Gender> },
+	#[error(transparent)]
+	Io(#[from] std::io::Error),
+	#[error(transparent)]
+	Json(#[from] serde_json::Error),
+	#[error("{}: {source}", .path.display())]
+	Toml { path: std::path::PathBuf, source: toml::de::Error },
+	#[error(transparent)]
+	TomlSerialize(#[from] toml::ser::Error),
+	// a directory to write into that is not there
+	#[error("{} is not a directory", .0.display())]
+	MissingDirectory(std::path::PathBuf),
+	// a chart of `RoundStats::plot` could not be drawn
+	#[error("could not draw {}: {message}", .path.display())]
+	Plot { path: std::path::PathBuf, message: String },
+	// the database of a `SqliteSink` could not be opened or written
+	#[error("{}: {message}", .path.display())]
+	Sqlite { path: std::path::PathBuf, message: String },
+	// a file given to `ParquetTable::read` that is not parquet, or uses more of it than the exports write
+	#[error("could not read {}: {message}", .path.display())]
+	Parquet { path: std::path::PathBuf, message: String },
+	// a request to the `SimulationServer` that is not http it understands
+	#[error("{0}")]
+	InvalidRequest(String)
+}
+
+fn empty_population(gender: &Option<Gender>) -> String {
+	return match gender {
+		Some(gender) => format!("there is no {} individual in the pool, nobody in it can be paired", gender.to_string().to_lowercase()),
+		None => String::from("nobody in the pool is compatible with anyone they could be paired with")
+	};
+}
+
+fn join(errors: &[ValidationError]) -> String {
+	return errors
+		.iter()
+		.map(|error| error.to_string())
+		.collect::<Vec<String>>()
+		.join("; ");
+}
+
+impl From<SchemaError> for SimulationError {
+	fn from(error: SchemaError) -> Self {
+		return match error {
+			SchemaError::WeightCountMismatch { attributes, weights } => SimulationError::WeightRatingLengthMismatch {
+				expected: attributes,
+				actual: weights
+			},
+			error => SimulationError::Schema(error)
+		};
+	}
+}
+
+impl From<ScoreError> for SimulationError {
+	fn from(error: ScoreError) -> Self {
+		return match error {
+			ScoreError::AttributeCountMismatch { weights, ratings, .. } => SimulationError::WeightRatingLengthMismatch {
+				expected: ratings,
+				actual: weights
+			}
+		};
+	}
+}
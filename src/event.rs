@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+
+use crate::encounter::EncounterOutcome;
+use crate::error::SimulationError;
+use crate::person_id::PersonId;
+use crate::statistics::RoundStats;
+
+/// what happened during a round of `Sample::match_making`, in the order it is emitted to the `EventSink`:
+/// the round starts, the proposals and rejections follow proposer by proposer, then the pairs
+/// that formed and broke and the round completes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SimEvent {
+	RoundStarted {
+		// 1-based
+		round: u32,
+		population: usize
+	},
+	// a proposer was scored by a reviewer
+	Proposal {
+		round: u32,
+		proposer: PersonId,
+		reviewer: PersonId,
+		// the reviewer's score of the proposer
+		score: f32
+	},
+	// a proposer was turned down or passed over
+	Rejection {
+		round: u32,
+		proposer: PersonId,
+		reviewer: PersonId,
+		reason: EncounterOutcome
+	},
+	// a mutual pair that did not exist at the start of the round, breakups included
+	MatchFormed {
+		round: u32,
+		proposer: PersonId,
+		reviewer: PersonId,
+		// the reviewer's score of the proposer and the other way around
+		reviewer_score: f32,
+		proposer_score: f32
+	},
+	// a mutual pair of the start of the round that no longer exists
+	MatchBroken {
+		round: u32,
+		proposer: PersonId,
+		reviewer: PersonId
+	},
+	RoundCompleted {
+		round: u32,
+		stats: RoundStats
+	}
+}
+
+/// where `Sample::match_making` sends its `SimEvent`s, see `Sample::set_event_sink`.
+/// an error aborts the round with it
+pub trait EventSink: std::fmt::Debug + Send + Sync {
+	fn emit(&mut self, event: &SimEvent) -> Result<(), SimulationError>;
+
+	/// called after the last event of every round
+	fn flush(&mut self) -> Result<(), SimulationError> {
+		return Ok(());
+	}
+
+	/// called once when `Sample::run_until` ends, interrupted or not
+	fn finish(&mut self) -> Result<(), SimulationError> {
+		return self.flush();
+	}
+
+	/// whether the sink takes events at all, a round that nobody listens to collects none
+	fn enabled(&self) -> bool {
+		return true;
+	}
+}
+
+/// drops every event, the sink of a `Sample` until another one is set
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoEvents;
+
+impl EventSink for NoEvents {
+	fn emit(&mut self, _event: &SimEvent) -> Result<(), SimulationError> {
+		return Ok(());
+	}
+
+	fn enabled(&self) -> bool {
+		return false;
+	}
+}
+
+/// hands every event to each of the sinks in turn
+impl EventSink for Vec<Box<dyn EventSink>> {
+	fn emit(&mut self, event: &SimEvent) -> Result<(), SimulationError> {
+		for sink in self.iter_mut().filter(|sink| sink.enabled()) {
+			sink.emit(event)?;
+		}
+
+		return Ok(());
+	}
+
+	fn flush(&mut self) -> Result<(), SimulationError> {
+		for sink in self.iter_mut() {
+			sink.flush()?;
+		}
+
+		return Ok(());
+	}
+
+	fn finish(&mut self) -> Result<(), SimulationError> {
+		for sink in self.iter_mut() {
+			sink.finish()?;
+		}
+
+		return Ok(());
+	}
+
+	fn enabled(&self) -> bool {
+		return self.iter().any(|sink| sink.enabled());
+	}
+}
+
+/// writes every event as one line of json, flushed after every round
+#[derive(Debug)]
+pub struct JsonLinesSink<W: std::io::Write + std::fmt::Debug + Send + Sync> {
+	writer: W
+}
+
+impl<W: std::io::Write + std::fmt::Debug + Send + Sync> JsonLinesSink<W> {
+	pub fn new(writer: W) -> Self {
+		return JsonLinesSink { writer: writer };
+	}
+}
+
+#[cfg(feature = "fs")]
+impl JsonLinesSink<std::io::BufWriter<std::fs::File>> {
+	/// a sink writing to a new file at `path`
+	pub fn create(path: &std::path::Path) -> Result<Self, SimulationError> {
+		let file = std::fs::File::create(path)?;
+
+		return Ok(JsonLinesSink::new(std::io::BufWriter::new(file)));
+	}
+}
+
+impl<W: std::io::Write + std::fmt::Debug + Send + Sync> EventSink for JsonLinesSink<W> {
+	fn emit(&mut self, event: &SimEvent) -> Result<(), SimulationError> {
+		// serialized up-front so that a failing writer surfaces as `SimulationError::Io`
+		let mut line = serde_json::to_vec(event)?;
+		line.push(b'\n');
+		self.writer.write_all(&line)?;
+
+		return Ok(());
+	}
+
+	fn flush(&mut self) -> Result<(), SimulationError> {
+		self.writer.flush()?;
+
+		return Ok(());
+	}
+}
+
+pub(crate) fn no_events() -> Box<dyn EventSink> {
+	return Box::new(NoEvents);
+}
@@ -0,0 +1,161 @@
+use crate::error::SimulationError;
+use crate::individual::{Gender, Individual};
+use crate::person_id::PersonId;
+
+/// which relation `Sample::export_graph` draws as edges
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphKind {
+	// one undirected edge per mutual pair
+	#[default]
+	Matches,
+	// a directed edge from every individual to each one on their blacklist
+	Blacklist
+}
+
+impl std::fmt::Display for GraphKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			GraphKind::Matches => "matches",
+			GraphKind::Blacklist => "blacklist"
+		};
+
+		return write!(f, "{}", name);
+	}
+}
+
+impl std::str::FromStr for GraphKind {
+	type Err = String;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		return match name {
+			"matches" => Ok(GraphKind::Matches),
+			"blacklist" => Ok(GraphKind::Blacklist),
+			_ => Err(format!("unknown graph kind `{}`, expected `matches` or `blacklist`", name))
+		};
+	}
+}
+
+/// the file format of `Sample::export_graph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphFormat {
+	// graphviz, e.g. `dot -Tsvg matches.dot -o matches.svg`
+	#[default]
+	Dot,
+	GraphMl
+}
+
+impl std::fmt::Display for GraphFormat {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			GraphFormat::Dot => "dot",
+			GraphFormat::GraphMl => "graphml"
+		};
+
+		return write!(f, "{}", name);
+	}
+}
+
+impl std::str::FromStr for GraphFormat {
+	type Err = String;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		return match name {
+			"dot" => Ok(GraphFormat::Dot),
+			"graphml" => Ok(GraphFormat::GraphMl),
+			_ => Err(format!("unknown graph format `{}`, expected `dot` or `graphml`", name))
+		};
+	}
+}
+
+/// an individual drawn as a node
+#[derive(Clone, Copy)]
+pub(crate) struct Node<'a> {
+	pub individual: &'a Individual,
+	pub matched: bool
+}
+
+fn gender_initial(gender: Gender) -> &'static str {
+	return match gender {
+		Gender::Male => "M",
+		Gender::Female => "F",
+		Gender::NonBinary => "NB"
+	};
+}
+
+fn label(individual: &Individual) -> String {
+	return format!("{} ({})", individual.short_id(), gender_initial(individual.gender));
+}
+
+/// escape the characters that cannot appear as they are in xml (and html) text and attributes
+pub(crate) fn escape_xml(text: &str) -> String {
+	return text
+		.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;");
+}
+
+/// write the nodes and the edges between their identities as graphviz dot
+pub(crate) fn write_dot(
+	mut writer: impl std::io::Write,
+	kind: GraphKind,
+	nodes: &[Node],
+	edges: &[(PersonId, PersonId)]
+) -> Result<(), SimulationError> {
+	let (graph, connector) = match kind {
+		GraphKind::Matches => ("graph", "--"),
+		GraphKind::Blacklist => ("digraph", "->")
+	};
+
+	writeln!(writer, "{} {} {{", graph, kind)?;
+	writeln!(writer, "\tnode [style=filled];")?;
+	for node in nodes {
+		writeln!(
+			writer,
+			"\t{:?} [label={:?}, fillcolor={}];",
+			node.individual.identity,
+			label(node.individual),
+			if node.matched { "palegreen" } else { "lightgray" }
+		)?;
+	}
+	for (from, to) in edges {
+		writeln!(writer, "\t{:?} {} {:?};", from, connector, to)?;
+	}
+	writeln!(writer, "}}")?;
+
+	return Ok(());
+}
+
+/// write the nodes and the edges between their identities as graphml
+pub(crate) fn write_graphml(
+	mut writer: impl std::io::Write,
+	kind: GraphKind,
+	nodes: &[Node],
+	edges: &[(PersonId, PersonId)]
+) -> Result<(), SimulationError> {
+	let edge_default = match kind {
+		GraphKind::Matches => "undirected",
+		GraphKind::Blacklist => "directed"
+	};
+
+	writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+	writeln!(writer, r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#)?;
+	writeln!(writer, r#"  <key id="label" for="node" attr.name="label" attr.type="string"/>"#)?;
+	writeln!(writer, r#"  <key id="gender" for="node" attr.name="gender" attr.type="string"/>"#)?;
+	writeln!(writer, r#"  <key id="matched" for="node" attr.name="matched" attr.type="boolean"/>"#)?;
+	writeln!(writer, r#"  <graph id="{}" edgedefault="{}">"#, kind, edge_default)?;
+	for node in nodes {
+		writeln!(writer, r#"    <node id="{}">"#, escape_xml(&node.individual.identity.as_str()))?;
+		writeln!(writer, r#"      <data key="label">{}</data>"#, escape_xml(&label(node.individual)))?;
+		writeln!(writer, r#"      <data key="gender">{}</data>"#, node.individual.gender)?;
+		writeln!(writer, r#"      <data key="matched">{}</data>"#, node.matched)?;
+		writeln!(writer, r#"    </node>"#)?;
+	}
+	for (from, to) in edges {
+		writeln!(writer, r#"    <edge source="{}" target="{}"/>"#, escape_xml(&from.as_str()), escape_xml(&to.as_str()))?;
+	}
+	writeln!(writer, r#"  </graph>"#)?;
+	writeln!(writer, r#"</graphml>"#)?;
+
+	return Ok(());
+}
@@ -0,0 +1,1268 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::attributes::{AttributeSchema, SchemaError};
+use crate::encounter::Encounter;
+use crate::error::SimulationError;
+use crate::names;
+use crate::person_id::{IdentityStr, PersonId};
+use crate::scoring::{self, ScoreError, ScoringStrategy, WeightedSum};
+
+/// the order of the variants decides who proposes to whom in the greedy scan, 
+/// see `MatchingAlgorithm`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Gender {
+	Male,
+	Female,
+	NonBinary
+}
+
+impl Gender {
+	pub const ALL: [Gender; 3] = [Gender::Male, Gender::Female, Gender::NonBinary];
+}
+
+impl std::fmt::Display for Gender {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			Gender::Male => "Male",
+			Gender::Female => "Female",
+			Gender::NonBinary => "Non-binary"
+		};
+		
+		return write!(f, "{}", name);
+	}
+}
+
+/// relative weights of the genders `Sample::generate` draws from, 
+/// they do not need to add up to one, e.g. `male = 60, female = 40`. 
+/// the default is an even split between males and females
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GenderDistribution {
+	pub male: f64,
+	pub female: f64,
+	pub non_binary: f64
+}
+
+impl Default for GenderDistribution {
+	fn default() -> Self {
+		return GenderDistribution {
+			male: 1.0,
+			female: 1.0,
+			non_binary: 0.0
+		};
+	}
+}
+
+impl GenderDistribution {
+	fn weights(&self) -> [(Gender, f64); 3] {
+		return [
+			(Gender::Male, self.male),
+			(Gender::Female, self.female),
+			(Gender::NonBinary, self.non_binary)
+		];
+	}
+	
+	/// the weights must be finite and non-negative, and at least one must be positive
+	pub fn validate(&self) -> Result<(), SimulationError> {
+		return validate_weights("gender", &self.weights());
+	}
+	
+	/// draw a gender, panics when `GenderDistribution::validate` fails
+	pub fn sample(&self, rng: &mut impl Rng) -> Gender {
+		return draw(&self.weights(), rng);
+	}
+}
+
+/// exactly how many males and females `Sample::generate` draws, in a random order, 
+/// instead of drawing every gender from the `GenderDistribution`. see `SampleConfigBuilder::gender_counts`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GenderCounts {
+	pub male: usize,
+	pub female: usize
+}
+
+impl GenderCounts {
+	pub fn total(&self) -> usize {
+		return self.male + self.female;
+	}
+	
+	/// the distribution of the same ratio, for the genders of arrivals and children
+	pub fn distribution(&self) -> GenderDistribution {
+		return GenderDistribution { male: self.male as f64, female: self.female as f64, non_binary: 0.0 };
+	}
+
+	/// the gender of every individual, shuffled with `rng`
+	pub(crate) fn shuffled(&self, rng: &mut impl Rng) -> Vec<Gender> {
+		let mut genders: Vec<Gender> = std::iter::repeat_n(Gender::Male, self.male)
+			.chain(std::iter::repeat_n(Gender::Female, self.female))
+			.collect();
+		genders.shuffle(rng);
+		
+		return genders;
+	}
+}
+
+/// which genders an individual is attracted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+pub enum Orientation {
+	// every gender but their own
+	#[default]
+	Heterosexual,
+	// only their own gender
+	Homosexual,
+	// every gender
+	Bisexual
+}
+
+impl Orientation {
+	pub const ALL: [Orientation; 3] = [Orientation::Heterosexual, Orientation::Homosexual, Orientation::Bisexual];
+	
+	/// whether someone of `own` gender with this orientation is attracted to `other`
+	pub fn attracted_to(&self, own: Gender, other: Gender) -> bool {
+		return match self {
+			Orientation::Heterosexual => own != other,
+			Orientation::Homosexual => own == other,
+			Orientation::Bisexual => true
+		};
+	}
+}
+
+impl std::fmt::Display for Orientation {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			Orientation::Heterosexual => "Heterosexual",
+			Orientation::Homosexual => "Homosexual",
+			Orientation::Bisexual => "Bisexual"
+		};
+		
+		return write!(f, "{}", name);
+	}
+}
+
+/// relative weights of the orientations `Sample::generate` draws from, 
+/// the default makes everyone heterosexual
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OrientationDistribution {
+	pub heterosexual: f64,
+	pub homosexual: f64,
+	pub bisexual: f64
+}
+
+impl Default for OrientationDistribution {
+	fn default() -> Self {
+		return OrientationDistribution {
+			heterosexual: 1.0,
+			homosexual: 0.0,
+			bisexual: 0.0
+		};
+	}
+}
+
+impl OrientationDistribution {
+	fn weights(&self) -> [(Orientation, f64); 3] {
+		return [
+			(Orientation::Heterosexual, self.heterosexual),
+			(Orientation::Homosexual, self.homosexual),
+			(Orientation::Bisexual, self.bisexual)
+		];
+	}
+	
+	/// the weights must be finite and non-negative, and at least one must be positive
+	pub fn validate(&self) -> Result<(), SimulationError> {
+		return validate_weights("orientation", &self.weights());
+	}
+	
+	/// draw an orientation, panics when `OrientationDistribution::validate` fails
+	pub fn sample(&self, rng: &mut impl Rng) -> Orientation {
+		return draw(&self.weights(), rng);
+	}
+}
+
+fn validate_weights<T: std::fmt::Display>(
+	distribution: &str, 
+	weights: &[(T, f64)]
+) -> Result<(), SimulationError> {
+	for (value, weight) in weights {
+		if !weight.is_finite() || *weight < 0.0 {
+			return Err(
+				SimulationError::InvalidConfig(format!("the weight of {} in the {} distribution must be a non-negative number, got {}", value, distribution, weight))
+			);
+		}
+	}
+	
+	if weights.iter().map(|(_, weight)| weight).sum::<f64>() <= 0.0 {
+		return Err(
+			SimulationError::InvalidConfig(format!("at least one weight of the {} distribution must be positive", distribution))
+		);
+	}
+	
+	return Ok(());
+}
+
+/// draw one of the values with a probability proportional to its weight
+pub(crate) fn draw<T: Copy>(weights: &[(T, f64)], rng: &mut impl Rng) -> T {
+	let total: f64 = weights.iter().map(|(_, weight)| weight).sum();
+	let mut drawn: f64 = rng.gen_range(0.0..total);
+	
+	for &(value, weight) in weights {
+		if drawn < weight {
+			return value;
+		}
+		drawn -= weight;
+	}
+	
+	// rounding left a remainder, it belongs to the last value with any weight
+	return weights
+		.iter()
+		.rev()
+		.find(|(_, weight)| *weight > 0.0)
+		.map(|&(value, _)| value)
+		.unwrap();
+}
+
+/// how the ratings of an attribute are drawn, 
+/// see `AttributeSpec` for the range they are clamped to
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum RatingDistribution {
+	Uniform { min: f32, max: f32 },
+	Normal { mean: f32, std_dev: f32 }
+}
+
+impl Default for RatingDistribution {
+	fn default() -> Self {
+		return RatingDistribution::Uniform {
+			min: Individual::MIN_RATING,
+			max: Individual::MAX_RATING
+		};
+	}
+}
+
+impl std::fmt::Display for RatingDistribution {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return match self {
+			RatingDistribution::Uniform { min, max } => write!(f, "uniform:{},{}", min, max),
+			RatingDistribution::Normal { mean, std_dev } => write!(f, "normal:{},{}", mean, std_dev)
+		};
+	}
+}
+
+impl std::str::FromStr for RatingDistribution {
+	type Err = String;
+
+	/// parse `uniform:<min>,<max>` or `normal:<mean>,<std_dev>`
+	fn from_str(description: &str) -> Result<Self, Self::Err> {
+		let usage = || format!(
+			"unknown rating distribution `{}`, expected `uniform:<min>,<max>` or `normal:<mean>,<std_dev>`", 
+			description
+		);
+		
+		let (kind, parameters) = description.split_once(':').ok_or_else(usage)?;
+		let parameters: Vec<f32> = parameters
+			.split(',')
+			.map(|parameter| parameter.trim().parse::<f32>())
+			.collect::<Result<Vec<f32>, _>>()
+			.map_err(|_| usage())?;
+		
+		return match (kind, parameters.as_slice()) {
+			("uniform", &[min, max]) => Ok(RatingDistribution::Uniform { min: min, max: max }),
+			("normal", &[mean, std_dev]) => Ok(RatingDistribution::Normal { mean: mean, std_dev: std_dev }),
+			_ => Err(usage())
+		};
+	}
+}
+
+impl RatingDistribution {
+	/// the parameters must be finite, a uniform range needs `min <= max` 
+	/// and a normal distribution a non-negative standard deviation
+	pub fn validate(&self) -> Result<(), SimulationError> {
+		match *self {
+			RatingDistribution::Uniform { min, max } => {
+				if !min.is_finite() || !max.is_finite() || min > max {
+					return Err(
+						SimulationError::InvalidConfig(format!("a uniform rating distribution needs finite bounds with min <= max, got min {} and max {}", min, max))
+					);
+				}
+			},
+			RatingDistribution::Normal { mean, std_dev } => {
+				if !mean.is_finite() || !std_dev.is_finite() || std_dev < 0.0 {
+					return Err(
+						SimulationError::InvalidConfig(format!(
+							"a normal rating distribution needs a finite mean and a non-negative standard deviation, got mean {} and standard deviation {}", 
+							mean, 
+							std_dev
+						))
+					);
+				}
+			}
+		}
+		
+		return Ok(());
+	}
+	
+	/// draw one rating, panics when `RatingDistribution::validate` fails
+	pub fn sample(&self, rng: &mut impl Rng) -> f32 {
+		return match *self {
+			RatingDistribution::Uniform { min, max } => rng.gen_range(min..=max),
+			RatingDistribution::Normal { mean, std_dev } => rand_distr::Normal::new(mean, std_dev)
+				.unwrap()
+				.sample(rng)
+		};
+	}
+}
+
+/// how the preference weights of individuals without predefined ones are drawn
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum WeightDistribution {
+	// every weight uniform between 0 and 1 on its own
+	#[default]
+	Uniform,
+	// every weight uniform between `min` and `max` on its own, a negative weight
+	// dislikes high ratings of its attribute, e.g. from -1 to 1
+	UniformRange { min: f32, max: f32 },
+	// weights that sum to 1 with one concentration per attribute, the mean weights are in proportion to them.
+	// below 1 most people care overwhelmingly about a single attribute, well above 1 everyone is balanced
+	Dirichlet { concentration: Vec<f64> }
+}
+
+impl std::fmt::Display for WeightDistribution {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return match self {
+			WeightDistribution::Uniform => write!(f, "uniform"),
+			WeightDistribution::UniformRange { min, max } => write!(f, "uniform:{},{}", min, max),
+			WeightDistribution::Dirichlet { concentration } => write!(
+				f,
+				"dirichlet:{}",
+				concentration.iter().map(|alpha| alpha.to_string()).collect::<Vec<String>>().join(",")
+			)
+		};
+	}
+}
+
+impl std::str::FromStr for WeightDistribution {
+	type Err = String;
+
+	/// parse `uniform`, `uniform:<min>,<max>` or `dirichlet:<alpha>,<alpha>,...`
+	fn from_str(description: &str) -> Result<Self, Self::Err> {
+		let usage = || format!(
+			"unknown weight distribution `{}`, expected `uniform`, `uniform:<min>,<max>` or `dirichlet:<alpha>,<alpha>,...`", 
+			description
+		);
+		
+		if description == "uniform" {
+			return Ok(WeightDistribution::Uniform);
+		}
+		if let Some(range) = description.strip_prefix("uniform:") {
+			let (min, max) = range.split_once(',').ok_or_else(usage)?;
+			
+			return Ok(WeightDistribution::UniformRange {
+				min: min.trim().parse().map_err(|_| usage())?,
+				max: max.trim().parse().map_err(|_| usage())?
+			});
+		}
+		let concentration = description.strip_prefix("dirichlet:").ok_or_else(usage)?;
+		let concentration: Vec<f64> = concentration
+			.split(',')
+			.map(|alpha| alpha.trim().parse::<f64>())
+			.collect::<Result<Vec<f64>, _>>()
+			.map_err(|_| usage())?;
+		
+		return Ok(WeightDistribution::Dirichlet { concentration: concentration });
+	}
+}
+
+impl WeightDistribution {
+	/// a range needs finite bounds with `min <= max`, 
+	/// a dirichlet distribution a finite concentration above 0 for each of the `attributes`
+	pub fn validate(&self, attributes: usize) -> Result<(), SimulationError> {
+		if let WeightDistribution::UniformRange { min, max } = *self {
+			if !min.is_finite() || !max.is_finite() || min > max {
+				return Err(
+					SimulationError::InvalidConfig(format!("a uniform weight distribution needs finite bounds with min <= max, got {} to {}", min, max))
+				);
+			}
+		}
+		if let WeightDistribution::Dirichlet { concentration } = self {
+			if concentration.len() != attributes {
+				return Err(
+					SimulationError::InvalidConfig(format!("a dirichlet weight distribution needs one concentration per attribute, got {} for {}", concentration.len(), attributes))
+				);
+			}
+			if let Some(alpha) = concentration.iter().find(|alpha| !alpha.is_finite() || **alpha <= 0.0) {
+				return Err(
+					SimulationError::InvalidConfig(format!("the concentrations of a dirichlet weight distribution must be finite and above 0, got {}", alpha))
+				);
+			}
+		}
+		
+		return Ok(());
+	}
+	
+	/// draw the weights of `attributes` attributes, panics when `WeightDistribution::validate` fails
+	pub fn sample(&self, attributes: usize, rng: &mut impl Rng) -> Vec<f32> {
+		return match self {
+			WeightDistribution::Uniform => rng
+				.sample_iter(rand::distributions::Standard)
+				.take(attributes)
+				.collect(),
+			WeightDistribution::UniformRange { min, max } => rng
+				.sample_iter(rand::distributions::Standard)
+				.take(attributes)
+				.map(|weight: f32| min + (max - min) * weight)
+				.collect(),
+			// a single attribute has all of the weight, rand_distr wants at least two
+			WeightDistribution::Dirichlet { concentration } if concentration.len() == 1 => vec![1.0],
+			WeightDistribution::Dirichlet { concentration } => rand_distr::Dirichlet::new(concentration)
+				.unwrap()
+				.sample(rng)
+				.into_iter()
+				.map(|weight| weight as f32)
+				.collect()
+		};
+	}
+}
+
+/// how `Individual::new` picks `Individual::min_acceptable_score`
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum ThresholdDistribution {
+	// everyone accepts any partner
+	#[default]
+	None,
+	Uniform { min: f32, max: f32 },
+	// `factor` times the score the individual would give to themselves
+	OwnScore { factor: f32 }
+}
+
+impl std::fmt::Display for ThresholdDistribution {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return match self {
+			ThresholdDistribution::None => write!(f, "none"),
+			ThresholdDistribution::Uniform { min, max } => write!(f, "uniform:{},{}", min, max),
+			ThresholdDistribution::OwnScore { factor } => write!(f, "own-score:{}", factor)
+		};
+	}
+}
+
+impl std::str::FromStr for ThresholdDistribution {
+	type Err = String;
+
+	/// parse `none`, `uniform:<min>,<max>` or `own-score:<factor>`
+	fn from_str(description: &str) -> Result<Self, Self::Err> {
+		let usage = || format!(
+			"unknown threshold distribution `{}`, expected `none`, `uniform:<min>,<max>` or `own-score:<factor>`", 
+			description
+		);
+		
+		if description == "none" {
+			return Ok(ThresholdDistribution::None);
+		}
+		
+		let (kind, parameters) = description.split_once(':').ok_or_else(usage)?;
+		let parameters: Vec<f32> = parameters
+			.split(',')
+			.map(|parameter| parameter.trim().parse::<f32>())
+			.collect::<Result<Vec<f32>, _>>()
+			.map_err(|_| usage())?;
+		
+		return match (kind, parameters.as_slice()) {
+			("uniform", &[min, max]) => Ok(ThresholdDistribution::Uniform { min: min, max: max }),
+			("own-score", &[factor]) => Ok(ThresholdDistribution::OwnScore { factor: factor }),
+			_ => Err(usage())
+		};
+	}
+}
+
+impl ThresholdDistribution {
+	/// the parameters must be finite, a uniform range needs `min <= max`
+	pub fn validate(&self) -> Result<(), SimulationError> {
+		let valid = match *self {
+			ThresholdDistribution::None => true,
+			ThresholdDistribution::Uniform { min, max } => min.is_finite() && max.is_finite() && min <= max,
+			ThresholdDistribution::OwnScore { factor } => factor.is_finite()
+		};
+		
+		if !valid {
+			return Err(SimulationError::InvalidConfig(format!("invalid minimum acceptable score distribution `{}`", self)));
+		}
+		
+		return Ok(());
+	}
+}
+
+/// how old individuals are generated and how they age, 
+/// the default draws ages uniformly from 18 to 60 and keeps them fixed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AgeConfig {
+	// draws below 0 are clamped to 0
+	pub distribution: RatingDistribution,
+	// added to everyone's age after every round of `Sample::match_making`
+	pub step: f32,
+	// the largest age difference anyone accepts, checked like a dealbreaker, `None` accepts any
+	pub max_gap: Option<f32>
+}
+
+impl Default for AgeConfig {
+	fn default() -> Self {
+		return AgeConfig {
+			distribution: RatingDistribution::Uniform { min: 18.0, max: 60.0 },
+			step: 0.0,
+			max_gap: None
+		};
+	}
+}
+
+impl AgeConfig {
+	pub fn validate(&self) -> Result<(), SimulationError> {
+		self.distribution.validate()?;
+		
+		if !self.step.is_finite() || self.step < 0.0 {
+			return Err(SimulationError::InvalidConfig(format!("the age step must be a non-negative number, got {}", self.step)));
+		}
+		
+		if let Some(max_gap) = self.max_gap.filter(|max_gap| !max_gap.is_finite() || *max_gap < 0.0) {
+			return Err(SimulationError::InvalidConfig(format!("the maximum age gap must be a non-negative number, got {}", max_gap)));
+		}
+		
+		return Ok(());
+	}
+	
+	/// draw the age of a new individual
+	pub fn sample(&self, rng: &mut impl Rng) -> f32 {
+		return self.distribution
+			.sample(rng)
+			.max(0.0);
+	}
+}
+
+/// the identities of a blacklist in ascending order
+fn sorted(blacklist: &HashMap<PersonId, u32>) -> Vec<&PersonId> {
+	let mut entries: Vec<&PersonId> = blacklist.keys().collect();
+	entries.sort();
+	
+	return entries;
+}
+
+fn serialize_sorted<S: serde::Serializer>(
+	blacklist: &HashMap<PersonId, u32>, 
+	serializer: S
+) -> Result<S::Ok, S::Error> {
+	return serializer.collect_map(
+		sorted(blacklist)
+			.into_iter()
+			.map(|identity| (identity, blacklist[identity]))
+	);
+}
+
+/// a blacklist as a map from identity to round, or as the plain list of identities older snapshots have
+fn deserialize_blacklist<'de, D: serde::Deserializer<'de>>(
+	deserializer: D
+) -> Result<HashMap<PersonId, u32>, D::Error> {
+	#[derive(Deserialize)]
+	#[serde(untagged)]
+	enum Blacklist {
+		Rounds(HashMap<PersonId, u32>),
+		// rejections before their rounds were recorded count as round 0
+		Identities(Vec<PersonId>)
+	}
+	
+	return Ok(match Blacklist::deserialize(deserializer)? {
+		Blacklist::Rounds(blacklist) => blacklist,
+		Blacklist::Identities(identities) => identities
+			.into_iter()
+			.map(|identity| (identity, 0))
+			.collect()
+	});
+}
+
+/// what an individual only holds with some features or once someone turned them down, see `Individual::extras`.
+/// kept out of `Individual` so that a population that has not met yet does not carry an empty blacklist,
+/// history and candidate list for everyone
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndividualExtras {
+	// where each rating ranks among the population, from `Individual::MIN_RATING` for the lowest
+	// to `Individual::MAX_RATING` for the highest, see `Sample::rank_ratings`. empty until ranked
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub percentile_ratings: Vec<f32>,
+	// the index of this person's category on each categorical attribute of the schema
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub categories: Vec<usize>,
+	// the individuals that rejected this individual with the 1-based round they last did,
+	// serialized sorted by identity so the output is reproducible
+	#[serde(serialize_with = "serialize_sorted", deserialize_with = "deserialize_blacklist")]
+	pub blacklist: HashMap<PersonId, u32>, 
+	// further candidates held next to `candidate` with their scores, best first.
+	// only filled with a `MatchingConfig::candidate_capacity` above 1 and emptied by `Sample::commit_candidates`
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub provisional_candidates: Vec<(PersonId, f32)>,
+	// the minimum rating this individual demands on each attribute of the schema, `None` for no demand.
+	// anyone failing one is skipped before they are scored, an empty list demands nothing
+	#[serde(default)]
+	pub dealbreakers: Vec<Option<f32>>,
+	// 1-based round before which this individual last divorced, see `ChurnConfig::divorce_every_rounds`
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub divorced_in_round: Option<u32>,
+	// the best score someone who turned this individual down gave them in the last round,
+	// how close they came to a match. `None` when nobody did, reset at the start of every round
+	#[serde(default)]
+	pub best_rejected_score: Option<f32>,
+	// partners more than this many years older or younger are skipped like a failed dealbreaker
+	#[serde(default)]
+	pub max_age_gap: Option<f32>,
+	// every proposal this individual made or received, in order, under `MatchingConfig::record_history`
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub history: Vec<Encounter>
+}
+
+fn empty_extras() -> &'static IndividualExtras {
+	static EMPTY: OnceLock<IndividualExtras> = OnceLock::new();
+
+	return EMPTY.get_or_init(IndividualExtras::default);
+}
+
+/// the extras of an individual, `None` until one of them is set. it compares, prints and serializes
+/// as the extras themselves, so that an individual without any equals one whose extras are empty
+#[derive(Clone, Default)]
+struct Extras(Option<Box<IndividualExtras>>);
+
+impl Extras {
+	/// `None` for empty extras, so that only individuals with some allocate them
+	fn new(extras: IndividualExtras) -> Self {
+		if extras == *empty_extras() {
+			return Extras(None);
+		}
+
+		return Extras(Some(Box::new(extras)));
+	}
+
+	fn get(&self) -> &IndividualExtras {
+		return self.0.as_deref().unwrap_or_else(|| empty_extras());
+	}
+}
+
+impl PartialEq for Extras {
+	fn eq(&self, other: &Self) -> bool {
+		return self.get() == other.get();
+	}
+}
+
+impl std::fmt::Debug for Extras {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return self.get().fmt(f);
+	}
+}
+
+impl Serialize for Extras {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		return self.get().serialize(serializer);
+	}
+}
+
+impl<'de> Deserialize<'de> for Extras {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		return Ok(Extras::new(IndividualExtras::deserialize(deserializer)?));
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Individual {
+	pub identity: PersonId,
+	// a readable name for reports, drawn with the identity. names repeat, identities do not
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub display_name: Option<String>,
+	pub gender: Gender,
+	// older snapshots predate orientations, everyone in them is heterosexual
+	#[serde(default)]
+	pub orientation: Orientation,
+	// a list of floats that represents how much does this person weight on different attributes
+	pub preference_weights: Vec<f32>,
+	// a list of integers that represents how much does this person score on each attribute
+	pub ratings: Vec<f32>,
+	// a field that stores the previously accepted candidate
+	pub candidate: Option<PersonId>,
+	// a field that stores the previously accepted candidate's score
+	pub candidate_score: Option<f32>,
+	// proposals this individual scores below are turned down even while single,
+	// with `mutual_acceptance` it also does not propose below it. `None` accepts any score, negative ones too
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub min_acceptable_score: Option<f32>,
+	// 1-based round in which the current pair formed, `None` while unmatched
+	#[serde(default)]
+	pub matched_in_round: Option<u32>,
+	// proposals of this individual that were turned down, and proposals they turned down
+	#[serde(default)]
+	pub rejections_received: u32,
+	#[serde(default)]
+	pub rejections_given: u32,
+	#[serde(default)]
+	pub age: f32,
+	// 0 for everyone drawn or given, one more than the later of the two parents for offspring,
+	// see `ReproductionConfig`
+	#[serde(default)]
+	pub generation: u32,
+	// scales the scores this individual demands, its `min_acceptable_score` and the worst score it holds.
+	// 1 demands them as they are, below 1 the individual settles for less
+	#[serde(default = "default_selectivity")]
+	pub selectivity: f32,
+	// how often a proposer came across this individual and evaluated them, 
+	// in the greedy scan, deferred acceptance, parallel proposal and speed dating
+	#[serde(default)]
+	pub times_evaluated: u64,
+	// how many proposals this individual received as a reviewer, every meeting of speed dating
+	// and every pairing of the random algorithm count as one
+	#[serde(default)]
+	pub proposals_received: u32,
+	// serialized with the fields of the individual, see `Individual::extras`
+	#[serde(flatten)]
+	extras: Extras
+}
+
+/// the selectivity of snapshots from before it was kept
+fn default_selectivity() -> f32 {
+	return 1.0;
+}
+
+impl std::fmt::Display for Individual {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return write!(f, "{}", self.describe(&AttributeSchema::default()));
+	}
+}
+
+impl Individual {
+	pub const MIN_RATING: f32 = 1.0;
+	pub const MAX_RATING: f32 = 10.0;
+	
+	/// use this method to generate an individual
+	/// with one preference weight and one rating per attribute of `attribute_schema`, 
+	/// each rating drawn as the attribute specifies. 
+	/// all randomness is drawn from `rng`, so the same rng state 
+	/// always produces the same individual. 
+	/// with `specified_predefined_weights` the individual gets a copy of exactly those weights, 
+	/// so everyone drawn with the same slice shares identical weights, otherwise they are drawn from `rng`. 
+	/// fails for a schema without attributes and for predefined weights of another length
+	pub fn new(
+		attribute_schema: &AttributeSchema, 
+		specified_predefined_weights: Option<&[f32]>,
+		gender: Gender,
+		orientation: Orientation,
+		threshold_distribution: &ThresholdDistribution,
+		age_config: &AgeConfig,
+		rng: &mut impl Rng
+	) -> Result<Self, SchemaError> {
+		if attribute_schema.is_empty() {
+			return Err(SchemaError::Empty);
+		}
+		
+		let predefined_weights: Vec<f32> = match specified_predefined_weights {
+			// if the `predefined_weights` is specified, use the specified the weights
+			Some(specified_predefined_weights) => {
+				if specified_predefined_weights.len() != attribute_schema.len() {
+					return Err(SchemaError::WeightCountMismatch {
+						attributes: attribute_schema.len(),
+						weights: specified_predefined_weights.len()
+					});
+				}
+				
+				specified_predefined_weights.to_vec()
+			},
+			// generate random weights for every attribute
+			// in case if the weights are not specified. 
+			None => (&mut *rng)
+				.sample_iter(rand::distributions::Standard)
+				.take(attribute_schema.len())
+				.collect()
+		};
+			
+		let identity = uuid::Builder::from_random_bytes(rng.r#gen()).into_uuid();
+		// generate random ratings as the schema specifies
+		let ratings: Vec<f32> = attribute_schema.sample_ratings(rng);
+		
+		let dealbreakers: Vec<Option<f32>> = if attribute_schema.has_dealbreakers() {
+			attribute_schema.attributes
+				.iter()
+				.map(|attribute| attribute.sample_dealbreaker(rng))
+				.collect()
+		} else {
+			Vec::new()
+		};
+		let categories: Vec<usize> = attribute_schema.sample_categories(rng);
+		
+		let mut individual = Individual {
+			identity: PersonId::from_uuid(identity), 
+			display_name: Some(names::name_for(identity.as_bytes())),
+			gender: gender, 
+			orientation: orientation,
+			preference_weights: predefined_weights,
+			ratings: ratings,
+			candidate: None,
+			candidate_score: None,
+			min_acceptable_score: None,
+			matched_in_round: None,
+			rejections_received: 0,
+			rejections_given: 0,
+			age: age_config.sample(rng),
+			generation: 0,
+			selectivity: 1.0,
+			times_evaluated: 0,
+			proposals_received: 0,
+			extras: Extras::new(IndividualExtras {
+				categories: categories,
+				dealbreakers: dealbreakers,
+				max_age_gap: age_config.max_gap,
+				..IndividualExtras::default()
+			})
+		};
+		
+		individual.min_acceptable_score = match *threshold_distribution {
+			ThresholdDistribution::None => None,
+			ThresholdDistribution::Uniform { min, max } => Some(rng.gen_range(min..=max)),
+			// both sides of the score have the same attributes, it cannot fail
+			ThresholdDistribution::OwnScore { factor } => Some(factor * individual.score(&individual).unwrap_or_default())
+		};
+		
+		return Ok(individual);
+	}
+	
+	/// the first 8 characters of the identity, see `PersonId::short_id`
+	pub fn short_id(&self) -> IdentityStr {
+		return self.identity.short_id();
+	}
+	
+	/// the blacklist, history and the rest of what only some individuals hold, empty ones for those without
+	pub fn extras(&self) -> &IndividualExtras {
+		return self.extras.get();
+	}
+	
+	/// the extras to change, allocated on the first call
+	pub fn extras_mut(&mut self) -> &mut IndividualExtras {
+		return self.extras.0.get_or_insert_with(Box::default);
+	}
+	
+	/// the extras to change if the individual has any, for what would only empty them
+	pub(crate) fn extras_if_any(&mut self) -> Option<&mut IndividualExtras> {
+		return self.extras.0.as_deref_mut();
+	}
+	
+	/// whether the extras were allocated, see `MemoryReport`
+	pub(crate) fn has_extras(&self) -> bool {
+		return self.extras.0.is_some();
+	}
+	
+	/// how reports name the individual: "Alice Gray (3f2a91bc)", the short id alone without a name, 
+	/// or the full identity with `full_ids` for output read by programs
+	pub fn label(&self, full_ids: bool) -> String {
+		if full_ids {
+			return self.identity.to_string();
+		}
+		
+		return match &self.display_name {
+			Some(name) => format!("{} ({})", name, self.short_id()),
+			None => self.short_id().to_string()
+		};
+	}
+	
+	/// the `Display` of the individual with weights and ratings labelled by attribute name
+	pub fn describe(&self, attribute_schema: &AttributeSchema) -> String {
+		let mut description = String::new();
+		
+		description.push_str(&format!("Identity: {}, {:#?}, {:#?}\n", self.identity, self.gender, self.orientation));
+		if let Some(name) = &self.display_name {
+			description.push_str(&format!("Name: {}\n", name));
+		}
+		description.push_str(&format!("Age: {:.1}\n", self.age));
+		description.push_str(&format!("Preference Weights: {}\n", attribute_schema.label(&self.preference_weights)));
+		description.push_str(&format!("Ratings: {}\n", attribute_schema.label(&self.ratings)));
+		let extras = self.extras();
+		if !extras.categories.is_empty() {
+			description.push_str(&format!("Categories: {}\n", attribute_schema.label_categories(&extras.categories)));
+		}
+		description.push_str(&format!("Blacklist: {:?}\n", sorted(&extras.blacklist)));
+		description.push_str(&format!("Candidate: {:?}\n", self.candidate));
+		description.push_str(&format!("Candidate Score: {:?}\n", self.candidate_score));
+		if !extras.provisional_candidates.is_empty() {
+			description.push_str(&format!("Provisional Candidates: {:?}\n", extras.provisional_candidates));
+		}
+		if let Some(round) = self.matched_in_round {
+			description.push_str(&format!("Matched In Round: {}\n", round));
+		}
+		description.push_str(&format!("Rejections Received: {}, Given: {}\n", self.rejections_received, self.rejections_given));
+		if let Some(score) = extras.best_rejected_score {
+			description.push_str(&format!("Best Rejected Score: {:.3}\n", score));
+		}
+		if let Some(score) = self.min_acceptable_score {
+			description.push_str(&format!("Minimum Acceptable Score: {}\n", score));
+		}
+		let dealbreakers: Vec<String> = extras.dealbreakers
+			.iter()
+			.enumerate()
+			.filter_map(|(position, dealbreaker)| {
+				dealbreaker.map(|minimum| format!("{} >= {:.2}", attribute_schema.name(position), minimum))
+			})
+			.collect();
+		if !dealbreakers.is_empty() {
+			description.push_str(&format!("Dealbreakers: {}\n", dealbreakers.join(", ")));
+		}
+		
+		return description;
+	}
+	
+	/// how appealing this individual is to the average member of the population, 
+	/// their ratings weighted by `mean_weights`, the population-average preference weights
+	pub fn desirability(&self, mean_weights: &[f32]) -> f32 {
+		return self.ratings
+			.iter()
+			.zip(mean_weights)
+			.map(|(rating, weight)| rating * weight)
+			.sum();
+	}
+	
+	/// whether `other` meets every dealbreaker of this individual, including their maximum age gap
+	pub fn accepts(&self, other: &Individual) -> bool {
+		let extras = self.extras();
+		if extras.max_age_gap.is_some_and(|max_gap| (self.age - other.age).abs() > max_gap) {
+			return false;
+		}
+		
+		return extras.dealbreakers
+			.iter()
+			.zip(&other.ratings)
+			.all(|(dealbreaker, &rating)| dealbreaker.is_none_or(|minimum| rating >= minimum));
+	}
+
+	/// whether the two are attracted to each other's gender
+	pub fn compatible_with(&self, other: &Individual) -> bool {
+		return self.orientation.attracted_to(self.gender, other.gender) && 
+			other.orientation.attracted_to(other.gender, self.gender);
+	}
+	
+	/// every candidate held with its score, `candidate` first and then the provisional ones
+	pub fn held_candidates(&self) -> impl Iterator<Item = (PersonId, f32)> + '_ {
+		let primary = self.candidate
+			.map(|candidate| (candidate, self.candidate_score.unwrap_or_default()));
+		
+		return primary.into_iter().chain(
+			self.extras().provisional_candidates.iter().copied()
+		);
+	}
+	
+	/// whether `identity` is among the held candidates
+	pub fn holds(&self, identity: PersonId) -> bool {
+		return self.held_candidates().any(|(candidate, _)| candidate == identity);
+	}
+	
+	/// the lowest score among the held candidates once `capacity` of them are held, `None` while there is room
+	pub fn worst_held_score(&self, capacity: usize) -> Option<f32> {
+		if self.held_candidates().count() < capacity {
+			return None;
+		}
+		
+		return self.held_candidates().last().map(|(_, score)| score);
+	}
+	
+	/// hold `identity` with `score` next to the current candidates, keeping at most `capacity` of them best first. 
+	/// a newcomer goes ahead of the ones it ties with, returns the candidate that no longer fits
+	pub(crate) fn hold(&mut self, identity: PersonId, score: f32, capacity: usize) -> Option<PersonId> {
+		let mut held: Vec<(PersonId, f32)> = vec![(identity, score)];
+		held.extend(
+			self.held_candidates().filter(|(candidate, _)| *candidate != identity)
+		);
+		held.sort_by(|first, second| second.1.total_cmp(&first.1));
+		
+		let evicted = if held.len() > capacity.max(1) {
+			held.pop().map(|(candidate, _)| candidate)
+		} else {
+			None
+		};
+		
+		let mut held = held.into_iter();
+		let (candidate, candidate_score) = held.next().unzip();
+		self.candidate = candidate;
+		self.candidate_score = candidate_score;
+		let provisional_candidates: Vec<(PersonId, f32)> = held.collect();
+		// with a capacity of 1 nobody needs the extras for it
+		if let Some(extras) = self.extras_if_any() {
+			extras.provisional_candidates = provisional_candidates;
+		} else if !provisional_candidates.is_empty() {
+			self.extras_mut().provisional_candidates = provisional_candidates;
+		}
+		
+		return evicted;
+	}
+	
+	/// stop holding `identity`, the best provisional candidate moves up if it was `candidate`. 
+	/// returns whether `identity` was held
+	pub(crate) fn drop_candidate(&mut self, identity: PersonId) -> bool {
+		if self.candidate == Some(identity) {
+			let next = self.extras_if_any()
+				.filter(|extras| !extras.provisional_candidates.is_empty())
+				.map(|extras| extras.provisional_candidates.remove(0));
+			(self.candidate, self.candidate_score) = next.unzip();
+			
+			return true;
+		}
+		
+		let Some(extras) = self.extras_if_any() else {
+			return false;
+		};
+		let held = extras.provisional_candidates.len();
+		extras.provisional_candidates.retain(|(candidate, _)| *candidate != identity);
+		
+		return extras.provisional_candidates.len() != held;
+	}
+	
+	/// start building an individual from fixed values, see `IndividualBuilder`
+	pub fn builder() -> IndividualBuilder {
+		return IndividualBuilder::new();
+	}
+	
+	/// the lowest score this individual accepts while single, its `min_acceptable_score` scaled by its `selectivity`,
+	/// negative infinity without one
+	pub fn demanded_score(&self) -> f32 {
+		return self.min_acceptable_score.map_or(f32::NEG_INFINITY, |score| self.selective(score));
+	}
+	
+	/// what this individual demands instead of `score` by its `selectivity`: a score above 0 is multiplied 
+	/// by it and one below 0 divided by it, so that a higher selectivity demands more either way
+	pub fn selective(&self, score: f32) -> f32 {
+		return match score < 0.0 {
+			true => score / self.selectivity,
+			false => self.selectivity * score
+		};
+	}
+	
+	/// whether `score` falls short of what this individual demands next to what it holds
+	/// with `capacity`, the worst held score scaled by its `selectivity`
+	pub fn turns_down(&self, score: f32, capacity: usize) -> bool {
+		return score < self.demanded_score() ||
+			self.worst_held_score(capacity).is_some_and(|worst_score| score < self.selective(worst_score));
+	}
+	
+	/// how far `score` falls below what `Individual::turns_down` asks of it, 0 when it does not
+	pub fn shortfall(&self, score: f32, capacity: usize) -> f32 {
+		let demanded = self.worst_held_score(capacity)
+			.map_or(self.demanded_score(), |worst_score| self.demanded_score().max(self.selective(worst_score)));
+		
+		return (demanded - score).max(0.0);
+	}
+	
+	/// whether `identity` is on the blacklist in the 1-based `round`, entries older than 
+	/// `duration` rounds have expired. without a duration they never do
+	pub fn has_blacklisted(&self, identity: PersonId, round: u32, duration: Option<u32>) -> bool {
+		return self.extras().blacklist
+			.get(&identity)
+			.is_some_and(|&added| duration.is_none_or(|duration| round < added.saturating_add(duration)));
+	}
+	
+	/// calculate the score of this individual to the other with `WeightedSum`, 
+	/// the matching itself goes through the sample's `ScoringStrategy`
+	pub fn score(
+		&self, 
+		matcher: &Individual
+	) -> Result<f32, ScoreError> {
+		return WeightedSum.score(self, matcher);
+	}
+	
+	/// what every attribute adds to `Individual::score`, its position with this individual's weight 
+	/// times the other's rating, in attribute order
+	pub fn score_breakdown(
+		&self, 
+		matcher: &Individual
+	) -> Result<Vec<(usize, f32)>, ScoreError> {
+		return Ok(
+			scoring::weighted_ratings(self, matcher, &matcher.ratings)?
+				.map(|(weight, rating)| weight * rating)
+				.enumerate()
+				.collect()
+		);
+	}
+	
+	/// the position of the attribute that adds the most to `Individual::score`, 
+	/// `None` without attributes
+	pub fn top_attribute(
+		&self, 
+		matcher: &Individual
+	) -> Result<Option<usize>, ScoreError> {
+		return Ok(
+			self.score_breakdown(matcher)?
+				.into_iter()
+				.max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)))
+				.map(|(position, _)| position)
+		);
+	}
+	
+}
+
+/// build an individual from fixed values instead of drawing them, e.g. for tests.
+/// whatever is not set stays deterministic: identity `individual`, a heterosexual male aged 30
+/// without matching state, thresholds, dealbreakers or categories. when only the weights or only the ratings
+/// are set the other side gets weights of 1 or ratings of `Individual::MIN_RATING` of the same length,
+/// with neither there is one attribute
+#[derive(Debug, Clone)]
+pub struct IndividualBuilder {
+	identity: Option<PersonId>,
+	display_name: Option<String>,
+	gender: Gender,
+	orientation: Orientation,
+	preference_weights: Option<Vec<f32>>,
+	ratings: Option<Vec<f32>>,
+	categories: Vec<usize>,
+	blacklist: HashMap<PersonId, u32>,
+	candidate: Option<(PersonId, f32)>,
+	min_acceptable_score: Option<f32>,
+	age: Option<f32>
+}
+
+impl Default for IndividualBuilder {
+	fn default() -> Self {
+		return IndividualBuilder {
+			identity: None,
+			display_name: None,
+			gender: Gender::Male,
+			orientation: Orientation::default(),
+			preference_weights: None,
+			ratings: None,
+			categories: Vec::new(),
+			blacklist: HashMap::new(),
+			candidate: None,
+			min_acceptable_score: None,
+			age: None
+		};
+	}
+}
+
+impl IndividualBuilder {
+	pub fn new() -> Self {
+		return IndividualBuilder::default();
+	}
+
+	pub fn identity(mut self, identity: &str) -> Self {
+		self.identity = Some(PersonId::new(identity));
+
+		return self;
+	}
+
+	pub fn display_name(mut self, display_name: &str) -> Self {
+		self.display_name = Some(display_name.to_string());
+
+		return self;
+	}
+
+	pub fn gender(mut self, gender: Gender) -> Self {
+		self.gender = gender;
+
+		return self;
+	}
+
+	pub fn orientation(mut self, orientation: Orientation) -> Self {
+		self.orientation = orientation;
+
+		return self;
+	}
+
+	pub fn preference_weights(mut self, preference_weights: Vec<f32>) -> Self {
+		self.preference_weights = Some(preference_weights);
+
+		return self;
+	}
+
+	pub fn ratings(mut self, ratings: Vec<f32>) -> Self {
+		self.ratings = Some(ratings);
+
+		return self;
+	}
+
+	/// the index of the category on every categorical attribute
+	pub fn categories(mut self, categories: Vec<usize>) -> Self {
+		self.categories = categories;
+
+		return self;
+	}
+
+	/// the identities that rejected this individual, as of round 0
+	pub fn blacklist<'a>(mut self, identities: impl IntoIterator<Item = &'a str>) -> Self {
+		self.blacklist = identities
+			.into_iter()
+			.map(|identity| (PersonId::new(identity), 0))
+			.collect();
+
+		return self;
+	}
+
+	/// point the individual at `candidate`, holding `score`
+	pub fn candidate(mut self, candidate: &str, score: f32) -> Self {
+		self.candidate = Some((PersonId::new(candidate), score));
+
+		return self;
+	}
+
+	pub fn min_acceptable_score(mut self, min_acceptable_score: f32) -> Self {
+		self.min_acceptable_score = Some(min_acceptable_score);
+
+		return self;
+	}
+
+	pub fn age(mut self, age: f32) -> Self {
+		self.age = Some(age);
+
+		return self;
+	}
+
+	/// fails when the weights and ratings were both set with different lengths
+	pub fn build(self) -> Result<Individual, SchemaError> {
+		let (preference_weights, ratings) = match (self.preference_weights, self.ratings) {
+			(Some(preference_weights), Some(ratings)) => {
+				if preference_weights.len() != ratings.len() {
+					return Err(SchemaError::WeightCountMismatch {
+						attributes: ratings.len(),
+						weights: preference_weights.len()
+					});
+				}
+
+				(preference_weights, ratings)
+			},
+			(Some(preference_weights), None) => {
+				let ratings = vec![Individual::MIN_RATING; preference_weights.len()];
+				(preference_weights, ratings)
+			},
+			(None, Some(ratings)) => (vec![1.0; ratings.len()], ratings),
+			(None, None) => (vec![1.0], vec![Individual::MIN_RATING])
+		};
+		let (candidate, candidate_score) = match self.candidate {
+			Some((candidate, score)) => (Some(candidate), Some(score)),
+			None => (None, None)
+		};
+
+		return Ok(Individual {
+			identity: self.identity.unwrap_or_else(|| PersonId::new("individual")),
+			display_name: self.display_name,
+			gender: self.gender,
+			orientation: self.orientation,
+			preference_weights: preference_weights,
+			ratings: ratings,
+			candidate: candidate,
+			candidate_score: candidate_score,
+			min_acceptable_score: self.min_acceptable_score,
+			matched_in_round: None,
+			rejections_received: 0,
+			rejections_given: 0,
+			age: self.age.unwrap_or(30.0),
+			generation: 0,
+			selectivity: 1.0,
+			times_evaluated: 0,
+			proposals_received: 0,
+			extras: Extras::new(IndividualExtras {
+				categories: self.categories,
+				blacklist: self.blacklist,
+				..IndividualExtras::default()
+			})
+		});
+	}
+}
@@ -0,0 +1,86 @@
+#![allow(clippy::needless_return, clippy::redundant_field_names)]
+
+mod assignment;
+mod attributes;
+mod cancellation;
+mod config;
+#[cfg(feature = "dashboard")]
+mod dashboard;
+mod diff;
+mod drift;
+mod encounter;
+mod error;
+mod event;
+mod graph;
+mod individual;
+mod matching;
+mod memory;
+mod metadata;
+mod monte_carlo;
+mod names;
+#[cfg(feature = "arrow")]
+mod parquet;
+mod person_id;
+#[cfg(feature = "plots")]
+mod plot;
+mod preset;
+mod progress;
+/// a c interface to `Sample` for the `python/dating_simulation` module, which loads the library with ctypes.
+/// configs and results cross it as json, every string it returns is freed with `dating_simulation_string_free`.
+/// ctypes lets go of the gil for every call, so long rounds leave other python threads running
+#[cfg(feature = "python")]
+pub mod python;
+mod report;
+mod rng;
+mod sample;
+mod score_cache;
+mod scoring;
+#[cfg(feature = "server")]
+mod server;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod statistics;
+#[cfg(feature = "fs")]
+mod stream;
+mod sweep;
+/// proptest strategies of individuals and samples that hold together: distinct uuids,
+/// as many weights as ratings and the same attributes for everyone of a population
+#[cfg(feature = "testing")]
+pub mod testing;
+mod validation;
+/// `WasmSample`, the bindings of a browser demo: `wasm-pack build --no-default-features --features wasm`
+/// gives a module with `new WasmSample(config_json)`, `step()` and `pairs_json()`
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use attributes::{AttributeSchema, AttributeSpec, CategoricalAttribute, CategoryPreference, CorrelationMatrix, SchemaError};
+pub use cancellation::CancellationToken;
+pub use config::{SampleConfig, SampleConfigBuilder, SimulationConfig};
+#[cfg(feature = "dashboard")]
+pub use dashboard::Dashboard;
+pub use diff::{diff_runs, PartnerChange, RunDiff, RunExport, StatisticDelta};
+pub use drift::{NoOp, PreferenceDrift, PreferenceUpdate};
+pub use encounter::{Encounter, EncounterOutcome, Encounters};
+pub use error::SimulationError;
+pub use event::{EventSink, JsonLinesSink, NoEvents, SimEvent};
+pub use graph::{GraphFormat, GraphKind};
+pub use individual::{AgeConfig, Gender, GenderCounts, GenderDistribution, Individual, IndividualBuilder, IndividualExtras, Orientation, OrientationDistribution, RatingDistribution, ThresholdDistribution, WeightDistribution};
+pub use matching::{MatchPair, MatchingAlgorithm, MatchingConfig, ProposerSide};
+pub use memory::MemoryReport;
+pub use metadata::RunMetadata;
+pub use monte_carlo::{MonteCarlo, MonteCarloReport, MonteCarloRun};
+#[cfg(feature = "arrow")]
+pub use parquet::{ParquetColumn, ParquetTable};
+pub use person_id::{IdentityStr, PersonId};
+pub use preset::Preset;
+pub use report::Report;
+pub use sample::{ChurnConfig, DisplayFilter, DisplayOptions, GenerationParameters, Juvenile, PairOrder, ReproductionConfig, Sample, SelectivityConfig};
+pub use score_cache::{ScoreCache, ScoreCacheMode};
+pub use scoring::{CategoricalScoring, EuclideanSimilarity, MinAttribute, PercentileScoring, RatingValues, ScoreError, ScoreNoise, Scoring, ScoringStrategy, WeightedSum};
+#[cfg(feature = "server")]
+pub use server::{HttpRequest, HttpResponse, SimulationServer};
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteSink;
+pub use statistics::{gini, pearson_correlation, AggregateStatistics, DecileStats, Desirability, DesirabilityReport, Estimate, GenderStats, GenerationStats, GroupCounts, MeanRejections, RoundDelta, RoundStats, RunReport, ScoreDistribution, Statistics, StopReason};
+pub use sweep::{SweepPoint, SweepResult, SweepRunner};
+pub use validation::ValidationError;
@@ -1,423 +1,1102 @@
-use rand::prelude::*;
-use uuid::Uuid;
+#![allow(clippy::needless_return, clippy::redundant_field_names)]
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub enum Gender {
-	Male,
-	Female
+use clap::{CommandFactory, Parser};
+use std::io::Write;
+
+use dating_simulation::{diff_runs, CancellationToken, Dashboard, Encounters, EventSink, GenderCounts, GenderDistribution, GraphFormat, GraphKind, JsonLinesSink, MatchingAlgorithm, MonteCarlo, MonteCarloRun, OrientationDistribution, Preset, ProposerSide, RatingDistribution, RatingValues, RoundStats, RunExport, Sample, SampleConfig, ScoreCacheMode, ScoreNoise, Scoring, SimulationConfig, SimulationError, StopReason, SweepResult, SweepRunner, ThresholdDistribution, WeightDistribution};
+use rand::Rng;
+use tracing_subscriber::filter::LevelFilter;
+
+/// the flags that make up the `SimulationConfig`, shared by a single run and `monte-carlo`
+#[derive(Debug, clap::Args)]
+struct SimulationArgs {
+	/// toml file with the simulation parameters,
+	/// flags given on the command line take precedence over it
+	#[arg(long)]
+	config: Option<std::path::PathBuf>,
+
+	/// named attributes, rating distributions and preference weights to start from,
+	/// replacing those of the config file, the other flags still apply on top of it
+	#[arg(long, value_parser = preset_parser())]
+	preset: Option<Preset>,
+
+	/// number of individuals to generate [default: 10000]
+	#[arg(long, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+	population: Option<usize>,
+
+	/// number of anonymous attributes each individual rates and weights,
+	/// ignored when the config lists named attributes [default: 3]
+	#[arg(long, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+	complexity: Option<usize>,
+
+	/// number of match-making rounds to run [default: 100]
+	#[arg(long)]
+	rounds: Option<u32>,
+
+	/// stop early once the pairs did not change for this many rounds in a row
+	#[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+	converge_after: Option<u32>,
+
+	/// comma separated preference weights shared by every individual,
+	/// one per attribute, e.g. `0.7,0.2,0.1`
+	#[arg(long, value_delimiter = ',', num_args = 1..)]
+	weights: Option<Vec<f32>>,
+
+	/// comma separated relative weights of males, females and non-binary individuals,
+	/// e.g. `60,40` or `48,48,4` [default: 1,1,0]
+	#[arg(long, value_delimiter = ',', num_args = 1..)]
+	gender_ratio: Option<Vec<f64>>,
+
+	/// exactly this many males and females, comma separated, e.g. `4000,6000`.
+	/// the population is the two together unless `--population` says the same
+	#[arg(long, value_delimiter = ',', num_args = 1..)]
+	gender_counts: Option<Vec<usize>>,
+
+	/// comma separated relative weights of heterosexual, homosexual and bisexual individuals,
+	/// e.g. `90,5,5` [default: 1,0,0]
+	#[arg(long, value_delimiter = ',', num_args = 1..)]
+	orientation_ratio: Option<Vec<f64>>,
+
+	/// how ratings are drawn: `uniform:<min>,<max>` or `normal:<mean>,<std_dev>`,
+	/// normal draws are clamped to 1..=10 [default: uniform:1,10]
+	#[arg(long)]
+	rating_distribution: Option<RatingDistribution>,
+
+	/// how the weights of everyone are drawn without `--weights`: `uniform` between 0 and 1, `uniform:<min>,<max>`,
+	/// e.g. `uniform:-1,1` for dislikes, or `dirichlet:<alpha>,<alpha>,...` with one concentration per attribute,
+	/// giving weights that sum to 1 [default: uniform]
+	#[arg(long)]
+	weight_distribution: Option<WeightDistribution>,
+
+	/// chance that an individual demands a minimum rating on each attribute,
+	/// anyone below it is skipped without being scored [default: 0]
+	#[arg(long)]
+	dealbreaker_probability: Option<f64>,
+
+	/// how ages are drawn: `uniform:<min>,<max>` or `normal:<mean>,<std_dev>` [default: uniform:18,60]
+	#[arg(long)]
+	age_distribution: Option<RatingDistribution>,
+
+	/// years everyone ages after each round [default: 0]
+	#[arg(long)]
+	age_step: Option<f32>,
+
+	/// the largest age difference anyone accepts in a partner
+	#[arg(long)]
+	max_age_gap: Option<f32>,
+
+	/// new individuals that join the pool before every round [default: 0]
+	#[arg(long)]
+	arrivals_per_round: Option<u64>,
+
+	/// chance that a matched pair leaves the pool after a round [default: 0]
+	#[arg(long)]
+	exit_probability: Option<f32>,
+
+	/// chance that a matched pair breaks up before a round [default: 0]
+	#[arg(long)]
+	breakup_probability: Option<f32>,
+
+	/// pairs that score each other below the mean break up more often, above it less
+	#[arg(long)]
+	breakup_scaled_by_score: bool,
+
+	/// the two who broke up blacklist each other
+	#[arg(long)]
+	blacklist_on_breakup: bool,
+
+	/// after every this many rounds the matched look at their partner again and divorce
+	/// below `--divorce-below` [default: never]
+	#[arg(long)]
+	divorce_every: Option<u32>,
+
+	/// a pair divorces when either scores the other below this fraction of the mean candidate score [default: 0]
+	#[arg(long)]
+	divorce_below: Option<f32>,
+
+	/// the two who divorced blacklist each other
+	#[arg(long)]
+	blacklist_on_divorce: bool,
+
+	/// the selectivity everyone starts with, it scales the scores they demand [default: 1]
+	#[arg(long)]
+	selectivity: Option<f32>,
+
+	/// what the selectivity of the unmatched is multiplied by after every round [default: 1]
+	#[arg(long)]
+	selectivity_decay: Option<f32>,
+
+	/// the selectivity never decays below it [default: 0]
+	#[arg(long)]
+	selectivity_floor: Option<f32>,
+
+	/// standard deviation of the random step every preference weight takes after a round [default: 0]
+	#[arg(long)]
+	drift_random_walk: Option<f32>,
+
+	/// how far the weights of those turned down move toward the ratings of who turned them down,
+	/// from 0 to 1, after every round [default: 0]
+	#[arg(long)]
+	drift_toward_rejecters: Option<f32>,
+
+	/// scale the weights to add up to 1 after they drifted
+	#[arg(long)]
+	drift_renormalize: bool,
+
+	/// children every matched pair has after every `--birth-every` rounds [default: 0]
+	#[arg(long)]
+	births_per_pair: Option<u32>,
+
+	/// rounds between the births of a pair [default: 1]
+	#[arg(long)]
+	birth_every: Option<u32>,
+
+	/// standard deviation of the noise on the ratings and weights children inherit [default: 0]
+	#[arg(long)]
+	mutation_std_dev: Option<f32>,
+
+	/// rounds a child waits before it joins the pool [default: 0]
+	#[arg(long)]
+	maturity_rounds: Option<u32>,
+
+	/// the score below which individuals turn everyone down, even while single:
+	/// `none`, `uniform:<min>,<max>` or `own-score:<factor>`, a factor of what
+	/// they would score themselves [default: none]
+	#[arg(long)]
+	min_score: Option<ThresholdDistribution>,
+
+	/// how the population is paired:
+	/// `greedy`, `deferred-acceptance`, `parallel-proposal`, `random`, `speed-dating` or `optimal` [default: greedy]
+	#[arg(long)]
+	algorithm: Option<MatchingAlgorithm>,
+
+	/// how individuals score each other:
+	/// `weighted-sum`, `euclidean-similarity` or `min-attribute` [default: weighted-sum]
+	#[arg(long)]
+	scoring: Option<Scoring>,
+
+	/// what the scoring reads of the rated individual: `raw` ratings or `percentile`,
+	/// where each rating ranks among the population on the same 1 to 10 scale [default: raw]
+	#[arg(long)]
+	rating_values: Option<RatingValues>,
+
+	/// proposers also score the reviewers and only propose to ones they prefer
+	/// over their current candidate
+	#[arg(long)]
+	mutual: bool,
+
+	/// a rejected proposer also lands on the blacklist of the reviewer who rejected them
+	#[arg(long)]
+	symmetric_blacklist: bool,
+
+	/// the rounds a rejection keeps the two apart, counting the round it happened in [default: for good]
+	#[arg(long)]
+	blacklist_duration: Option<u32>,
+
+	/// the error on every score of a round, `none` or `gaussian:<std-dev>`.
+	/// rejections within one standard deviation do not blacklist [default: none]
+	#[arg(long)]
+	score_noise: Option<ScoreNoise>,
+
+	/// scores less than this apart are a tie, the candidate a reviewer holds stays and nobody is blacklisted [default: 0]
+	#[arg(long)]
+	score_epsilon: Option<f32>,
+
+	/// how many reviewers every proposer scores per round, drawn at random besides their current candidate.
+	/// needs the greedy or the parallel-proposal algorithm [default: everyone]
+	#[arg(long)]
+	max_evaluations: Option<usize>,
+
+	/// who the proposers come across under `--max-evaluations`: `uniform`, or `desirability`
+	/// for the desirable ones more often [default: uniform]
+	#[arg(long)]
+	encounters: Option<Encounters>,
+
+	/// keep every proposal everyone made or received in their history, written with `--json`.
+	/// it grows with every pair of individuals and round
+	#[arg(long)]
+	record_history: bool,
+
+	/// the gender that proposes: `male`, `female` or `alternate` between rounds
+	#[arg(long)]
+	proposer_side: Option<ProposerSide>,
+
+	/// let the proposers take their turns in a new random order every round
+	#[arg(long)]
+	shuffle_proposers: bool,
+
+	/// let every proposer of the greedy scan walk the reviewers in their own random order
+	#[arg(long)]
+	shuffle_reviewers: bool,
+
+	/// keep scores between rounds: `off`, `lazy` (kept when first computed) or `eager` (all computed up-front),
+	/// populations above 5000 score without a cache
+	#[arg(long)]
+	score_cache: Option<ScoreCacheMode>,
+
+	/// how many candidates everyone may hold at once in the greedy scan, the best mutual one is kept at the end
+	#[arg(long)]
+	candidate_capacity: Option<usize>,
+
+	/// leave the matched out of the greedy scan, only those without a partner propose in later rounds
+	#[arg(long)]
+	only_unmatched: bool,
+
+	/// check the consistency of the sample after every round (debug builds only)
+	#[arg(long)]
+	validate: bool,
+
+	/// seed for the random generator, reuse it to reproduce a run
+	#[arg(long)]
+	seed: Option<u64>
 }
 
-impl Gender {
-	fn new() -> Self {
-		let mut rng = rand::thread_rng();
-		let genders = [Gender::Male, Gender::Female];
-		
-		return genders.choose(&mut rng).unwrap().clone();
-	}
+/// a virtual social experiment on dating
+#[derive(Debug, Parser)]
+#[command(name = "dating-simulation", version, about, allow_negative_numbers = true, args_conflicts_with_subcommands = true)]
+struct Cli {
+	#[command(subcommand)]
+	command: Option<Command>,
+
+	#[command(flatten)]
+	simulation: SimulationArgs,
+
+	/// continue simulating a population saved with `--json` instead of generating one,
+	/// the population parameters of the config are ignored in that case
+	#[arg(long)]
+	load: Option<std::path::PathBuf>,
+
+	/// continue a run from a `--checkpoint` file up to `--rounds` rounds in total,
+	/// the configuration saved with it is kept and the other simulation flags are ignored
+	#[arg(long, conflicts_with = "load")]
+	resume: Option<std::path::PathBuf>,
+
+	/// write the full state of the run to this file every `--checkpoint-every` rounds, see `--resume`
+	#[arg(long)]
+	checkpoint: Option<std::path::PathBuf>,
+
+	/// rounds between two checkpoints [default: 1]
+	#[arg(long, requires = "checkpoint", value_parser = clap::value_parser!(u32).range(1..))]
+	checkpoint_every: Option<u32>,
+
+	/// write the final population and matching state as json to this file
+	#[arg(long)]
+	json: Option<std::path::PathBuf>,
+
+	/// write the final matched pairs as csv to this file
+	#[arg(long)]
+	matches_csv: Option<std::path::PathBuf>,
+
+	/// also list unmatched individuals in the `--matches-csv` output
+	#[arg(long, requires = "matches_csv")]
+	include_unmatched: bool,
+
+	/// write the final population as a graph to this file, see `--graph-kind` and `--graph-format`
+	#[arg(long)]
+	graph: Option<std::path::PathBuf>,
+
+	/// the edges of the `--graph` output: `matches` or `blacklist`
+	#[arg(long, default_value_t = GraphKind::Matches, requires = "graph")]
+	graph_kind: GraphKind,
+
+	/// the file format of the `--graph` output: `dot` or `graphml`
+	#[arg(long, default_value_t = GraphFormat::Dot, requires = "graph")]
+	graph_format: GraphFormat,
+
+	/// draw at most this many individuals in the `--graph` output so that it stays renderable
+	#[arg(long, requires = "graph")]
+	graph_max_nodes: Option<usize>,
+
+	/// write the final statistics, score histograms included, as json to this file
+	#[arg(long)]
+	statistics_json: Option<std::path::PathBuf>,
+
+	/// write a self-contained html report of the run to this file: the statistics, the match rate of every round,
+	/// the score histograms and the top pairs
+	#[arg(long)]
+	report: Option<std::path::PathBuf>,
+
+	/// draw the match rate by gender and the mean candidate score over the rounds as svg into this directory
+	#[cfg(feature = "plots")]
+	#[arg(long)]
+	plot: Option<std::path::PathBuf>,
+
+	/// write the final pairs as parquet to this file, the retired ones included
+	#[cfg(feature = "arrow")]
+	#[arg(long)]
+	pairs_parquet: Option<std::path::PathBuf>,
+
+	/// write the stats of every round as parquet to this file once the run ends
+	#[cfg(feature = "arrow")]
+	#[arg(long)]
+	round_stats_parquet: Option<std::path::PathBuf>,
+
+	/// write the stats of every round as csv to this file
+	#[arg(long)]
+	round_stats_csv: Option<std::path::PathBuf>,
+
+	/// stream every event of the run to this file as json lines: the start of every round, its proposals,
+	/// rejections, the pairs that formed and broke and the stats of the round, flushed after every round
+	#[arg(long)]
+	events: Option<std::path::PathBuf>,
+
+	/// write the population, the pairs after every round and the stats of every round to this sqlite database
+	/// as a new run, the tables are made if they are missing
+	#[cfg(feature = "sqlite")]
+	#[arg(long)]
+	sqlite: Option<std::path::PathBuf>,
+
+	/// follow the run on a live dashboard on stderr rather than printing the statistics of every round:
+	/// the match rate of every gender, the mean candidate score over the rounds and the notable events.
+	/// without a terminal the statistics are printed as usual
+	#[arg(long)]
+	tui: bool,
+
+	/// print the effective configuration as toml before running
+	#[arg(long, global = true)]
+	print_config: bool,
+
+	/// do not print the statistics after every round
+	#[arg(long)]
+	quiet: bool,
+
+	/// count the blocking pairs in the statistics printed every round and in `--statistics-json`.
+	/// every pair is scored, larger populations than 2000 get an estimate from a million of them
+	#[arg(long)]
+	blocking_pairs: bool,
+
+	/// print a histogram of the candidate scores per gender after the last round
+	#[arg(long)]
+	histogram: bool,
+
+	/// print every proposal the individual with this identity made or received after the last round,
+	/// round by round. can be given more than once, records the history of everyone
+	#[arg(long, value_name = "UUID")]
+	trace: Vec<String>,
+
+	/// log more on stderr, `-v` adds debug events and `-vv` trace events.
+	/// without it `RUST_LOG` picks the levels, e.g. `RUST_LOG=dating_simulation=debug`, info by default
+	#[arg(short, long, global = true, action = clap::ArgAction::Count)]
+	verbose: u8
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct Individual {
-	pub identity: String,
-	pub gender: Gender,
-	// a list of floats that represents how much does this person weight on different attributes
-	pub preference_weights: Vec<f32>,
-	// a list of integers that represents how much does this person score on each attribute
-	pub ratings: Vec<f32>,
-	// a list to record the individuals that rejected this individual
-	pub blacklist: Vec<String>, 
-	// a field that stores the previously accepted candidate
-	pub candidate: Option<String>,
-	// a field that stores the previously accepted candidate's score
-	pub candidate_score: Option<f32>
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+	/// run independent simulations with seeds drawn from `--seed`
+	/// and report the mean and spread of their outcomes
+	MonteCarlo(MonteCarloArgs),
+
+	/// run the full simulation for every combination of the swept values
+	/// and write the stats of every round as csv, one row per population, complexity, seed and round
+	Sweep(SweepArgs),
+
+	/// compare the final pairs and statistics of two json exports or checkpoints,
+	/// exits with 1 when they differ
+	Diff(DiffArgs),
+
+	/// serve simulations over http as a json api until stopped
+	#[cfg(feature = "server")]
+	Serve(ServeArgs)
 }
 
-impl std::fmt::Display for Individual {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		writeln!(f, "Identity: {}, {:#?}", self.identity, self.gender);
-		writeln!(f, "Preference Weights: {:?}", self.preference_weights);
-		writeln!(f, "Ratings: {:?}", self.ratings);
-		writeln!(f, "Blacklist: {:?}", self.blacklist);
-		writeln!(f, "Candidate: {:?}", self.candidate);
-		writeln!(f, "Candidate Score: {:?}", self.candidate_score);
-		
-		return Ok(());
-	}
+#[derive(Debug, clap::Args)]
+struct MonteCarloArgs {
+	#[command(flatten)]
+	simulation: SimulationArgs,
+
+	/// number of simulations to run, in parallel
+	#[arg(long, default_value_t = 10, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+	runs: usize,
+
+	/// write the final statistics of every run as one csv row to this file
+	#[arg(long)]
+	runs_csv: Option<std::path::PathBuf>,
+
+	/// write the aggregate statistics and those of every run as json to this file
+	#[arg(long)]
+	report_json: Option<std::path::PathBuf>
 }
 
-impl Individual {
-	/// use this method to generate an individual
-	/// the preference complexity specifies the number of preference_weights
-	/// and ratings will be used. 
-	pub fn new(
-		preference_complexity: i8, 
-		specified_predefined_weights: Option<Vec<f32>>
-	) -> Self {
-		
-		let mut rng = rand::thread_rng();
-		
-		let mut predefined_weights: Vec<f32> = Vec::new();	
-		
-		if specified_predefined_weights.is_some() {
-			// if the `predefined_weights` is specified, use the specified the weights
-			if specified_predefined_weights.clone().unwrap().len() != preference_complexity as usize {
-				panic!("Wrong size of specified predefined weights!");
-			} else {
-				predefined_weights = specified_predefined_weights.unwrap();
+#[derive(Debug, clap::Args)]
+struct SweepArgs {
+	#[command(flatten)]
+	simulation: SimulationArgs,
+
+	/// comma separated population sizes, e.g. `1000,5000,10000` [default: --population]
+	#[arg(long, value_delimiter = ',', num_args = 1.., value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+	sweep_population: Vec<usize>,
+
+	/// comma separated numbers of anonymous attributes, e.g. `2,3,5` [default: --complexity]
+	#[arg(long, value_delimiter = ',', num_args = 1.., value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+	sweep_complexity: Vec<usize>,
+
+	/// number of seeds, drawn from `--seed`, to simulate every combination with
+	#[arg(long, default_value_t = 1, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+	seeds: usize,
+
+	/// write the csv to this file instead of stdout
+	#[arg(long)]
+	csv: Option<std::path::PathBuf>
+}
+
+#[derive(Debug, clap::Args)]
+struct DiffArgs {
+	/// the run to compare against, e.g. of the previous version
+	a: std::path::PathBuf,
+
+	/// the run compared to it
+	b: std::path::PathBuf,
+
+	/// write the differences as json to this file, every pair and partner change included
+	#[arg(long)]
+	json: Option<std::path::PathBuf>
+}
+
+#[cfg(feature = "server")]
+#[derive(Debug, clap::Args)]
+struct ServeArgs {
+	/// port to listen on
+	#[arg(long, default_value_t = 8080)]
+	port: u16,
+
+	/// address to listen on, `0.0.0.0` for every interface
+	#[arg(long, default_value = "127.0.0.1")]
+	address: std::net::IpAddr,
+
+	/// the largest population a request can create
+	#[arg(long, default_value_t = 100_000)]
+	max_population: usize,
+
+	/// the most rounds one request can run
+	#[arg(long, default_value_t = 1000)]
+	max_rounds: u32
+}
+
+/// the names of the presets, each listed with its description in `--help`
+fn preset_parser() -> impl clap::builder::TypedValueParser<Value = Preset> {
+	let names = Preset::ALL.map(|preset| clap::builder::PossibleValue::new(preset.name()).help(preset.description()));
+
+	return clap::builder::TypedValueParser::map(clap::builder::PossibleValuesParser::new(names), |name: String| {
+		return name.parse::<Preset>().expect("every possible value names a preset");
+	});
+}
+
+impl SimulationArgs {
+	/// merge the config file (if any) with the command-line flags,
+	/// exits with a usage error when the result is invalid
+	fn simulation_config(&self) -> SimulationConfig {
+		let mut config = match &self.config {
+			Some(path) => SimulationConfig::from_toml_file(path)
+				.unwrap_or_else(|error| fail(error)),
+			None => SimulationConfig::default()
+		};
+
+		if let Some(preset) = self.preset {
+			let preset = SampleConfig::preset(preset);
+			config.attributes = preset.attribute_schema;
+			config.attribute_correlation = None;
+			config.weight_distribution = preset.weight_distribution;
+		}
+		if let Some(population) = self.population {
+			config.population_size = population;
+		}
+		if let Some(complexity) = self.complexity {
+			config.preference_complexity = complexity;
+		}
+		if let Some(rounds) = self.rounds {
+			config.rounds = rounds;
+		}
+		if let Some(converge_after) = self.converge_after {
+			config.convergence_rounds = Some(converge_after);
+		}
+		if let Some(weights) = &self.weights {
+			config.predefined_weights = Some(weights.clone());
+		}
+		if let Some(gender_ratio) = &self.gender_ratio {
+			if !(2..=3).contains(&gender_ratio.len()) {
+				exit_with_error(
+					clap::error::ErrorKind::WrongNumberOfValues,
+					format!("--gender-ratio takes 2 or 3 weights, got {}", gender_ratio.len())
+				);
+			}
+			config.gender_distribution = GenderDistribution {
+				male: gender_ratio[0],
+				female: gender_ratio[1],
+				non_binary: gender_ratio.get(2).copied().unwrap_or_default()
+			};
+		}
+		if let Some(gender_counts) = &self.gender_counts {
+			if gender_counts.len() != 2 {
+				exit_with_error(
+					clap::error::ErrorKind::WrongNumberOfValues,
+					format!("--gender-counts takes the number of males and of females, got {} values", gender_counts.len())
+				);
+			}
+			let counts = GenderCounts { male: gender_counts[0], female: gender_counts[1] };
+			config.gender_counts = Some(counts);
+			// arrivals keep the ratio, as with `SampleConfigBuilder::gender_counts`
+			if counts.total() > 0 {
+				config.gender_distribution = counts.distribution();
 			}
-		} else {
-			// generate random weights based on the given complexity
-			// in case if the weights are not specified. 
-			for _ in 0..preference_complexity {
-				let weight: f32 = rng.r#gen();
-				
-				predefined_weights.push(
-					weight
+			if self.population.is_none() {
+				config.population_size = counts.total();
+			}
+		}
+		if let Some(orientation_ratio) = &self.orientation_ratio {
+			if orientation_ratio.len() != 3 {
+				exit_with_error(
+					clap::error::ErrorKind::WrongNumberOfValues,
+					format!("--orientation-ratio takes 3 weights, got {}", orientation_ratio.len())
 				);
-			} 
-		}
-			
-		let gender = Gender::new();
-		let identity = Uuid::new_v4();
-		let mut ratings: Vec<f32> = Vec::new();
-			
-		// generate random ratings based on the given complexity
-		for _ in 0..preference_complexity {
-			ratings.push(
-				rng.gen_range(1.0..=10.0)
-			);
-		}
-		
-		return Individual {
-			identity: identity.to_string(), 
-			gender: gender, 
-			preference_weights: predefined_weights,
-			ratings: ratings,
-			blacklist: Vec::new(),
-			candidate: None,
-			candidate_score: None
-		};
-	}
-	
-	/// calculate the score of this individual to the other
-	pub fn score(
-		&self, 
-		matcher: &Individual
-	) -> Result<f32, Box<dyn std::error::Error>> {
-		
-		if self.preference_weights.len() != matcher.ratings.len() {
-			return Err(
-				"Twos' predefined weights and ratings do not match.".into()
-			);
-		}
-		
-		let score = self.preference_weights
-			.iter()
-			.zip(
-				matcher.ratings.iter()
-			)
-			.map(|(w, r)| w * r)
-			.sum();
-		
-		return Ok(score); 
+			}
+			config.orientation_distribution = OrientationDistribution {
+				heterosexual: orientation_ratio[0],
+				homosexual: orientation_ratio[1],
+				bisexual: orientation_ratio[2]
+			};
+		}
+		if let Some(rating_distribution) = self.rating_distribution {
+			config.rating_distribution = rating_distribution;
+		}
+		if let Some(weight_distribution) = &self.weight_distribution {
+			config.weight_distribution = weight_distribution.clone();
+		}
+		if let Some(dealbreaker_probability) = self.dealbreaker_probability {
+			config.dealbreaker_probability = dealbreaker_probability;
+		}
+		if let Some(age_distribution) = self.age_distribution {
+			config.age.distribution = age_distribution;
+		}
+		if let Some(age_step) = self.age_step {
+			config.age.step = age_step;
+		}
+		if let Some(max_age_gap) = self.max_age_gap {
+			config.age.max_gap = Some(max_age_gap);
+		}
+		if let Some(arrivals_per_round) = self.arrivals_per_round {
+			config.churn.arrivals_per_round = arrivals_per_round;
+		}
+		if let Some(exit_probability) = self.exit_probability {
+			config.churn.exit_on_match_probability = exit_probability;
+		}
+		if let Some(breakup_probability) = self.breakup_probability {
+			config.churn.breakup_probability = breakup_probability;
+		}
+		if self.breakup_scaled_by_score {
+			config.churn.breakup_scaled_by_score = true;
+		}
+		if self.blacklist_on_breakup {
+			config.churn.blacklist_on_breakup = true;
+		}
+		if let Some(divorce_every) = self.divorce_every {
+			config.churn.divorce_every_rounds = Some(divorce_every);
+		}
+		if let Some(divorce_below) = self.divorce_below {
+			config.churn.divorce_below = divorce_below;
+		}
+		if self.blacklist_on_divorce {
+			config.churn.blacklist_on_divorce = true;
+		}
+		if let Some(selectivity) = self.selectivity {
+			config.selectivity.initial = selectivity;
+		}
+		if let Some(selectivity_decay) = self.selectivity_decay {
+			config.selectivity.decay = selectivity_decay;
+		}
+		if let Some(selectivity_floor) = self.selectivity_floor {
+			config.selectivity.floor = selectivity_floor;
+		}
+		if let Some(drift_random_walk) = self.drift_random_walk {
+			config.drift.random_walk = drift_random_walk;
+		}
+		if let Some(drift_toward_rejecters) = self.drift_toward_rejecters {
+			config.drift.toward_rejecters = drift_toward_rejecters;
+		}
+		if self.drift_renormalize {
+			config.drift.renormalize = true;
+		}
+		if let Some(births_per_pair) = self.births_per_pair {
+			config.reproduction.births_per_pair = births_per_pair;
+		}
+		if let Some(birth_every) = self.birth_every {
+			config.reproduction.every_rounds = birth_every;
+		}
+		if let Some(mutation_std_dev) = self.mutation_std_dev {
+			config.reproduction.mutation_std_dev = mutation_std_dev;
+		}
+		if let Some(maturity_rounds) = self.maturity_rounds {
+			config.reproduction.maturity_rounds = maturity_rounds;
+		}
+		if let Some(min_score) = self.min_score {
+			config.min_acceptable_score = min_score;
+		}
+		if let Some(seed) = self.seed {
+			config.seed = Some(seed);
+		}
+		if let Some(algorithm) = self.algorithm {
+			config.matching.algorithm = algorithm;
+		}
+		if let Some(scoring) = self.scoring {
+			config.matching.scoring = scoring;
+		}
+		if let Some(rating_values) = self.rating_values {
+			config.matching.rating_values = rating_values;
+		}
+		if self.mutual {
+			config.matching.mutual_acceptance = true;
+		}
+		if self.symmetric_blacklist {
+			config.matching.symmetric_blacklist = true;
+		}
+		if let Some(blacklist_duration) = self.blacklist_duration {
+			config.matching.blacklist_duration_rounds = Some(blacklist_duration);
+		}
+		if let Some(max_evaluations) = self.max_evaluations {
+			config.matching.max_evaluations_per_round = Some(max_evaluations);
+		}
+		if let Some(encounters) = self.encounters {
+			config.matching.encounters = encounters;
+		}
+		if let Some(score_noise) = self.score_noise {
+			config.matching.score_noise = score_noise;
+		}
+		if let Some(score_epsilon) = self.score_epsilon {
+			config.matching.score_epsilon = score_epsilon;
+		}
+		if self.record_history {
+			config.matching.record_history = true;
+		}
+		if let Some(proposer_side) = self.proposer_side {
+			config.matching.proposer_side = proposer_side;
+		}
+		if self.shuffle_proposers {
+			config.matching.shuffle_proposers = true;
+		}
+		if self.shuffle_reviewers {
+			config.matching.shuffle_reviewers = true;
+		}
+		if let Some(score_cache) = self.score_cache {
+			config.matching.score_cache = score_cache;
+		}
+		if let Some(candidate_capacity) = self.candidate_capacity {
+			config.matching.candidate_capacity = candidate_capacity;
+		}
+		if self.only_unmatched {
+			config.matching.only_unmatched = true;
+		}
+		if self.validate {
+			config.matching.validate_rounds = true;
+		}
+
+		// pin the seed so that the printed config reproduces this exact run
+		config.seed.get_or_insert_with(|| rand::rngs::OsRng.r#gen());
+
+		if let Err(error) = config.validate() {
+			fail(error);
+		}
+
+		return config;
 	}
-	
 }
 
-#[derive(Debug)]
-pub struct Sample {
-	pub male_population: Vec<Individual>,
-	pub female_population: Vec<Individual> 
+fn exit_with_error(kind: clap::error::ErrorKind, error: impl std::fmt::Display) -> ! {
+	Cli::command()
+		.error(kind, error)
+		.exit();
+}
+
+/// report why the simulation could not go on and exit with a code telling what kind of problem it was:
+/// 2 for an invalid config like other usage errors, 65 for a sample that does not hold together,
+/// 74 for a file that could not be read or written and 70 for anything else
+fn fail(error: SimulationError) -> ! {
+	let code = match &error {
+		SimulationError::InvalidConfig(_) |
+		SimulationError::Schema(_) |
+		SimulationError::Toml { .. } => 2,
+		SimulationError::WeightRatingLengthMismatch { .. } |
+		SimulationError::Parquet { .. } |
+		SimulationError::InvalidRequest(_) |
+		SimulationError::UnknownIdentity(_) |
+		SimulationError::InconsistentRound { .. } |
+		SimulationError::EmptyPopulation { .. } |
+		SimulationError::Json(_) => 65,
+		SimulationError::Io(_) |
+		SimulationError::MissingDirectory(_) |
+		SimulationError::Sqlite { .. } => 74,
+		SimulationError::TomlSerialize(_) |
+		SimulationError::Plot { .. } => 70
+	};
+
+	Dashboard::restore();
+	eprintln!("error: {}", error);
+	std::process::exit(code);
 }
 
-impl std::fmt::Display for Sample {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		writeln!(f, "Male Population: ")?;
-		for male_individual in &self.male_population {
-			writeln!(f, "===================")?;
-			writeln!(f, "{}", male_individual)?;
-		}
-		
-		writeln!(f, "Female Population: ")?;
-		for female_individual in &self.female_population {
-			writeln!(f, "===================")?;
-			writeln!(f, "{}", female_individual)?;
-		}
-		
-		return Ok(());
+/// the exit code of a run stopped with ctrl-c, 128 + SIGINT like a shell reports it
+const INTERRUPTED: i32 = 130;
+
+/// where an interrupted run without `--checkpoint` saves its state
+const INTERRUPTED_CHECKPOINT: &str = "dating-simulation-interrupted.json";
+
+/// where an interrupted run without `--statistics-json` saves its statistics
+const INTERRUPTED_STATISTICS: &str = "dating-simulation-interrupted-statistics.json";
+
+/// cancel `cancellation` on the first ctrl-c so the run stops after the current round,
+/// a second one exits right away
+fn handle_interrupts(cancellation: &CancellationToken) {
+	let cancellation = cancellation.clone();
+	let result = ctrlc::set_handler(move || {
+		if cancellation.is_cancelled() {
+			Dashboard::restore();
+			std::process::exit(INTERRUPTED);
+		}
+
+		tracing::warn!("interrupted, stopping after the current round, press ctrl-c again to quit right away");
+		cancellation.cancel();
+	});
+
+	if let Err(error) = result {
+		tracing::warn!("ctrl-c will stop the run without saving it: {}", error);
 	}
 }
 
-impl Sample {
-	/// initiate a population for simulating match-making
-	pub fn new(
-		population_size: i64,
-		preference_complexity: i8,
-		specified_predefined_weights: Option<Vec<f32>>
-	) -> Self {
-		let mut male_population: Vec<Individual> = Vec::new();
-		let mut female_population: Vec<Individual> = Vec::new();
-		
-		let progress_bar = indicatif::ProgressBar::new(
-			population_size as u64
-		);
-		progress_bar.set_style(indicatif::ProgressStyle::with_template(
-			"{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} ({eta})"
+/// log to stderr so stdout only carries the statistics and other requested output,
+/// every round is logged with its duration when its span closes, at `default` and above without `-v` or `RUST_LOG`
+fn init_tracing(verbose: u8, default: LevelFilter) {
+	use tracing_subscriber::filter::Targets;
+	use tracing_subscriber::fmt::format::FmtSpan;
+	use tracing_subscriber::prelude::*;
+
+	let filter = match verbose {
+		0 => std::env::var("RUST_LOG")
+			.ok()
+			.and_then(|directives| directives.parse::<Targets>().ok())
+			.unwrap_or_else(|| Targets::new().with_default(default)),
+		1 => Targets::new().with_default(LevelFilter::DEBUG),
+		_ => Targets::new().with_default(LevelFilter::TRACE)
+	};
+
+	tracing_subscriber::registry()
+		.with(
+			tracing_subscriber::fmt::layer()
+				.with_writer(std::io::stderr)
+				.with_ansi(std::io::IsTerminal::is_terminal(&std::io::stderr()))
+				.with_target(false)
+				.with_span_events(FmtSpan::CLOSE)
 		)
-	        .unwrap()
-	        .with_key(
-				"eta", 
-				|state: &indicatif::ProgressState, w: &mut dyn std::fmt::Write| write!(
-					w, "{:.1}s", state.eta().as_secs_f64()).unwrap()
-				)
-	        .progress_chars("#>-"));
-		
-		let mut progress_bar_position = 0;
-		
-		println!("Preparing the simulation data...");
-		
-		for _ in 0..population_size {
-			let individual = Individual::new(
-				preference_complexity, 
-				specified_predefined_weights.clone()
-			);
-			
-			if individual.gender == Gender::Female {
-				female_population.push(
-					individual
-				);
-			} else {
-				male_population.push(
-					individual
-				);
-			}
-			
-			progress_bar_position += 1;
-			progress_bar.set_position(progress_bar_position);
-		}
-		
-		progress_bar.finish_with_message(
-			format!(
-				"Simulation data preparation has completed in {}", 
-				progress_bar.elapsed().as_secs()
-			)
-		);
-		
-		return Sample {
-			male_population: male_population, 
-			female_population: female_population
-		};
+		.with(filter)
+		.init();
+}
+
+/// the independent runs of `monte-carlo`, their aggregate goes to stdout
+fn monte_carlo(cli: &Cli, args: &MonteCarloArgs) {
+	let config = args.simulation.simulation_config();
+
+	if cli.print_config {
+		println!("{}", config.to_toml_string().unwrap_or_else(|error| fail(error)));
 	}
-	
-	/// process the action after the two gets matched
-	pub fn liked(
-		female_individual: &mut Individual, 
-		male_individual: &mut Individual, 
-		score: f32
-	) {
-		female_individual.candidate = Some(
-			male_individual.identity.clone()
-		);
-		female_individual.candidate_score = Some(
-			score
-		);
-		
-		male_individual.candidate = Some(
-			female_individual.identity.clone()
-		);
-		male_individual.candidate_score = Some(
-			score
-		);
+
+	let mut monte_carlo = MonteCarlo::new(config, args.runs);
+	monte_carlo.show_progress = true;
+
+	tracing::info!("simulating {} runs", args.runs);
+	let report = monte_carlo.run().unwrap_or_else(|error| fail(error));
+	print!("{}", report.aggregate);
+
+	if let Some(path) = &args.runs_csv {
+		let file = std::fs::File::create(path)
+			.unwrap_or_else(|error| fail(error.into()));
+		let mut writer = std::io::BufWriter::new(file);
+		writeln!(writer, "{}", MonteCarloRun::CSV_HEADER).unwrap_or_else(|error| fail(error.into()));
+		for run in &report.runs {
+			run.export_csv(&mut writer).unwrap_or_else(|error| fail(error));
+		}
+		tracing::info!("runs written to {}", path.display());
 	}
-	
-	pub fn match_making(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-		
-		let progress_bar_male = indicatif::ProgressBar::new(
-			self.male_population.len() as u64
-		);
-		progress_bar_male.set_style(indicatif::ProgressStyle::with_template(
-			"{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} ({eta})"
-		)
-	        .unwrap()
-	        .with_key(
-				"eta", 
-				|state: &indicatif::ProgressState, w: &mut dyn std::fmt::Write| write!(
-					w, "{:.1}s", state.eta().as_secs_f64()).unwrap()
-				)
-	        .progress_chars("#>-"));
-		
-		let mut progress_bar_male_position = 0;
-		
-		println!("Simulating...");
-		
-		for male_individual in &mut self.male_population {
-			for female_individual in &mut self.female_population {
-				
-				if male_individual.blacklist.contains(&&female_individual.identity) {
-					continue;
-				}
-				
-				let score = female_individual
-					.score(male_individual)?;
-				
-				// if the score is smaller than the previous candidate, 
-				// the male is going to put the female to a blacklist,
-				// and the female will do the same
-				if female_individual.candidate_score.is_some() {
-					
-					if score < female_individual.candidate_score.unwrap() {
-						male_individual.blacklist.push(
-							female_individual.identity.clone()
-						);
-					} else {
-						Sample::liked(female_individual, male_individual, score);
-						break;
-					}
-					
-				} else {
-					Sample::liked(female_individual, male_individual, score);
-					break;
-				}
-			}
-			
-			progress_bar_male_position += 1;
-			progress_bar_male.set_position(progress_bar_male_position);
-		}
-		
-		progress_bar_male.finish_with_message(
-			format!(
-				"Simulation completed in {} secs", 
-				progress_bar_male.elapsed().as_secs()
-			)
-		);
-		
-		return Ok(());
+
+	if let Some(path) = &args.report_json {
+		report.to_json_file(path).unwrap_or_else(|error| fail(error));
+		tracing::info!("report written to {}", path.display());
+	}
+}
+
+/// the combinations of `sweep`, the csv goes to `--csv` or stdout
+fn sweep(cli: &Cli, args: &SweepArgs) {
+	let config = args.simulation.simulation_config();
+
+	if cli.print_config {
+		println!("{}", config.to_toml_string().unwrap_or_else(|error| fail(error)));
+	}
+
+	let mut runner = SweepRunner::new(config, args.sweep_population.clone(), args.sweep_complexity.clone());
+	runner.seeds_per_point = args.seeds;
+	runner.show_progress = true;
+	let results = runner.run().unwrap_or_else(|error| fail(error));
+
+	let mut writer: Box<dyn Write> = match &args.csv {
+		Some(path) => Box::new(std::io::BufWriter::new(
+			std::fs::File::create(path).unwrap_or_else(|error| fail(error.into()))
+		)),
+		None => Box::new(std::io::stdout().lock())
+	};
+	writeln!(writer, "{}", SweepResult::csv_header()).unwrap_or_else(|error| fail(error.into()));
+	for result in &results {
+		result.export_csv(&mut writer).unwrap_or_else(|error| fail(error));
+	}
+	writer.flush().unwrap_or_else(|error| fail(error.into()));
+}
+
+/// the text of `diff` goes to stdout, the exit code tells whether the runs differ
+fn diff(args: &DiffArgs) {
+	let a = RunExport::from_json_file(&args.a).unwrap_or_else(|error| fail(error));
+	let b = RunExport::from_json_file(&args.b).unwrap_or_else(|error| fail(error));
+	let diff = diff_runs(&a, &b);
+	print!("{}", diff);
+
+	if let Some(path) = &args.json {
+		let file = std::fs::File::create(path)
+			.unwrap_or_else(|error| fail(error.into()));
+		serde_json::to_writer_pretty(std::io::BufWriter::new(file), &diff)
+			.unwrap_or_else(|error| fail(error.into()));
+		tracing::info!("differences written to {}", path.display());
 	}
-	
-	// display matched pairs
-	pub fn display_matches(&self) {
-		
-		// store the female individuals that have no matches 
-		let mut no_match_female_individuals: Vec<&Individual> = Vec::new(); 
-		
-		for male_individual in &self.male_population {
-			
-			// a vec that is used to store the reference of matched individuals
-			let mut matches: Vec<&Individual> = Vec::new();
-			
-			for female_individual in &self.female_population {
-				if Some(male_individual.identity.clone()) == female_individual.candidate {
-					matches.push(female_individual);
-				} else if female_individual.candidate.is_none() {
-					no_match_female_individuals.push(female_individual);
-				}
+
+	if !diff.is_empty() {
+		std::process::exit(1);
+	}
+}
+
+#[cfg(feature = "server")]
+fn serve(args: &ServeArgs) {
+	let listener = std::net::TcpListener::bind((args.address, args.port))
+		.unwrap_or_else(|error| fail(error.into()));
+	let mut server = dating_simulation::SimulationServer::new();
+	server.max_population = args.max_population;
+	server.max_rounds = args.max_rounds;
+	std::sync::Arc::new(server).serve(listener).unwrap_or_else(|error| fail(error));
+}
+
+fn main() {
+
+	let cli = Cli::parse();
+	// the dashboard takes the place of the info messages too
+	let tui = cli.tui && console::Term::stderr().is_term();
+	init_tracing(cli.verbose, if tui { LevelFilter::WARN } else { LevelFilter::INFO });
+	if cli.tui && !tui {
+		tracing::warn!("stderr is not a terminal, printing the statistics of every round rather than the dashboard");
+	}
+
+	match &cli.command {
+		Some(Command::MonteCarlo(args)) => return monte_carlo(&cli, args),
+		Some(Command::Sweep(args)) => return sweep(&cli, args),
+		Some(Command::Diff(args)) => return diff(args),
+		#[cfg(feature = "server")]
+		Some(Command::Serve(args)) => return serve(args),
+		None => {}
+	}
+
+	let config = cli.simulation.simulation_config();
+
+	if cli.print_config {
+		println!("{}", config.to_toml_string().unwrap_or_else(|error| fail(error)));
+	}
+
+	let resumed = cli.resume.is_some();
+	let mut sample = match (&cli.resume, &cli.load) {
+		(Some(path), _) => {
+			let mut sample = Sample::resume(path)
+				.unwrap_or_else(|error| fail(error));
+			sample.show_progress = true;
+
+			sample
+		},
+		(None, Some(path)) => {
+			tracing::info!("loading the simulation data from {}", path.display());
+
+			let mut sample = Sample::from_json_file(path)
+				.unwrap_or_else(|error| fail(error));
+			sample.show_progress = true;
+
+			sample
+		},
+		(None, None) => {
+			tracing::info!("preparing the simulation data");
+
+			let mut sample_config = config.sample_config();
+			sample_config.show_progress = true;
+
+			let mut sample = Sample::generate(&sample_config)
+				.unwrap_or_else(|error| fail(error));
+			sample.set_selectivity(config.selectivity);
+			// next to the progress bars, out of the way of the statistics on stdout
+			if cli.verbose > 0 && !cli.quiet {
+				eprint!("{}", sample.memory_estimate());
 			}
-			
-			// print the male individual's information 
-			println!("============================================");
-			println!("Matches of Male {}", male_individual.identity);
-			println!("Below is Male {}'s information", male_individual.identity);
-			println!("{}", male_individual);
-			println!("");
-			
-			// print the male matches in the `male_individual`
-			if matches.is_empty() {
-				println!("No match!");
+
+			sample
+		}
+	};
+
+	// a resumed run goes on with the configuration it was checkpointed with
+	if !resumed {
+		sample.matching_config = config.matching.clone();
+		sample.convergence_rounds = config.convergence_rounds;
+		sample.churn = config.churn;
+		// a loaded population keeps the selectivity it has reached
+		sample.selectivity = config.selectivity;
+		sample.drift = config.drift;
+		sample.reproduction = config.reproduction;
+	}
+
+	// an identity that is not there fails before the run rather than tracing nothing
+	if !cli.trace.is_empty() {
+		if let Some(identity) = cli.trace.iter().find(|identity| sample.get(identity).is_none()) {
+			fail(SimulationError::UnknownIdentity(identity.clone()));
+		}
+		sample.matching_config.record_history = true;
+	}
+
+	let mut sinks: Vec<Box<dyn EventSink>> = Vec::new();
+	if let Some(path) = &cli.events {
+		sinks.push(Box::new(JsonLinesSink::create(path).unwrap_or_else(|error| fail(error))));
+	}
+	#[cfg(feature = "sqlite")]
+	if let Some(path) = &cli.sqlite {
+		let sink = dating_simulation::SqliteSink::create(path, &sample).unwrap_or_else(|error| fail(error));
+		tracing::info!("writing run {} to {}", sink.run_id(), path.display());
+		sinks.push(Box::new(sink));
+	}
+	if tui {
+		sample.show_progress = false;
+		sinks.extend(Dashboard::stderr(config.rounds).map(|dashboard| Box::new(dashboard) as Box<dyn EventSink>));
+
+		// a panic leaves the alternate screen before its message is printed
+		let hook = std::panic::take_hook();
+		std::panic::set_hook(Box::new(move |info| {
+			Dashboard::restore();
+			hook(info);
+		}));
+	}
+	if !sinks.is_empty() {
+		sample.set_event_sink(Box::new(sinks));
+	}
+
+	let mut round_stats_writer = cli.round_stats_csv.as_ref().map(|path| {
+		let file = std::fs::File::create(path)
+			.unwrap_or_else(|error| fail(error.into()));
+		let mut writer = std::io::LineWriter::new(file);
+		// taken before the run, the rows tell how many rounds followed
+		sample.metadata().write_csv_comment(&mut writer).unwrap_or_else(|error| fail(error));
+		writeln!(writer, "{}", RoundStats::CSV_HEADER).unwrap_or_else(|error| fail(error.into()));
+
+		writer
+	});
+
+	// `--rounds` counts the rounds before the checkpoint too
+	let rounds = if resumed {
+		config.rounds.saturating_sub(sample.round)
+	} else {
+		config.rounds
+	};
+	let checkpoint_every = cli.checkpoint_every.unwrap_or(1);
+
+	let cancellation = CancellationToken::new();
+	handle_interrupts(&cancellation);
+
+	tracing::info!("simulating up to {} rounds", rounds);
+	let report = sample.run_until(rounds, &cancellation, |sample, round_stats| {
+		if let Some(writer) = &mut round_stats_writer {
+			round_stats.export_csv(writer).unwrap_or_else(|error| fail(error));
+		}
+
+		if let Some(path) = &cli.checkpoint {
+			if sample.round % checkpoint_every == 0 {
+				sample.checkpoint(path).unwrap_or_else(|error| fail(error));
 			}
-			
-			for matched in matches {
-				println!("{}", matched);
+		}
+
+		if !cli.quiet && !tui {
+			if cli.blocking_pairs {
+				println!("{}", sample.statistics_with_blocking_pairs());
+			} else {
+				sample.display_statistics();
 			}
 		}
-		
+		// sample.display_matches();
+	}).unwrap_or_else(|error| fail(error));
+
+	tracing::info!("stopped after {} rounds, {}", report.rounds_executed, report.stop_reason);
+
+	if report.stop_reason == StopReason::Cancelled {
+		let checkpoint = cli.checkpoint.clone().unwrap_or_else(|| INTERRUPTED_CHECKPOINT.into());
+		let statistics = cli.statistics_json.clone().unwrap_or_else(|| INTERRUPTED_STATISTICS.into());
+
+		sample.checkpoint(&checkpoint).unwrap_or_else(|error| fail(error));
+		sample.statistics().to_json_file(&statistics).unwrap_or_else(|error| fail(error));
+		eprintln!(
+			"interrupted after round {}, saved the run to {} and its statistics to {}, continue it with --resume {}",
+			sample.round,
+			checkpoint.display(),
+			statistics.display(),
+			checkpoint.display()
+		);
+		std::process::exit(INTERRUPTED);
 	}
-	
-	pub fn display_statistics(&self) {
-		
-		// store the match information
-	    let mut no_match_female_individuals: Vec<&Individual> = Vec::new(); 
-	    let mut no_match_male_individuals: Vec<&Individual> = Vec::new();
-	    
-	    let mut matched_male_individuals: Vec<&Individual> = Vec::new();
-	    let mut matched_female_individuals: Vec<&Individual> = Vec::new();
-	    
-	    for male_individual in &self.male_population {
-	        let mut matched = false;
-	        
-	        for female_individual in &self.female_population {
-	            if let Some(candidate) = &female_individual.candidate {
-	                if male_individual.identity == *candidate {
-	                    matched_female_individuals.push(female_individual);
-	                    matched_male_individuals.push(male_individual);
-	                    matched = true;
-	                    break; // Stop further checking if matched
-	                }
-	            }
-	        }
-	        
-	        if !matched {
-	            no_match_male_individuals.push(male_individual);
-	        }
-	    }
-	    
-	    for female_individual in &self.female_population {
-	        if female_individual.candidate.is_none() || 
-	           !matched_female_individuals.contains(&female_individual) {
-	            no_match_female_individuals.push(female_individual);
-	        }
-	    }
-	    
-	    println!("Statistics:");
-	    println!("Males that do not have a match: {}/{}", no_match_male_individuals.len(), self.male_population.len());
-	    println!("Females that do not have a match: {}/{}", no_match_female_individuals.len(), self.female_population.len());
-	    println!("Males that have a match: {}/{}", matched_male_individuals.len(), self.male_population.len());
-	    println!("Females that have a match: {}/{}", matched_female_individuals.len(), self.female_population.len());
-	    
-	    let male_population_size = self.male_population.len();
-	    let female_population_size = self.female_population.len();
-	    let total_population_size = male_population_size + female_population_size;
-	    let total_unmatched_individuals = no_match_male_individuals.len() + no_match_female_individuals.len();
-	    
-	    println!("Descriptions:");
-	    if male_population_size > female_population_size {
-	        println!("In this simulation, male population EXCEEDED that of female by {}", male_population_size - female_population_size);
-	    } else {
-	        println!("In this simulation, male population FEWER that of female by {}", female_population_size - male_population_size);
-	    }
-	    
-	    let unmatched_percentage = (total_unmatched_individuals as f64 / total_population_size as f64) * 100.0;
-	    println!("{:.2}% of individuals were never matched.", unmatched_percentage);
-		
+
+	if cli.histogram {
+		print!("{}", sample.statistics().render_histogram());
 	}
-}
 
-fn main() {
-	
-	let rounds: i8 = 100;
-	
-	let mut sample = Sample::new(
-		10000, 
-		3, 
-		// Some(vec![0.7, 0.2, 0.1]),
-		None
-	);
-	
-	let mut current_round: i8 = 0;
-	for _ in 0..rounds {
-		let start = std::time::Instant::now();
-		
-		sample.match_making().unwrap();
-		sample.display_statistics();
-		
-		current_round += 1;
-		
-		println!(
-			"Simulation completed in {} seconds. {}/{}", 
-			start.elapsed().as_secs(),
-			current_round,
-			rounds,
-		);
-		// sample.display_matches();
+	for identity in &cli.trace {
+		print!("{}", sample.trace(identity).unwrap_or_else(|error| fail(error)));
 	}
-	
-}
\ No newline at end of file
+
+	if let Some(path) = &cli.json {
+		sample.to_json_file(path).unwrap_or_else(|error| fail(error));
+		tracing::info!("population written to {}", path.display());
+	}
+
+	if let Some(path) = &cli.statistics_json {
+		let statistics = if cli.blocking_pairs { sample.statistics_with_blocking_pairs() } else { sample.statistics() };
+		statistics.to_json_file(path).unwrap_or_else(|error| fail(error));
+		tracing::info!("statistics written to {}", path.display());
+	}
+
+	if let Some(path) = &cli.report {
+		std::fs::write(path, sample.report(&report).render()).unwrap_or_else(|error| fail(error.into()));
+		tracing::info!("report written to {}", path.display());
+	}
+
+	#[cfg(feature = "arrow")]
+	if let Some(path) = &cli.pairs_parquet {
+		sample.export_pairs_parquet(path).unwrap_or_else(|error| fail(error));
+		tracing::info!("pairs written to {}", path.display());
+	}
+
+	#[cfg(feature = "arrow")]
+	if let Some(path) = &cli.round_stats_parquet {
+		RoundStats::export_parquet(&report.round_stats, path).unwrap_or_else(|error| fail(error));
+		tracing::info!("round stats written to {}", path.display());
+	}
+
+	#[cfg(feature = "plots")]
+	if let Some(directory) = &cli.plot {
+		RoundStats::plot(&report.round_stats, directory).unwrap_or_else(|error| fail(error));
+		tracing::info!("plots written to {}", directory.display());
+	}
+
+	if let Some(path) = &cli.matches_csv {
+		let file = std::fs::File::create(path)
+			.unwrap_or_else(|error| fail(error.into()));
+		sample.export_matches_csv(std::io::BufWriter::new(file), cli.include_unmatched).unwrap_or_else(|error| fail(error));
+		tracing::info!("matches written to {}", path.display());
+	}
+
+	if let Some(path) = &cli.graph {
+		let file = std::fs::File::create(path)
+			.unwrap_or_else(|error| fail(error.into()));
+		sample.export_graph(std::io::BufWriter::new(file), cli.graph_kind, cli.graph_format, cli.graph_max_nodes).unwrap_or_else(|error| fail(error));
+		tracing::info!("graph written to {}", path.display());
+	}
+
+}
@@ -0,0 +1,1404 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::assignment;
+use crate::encounter::{AliasTable, Encounter, EncounterOutcome, Encounters, VisibilityTable};
+use crate::error::SimulationError;
+use crate::individual::{Gender, Individual};
+use crate::person_id::PersonId;
+use crate::progress::Progress;
+use crate::sample::Sample;
+use crate::score_cache::{ScoreCache, ScoreCacheMode, Scorer};
+use crate::scoring::{RatingValues, ScoreError, ScoreNoise, Scoring};
+
+/// the procedure `Sample::match_making` uses to pair the population, 
+/// only individuals that are attracted to each other's gender are ever paired. 
+/// in the greedy scan everyone proposes to the individuals of their own gender 
+/// and of the genders that come after their own in the `Gender` order, with the proposing gender 
+/// of `ProposerSide` moved to the front. with males proposing, males propose to everyone 
+/// and females to females and non-binary individuals. 
+/// the two-sided algorithms have the proposing gender propose to everyone of another gender
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MatchingAlgorithm {
+	// every proposer takes the first reviewer that prefers them over their current candidate
+	#[default]
+	Greedy,
+	// gale-shapley with the proposer side proposing, the resulting matching is stable
+	DeferredAcceptance,
+	// all proposers propose to their best admissible reviewer in parallel,
+	// then every reviewer keeps the best of their proposers
+	ParallelProposal,
+	// the single proposers are paired with single reviewers drawn at random, regardless of their scores,
+	// a baseline to compare the other algorithms against
+	Random,
+	// the single proposers meet single reviewers drawn at random one-on-one,
+	// the two pair up if neither turns the other down
+	SpeedDating,
+	// the pairing that maximizes the sum of the scores both sides give each other,
+	// solved as an assignment problem between the proposing gender and the other genders
+	Optimal
+}
+
+impl std::fmt::Display for MatchingAlgorithm {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			MatchingAlgorithm::Greedy => "greedy",
+			MatchingAlgorithm::DeferredAcceptance => "deferred-acceptance",
+			MatchingAlgorithm::ParallelProposal => "parallel-proposal",
+			MatchingAlgorithm::Random => "random",
+			MatchingAlgorithm::SpeedDating => "speed-dating",
+			MatchingAlgorithm::Optimal => "optimal"
+		};
+
+		return write!(f, "{}", name);
+	}
+}
+
+impl std::str::FromStr for MatchingAlgorithm {
+	type Err = String;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		return match name {
+			"greedy" => Ok(MatchingAlgorithm::Greedy),
+			"deferred-acceptance" => Ok(MatchingAlgorithm::DeferredAcceptance),
+			"parallel-proposal" => Ok(MatchingAlgorithm::ParallelProposal),
+			"random" => Ok(MatchingAlgorithm::Random),
+			"speed-dating" => Ok(MatchingAlgorithm::SpeedDating),
+			"optimal" => Ok(MatchingAlgorithm::Optimal),
+			_ => Err(
+				format!(
+					"unknown matching algorithm `{}`, expected `greedy`, `deferred-acceptance`, `parallel-proposal`, `random`, `speed-dating` or `optimal`", 
+					name
+				)
+			)
+		};
+	}
+}
+
+/// which gender proposes in `Sample::match_making`, the other genders review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProposerSide {
+	#[default]
+	Male,
+	Female,
+	// males in odd rounds and females in even rounds
+	Alternate
+}
+
+impl ProposerSide {
+	/// the gender that proposes in the 1-based `round`
+	pub fn proposing_gender(&self, round: u32) -> Gender {
+		return match self {
+			ProposerSide::Male => Gender::Male,
+			ProposerSide::Female => Gender::Female,
+			ProposerSide::Alternate if round % 2 == 1 => Gender::Male,
+			ProposerSide::Alternate => Gender::Female
+		};
+	}
+}
+
+impl std::fmt::Display for ProposerSide {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			ProposerSide::Male => "male",
+			ProposerSide::Female => "female",
+			ProposerSide::Alternate => "alternate"
+		};
+
+		return write!(f, "{}", name);
+	}
+}
+
+impl std::str::FromStr for ProposerSide {
+	type Err = String;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		return match name {
+			"male" => Ok(ProposerSide::Male),
+			"female" => Ok(ProposerSide::Female),
+			"alternate" => Ok(ProposerSide::Alternate),
+			_ => Err(format!("unknown proposer side `{}`, expected `male`, `female` or `alternate`", name))
+		};
+	}
+}
+
+/// settings of `Sample::match_making`, the default reproduces the original behavior
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MatchingConfig {
+	pub algorithm: MatchingAlgorithm,
+	// in the greedy scan, proposers only propose to reviewers they score above their current candidate
+	// and both sides keep their own score, otherwise the proposer accepts anyone
+	// and both sides share the reviewer's score
+	pub mutual_acceptance: bool,
+	// a rejected proposer also lands on the blacklist of the one who rejected them,
+	// otherwise only the proposer records the rejection
+	pub symmetric_blacklist: bool,
+	// in debug builds, run `Sample::validate` after every round and fail the round on violations
+	pub validate_rounds: bool,
+	// the built-in strategy everyone scores with, unless `Sample::set_scoring_strategy` replaced it
+	pub scoring: Scoring,
+	// whether the strategy scores the ratings or where they rank among the population
+	pub rating_values: RatingValues,
+	// walk the proposers in a new random order every round instead of the population order,
+	// in the greedy scan and for the proposers of deferred acceptance. the order is drawn from the sample's seed
+	pub shuffle_proposers: bool,
+	// in the greedy scan, let every proposer walk the reviewers in their own random order
+	pub shuffle_reviewers: bool,
+	pub proposer_side: ProposerSide,
+	// how many candidates everyone may hold at once in the greedy scan. above 1 a reviewer accepts
+	// anyone better than the worst one they hold and the run ends with `Sample::commit_candidates`
+	pub candidate_capacity: usize,
+	// in the greedy scan, leave out the proposers who hold as many candidates as they may at the start of their turn.
+	// they would mostly walk past the reviewers who turned them down, who they lose a partner to proposes again
+	pub only_unmatched: bool,
+	// keep the scores of a round for the next ones, see `ScoreCache`
+	pub score_cache: ScoreCacheMode,
+	// the largest population × population a `ScoreCache` is built for, larger populations score without one
+	pub score_cache_max_entries: usize,
+	// the largest population the optimal algorithm pairs, it takes O(n³) time and larger populations fail the round
+	pub optimal_max_population: usize,
+	// the rounds a rejection keeps the two apart, counting the round it happened in.
+	// `None` keeps them apart for good
+	pub blacklist_duration_rounds: Option<u32>,
+	// how many admissible reviewers every proposer scores per round in the greedy scan and parallel proposal, 
+	// drawn at random anew every round, besides their current candidate. `None` scores all of them
+	pub max_evaluations_per_round: Option<usize>,
+	// who the proposers come across under `max_evaluations_per_round`
+	pub encounters: Encounters,
+	// the error on every score of a round. a rejection only blacklists once the score
+	// falls short by more than the standard deviation, one bad draw does not keep two apart
+	pub score_noise: ScoreNoise,
+	// scores less than this apart are a tie. a reviewer keeps the candidate a proposer ties with and turns
+	// the proposer away without blacklisting either, of two new proposers the one with the lower identity wins.
+	// 0 compares the scores as they are
+	pub score_epsilon: f32,
+	// keep every proposal in the `Individual::history` of both sides, see `Sample::history_of`.
+	// it grows by up to a pair of proposals per pair of individuals and round. the optimal algorithm records none
+	pub record_history: bool
+}
+
+impl Default for MatchingConfig {
+	fn default() -> Self {
+		return MatchingConfig {
+			algorithm: MatchingAlgorithm::default(),
+			mutual_acceptance: false,
+			symmetric_blacklist: false,
+			validate_rounds: false,
+			scoring: Scoring::default(),
+			rating_values: RatingValues::default(),
+			shuffle_proposers: false,
+			shuffle_reviewers: false,
+			proposer_side: ProposerSide::default(),
+			candidate_capacity: 1,
+			only_unmatched: false,
+			score_cache: ScoreCacheMode::default(),
+			score_cache_max_entries: ScoreCache::DEFAULT_MAX_ENTRIES,
+			optimal_max_population: 2000,
+			blacklist_duration_rounds: None,
+			max_evaluations_per_round: None,
+			encounters: Encounters::default(),
+			score_noise: ScoreNoise::None,
+			score_epsilon: 0.0,
+			record_history: false
+		};
+	}
+}
+
+impl MatchingConfig {
+	/// reject a `candidate_capacity` of 0, capacities above 1 outside the greedy scan, 
+	/// a `blacklist_duration_rounds` of 0, evaluation budgets of 0 or for the algorithms that evaluate everyone
+	/// and weighted encounters without a budget
+	pub fn validate(&self) -> Result<(), SimulationError> {
+		self.score_noise.validate()?;
+		
+		if !self.score_epsilon.is_finite() || self.score_epsilon < 0.0 {
+			return Err(
+				SimulationError::InvalidConfig(format!("score_epsilon must be a non-negative number, got {}", self.score_epsilon))
+			);
+		}
+		
+		if self.candidate_capacity == 0 {
+			return Err(SimulationError::InvalidConfig(String::from("candidate_capacity must be at least 1")));
+		}
+		
+		if self.blacklist_duration_rounds == Some(0) {
+			return Err(SimulationError::InvalidConfig(String::from("blacklist_duration_rounds must be at least 1")));
+		}
+		
+		if self.max_evaluations_per_round == Some(0) {
+			return Err(SimulationError::InvalidConfig(String::from("max_evaluations_per_round must be at least 1")));
+		}
+		
+		if self.encounters != Encounters::Uniform && self.max_evaluations_per_round.is_none() {
+			return Err(
+				SimulationError::InvalidConfig(format!("{} encounters need a max_evaluations_per_round, otherwise everyone meets everyone", self.encounters))
+			);
+		}
+		
+		if self.max_evaluations_per_round.is_some() && 
+			!matches!(self.algorithm, MatchingAlgorithm::Greedy | MatchingAlgorithm::ParallelProposal) {
+			return Err(
+				SimulationError::InvalidConfig(format!(
+					"max_evaluations_per_round needs the greedy or the parallel-proposal algorithm, not {}",
+					self.algorithm
+				))
+			);
+		}
+		
+		if self.candidate_capacity > 1 && self.algorithm != MatchingAlgorithm::Greedy {
+			return Err(
+				SimulationError::InvalidConfig(format!(
+					"a candidate_capacity of {} needs the greedy algorithm, {} holds one candidate",
+					self.candidate_capacity,
+					self.algorithm
+				))
+			);
+		}
+		
+		if self.only_unmatched && self.algorithm != MatchingAlgorithm::Greedy {
+			return Err(
+				SimulationError::InvalidConfig(format!("only_unmatched needs the greedy algorithm, {} pairs everyone anew", self.algorithm))
+			);
+		}
+		
+		return Ok(());
+	}
+	
+	/// whether the two scores are less than `score_epsilon` apart
+	pub(crate) fn ties(&self, score: f32, other: f32) -> bool {
+		return (score - other).abs() < self.score_epsilon;
+	}
+	
+	/// whether `score` of `identity` beats `other` of `other_identity`, a tie goes to the lower identity
+	fn beats(&self, score: f32, identity: PersonId, other: f32, other_identity: PersonId) -> bool {
+		if score == other || self.ties(score, other) {
+			return identity < other_identity;
+		}
+		
+		return score > other;
+	}
+}
+
+/// the order the greedy scan walks the population in and deferred acceptance lets the proposers propose in, 
+/// positions into the population. parallel proposal does not depend on the order, 
+/// only on the reviewers every proposer gets to evaluate
+pub(crate) struct ScanOrder {
+	proposers: Vec<usize>,
+	reviewers: Vec<usize>,
+	// reshuffles `reviewers` before every proposer scans them
+	reviewer_rng: Option<StdRng>,
+	// `MatchingConfig::max_evaluations_per_round`, with the seed the evaluated reviewers are drawn from
+	evaluation_budget: Option<usize>,
+	evaluation_seed: u64,
+	// what the evaluated reviewers are drawn by under `Encounters::Desirability`, uniformly without it
+	visibility: Option<Arc<VisibilityTable>>
+}
+
+impl ScanOrder {
+	/// the population order, shuffled with `seed` where `config` asks for it
+	pub fn new(len: usize, config: &MatchingConfig, seed: u64, visibility: Option<Arc<VisibilityTable>>) -> Self {
+		let mut rng = StdRng::seed_from_u64(seed);
+		
+		let mut proposers: Vec<usize> = (0..len).collect();
+		if config.shuffle_proposers {
+			proposers.shuffle(&mut rng);
+		}
+		
+		return ScanOrder {
+			proposers: proposers,
+			reviewers: (0..len).collect(),
+			reviewer_rng: config.shuffle_reviewers.then(|| StdRng::seed_from_u64(rng.r#gen())),
+			evaluation_budget: config.max_evaluations_per_round,
+			evaluation_seed: rng.r#gen(),
+			visibility: visibility
+		};
+	}
+	
+	/// `proposers` in their order, every one of them scanning the `len` reviewers in population order
+	#[cfg(feature = "fs")]
+	pub fn fixed(proposers: Vec<usize>, len: usize) -> Self {
+		return ScanOrder {
+			proposers: proposers,
+			reviewers: (0..len).collect(),
+			reviewer_rng: None,
+			evaluation_budget: None,
+			evaluation_seed: 0,
+			visibility: None
+		};
+	}
+	
+	/// the reviewers the next proposer scans, in order
+	fn next_reviewers(&mut self) -> &[usize] {
+		if let Some(rng) = &mut self.reviewer_rng {
+			self.reviewers.shuffle(rng);
+		}
+		
+		return &self.reviewers;
+	}
+	
+	/// the reviewers out of `admissible` that `proposer` evaluates, all of them without an evaluation budget. 
+	/// with one a sample of that many, drawn from the proposer's own seed so that the proposers can draw in parallel. 
+	/// the proposer's `candidate` comes on top, a proposer knows who they are with
+	fn evaluated(&self, proposer: usize, mut admissible: Vec<usize>, candidate: Option<usize>) -> Vec<usize> {
+		let Some(budget) = self.evaluation_budget else {
+			return admissible;
+		};
+		
+		let candidate = candidate.filter(|candidate| admissible.contains(candidate));
+		admissible.retain(|&reviewer| Some(reviewer) != candidate);
+		
+		let mut rng = StdRng::seed_from_u64(self.evaluation_seed ^ (proposer as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+		let mut evaluated = match &self.visibility {
+			Some(visibility) => draw_visible(&visibility.table, &admissible, budget, &mut rng),
+			None => admissible.partial_shuffle(&mut rng, budget).0.to_vec()
+		};
+		evaluated.extend(candidate);
+		
+		return evaluated;
+	}
+}
+
+/// the draws a proposer wastes on individuals they can not propose to, or already drew, 
+/// per reviewer of their budget before they give up on the rest
+const WASTED_DRAWS_PER_EVALUATION: usize = 32;
+
+/// up to `budget` different reviewers of `admissible`, each drawn from `table` over the whole population
+fn draw_visible(table: &AliasTable, admissible: &[usize], budget: usize, rng: &mut StdRng) -> Vec<usize> {
+	let mut remaining: HashSet<usize> = admissible.iter().copied().collect();
+	let mut evaluated = Vec::new();
+	let mut wasted = 0;
+	
+	while evaluated.len() < budget && !remaining.is_empty() && wasted < budget * WASTED_DRAWS_PER_EVALUATION {
+		let drawn = table.sample(rng);
+		if remaining.remove(&drawn) {
+			evaluated.push(drawn);
+		} else {
+			wasted += 1;
+		}
+	}
+	
+	return evaluated;
+}
+
+/// the reviewers out of `reviewers` that `proposer` may propose to in `round`, the ones `admits`
+/// and neither of the two has blacklisted, with the number skipped over a dealbreaker
+fn admissible_reviewers(
+	population: &[Individual],
+	proposer: usize,
+	reviewers: &[usize],
+	round: u32,
+	config: &MatchingConfig,
+	admits: impl Fn(&Individual, &Individual) -> bool
+) -> (Vec<usize>, usize) {
+	let proposer_individual = &population[proposer];
+	let mut dealbreaker_rejections = 0;
+	
+	let admissible = reviewers
+		.iter()
+		.copied()
+		.filter(|&reviewer| {
+			let reviewer_individual = &population[reviewer];
+			if reviewer == proposer || !admits(proposer_individual, reviewer_individual) ||
+				blacklisted_between(proposer_individual, reviewer_individual, round, config) {
+				return false;
+			}
+			
+			if dealbreaker_between(proposer_individual, reviewer_individual) {
+				dealbreaker_rejections += 1;
+				return false;
+			}
+			
+			return true;
+		})
+		.collect();
+	
+	return (admissible, dealbreaker_rejections);
+}
+
+/// two individuals that name each other as candidate. 
+/// the proposer is the one whose gender comes first in the `Gender` order, 
+/// for two individuals of the same gender the one that comes first in the population
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchPair {
+	pub proposer_id: PersonId,
+	pub reviewer_id: PersonId,
+	// the reviewer's score of the proposer
+	pub reviewer_score: f32,
+	// the proposer's score of the reviewer
+	pub proposer_score: f32,
+	// 1-based round in which the pair formed
+	#[serde(default)]
+	pub matched_in_round: Option<u32>
+}
+
+/// whether `proposer` evaluates `reviewer` in the greedy scan where `proposing` proposes first
+fn proposes_to(proposer: &Individual, reviewer: &Individual, proposing: Gender) -> bool {
+	let rank = |gender: Gender| (gender != proposing, gender);
+	
+	return rank(proposer.gender) <= rank(reviewer.gender) && proposer.compatible_with(reviewer);
+}
+
+/// whether either of the two still has the other on their blacklist in `round`,
+/// expired entries are passed over where they are looked up rather than removed
+fn blacklisted_between(first: &Individual, second: &Individual, round: u32, config: &MatchingConfig) -> bool {
+	return first.has_blacklisted(second.identity, round, config.blacklist_duration_rounds) ||
+		second.has_blacklisted(first.identity, round, config.blacklist_duration_rounds);
+}
+
+/// whether `score` ties with the worst candidate `reviewer` holds with `capacity`, who then stays.
+/// a score below the reviewer's minimum is no tie, it is turned down
+fn ties_with_held(reviewer: &Individual, score: f32, capacity: usize, config: &MatchingConfig) -> bool {
+	return score >= reviewer.demanded_score() &&
+		reviewer.worst_held_score(capacity).is_some_and(|worst_score| config.ties(score, reviewer.selective(worst_score)));
+}
+
+/// whether either of the two fails a dealbreaker of the other, they are skipped without being scored
+fn dealbreaker_between(first: &Individual, second: &Individual) -> bool {
+	return !first.accepts(second) || !second.accepts(first);
+}
+
+/// add the evaluations and the proposals of a round to everyone's `Individual::times_evaluated`
+/// and `Individual::proposals_received`, by position
+fn count_attention(population: &mut [Individual], evaluations: &[u64], proposals: &[u32]) {
+	for ((individual, &evaluations), &proposals) in population.iter_mut().zip(evaluations).zip(proposals) {
+		individual.times_evaluated += evaluations;
+		individual.proposals_received += proposals;
+	}
+}
+
+/// under `MatchingConfig::record_history`, add the proposal of `proposer` to `reviewer` to the history of both
+fn record(
+	population: &mut [Individual],
+	proposer: usize,
+	reviewer: usize,
+	round: u32,
+	score: Option<f32>,
+	outcome: EncounterOutcome,
+	config: &MatchingConfig
+) {
+	if !config.record_history {
+		return;
+	}
+
+	let (proposer_individual, reviewer_individual) = pair_mut(population, proposer, reviewer);
+	proposer_individual.extras_mut().history.push(Encounter {
+		round: round,
+		other: reviewer_individual.identity,
+		proposed: true,
+		score: score,
+		outcome: outcome
+	});
+	reviewer_individual.extras_mut().history.push(Encounter {
+		round: round,
+		other: proposer_individual.identity,
+		proposed: false,
+		score: score,
+		outcome: outcome
+	});
+}
+
+/// positions of the proposing and the reviewing side of the two-sided algorithms
+fn sides(population: &[Individual], proposing: Gender) -> (Vec<usize>, Vec<usize>) {
+	return (0..population.len()).partition(|&position| population[position].gender == proposing);
+}
+
+/// mutable access to two different individuals of the population at once
+fn pair_mut(
+	population: &mut [Individual],
+	first: usize,
+	second: usize
+) -> (&mut Individual, &mut Individual) {
+	if first < second {
+		let (left, right) = population.split_at_mut(second);
+		return (&mut left[first], &mut right[0]);
+	}
+
+	let (left, right) = population.split_at_mut(first);
+	return (&mut right[0], &mut left[second]);
+}
+
+/// pair the two up through `Sample::liked`, whoever either of them held before
+/// is released so they re-enter the market instead of pointing at someone who moved on
+fn pair_up(
+	population: &mut [Individual],
+	index: &HashMap<PersonId, usize>,
+	proposer: usize,
+	reviewer: usize,
+	reviewer_score: f32,
+	proposer_score: f32,
+	round: u32
+) {
+	let previous_of_reviewer = population[reviewer].candidate;
+	let previous_of_proposer = population[proposer].candidate;
+
+	let (proposer_individual, reviewer_individual) = pair_mut(population, proposer, reviewer);
+	Sample::liked(
+		reviewer_individual,
+		proposer_individual,
+		reviewer_score,
+		proposer_score,
+		round
+	);
+
+	let proposer_identity = population[proposer].identity;
+	let reviewer_identity = population[reviewer].identity;
+
+	// the two may already have been each other's candidate, there is nobody to release then
+	if let Some(previous) = previous_of_reviewer.filter(|previous| *previous != proposer_identity) {
+		release(population, index, previous, reviewer_identity);
+	}
+	if let Some(previous) = previous_of_proposer.filter(|previous| *previous != reviewer_identity) {
+		release(population, index, previous, proposer_identity);
+	}
+}
+
+/// `reviewer` turns `proposer` down in `round` with a `score` `shortfall` below what it takes, 
+/// the proposer blacklists the reviewer, with `symmetric_blacklist` the reviewer does the same. 
+/// under `score_noise` a shortfall within the standard deviation blacklists nobody. an expired entry starts over
+fn reject(proposer: &mut Individual, reviewer: &mut Individual, score: f32, shortfall: f32, config: &MatchingConfig, round: u32) {
+	proposer.rejections_received += 1;
+	reviewer.rejections_given += 1;
+	let best_rejected_score = &mut proposer.extras_mut().best_rejected_score;
+	*best_rejected_score = Some(best_rejected_score.map_or(score, |best| best.max(score)));
+
+	let std_dev = config.score_noise.std_dev();
+	if std_dev > 0.0 && shortfall <= std_dev {
+		return;
+	}
+
+	proposer.extras_mut().blacklist.insert(
+		reviewer.identity,
+		round
+	);
+	if config.symmetric_blacklist {
+		reviewer.extras_mut().blacklist.insert(
+			proposer.identity,
+			round
+		);
+	}
+}
+
+/// `identity` stops holding `released_by`, nothing changes when they do not hold them anymore
+fn release(
+	population: &mut [Individual],
+	index: &HashMap<PersonId, usize>,
+	identity: PersonId,
+	released_by: PersonId
+) {
+	let Some(&position) = index.get(&identity) else {
+		return;
+	};
+
+	let individual = &mut population[position];
+	if individual.drop_candidate(released_by) && individual.candidate.is_none() {
+		individual.matched_in_round = None;
+	}
+}
+
+/// both hold each other next to the candidates they already have, whoever no longer fits
+/// into `capacity` is evicted and released in turn
+#[allow(clippy::too_many_arguments)]
+fn hold_up(
+	population: &mut [Individual],
+	index: &HashMap<PersonId, usize>,
+	proposer: usize,
+	reviewer: usize,
+	reviewer_score: f32,
+	proposer_score: f32,
+	round: u32,
+	capacity: usize
+) {
+	let proposer_identity = population[proposer].identity;
+	let reviewer_identity = population[reviewer].identity;
+	
+	let evicted_by_reviewer = population[reviewer].hold(proposer_identity, reviewer_score, capacity);
+	let evicted_by_proposer = population[proposer].hold(reviewer_identity, proposer_score, capacity);
+	for position in [proposer, reviewer] {
+		population[position].matched_in_round.get_or_insert(round);
+	}
+	
+	if let Some(evicted) = evicted_by_reviewer {
+		release(population, index, evicted, reviewer_identity);
+	}
+	if let Some(evicted) = evicted_by_proposer {
+		release(population, index, evicted, proposer_identity);
+	}
+}
+
+/// one pass over the population, each proposer stops at the first reviewer that accepts them,
+/// reviewers turn down anyone below their `Individual::demanded_score` even while single.
+/// with `mutual_acceptance` a proposer skips the reviewers they do not score
+/// above their current candidate and their own minimum.
+/// with a `candidate_capacity` above 1 a proposer goes on until that many accepted them,
+/// and a full side only takes someone better than the worst candidate it holds.
+/// under `only_unmatched` a proposer holding that many sits their turn out
+pub(crate) fn greedy(
+	population: &mut [Individual],
+	index: &HashMap<PersonId, usize>,
+	config: &MatchingConfig,
+	scorer: Scorer,
+	round: u32,
+	order: &mut ScanOrder,
+	progress: &Progress
+) -> Result<usize, SimulationError> {
+	let proposing = config.proposer_side.proposing_gender(round);
+	let capacity = config.candidate_capacity.max(1);
+	let mut dealbreaker_rejections = 0;
+	let mut evaluations: Vec<u64> = vec![0; population.len()];
+	let mut proposals: Vec<u32> = vec![0; population.len()];
+
+	for position in 0..order.proposers.len() {
+		let proposer = order.proposers[position];
+		if config.only_unmatched && population[proposer].held_candidates().count() >= capacity {
+			progress.inc(1);
+			continue;
+		}
+		let mut accepted = 0;
+		
+		// under an evaluation budget only a few of the admissible reviewers are scanned,
+		// the checks below pass them all again
+		let evaluated: Vec<usize>;
+		let reviewers: &[usize] = if order.evaluation_budget.is_some() {
+			let (admissible, skipped) = admissible_reviewers(
+				population, 
+				proposer, 
+				order.next_reviewers(), 
+				round, 
+				config, 
+				|proposer, reviewer| proposes_to(proposer, reviewer, proposing)
+			);
+			dealbreaker_rejections += skipped;
+			let candidate = population[proposer].candidate.map(|candidate| index[&candidate]);
+			evaluated = order.evaluated(proposer, admissible, candidate);
+			&evaluated
+		} else {
+			order.next_reviewers()
+		};
+		
+		for &reviewer in reviewers {
+			if proposer == reviewer || !proposes_to(&population[proposer], &population[reviewer], proposing) {
+				continue;
+			}
+
+			let (proposer_individual, reviewer_individual) = (&population[proposer], &population[reviewer]);
+
+			if blacklisted_between(proposer_individual, reviewer_individual, round, config) {
+				record(population, proposer, reviewer, round, None, EncounterOutcome::Blacklisted, config);
+				continue;
+			}
+
+			if dealbreaker_between(proposer_individual, reviewer_individual) {
+				dealbreaker_rejections += 1;
+				record(population, proposer, reviewer, round, None, EncounterOutcome::Dealbreaker, config);
+				continue;
+			}
+
+			evaluations[reviewer] += 1;
+			let score = scorer.score(population, reviewer, proposer)?;
+
+			let proposer_score = if config.mutual_acceptance {
+				let proposer_score = scorer.score(population, proposer, reviewer)?;
+
+				// the proposer declines, the reviewer is not better than the one they already have
+				// or falls short of their minimum
+				if proposer_score < proposer_individual.demanded_score() ||
+					proposer_individual.worst_held_score(capacity).is_some_and(|worst_score| {
+						let held = proposer_individual.selective(worst_score);
+						proposer_score <= held || config.ties(proposer_score, held)
+					}) {
+					continue;
+				}
+
+				proposer_score
+			} else {
+				score
+			};
+
+			// if the score ties with the worst candidate the reviewer holds, the reviewer keeps them.
+			// if it is smaller than that candidate or the reviewer's minimum, the reviewer rejects the proposer
+			proposals[reviewer] += 1;
+			if ties_with_held(reviewer_individual, score, capacity, config) {
+				record(population, proposer, reviewer, round, Some(score), EncounterOutcome::Tied, config);
+				continue;
+			}
+			if reviewer_individual.turns_down(score, capacity) {
+				let shortfall = reviewer_individual.shortfall(score, capacity);
+				let (proposer_individual, reviewer_individual) = pair_mut(population, proposer, reviewer);
+				reject(proposer_individual, reviewer_individual, score, shortfall, config, round);
+				record(population, proposer, reviewer, round, Some(score), EncounterOutcome::RejectedBelowCandidate, config);
+				continue;
+			}
+
+			record(population, proposer, reviewer, round, Some(score), EncounterOutcome::Accepted, config);
+			if capacity == 1 {
+				pair_up(
+					population,
+					index,
+					proposer,
+					reviewer,
+					score,
+					proposer_score,
+					round
+				);
+				break;
+			}
+
+			hold_up(
+				population,
+				index,
+				proposer,
+				reviewer,
+				score,
+				proposer_score,
+				round,
+				capacity
+			);
+			accepted += 1;
+			if accepted == capacity {
+				break;
+			}
+		}
+
+		progress.inc(1);
+		progress.set_matched(|| population.iter().filter(|individual| individual.candidate.is_some()).count());
+	}
+	count_attention(population, &evaluations, &proposals);
+
+	return Ok(dealbreaker_rejections);
+}
+
+/// gale-shapley deferred acceptance with the `ProposerSide` proposing,
+/// proposers propose in descending order of their own score of each reviewer
+/// and every reviewer holds on to the best proposer by their score.
+/// both sides keep their own score of the partner.
+/// the matching is rebuilt from scratch, a reviewer that rejects a proposer
+/// ends up on their blacklist so later runs skip them
+pub(crate) fn deferred_acceptance(
+	population: &mut [Individual],
+	config: &MatchingConfig,
+	scorer: Scorer,
+	round: u32,
+	order: &ScanOrder,
+	progress: &Progress
+) -> Result<usize, SimulationError> {
+	let proposing = config.proposer_side.proposing_gender(round);
+	let (proposers, reviewers) = sides(population, proposing);
+	let mut dealbreaker_rejections = 0;
+
+	let mut evaluations: Vec<u64> = vec![0; population.len()];
+	let mut proposals: Vec<u32> = vec![0; population.len()];
+
+	// each proposer's admissible reviewers ordered by their preference, ties broken by position
+	let mut proposal_orders: Vec<Vec<usize>> = vec![Vec::new(); population.len()];
+	for &proposer in &proposers {
+		let mut scored_reviewers: Vec<(usize, f32)> = Vec::new();
+
+		for &reviewer in &reviewers {
+			let (proposer_individual, reviewer_individual) = (&population[proposer], &population[reviewer]);
+			if !proposer_individual.compatible_with(reviewer_individual) {
+				continue;
+			}
+
+			if blacklisted_between(proposer_individual, reviewer_individual, round, config) {
+				record(population, proposer, reviewer, round, None, EncounterOutcome::Blacklisted, config);
+				continue;
+			}
+
+			if dealbreaker_between(proposer_individual, reviewer_individual) {
+				dealbreaker_rejections += 1;
+				record(population, proposer, reviewer, round, None, EncounterOutcome::Dealbreaker, config);
+				continue;
+			}
+
+			evaluations[reviewer] += 1;
+			let proposer_score = scorer.score(population, proposer, reviewer)?;
+			// the proposer would not accept them either way
+			if proposer_score < proposer_individual.demanded_score() {
+				continue;
+			}
+
+			scored_reviewers.push((reviewer, proposer_score));
+		}
+
+		scored_reviewers.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+		proposal_orders[proposer] = scored_reviewers.into_iter().map(|(reviewer, _)| reviewer).collect();
+	}
+
+	// the proposer each reviewer currently holds, together with the reviewer's score of them
+	let mut held: Vec<Option<(usize, f32)>> = vec![None; population.len()];
+	let mut next_proposal: Vec<usize> = vec![0; population.len()];
+	// popped from the back, so the first in the scan order proposes first
+	let mut free_proposers: Vec<usize> = order.proposers
+		.iter()
+		.rev()
+		.copied()
+		.filter(|&proposer| population[proposer].gender == proposing)
+		.collect();
+
+	while let Some(proposer) = free_proposers.pop() {
+		let Some(&reviewer) = proposal_orders[proposer].get(next_proposal[proposer]) else {
+			// the proposer has proposed to everyone they could and stays single
+			progress.inc(1);
+			continue;
+		};
+		next_proposal[proposer] += 1;
+		proposals[reviewer] += 1;
+
+		let score = scorer.score(population, reviewer, proposer)?;
+
+		// the one turned down with the reviewer's score of them and how far it fell short of the one that was kept
+		let rejected = match held[reviewer] {
+			_ if score < population[reviewer].demanded_score() => Some((proposer, score, population[reviewer].demanded_score() - score)),
+			// a tie keeps the one held, the proposer moves on without blacklisting them
+			Some((_, held_score)) if config.ties(score, held_score) => {
+				record(population, proposer, reviewer, round, Some(score), EncounterOutcome::Tied, config);
+				free_proposers.push(proposer);
+				None
+			},
+			Some((_, held_score)) if held_score >= score => Some((proposer, score, held_score - score)),
+			Some((held_proposer, held_score)) => {
+				held[reviewer] = Some((proposer, score));
+				Some((held_proposer, held_score, score - held_score))
+			},
+			None => {
+				held[reviewer] = Some((proposer, score));
+				None
+			}
+		};
+
+		if held[reviewer].is_some_and(|(held_proposer, _)| held_proposer == proposer) {
+			record(population, proposer, reviewer, round, Some(score), EncounterOutcome::Accepted, config);
+		}
+		if let Some((rejected, rejected_score, shortfall)) = rejected {
+			let (rejected_individual, reviewer_individual) = pair_mut(population, rejected, reviewer);
+			reject(rejected_individual, reviewer_individual, rejected_score, shortfall, config, round);
+			record(population, rejected, reviewer, round, Some(rejected_score), EncounterOutcome::RejectedBelowCandidate, config);
+			free_proposers.push(rejected);
+		}
+	}
+	count_attention(population, &evaluations, &proposals);
+
+	// everyone who is not held by their previous partner again is released,
+	// pairs that held together keep the round they formed in
+	let mut partners: Vec<Option<usize>> = vec![None; population.len()];
+	for (reviewer, held_proposer) in held.iter().enumerate() {
+		if let Some((proposer, _)) = *held_proposer {
+			partners[reviewer] = Some(proposer);
+			partners[proposer] = Some(reviewer);
+		}
+	}
+	let identities: Vec<Option<PersonId>> = partners
+		.iter()
+		.map(|partner| partner.map(|partner| population[partner].identity))
+		.collect();
+	for (individual, partner) in population.iter_mut().zip(identities) {
+		if individual.candidate != partner {
+			individual.candidate = None;
+			individual.candidate_score = None;
+			individual.matched_in_round = None;
+		}
+	}
+
+	for (reviewer, held_proposer) in held.into_iter().enumerate() {
+		if let Some((proposer, score)) = held_proposer {
+			let proposer_score = scorer.score(population, proposer, reviewer)?;
+
+			let (proposer_individual, reviewer_individual) = pair_mut(population, proposer, reviewer);
+			Sample::liked(
+				reviewer_individual,
+				proposer_individual,
+				score,
+				proposer_score,
+				round
+			);
+			progress.inc(1);
+		}
+	}
+	progress.set_matched(|| population.iter().filter(|individual| individual.candidate.is_some()).count());
+
+	return Ok(dealbreaker_rejections);
+}
+
+/// a proposal of one proposer in `parallel_proposal`
+struct Proposal {
+	proposer: usize,
+	reviewer: usize,
+	// the reviewer's score of the proposer
+	reviewer_score: f32,
+	// the proposer's score of the reviewer
+	proposer_score: f32
+}
+
+/// what one proposer of `parallel_proposal` found: their best proposal, the reviewers who turned them down
+/// with their score and its shortfall, how many dealbreakers they ran into and the reviewers they evaluated
+type Scan = (usize, Option<Proposal>, Vec<(usize, f32, f32)>, usize, Vec<usize>);
+
+/// one round in two phases so the expensive scoring runs on all cores.
+/// first every proposer of the `ProposerSide`, in parallel, scores all reviewers and proposes 
+/// to the one they like best among those who would take them over their current candidate,
+/// blacklisting the ones who would not. then every reviewer keeps their best proposer
+/// and the other proposers blacklist them, the proposers they let go are released. ties are resolved by identity,
+/// so the outcome does not depend on the number of threads
+pub(crate) fn parallel_proposal(
+	population: &mut [Individual],
+	index: &HashMap<PersonId, usize>,
+	config: &MatchingConfig,
+	scorer: Scorer,
+	round: u32,
+	order: &ScanOrder,
+	progress: &Progress
+) -> Result<usize, SimulationError> {
+	let proposing = config.proposer_side.proposing_gender(round);
+	let (proposers, reviewers) = sides(population, proposing);
+	let individuals: &[Individual] = population;
+
+	// the proposal of every proposer, plus the reviewers that rejected them during the scan
+	// and the number of reviewers skipped over a dealbreaker
+	let scans: Vec<Scan> = proposers
+		.par_iter()
+		.map(|&proposer| -> Result<Scan, ScoreError> {
+			let proposer_individual = &individuals[proposer];
+			let mut best: Option<Proposal> = None;
+			// the reviewers who turned the proposer down, with their score and its shortfall
+			let mut rejected_by: Vec<(usize, f32, f32)> = Vec::new();
+			let mut dealbreaker_rejections = 0;
+			let mut evaluated_reviewers: Vec<usize> = Vec::new();
+
+			// as in the greedy scan, an evaluation budget narrows the reviewers down up-front
+			let evaluated: Vec<usize>;
+			let scanned: &[usize] = if order.evaluation_budget.is_some() {
+				let (admissible, skipped) = admissible_reviewers(
+					individuals, 
+					proposer, 
+					&reviewers, 
+					round, 
+					config, 
+					|proposer, reviewer| proposer.compatible_with(reviewer)
+				);
+				dealbreaker_rejections += skipped;
+				let candidate = proposer_individual.candidate.map(|candidate| index[&candidate]);
+				evaluated = order.evaluated(proposer, admissible, candidate);
+				&evaluated
+			} else {
+				&reviewers
+			};
+
+			for &reviewer in scanned {
+				let reviewer_individual = &individuals[reviewer];
+				if !proposer_individual.compatible_with(reviewer_individual) ||
+					blacklisted_between(proposer_individual, reviewer_individual, round, config) {
+					continue;
+				}
+
+				if dealbreaker_between(proposer_individual, reviewer_individual) {
+					dealbreaker_rejections += 1;
+					continue;
+				}
+
+				evaluated_reviewers.push(reviewer);
+				let reviewer_score = scorer.score(individuals, reviewer, proposer)?;
+
+				// the reviewer keeps a partner the proposer ties with, neither blacklists the other
+				if ties_with_held(reviewer_individual, reviewer_score, 1, config) {
+					continue;
+				}
+				if reviewer_individual.turns_down(reviewer_score, 1) {
+					rejected_by.push((reviewer, reviewer_score, reviewer_individual.shortfall(reviewer_score, 1)));
+					continue;
+				}
+
+				let proposer_score = scorer.score(individuals, proposer, reviewer)?;
+
+				if proposer_score < proposer_individual.demanded_score() {
+					continue;
+				}
+
+				let better = match &best {
+					Some(proposal) => config.beats(
+						proposer_score,
+						reviewer_individual.identity,
+						proposal.proposer_score,
+						individuals[proposal.reviewer].identity
+					),
+					None => true
+				};
+
+				if better {
+					best = Some(Proposal {
+						proposer: proposer,
+						reviewer: reviewer,
+						reviewer_score: reviewer_score,
+						proposer_score: proposer_score
+					});
+				}
+			}
+
+			progress.inc(1);
+
+			return Ok((proposer, best, rejected_by, dealbreaker_rejections, evaluated_reviewers));
+		})
+		.collect::<Result<Vec<Scan>, ScoreError>>()?;
+
+	let mut proposals: Vec<Proposal> = Vec::new();
+	let mut dealbreaker_rejections = 0;
+	for (proposer, proposal, rejected_by, skipped, evaluated_reviewers) in scans {
+		dealbreaker_rejections += skipped;
+		for reviewer in evaluated_reviewers {
+			population[reviewer].times_evaluated += 1;
+		}
+
+		// the ones who turned the proposer down during the scan were proposed to as well
+		for (reviewer, score, shortfall) in rejected_by {
+			population[reviewer].proposals_received += 1;
+			let (proposer_individual, reviewer_individual) = pair_mut(population, proposer, reviewer);
+			reject(proposer_individual, reviewer_individual, score, shortfall, config, round);
+			record(population, proposer, reviewer, round, Some(score), EncounterOutcome::RejectedBelowCandidate, config);
+		}
+
+		if let Some(proposal) = &proposal {
+			population[proposal.reviewer].proposals_received += 1;
+		}
+		proposals.extend(proposal);
+	}
+
+	// the proposal each reviewer keeps
+	let mut accepted: Vec<Option<Proposal>> = (0..population.len()).map(|_| None).collect();
+	let mut rejected: Vec<Proposal> = Vec::new();
+
+	for proposal in proposals {
+		let reviewer = proposal.reviewer;
+
+		let better = match &accepted[reviewer] {
+			Some(kept) => config.beats(
+				proposal.reviewer_score,
+				population[proposal.proposer].identity,
+				kept.reviewer_score,
+				population[kept.proposer].identity
+			),
+			None => true
+		};
+
+		if better {
+			if let Some(kept) = accepted[reviewer].replace(proposal) {
+				rejected.push(kept);
+			}
+		} else {
+			rejected.push(proposal);
+		}
+	}
+
+	for proposal in rejected {
+		// every rejected proposal lost to the one the reviewer kept, on a tie without blacklisting
+		let kept_score = accepted[proposal.reviewer].as_ref().map(|kept| kept.reviewer_score);
+		if kept_score.is_some_and(|kept_score| config.ties(proposal.reviewer_score, kept_score)) {
+			record(
+				population,
+				proposal.proposer,
+				proposal.reviewer,
+				round,
+				Some(proposal.reviewer_score),
+				EncounterOutcome::Tied,
+				config
+			);
+			continue;
+		}
+		let shortfall = kept_score.map_or(0.0, |kept_score| kept_score - proposal.reviewer_score);
+		let (proposer_individual, reviewer_individual) = pair_mut(population, proposal.proposer, proposal.reviewer);
+		reject(proposer_individual, reviewer_individual, proposal.reviewer_score, shortfall, config, round);
+		record(
+			population,
+			proposal.proposer,
+			proposal.reviewer,
+			round,
+			Some(proposal.reviewer_score),
+			EncounterOutcome::RejectedBelowCandidate,
+			config
+		);
+	}
+
+	for proposal in accepted.into_iter().flatten() {
+		record(population, proposal.proposer, proposal.reviewer, round, Some(proposal.reviewer_score), EncounterOutcome::Accepted, config);
+		pair_up(
+			population,
+			index,
+			proposal.proposer,
+			proposal.reviewer,
+			proposal.reviewer_score,
+			proposal.proposer_score,
+			round
+		);
+	}
+	progress.set_matched(|| population.iter().filter(|individual| individual.candidate.is_some()).count());
+
+	return Ok(dealbreaker_rejections);
+}
+
+/// whether the individual at `position` and their candidate name each other
+fn in_pair(population: &[Individual], index: &HashMap<PersonId, usize>, position: usize) -> bool {
+	let individual = &population[position];
+
+	return individual.candidate
+		.and_then(|candidate| index.get(&candidate))
+		.is_some_and(|&partner| population[partner].candidate == Some(individual.identity));
+}
+
+/// the proposers and the reviewers of the two-sided algorithms who are not in a pair, each side shuffled with `rng`
+fn shuffled_singles(
+	population: &[Individual],
+	index: &HashMap<PersonId, usize>,
+	proposing: Gender,
+	rng: &mut StdRng
+) -> (Vec<usize>, Vec<usize>) {
+	let (proposers, reviewers) = sides(population, proposing);
+
+	let mut single_proposers: Vec<usize> = proposers
+		.into_iter()
+		.filter(|&proposer| !in_pair(population, index, proposer))
+		.collect();
+	let mut single_reviewers: Vec<usize> = reviewers
+		.into_iter()
+		.filter(|&reviewer| !in_pair(population, index, reviewer))
+		.collect();
+	single_proposers.shuffle(rng);
+	single_reviewers.shuffle(rng);
+
+	return (single_proposers, single_reviewers);
+}
+
+/// pairs stay together, every proposer of the `ProposerSide` without a partner is paired
+/// with a reviewer drawn at random among the compatible ones without a partner, drawing from `seed`.
+/// scores, thresholds, dealbreakers and blacklists play no part in who is paired,
+/// the two still score each other so the statistics compare with the other algorithms
+pub(crate) fn random(
+	population: &mut [Individual],
+	index: &HashMap<PersonId, usize>,
+	config: &MatchingConfig,
+	scorer: Scorer,
+	round: u32,
+	seed: u64,
+	progress: &Progress
+) -> Result<usize, SimulationError> {
+	let mut rng = StdRng::seed_from_u64(seed);
+	let proposing = config.proposer_side.proposing_gender(round);
+	let (single_proposers, mut single_reviewers) = shuffled_singles(population, index, proposing, &mut rng);
+
+	for proposer in single_proposers {
+		let drawn = single_reviewers
+			.iter()
+			.position(|&reviewer| population[proposer].compatible_with(&population[reviewer]));
+		progress.inc(1);
+		let Some(drawn) = drawn else {
+			continue;
+		};
+		let reviewer = single_reviewers.swap_remove(drawn);
+		population[reviewer].proposals_received += 1;
+
+		let reviewer_score = scorer.score(population, reviewer, proposer)?;
+		let proposer_score = scorer.score(population, proposer, reviewer)?;
+		record(population, proposer, reviewer, round, Some(reviewer_score), EncounterOutcome::Accepted, config);
+		pair_up(
+			population,
+			index,
+			proposer,
+			reviewer,
+			reviewer_score,
+			proposer_score,
+			round
+		);
+	}
+	progress.set_matched(|| population.iter().filter(|individual| individual.candidate.is_some()).count());
+
+	return Ok(0);
+}
+
+/// pairs stay together, every proposer of the `ProposerSide` without a partner meets one reviewer without a partner,
+/// drawn at random among the compatible ones neither of them has blacklisted, drawing from `seed`. 
+/// whoever is left over sits the round out. the two score each other and pair up unless one of them
+/// turns the other down, the one turned down blacklists the other as a rejected proposer would
+pub(crate) fn speed_dating(
+	population: &mut [Individual],
+	index: &HashMap<PersonId, usize>,
+	config: &MatchingConfig,
+	scorer: Scorer,
+	round: u32,
+	seed: u64,
+	progress: &Progress
+) -> Result<usize, SimulationError> {
+	let mut rng = StdRng::seed_from_u64(seed);
+	let proposing = config.proposer_side.proposing_gender(round);
+	let (single_proposers, mut single_reviewers) = shuffled_singles(population, index, proposing, &mut rng);
+	let mut dealbreaker_rejections = 0;
+
+	for proposer in single_proposers {
+		let drawn = single_reviewers
+			.iter()
+			.position(|&reviewer| {
+				let (proposer_individual, reviewer_individual) = (&population[proposer], &population[reviewer]);
+
+				return proposer_individual.compatible_with(reviewer_individual) &&
+					!blacklisted_between(proposer_individual, reviewer_individual, round, config);
+			});
+		progress.inc(1);
+		let Some(drawn) = drawn else {
+			continue;
+		};
+		let reviewer = single_reviewers.swap_remove(drawn);
+
+		if dealbreaker_between(&population[proposer], &population[reviewer]) {
+			dealbreaker_rejections += 1;
+			record(population, proposer, reviewer, round, None, EncounterOutcome::Dealbreaker, config);
+			continue;
+		}
+
+		population[reviewer].times_evaluated += 1;
+		population[reviewer].proposals_received += 1;
+		let reviewer_score = scorer.score(population, reviewer, proposer)?;
+		let proposer_score = scorer.score(population, proposer, reviewer)?;
+		// either side may still hold someone who does not hold them back, and keeps them on a tie
+		if ties_with_held(&population[reviewer], reviewer_score, 1, config) || ties_with_held(&population[proposer], proposer_score, 1, config) {
+			record(population, proposer, reviewer, round, Some(reviewer_score), EncounterOutcome::Tied, config);
+			continue;
+		}
+		let reviewer_declines = population[reviewer].turns_down(reviewer_score, 1);
+		let proposer_declines = population[proposer].turns_down(proposer_score, 1);
+
+		if reviewer_declines {
+			let shortfall = population[reviewer].shortfall(reviewer_score, 1);
+			let (proposer_individual, reviewer_individual) = pair_mut(population, proposer, reviewer);
+			reject(proposer_individual, reviewer_individual, reviewer_score, shortfall, config, round);
+		}
+		if proposer_declines {
+			let shortfall = population[proposer].shortfall(proposer_score, 1);
+			let (reviewer_individual, proposer_individual) = pair_mut(population, reviewer, proposer);
+			reject(reviewer_individual, proposer_individual, proposer_score, shortfall, config, round);
+		}
+		if reviewer_declines || proposer_declines {
+			record(population, proposer, reviewer, round, Some(reviewer_score), EncounterOutcome::RejectedBelowCandidate, config);
+			continue;
+		}
+
+		record(population, proposer, reviewer, round, Some(reviewer_score), EncounterOutcome::Accepted, config);
+		pair_up(
+			population,
+			index,
+			proposer,
+			reviewer,
+			reviewer_score,
+			proposer_score,
+			round
+		);
+	}
+	progress.set_matched(|| population.iter().filter(|individual| individual.candidate.is_some()).count());
+
+	return Ok(dealbreaker_rejections);
+}
+
+/// everyone is paired anew with the pairing that maximizes the sum of the scores both sides
+/// of every pair give each other, among pairs who are compatible, not blacklisted, pass each other's
+/// dealbreakers and score each other at least their `Individual::demanded_score`.
+/// pairs that are part of the new pairing keep the round they formed in
+pub(crate) fn optimal(
+	population: &mut [Individual],
+	config: &MatchingConfig,
+	scorer: Scorer,
+	round: u32,
+	progress: &Progress
+) -> Result<usize, SimulationError> {
+	if population.len() > config.optimal_max_population {
+		return Err(
+			SimulationError::InvalidConfig(format!(
+				"the optimal algorithm takes O(n³) time, the population of {} is above the optimal_max_population of {}",
+				population.len(),
+				config.optimal_max_population
+			))
+		);
+	}
+
+	let proposing = config.proposer_side.proposing_gender(round);
+	let (proposers, reviewers) = sides(population, proposing);
+	let mut dealbreaker_rejections = 0;
+
+	// the sum of the two scores of every admissible pair, a proposer per row and a reviewer per column
+	let mut weights: Vec<Vec<Option<f64>>> = Vec::with_capacity(proposers.len());
+	for &proposer in &proposers {
+		let proposer_individual = &population[proposer];
+		let mut row: Vec<Option<f64>> = Vec::with_capacity(reviewers.len());
+
+		for &reviewer in &reviewers {
+			let reviewer_individual = &population[reviewer];
+			if !proposer_individual.compatible_with(reviewer_individual) ||
+				blacklisted_between(proposer_individual, reviewer_individual, round, config) {
+				row.push(None);
+				continue;
+			}
+
+			if dealbreaker_between(proposer_individual, reviewer_individual) {
+				dealbreaker_rejections += 1;
+				row.push(None);
+				continue;
+			}
+
+			let proposer_score = scorer.score(population, proposer, reviewer)?;
+			let reviewer_score = scorer.score(population, reviewer, proposer)?;
+			if proposer_score < proposer_individual.demanded_score() ||
+				reviewer_score < reviewer_individual.demanded_score() {
+				row.push(None);
+				continue;
+			}
+
+			row.push(Some(proposer_score as f64 + reviewer_score as f64));
+		}
+
+		weights.push(row);
+		progress.inc(1);
+	}
+
+	let pairs: Vec<(usize, usize)> = assignment::max_weight_assignment(&weights)
+		.into_iter()
+		.enumerate()
+		.filter_map(|(row, column)| column.map(|column| (proposers[row], reviewers[column])))
+		.collect();
+
+	// everyone who is not paired with their previous partner again is released
+	let mut partners: Vec<Option<usize>> = vec![None; population.len()];
+	for &(proposer, reviewer) in &pairs {
+		partners[proposer] = Some(reviewer);
+		partners[reviewer] = Some(proposer);
+	}
+	let identities: Vec<Option<PersonId>> = partners
+		.iter()
+		.map(|partner| partner.map(|partner| population[partner].identity))
+		.collect();
+	for (individual, partner) in population.iter_mut().zip(identities) {
+		if individual.candidate != partner {
+			individual.candidate = None;
+			individual.candidate_score = None;
+			individual.matched_in_round = None;
+		}
+	}
+
+	for (proposer, reviewer) in pairs {
+		let reviewer_score = scorer.score(population, reviewer, proposer)?;
+		let proposer_score = scorer.score(population, proposer, reviewer)?;
+
+		let (proposer_individual, reviewer_individual) = pair_mut(population, proposer, reviewer);
+		Sample::liked(
+			reviewer_individual,
+			proposer_individual,
+			reviewer_score,
+			proposer_score,
+			round
+		);
+	}
+	progress.set_matched(|| population.iter().filter(|individual| individual.candidate.is_some()).count());
+
+	return Ok(dealbreaker_rejections);
+}
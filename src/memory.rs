@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::individual::{Individual, IndividualExtras};
+
+/// the heap a hash map of `capacity` entries takes: a slot per entry and a control byte
+pub(crate) fn map_bytes<K, V>(map: &HashMap<K, V>) -> usize {
+	return map.capacity() * (std::mem::size_of::<(K, V)>() + 1);
+}
+
+pub(crate) fn vec_bytes<T>(values: &Vec<T>) -> usize {
+	return values.capacity() * std::mem::size_of::<T>();
+}
+
+/// an estimate of the memory a `Sample` takes, in bytes, see `Sample::memory_estimate`.
+/// it adds up the capacities of every allocation, what the allocator adds on top is left out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct MemoryReport {
+	// in the pool, juveniles and retired pairs aside
+	pub individuals: usize,
+	// the `Individual`s themselves, the population vector with what it has spare
+	pub population: usize,
+	// the weights, ratings and percentile ratings of everyone
+	pub weights_and_ratings: usize,
+	pub blacklists: usize,
+	// the display names
+	pub names: usize,
+	// the identities kept in the table of `PersonId` while the sample holds them, those neither uuids nor of up to 15 bytes
+	pub identities: usize,
+	// the extras of those who have any, candidates held on top of the best one, dealbreakers and categories
+	pub other_attributes: usize,
+	pub history: usize,
+	// the position of every identity and the cached scores of `MatchingConfig::score_cache`
+	pub index: usize,
+	pub score_cache: usize,
+	// juveniles, retired pairs and anything else the sample holds
+	pub rest: usize
+}
+
+impl MemoryReport {
+	/// add what `individual` allocates besides the `Individual` itself
+	pub(crate) fn add(&mut self, individual: &Individual) {
+		let extras = individual.extras();
+		self.individuals += 1;
+		self.weights_and_ratings += vec_bytes(&individual.preference_weights)
+			+ vec_bytes(&individual.ratings)
+			+ vec_bytes(&extras.percentile_ratings);
+		self.blacklists += map_bytes(&extras.blacklist);
+		self.names += individual.display_name.as_ref().map_or(0, String::capacity);
+		// uuids and short identities live in the `PersonId` itself
+		self.identities += individual.identity.table_bytes();
+		if individual.has_extras() {
+			self.other_attributes += std::mem::size_of::<IndividualExtras>();
+		}
+		self.other_attributes += vec_bytes(&extras.provisional_candidates)
+			+ vec_bytes(&extras.dealbreakers)
+			+ vec_bytes(&extras.categories);
+		self.history += vec_bytes(&extras.history);
+	}
+
+	pub fn total(&self) -> usize {
+		return self.population
+			+ self.weights_and_ratings
+			+ self.blacklists
+			+ self.names
+			+ self.identities
+			+ self.other_attributes
+			+ self.history
+			+ self.index
+			+ self.score_cache
+			+ self.rest;
+	}
+
+	/// the total over the individuals, 0 without any
+	pub fn per_individual(&self) -> usize {
+		return self.total().checked_div(self.individuals).unwrap_or_default();
+	}
+}
+
+fn megabytes(bytes: usize) -> f64 {
+	return bytes as f64 / (1024.0 * 1024.0);
+}
+
+impl std::fmt::Display for MemoryReport {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(
+			f,
+			"Estimated memory of {} individuals: {:.1} MB, {} bytes each",
+			self.individuals,
+			megabytes(self.total()),
+			self.per_individual()
+		)?;
+		for (name, bytes) in [
+			("Individuals", self.population),
+			("Weights and ratings", self.weights_and_ratings),
+			("Blacklists", self.blacklists),
+			("Names", self.names),
+			("Identities", self.identities),
+			("Other attributes", self.other_attributes),
+			("History", self.history),
+			("Index", self.index),
+			("Score cache", self.score_cache),
+			("Rest", self.rest)
+		] {
+			if bytes > 0 {
+				writeln!(f, "  {:<20}{:>10.1} MB", name, megabytes(bytes))?;
+			}
+		}
+
+		return Ok(());
+	}
+}
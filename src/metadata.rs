@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::SampleConfig;
+use crate::error::SimulationError;
+use crate::matching::MatchingAlgorithm;
+
+/// what produced an export, written at the top of the json export, of checkpoints and of the csv exports.
+/// see `Sample::metadata`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunMetadata {
+	pub crate_version: String,
+	// seconds since the unix epoch when the metadata was taken,
+	// `SOURCE_DATE_EPOCH` instead when it is set, so that seeded runs can write identical files
+	pub timestamp: u64,
+	pub seed: u64,
+	// `None` for samples made up of given individuals
+	pub sample_config: Option<SampleConfig>,
+	pub algorithm: MatchingAlgorithm,
+	pub rounds_executed: u32,
+	// `git describe` of the checkout the crate was built from, `None` outside of one
+	#[serde(default)]
+	pub git_describe: Option<String>
+}
+
+/// seconds since the unix epoch, `SystemTime` has no clock on `wasm32-unknown-unknown` and panics there
+#[cfg(not(target_arch = "wasm32"))]
+fn unix_seconds() -> u64 {
+	return std::time::SystemTime::now()
+		.duration_since(std::time::UNIX_EPOCH)
+		.map(|elapsed| elapsed.as_secs())
+		.unwrap_or_default();
+}
+
+/// seconds since the unix epoch by the clock of the browser
+#[cfg(target_arch = "wasm32")]
+fn unix_seconds() -> u64 {
+	return (js_sys::Date::now() / 1000.0) as u64;
+}
+
+impl RunMetadata {
+	/// the metadata of a sample as of now
+	pub(crate) fn new(seed: u64, sample_config: Option<SampleConfig>, algorithm: MatchingAlgorithm, rounds_executed: u32) -> Self {
+		let timestamp = std::env::var("SOURCE_DATE_EPOCH")
+			.ok()
+			.and_then(|epoch| epoch.trim().parse().ok())
+			.unwrap_or_else(unix_seconds);
+
+		return RunMetadata {
+			crate_version: String::from(env!("CARGO_PKG_VERSION")),
+			timestamp: timestamp,
+			seed: seed,
+			sample_config: sample_config,
+			algorithm: algorithm,
+			rounds_executed: rounds_executed,
+			git_describe: option_env!("DATING_SIMULATION_GIT_DESCRIBE").map(String::from)
+		};
+	}
+
+	/// write the metadata as `# name: value` lines, the sample config as one line of json,
+	/// to go before the header of a csv
+	pub fn write_csv_comment(&self, mut writer: impl std::io::Write) -> Result<(), SimulationError> {
+		writeln!(writer, "# crate_version: {}", self.crate_version)?;
+		if let Some(git_describe) = &self.git_describe {
+			writeln!(writer, "# git_describe: {}", git_describe)?;
+		}
+		writeln!(writer, "# timestamp: {}", self.timestamp)?;
+		writeln!(writer, "# seed: {}", self.seed)?;
+		writeln!(writer, "# algorithm: {}", self.algorithm)?;
+		writeln!(writer, "# rounds_executed: {}", self.rounds_executed)?;
+		if let Some(sample_config) = &self.sample_config {
+			writeln!(writer, "# sample_config: {}", serde_json::to_string(sample_config)?)?;
+		}
+
+		return Ok(());
+	}
+}
@@ -0,0 +1,161 @@
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::config::SimulationConfig;
+use crate::error::SimulationError;
+use crate::progress::Progress;
+use crate::statistics::{AggregateStatistics, Statistics, StopReason};
+
+/// the outcome of one of the runs of `MonteCarlo`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonteCarloRun {
+	pub seed: u64,
+	pub rounds_executed: u32,
+	pub stop_reason: StopReason,
+	// the statistics after the last round
+	pub statistics: Statistics
+}
+
+impl MonteCarloRun {
+	pub const CSV_HEADER: &'static str = "seed,rounds_executed,stop_reason,total_population,matched,match_rate,mean_matched_score,median_matched_score,unmatched_males,unmatched_females,unmatched_non_binary";
+
+	/// append this run as one csv row, write `MonteCarloRun::CSV_HEADER` first
+	pub fn export_csv(
+		&self,
+		mut writer: impl std::io::Write
+	) -> Result<(), SimulationError> {
+		let statistics = &self.statistics;
+
+		writeln!(
+			writer,
+			"{},{},{},{},{},{},{},{},{},{},{}",
+			self.seed,
+			self.rounds_executed,
+			self.stop_reason.as_str(),
+			statistics.total_population,
+			statistics.matched_males + statistics.matched_females + statistics.matched_non_binary,
+			statistics.match_rate().map(|rate| rate.to_string()).unwrap_or_default(),
+			statistics.mean_candidate_score.map(|score| score.to_string()).unwrap_or_default(),
+			statistics.median_candidate_score.map(|score| score.to_string()).unwrap_or_default(),
+			statistics.unmatched_males,
+			statistics.unmatched_females,
+			statistics.unmatched_non_binary
+		)?;
+
+		return Ok(());
+	}
+}
+
+/// outcome of `MonteCarlo::run`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonteCarloReport {
+	// in the order `MonteCarlo::seeds` drew their seeds, whichever finished first
+	pub runs: Vec<MonteCarloRun>,
+	pub aggregate: AggregateStatistics
+}
+
+impl MonteCarloReport {
+	/// write the aggregate and every run, statistics included, as json
+	#[cfg(feature = "fs")]
+	pub fn to_json_file(
+		&self, 
+		path: &std::path::Path
+	) -> Result<(), SimulationError> {
+		let file = std::fs::File::create(path)?;
+		serde_json::to_writer_pretty(
+			std::io::BufWriter::new(file), 
+			self
+		)?;
+
+		return Ok(());
+	}
+}
+
+/// `runs` independent simulations of `config`, each on its own population.
+/// the seeds of the runs are drawn from `config.seed`, the same master seed repeats every run
+#[derive(Debug, Clone)]
+pub struct MonteCarlo {
+	pub config: SimulationConfig,
+	pub runs: usize,
+	// whether to draw a progress bar over the runs, the runs themselves draw none
+	pub show_progress: bool
+}
+
+impl MonteCarlo {
+	pub fn new(config: SimulationConfig, runs: usize) -> Self {
+		return MonteCarlo { config: config, runs: runs, show_progress: false };
+	}
+
+	/// the seed of every run, drawn from the master seed or a random one when the config has none
+	pub fn seeds(&self) -> Vec<u64> {
+		let master_seed = self.config.seed.unwrap_or_else(|| rand::rngs::OsRng.r#gen());
+		let mut rng = StdRng::seed_from_u64(master_seed);
+
+		return (0..self.runs).map(|_| rng.r#gen()).collect();
+	}
+
+	/// simulate every run in parallel and summarize their final statistics
+	pub fn run(&self) -> Result<MonteCarloReport, SimulationError> {
+		self.config.validate()?;
+
+		let progress = Progress::counting(self.runs as u64, "runs", self.show_progress);
+		let runs = self.seeds()
+			.into_par_iter()
+			.map(|seed| -> Result<MonteCarloRun, SimulationError> {
+				let run = self.run_once(seed)?;
+				progress.inc(1);
+				progress.set_message(|| format!(
+					"seed {} matched {:.1}%",
+					seed,
+					run.statistics.match_rate().unwrap_or_default() * 100.0
+				));
+				tracing::debug!(seed, rounds = run.rounds_executed, "finished a run");
+
+				return Ok(run);
+			})
+			.collect::<Result<Vec<MonteCarloRun>, SimulationError>>()?;
+		progress.finish(|elapsed| format!("{} runs in {:.1}s", self.runs, elapsed));
+
+		let statistics: Vec<Statistics> = runs
+			.iter()
+			.filter(|run| run.stop_reason != StopReason::Unpairable)
+			.map(|run| run.statistics.clone())
+			.collect();
+		let mut aggregate = AggregateStatistics::new(&statistics);
+		aggregate.unpairable_runs = runs.len() - statistics.len();
+
+		return Ok(MonteCarloReport {
+			aggregate: aggregate,
+			runs: runs
+		});
+	}
+
+	/// one simulation of the config seeded with `seed`. a pool that cannot be paired, e.g. a small one drawn
+	/// without anyone of a gender, ends the run as `StopReason::Unpairable` rather than failing all of them
+	fn run_once(&self, seed: u64) -> Result<MonteCarloRun, SimulationError> {
+		let config = SimulationConfig {
+			seed: Some(seed),
+			..self.config.clone()
+		};
+
+		let mut sample = config.generate_sample()?;
+		let (rounds_executed, stop_reason) = match sample.run(self.config.rounds) {
+			Ok(report) => (report.rounds_executed, report.stop_reason),
+			Err(error @ SimulationError::EmptyPopulation { .. }) => {
+				tracing::warn!(seed, "{}, the run is left out of the aggregate", error);
+
+				(sample.round, StopReason::Unpairable)
+			},
+			Err(error) => return Err(error)
+		};
+
+		return Ok(MonteCarloRun {
+			seed: seed,
+			rounds_executed: rounds_executed,
+			stop_reason: stop_reason,
+			statistics: sample.statistics()
+		});
+	}
+}
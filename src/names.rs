@@ -0,0 +1,31 @@
+// 64 of each, so that a byte of the identity picks either half of a name
+const FIRST_NAMES: [&str; 64] = [
+	"Alice", "Amara", "Ben", "Bianca", "Carlos", "Chloe", "Daniel", "Dara",
+	"Elena", "Eli", "Farah", "Felix", "Grace", "Gus", "Hana", "Hugo",
+	"Ines", "Ivan", "Jade", "Jonas", "Kai", "Keira", "Leo", "Lina",
+	"Maya", "Milo", "Nadia", "Noah", "Olga", "Omar", "Paula", "Pavel",
+	"Quinn", "Rafael", "Rosa", "Ravi", "Sana", "Sam", "Tara", "Theo",
+	"Uma", "Ulrich", "Vera", "Victor", "Wen", "Wes", "Ximena", "Xavier",
+	"Yara", "Yusuf", "Zoe", "Zane", "Aiko", "Arjun", "Bea", "Bruno",
+	"Cora", "Dev", "Esme", "Ezra", "Freya", "Gil", "Iris", "Jun"
+];
+
+const LAST_NAMES: [&str; 64] = [
+	"Gray", "Abbott", "Bauer", "Bell", "Castro", "Chen", "Dalton", "Diaz",
+	"Ellis", "Evans", "Fischer", "Ford", "Garcia", "Gupta", "Hale", "Haas",
+	"Ito", "Ivers", "Jensen", "Joshi", "Kato", "Klein", "Lane", "Lopez",
+	"Marsh", "Moreau", "Nakamura", "Novak", "Olsen", "Ortiz", "Park", "Price",
+	"Quint", "Reyes", "Rossi", "Russo", "Sato", "Silva", "Stone", "Suzuki",
+	"Tan", "Torres", "Underwood", "Usman", "Vance", "Varga", "Walsh", "Weber",
+	"Xu", "Young", "Yilmaz", "Zhang", "Ziegler", "Adler", "Brooks", "Cole",
+	"Dunn", "Frost", "Hart", "King", "Moss", "Nash", "Reed", "West"
+];
+
+/// a first and a last name picked by the random bytes of an identity,
+/// the same bytes always give the same name and different ones may too
+pub(crate) fn name_for(identity: &[u8; 16]) -> String {
+	let first = FIRST_NAMES[identity[0] as usize % FIRST_NAMES.len()];
+	let last = LAST_NAMES[identity[1] as usize % LAST_NAMES.len()];
+
+	return format!("{} {}", first, last);
+}
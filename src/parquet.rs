@@ -0,0 +1,707 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::error::SimulationError;
+use crate::matching::MatchPair;
+use crate::sample::Sample;
+use crate::statistics::RoundStats;
+
+/// the values of one column of a parquet file, `None` for nulls
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParquetColumn {
+	Utf8(Vec<Option<String>>),
+	UInt32(Vec<Option<u32>>),
+	Float32(Vec<Option<f32>>),
+	Boolean(Vec<Option<bool>>)
+}
+
+impl ParquetColumn {
+	pub fn len(&self) -> usize {
+		return match self {
+			ParquetColumn::Utf8(values) => values.len(),
+			ParquetColumn::UInt32(values) => values.len(),
+			ParquetColumn::Float32(values) => values.len(),
+			ParquetColumn::Boolean(values) => values.len()
+		};
+	}
+
+	pub fn is_empty(&self) -> bool {
+		return self.len() == 0;
+	}
+
+	/// the physical type and the converted type of the schema
+	fn types(&self) -> (i32, Option<i32>) {
+		return match self {
+			ParquetColumn::Utf8(_) => (BYTE_ARRAY, Some(UTF8)),
+			ParquetColumn::UInt32(_) => (INT32, Some(UINT_32)),
+			ParquetColumn::Float32(_) => (FLOAT, None),
+			ParquetColumn::Boolean(_) => (BOOLEAN, None)
+		};
+	}
+
+	/// whether the value at `row` is present
+	fn present(&self) -> Vec<bool> {
+		return match self {
+			ParquetColumn::Utf8(values) => values.iter().map(Option::is_some).collect(),
+			ParquetColumn::UInt32(values) => values.iter().map(Option::is_some).collect(),
+			ParquetColumn::Float32(values) => values.iter().map(Option::is_some).collect(),
+			ParquetColumn::Boolean(values) => values.iter().map(Option::is_some).collect()
+		};
+	}
+
+	/// the values that are present in the plain encoding
+	fn plain(&self) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		match self {
+			ParquetColumn::Utf8(values) => {
+				for value in values.iter().flatten() {
+					bytes.extend((value.len() as u32).to_le_bytes());
+					bytes.extend(value.as_bytes());
+				}
+			},
+			ParquetColumn::UInt32(values) => {
+				for value in values.iter().flatten() {
+					bytes.extend(value.to_le_bytes());
+				}
+			},
+			ParquetColumn::Float32(values) => {
+				for value in values.iter().flatten() {
+					bytes.extend(value.to_le_bytes());
+				}
+			},
+			ParquetColumn::Boolean(values) => {
+				// one bit per value, the first in the lowest bit
+				let values: Vec<bool> = values.iter().flatten().copied().collect();
+				for chunk in values.chunks(8) {
+					bytes.push(chunk.iter().enumerate().fold(0, |byte, (bit, &value)| byte | ((value as u8) << bit)));
+				}
+			}
+		}
+
+		return bytes;
+	}
+}
+
+/// a table as `ParquetTable::read` finds it, the columns in the order of the schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParquetTable {
+	pub columns: Vec<(String, ParquetColumn)>,
+	// the `key_value_metadata` of the file, e.g. the `RunMetadata` of a pairs export
+	pub metadata: BTreeMap<String, String>
+}
+
+impl ParquetTable {
+	/// the column named `name`
+	pub fn column(&self, name: &str) -> Option<&ParquetColumn> {
+		return self.columns.iter().find(|(column, _)| column == name).map(|(_, values)| values);
+	}
+
+	pub fn rows(&self) -> usize {
+		return self.columns.first().map_or(0, |(_, values)| values.len());
+	}
+
+	/// read a file as the exports write it: flat, uncompressed and plain encoded,
+	/// with the column types of `ParquetColumn`. other files are refused
+	pub fn read(path: &Path) -> Result<Self, SimulationError> {
+		let bytes = std::fs::read(path)?;
+
+		return read_table(&bytes).map_err(|message| SimulationError::Parquet { path: path.to_path_buf(), message: message });
+	}
+}
+
+impl Sample {
+	/// the column names of `Sample::export_pairs_parquet`, in their order. kept from one version to the next
+	pub const PAIRS_PARQUET_COLUMNS: [&'static str; 6] = ["proposer_id", "reviewer_id", "reviewer_score", "proposer_score", "matched_in_round", "retired"];
+
+	/// write the current pairs, then the retired ones, as parquet to `path`, with the `Sample::metadata`
+	/// as json under the `dating_simulation.metadata` key
+	pub fn export_pairs_parquet(&self, path: &Path) -> Result<(), SimulationError> {
+		let current = self.pairs();
+		let pairs: Vec<(&MatchPair, bool)> = current
+			.iter()
+			.map(|pair| (pair, false))
+			.chain(self.retired.iter().map(|pair| (pair, true)))
+			.collect();
+
+		let columns = vec![
+			(false, ParquetColumn::Utf8(pairs.iter().map(|(pair, _)| Some(pair.proposer_id.to_string())).collect())),
+			(false, ParquetColumn::Utf8(pairs.iter().map(|(pair, _)| Some(pair.reviewer_id.to_string())).collect())),
+			(false, ParquetColumn::Float32(pairs.iter().map(|(pair, _)| Some(pair.reviewer_score)).collect())),
+			(false, ParquetColumn::Float32(pairs.iter().map(|(pair, _)| Some(pair.proposer_score)).collect())),
+			(true, ParquetColumn::UInt32(pairs.iter().map(|(pair, _)| pair.matched_in_round).collect())),
+			(false, ParquetColumn::Boolean(pairs.iter().map(|(_, retired)| Some(*retired)).collect()))
+		];
+		let metadata = [(String::from("dating_simulation.metadata"), serde_json::to_string(&self.metadata())?)];
+		std::fs::write(path, write_table(&Sample::PAIRS_PARQUET_COLUMNS, &columns, &metadata))?;
+		tracing::debug!(path = %path.display(), pairs = pairs.len(), "wrote the pairs as parquet");
+
+		return Ok(());
+	}
+}
+
+impl RoundStats {
+	/// write `round_stats` as parquet to `path`, one row per round with the columns of `RoundStats::CSV_HEADER`
+	/// in its order. counts are UInt32, the mean scores Float32 and null when there is none
+	pub fn export_parquet(round_stats: &[RoundStats], path: &Path) -> Result<(), SimulationError> {
+		let count = |value: fn(&RoundStats) -> usize| -> (bool, ParquetColumn) {
+			return (false, ParquetColumn::UInt32(round_stats.iter().map(|stats| Some(value(stats) as u32)).collect()));
+		};
+		let columns = vec![
+			(false, ParquetColumn::UInt32(round_stats.iter().map(|stats| Some(stats.round)).collect())),
+			count(|stats| stats.matched_males),
+			count(|stats| stats.matched_females),
+			count(|stats| stats.unmatched_males),
+			count(|stats| stats.unmatched_females),
+			(true, ParquetColumn::Float32(round_stats.iter().map(|stats| stats.mean_candidate_score).collect())),
+			count(|stats| stats.blacklist_growth),
+			count(|stats| stats.matched_non_binary),
+			count(|stats| stats.unmatched_non_binary),
+			count(|stats| stats.dealbreaker_rejections),
+			count(|stats| stats.delta.new_matches),
+			count(|stats| stats.delta.upgrades),
+			count(|stats| stats.delta.dissolved),
+			count(|stats| stats.arrivals),
+			count(|stats| stats.retired_pairs),
+			(true, ParquetColumn::Float32(round_stats.iter().map(|stats| stats.mean_selectivity).collect())),
+			count(|stats| stats.breakups),
+			count(|stats| stats.divorces),
+			count(|stats| stats.population),
+			count(|stats| stats.births),
+			count(|stats| stats.juveniles)
+		];
+		let names: Vec<&str> = RoundStats::CSV_HEADER.split(',').collect();
+		std::fs::write(path, write_table(&names, &columns, &[]))?;
+
+		return Ok(());
+	}
+}
+
+// the parts of the parquet format the exports use, see https://github.com/apache/parquet-format
+const MAGIC: &[u8] = b"PAR1";
+
+// physical types
+const BOOLEAN: i32 = 0;
+const INT32: i32 = 1;
+const FLOAT: i32 = 4;
+const BYTE_ARRAY: i32 = 6;
+
+// converted types
+const UTF8: i32 = 0;
+const UINT_32: i32 = 13;
+
+// repetition types
+const REQUIRED: i32 = 0;
+const OPTIONAL: i32 = 1;
+
+// encodings
+const PLAIN: i32 = 0;
+const RLE: i32 = 3;
+
+const DATA_PAGE: i32 = 0;
+const UNCOMPRESSED: i32 = 0;
+
+/// a file of one row group with one uncompressed page per column,
+/// `columns` as whether they take nulls and their values
+fn write_table(names: &[&str], columns: &[(bool, ParquetColumn)], metadata: &[(String, String)]) -> Vec<u8> {
+	let rows = columns.first().map_or(0, |(_, values)| values.len());
+	let mut file = MAGIC.to_vec();
+
+	// the offset and the size of every column chunk
+	let mut chunks = Vec::with_capacity(columns.len());
+	for (nullable, values) in columns {
+		let mut page = Vec::new();
+		if *nullable {
+			let levels = definition_levels(&values.present());
+			page.extend((levels.len() as u32).to_le_bytes());
+			page.extend(levels);
+		}
+		page.extend(values.plain());
+
+		let mut header = CompactWriter::new();
+		header.i32_field(1, DATA_PAGE);
+		header.i32_field(2, page.len() as i32);
+		header.i32_field(3, page.len() as i32);
+		header.struct_begin(5);
+		header.i32_field(1, rows as i32);
+		header.i32_field(2, PLAIN);
+		header.i32_field(3, RLE);
+		header.i32_field(4, RLE);
+		header.struct_end();
+		let header = header.finish();
+
+		chunks.push((file.len() as i64, (header.len() + page.len()) as i64));
+		file.extend(header);
+		file.extend(page);
+	}
+
+	let mut footer = CompactWriter::new();
+	footer.i32_field(1, 1);
+	footer.list_begin(2, STRUCT, columns.len() + 1);
+	footer.element_begin();
+	footer.binary_field(4, b"schema");
+	footer.i32_field(5, columns.len() as i32);
+	footer.struct_end();
+	for (name, (nullable, values)) in names.iter().zip(columns) {
+		let (physical, converted) = values.types();
+		footer.element_begin();
+		footer.i32_field(1, physical);
+		footer.i32_field(3, if *nullable { OPTIONAL } else { REQUIRED });
+		footer.binary_field(4, name.as_bytes());
+		if let Some(converted) = converted {
+			footer.i32_field(6, converted);
+		}
+		footer.struct_end();
+	}
+	footer.i64_field(3, rows as i64);
+
+	footer.list_begin(4, STRUCT, 1);
+	footer.element_begin();
+	footer.list_begin(1, STRUCT, columns.len());
+	for ((name, (_, values)), (offset, size)) in names.iter().zip(columns).zip(&chunks) {
+		footer.element_begin();
+		footer.i64_field(2, *offset);
+		footer.struct_begin(3);
+		footer.i32_field(1, values.types().0);
+		footer.list_begin(2, I32, 2);
+		footer.i32_element(PLAIN);
+		footer.i32_element(RLE);
+		footer.list_begin(3, BINARY, 1);
+		footer.binary_element(name.as_bytes());
+		footer.i32_field(4, UNCOMPRESSED);
+		footer.i64_field(5, rows as i64);
+		footer.i64_field(6, *size);
+		footer.i64_field(7, *size);
+		footer.i64_field(9, *offset);
+		footer.struct_end();
+		footer.struct_end();
+	}
+	footer.i64_field(2, chunks.iter().map(|(_, size)| size).sum());
+	footer.i64_field(3, rows as i64);
+	footer.struct_end();
+
+	if !metadata.is_empty() {
+		footer.list_begin(5, STRUCT, metadata.len());
+		for (key, value) in metadata {
+			footer.element_begin();
+			footer.binary_field(1, key.as_bytes());
+			footer.binary_field(2, value.as_bytes());
+			footer.struct_end();
+		}
+	}
+	footer.binary_field(6, format!("dating-simulation version {}", env!("CARGO_PKG_VERSION")).as_bytes());
+	let footer = footer.finish();
+
+	let footer_length = footer.len() as u32;
+	file.extend(footer);
+	file.extend(footer_length.to_le_bytes());
+	file.extend(MAGIC);
+
+	return file;
+}
+
+/// the definition levels of a column that takes nulls, 1 for a value and 0 for a null,
+/// as runs of the rle hybrid encoding with a bit width of 1
+fn definition_levels(present: &[bool]) -> Vec<u8> {
+	let mut bytes = Vec::new();
+	let mut position = 0;
+	while position < present.len() {
+		let level = present[position];
+		let run = present[position..].iter().take_while(|&&other| other == level).count();
+		write_varint(&mut bytes, (run as u64) << 1);
+		bytes.push(level as u8);
+		position += run;
+	}
+
+	return bytes;
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+	while value >= 0x80 {
+		bytes.push((value as u8) | 0x80);
+		value >>= 7;
+	}
+	bytes.push(value as u8);
+}
+
+// the types of the thrift compact protocol
+const I32: u8 = 5;
+const I64: u8 = 6;
+const BINARY: u8 = 8;
+const LIST: u8 = 9;
+const STRUCT: u8 = 12;
+
+/// the thrift compact protocol parquet writes its metadata in, as much of it as the footer needs
+struct CompactWriter {
+	bytes: Vec<u8>,
+	// the last field id of every struct that is open
+	last_fields: Vec<i16>
+}
+
+impl CompactWriter {
+	fn new() -> Self {
+		return CompactWriter { bytes: Vec::new(), last_fields: vec![0] };
+	}
+
+	/// the bytes of the outermost struct
+	fn finish(mut self) -> Vec<u8> {
+		self.bytes.push(0);
+
+		return self.bytes;
+	}
+
+	fn field_header(&mut self, id: i16, kind: u8) {
+		let last = self.last_fields.last_mut().unwrap();
+		let delta = id - *last;
+		*last = id;
+		if (1..=15).contains(&delta) {
+			self.bytes.push(((delta as u8) << 4) | kind);
+		} else {
+			self.bytes.push(kind);
+			write_varint(&mut self.bytes, zigzag(id as i64));
+		}
+	}
+
+	fn i32_field(&mut self, id: i16, value: i32) {
+		self.field_header(id, I32);
+		self.i32_element(value);
+	}
+
+	fn i64_field(&mut self, id: i16, value: i64) {
+		self.field_header(id, I64);
+		write_varint(&mut self.bytes, zigzag(value));
+	}
+
+	fn binary_field(&mut self, id: i16, value: &[u8]) {
+		self.field_header(id, BINARY);
+		self.binary_element(value);
+	}
+
+	fn struct_begin(&mut self, id: i16) {
+		self.field_header(id, STRUCT);
+		self.element_begin();
+	}
+
+	fn struct_end(&mut self) {
+		self.bytes.push(0);
+		self.last_fields.pop();
+	}
+
+	fn list_begin(&mut self, id: i16, element: u8, size: usize) {
+		self.field_header(id, LIST);
+		if size < 15 {
+			self.bytes.push(((size as u8) << 4) | element);
+		} else {
+			self.bytes.push(0xF0 | element);
+			write_varint(&mut self.bytes, size as u64);
+		}
+	}
+
+	/// a struct in a list, closed with `CompactWriter::struct_end`
+	fn element_begin(&mut self) {
+		self.last_fields.push(0);
+	}
+
+	fn i32_element(&mut self, value: i32) {
+		write_varint(&mut self.bytes, zigzag(value as i64));
+	}
+
+	fn binary_element(&mut self, value: &[u8]) {
+		write_varint(&mut self.bytes, value.len() as u64);
+		self.bytes.extend(value);
+	}
+}
+
+fn zigzag(value: i64) -> u64 {
+	return ((value << 1) ^ (value >> 63)) as u64;
+}
+
+/// a value read with the thrift compact protocol
+#[derive(Debug)]
+enum Thrift {
+	// a bool or a double, the footer needs neither
+	Skipped,
+	Integer(i64),
+	Binary(Vec<u8>),
+	List(Vec<Thrift>),
+	Struct(HashMap<i16, Thrift>)
+}
+
+impl Thrift {
+	fn integer(&self) -> Result<i64, String> {
+		return match self {
+			Thrift::Integer(value) => Ok(*value),
+			other => Err(format!("expected an integer, found {:?}", other))
+		};
+	}
+
+	fn binary(&self) -> Result<&[u8], String> {
+		return match self {
+			Thrift::Binary(value) => Ok(value),
+			other => Err(format!("expected a binary, found {:?}", other))
+		};
+	}
+
+	fn list(&self) -> Result<&[Thrift], String> {
+		return match self {
+			Thrift::List(values) => Ok(values),
+			other => Err(format!("expected a list, found {:?}", other))
+		};
+	}
+
+	fn field(&self, id: i16) -> Result<&Thrift, String> {
+		return match self {
+			Thrift::Struct(fields) => fields.get(&id).ok_or_else(|| format!("the field {} is missing", id)),
+			other => Err(format!("expected a struct, found {:?}", other))
+		};
+	}
+
+	fn optional_field(&self, id: i16) -> Option<&Thrift> {
+		return match self {
+			Thrift::Struct(fields) => fields.get(&id),
+			_ => None
+		};
+	}
+}
+
+/// reads the thrift compact protocol from `bytes` onwards
+struct CompactReader<'a> {
+	bytes: &'a [u8],
+	position: usize
+}
+
+impl<'a> CompactReader<'a> {
+	fn byte(&mut self) -> Result<u8, String> {
+		let byte = *self.bytes.get(self.position).ok_or("the metadata ends early")?;
+		self.position += 1;
+
+		return Ok(byte);
+	}
+
+	fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+		let bytes = self.bytes.get(self.position..self.position + length).ok_or("the metadata ends early")?;
+		self.position += length;
+
+		return Ok(bytes);
+	}
+
+	fn varint(&mut self) -> Result<u64, String> {
+		let mut value = 0;
+		for shift in (0..64).step_by(7) {
+			let byte = self.byte()?;
+			value |= ((byte & 0x7F) as u64) << shift;
+			if byte & 0x80 == 0 {
+				return Ok(value);
+			}
+		}
+
+		return Err(String::from("a varint runs past 64 bits"));
+	}
+
+	fn zigzag(&mut self) -> Result<i64, String> {
+		let value = self.varint()?;
+
+		return Ok((value >> 1) as i64 ^ -((value & 1) as i64));
+	}
+
+	fn read_struct(&mut self) -> Result<Thrift, String> {
+		let mut fields = HashMap::new();
+		let mut last = 0;
+		loop {
+			let header = self.byte()?;
+			if header == 0 {
+				return Ok(Thrift::Struct(fields));
+			}
+
+			let delta = (header >> 4) as i16;
+			let id = if delta == 0 { self.zigzag()? as i16 } else { last + delta };
+			last = id;
+			let value = match header & 0x0F {
+				1 | 2 => Thrift::Skipped,
+				kind => self.read_value(kind)?
+			};
+			fields.insert(id, value);
+		}
+	}
+
+	fn read_value(&mut self, kind: u8) -> Result<Thrift, String> {
+		return match kind {
+			// a bool in a list
+			1 | 2 => self.byte().map(|_| Thrift::Skipped),
+			3 => Ok(Thrift::Integer(self.byte()? as i8 as i64)),
+			4..=6 => Ok(Thrift::Integer(self.zigzag()?)),
+			7 => self.take(8).map(|_| Thrift::Skipped),
+			BINARY => {
+				let length = self.varint()? as usize;
+
+				Ok(Thrift::Binary(self.take(length)?.to_vec()))
+			},
+			9 | 10 => {
+				let header = self.byte()?;
+				let size = if header >> 4 == 15 { self.varint()? as usize } else { (header >> 4) as usize };
+				let values = (0..size).map(|_| self.read_value(header & 0x0F)).collect::<Result<Vec<Thrift>, String>>()?;
+
+				Ok(Thrift::List(values))
+			},
+			STRUCT => self.read_struct(),
+			kind => Err(format!("unsupported thrift type {}", kind))
+		};
+	}
+}
+
+fn read_table(bytes: &[u8]) -> Result<ParquetTable, String> {
+	if bytes.len() < 12 || &bytes[..4] != MAGIC || &bytes[bytes.len() - 4..] != MAGIC {
+		return Err(String::from("not a parquet file"));
+	}
+	let footer_length = u32::from_le_bytes(bytes[bytes.len() - 8..bytes.len() - 4].try_into().unwrap()) as usize;
+	let footer_start = (bytes.len() - 8).checked_sub(footer_length).ok_or("the footer is longer than the file")?;
+	let footer = CompactReader { bytes: &bytes[..bytes.len() - 8], position: footer_start }.read_struct()?;
+
+	// the root comes first, then every column of a flat schema
+	let schema = footer.field(2)?.list()?;
+	let mut columns = Vec::new();
+	for (position, element) in schema.iter().enumerate().skip(1) {
+		let name = String::from_utf8_lossy(element.field(4)?.binary()?).into_owned();
+		let nullable = element.optional_field(3).map(Thrift::integer).transpose()? == Some(OPTIONAL as i64);
+		let values = match element.field(1)?.integer()? as i32 {
+			BYTE_ARRAY => ParquetColumn::Utf8(Vec::new()),
+			INT32 => ParquetColumn::UInt32(Vec::new()),
+			FLOAT => ParquetColumn::Float32(Vec::new()),
+			BOOLEAN => ParquetColumn::Boolean(Vec::new()),
+			other => return Err(format!("the column {} has the unsupported type {}", name, other))
+		};
+		columns.push((position - 1, name, nullable, values));
+	}
+
+	for row_group in footer.field(4)?.list()? {
+		for chunk in row_group.field(1)?.list()?.iter() {
+			let column_metadata = chunk.field(3)?;
+			let path = column_metadata.field(3)?.list()?;
+			let name = String::from_utf8_lossy(path.first().ok_or("a column chunk has no path")?.binary()?).into_owned();
+			let (_, _, nullable, values) = columns
+				.iter_mut()
+				.find(|(_, column, _, _)| *column == name)
+				.ok_or_else(|| format!("the column chunk {} is not in the schema", name))?;
+			if column_metadata.field(4)?.integer()? as i32 != UNCOMPRESSED {
+				return Err(format!("the column {} is compressed", name));
+			}
+
+			let mut remaining = column_metadata.field(5)?.integer()? as usize;
+			let mut reader = CompactReader { bytes: bytes, position: column_metadata.field(9)?.integer()? as usize };
+			while remaining > 0 {
+				let header = reader.read_struct()?;
+				if header.field(1)?.integer()? as i32 != DATA_PAGE {
+					return Err(format!("the column {} has a page other than a data page", name));
+				}
+				let page = reader.take(header.field(3)?.integer()? as usize)?;
+				let data_page = header.field(5)?;
+				if data_page.field(2)?.integer()? as i32 != PLAIN {
+					return Err(format!("the column {} is not plain encoded", name));
+				}
+
+				let count = data_page.field(1)?.integer()? as usize;
+				read_page(page, count, *nullable, values).map_err(|message| format!("the column {}: {}", name, message))?;
+				remaining = remaining.saturating_sub(count);
+			}
+		}
+	}
+
+	let metadata = match footer.optional_field(5) {
+		Some(pairs) => pairs
+			.list()?
+			.iter()
+			.map(|pair| Ok((
+				String::from_utf8_lossy(pair.field(1)?.binary()?).into_owned(),
+				pair.optional_field(2).map(Thrift::binary).transpose()?.map(|value| String::from_utf8_lossy(value).into_owned()).unwrap_or_default()
+			)))
+			.collect::<Result<BTreeMap<String, String>, String>>()?,
+		None => BTreeMap::new()
+	};
+
+	columns.sort_by_key(|(position, _, _, _)| *position);
+
+	return Ok(ParquetTable {
+		columns: columns.into_iter().map(|(_, name, _, values)| (name, values)).collect(),
+		metadata: metadata
+	});
+}
+
+/// append the `count` rows of a data page to `values`
+fn read_page(page: &[u8], count: usize, nullable: bool, values: &mut ParquetColumn) -> Result<(), String> {
+	let (present, mut plain) = if nullable {
+		let length = u32::from_le_bytes(page.get(..4).ok_or("the page ends early")?.try_into().unwrap()) as usize;
+		let levels = page.get(4..4 + length).ok_or("the page ends early")?;
+
+		(read_definition_levels(levels, count)?, &page[4 + length..])
+	} else {
+		(vec![true; count], page)
+	};
+
+	let mut take = |length: usize| -> Result<&[u8], String> {
+		let (bytes, rest) = (plain.get(..length).ok_or("the page ends early")?, &plain[length..]);
+		plain = rest;
+
+		return Ok(bytes);
+	};
+	match values {
+		ParquetColumn::Utf8(values) => {
+			for &present in &present {
+				values.push(match present {
+					true => {
+						let length = u32::from_le_bytes(take(4)?.try_into().unwrap()) as usize;
+
+						Some(String::from_utf8(take(length)?.to_vec()).map_err(|error| error.to_string())?)
+					},
+					false => None
+				});
+			}
+		},
+		ParquetColumn::UInt32(values) => {
+			for &present in &present {
+				values.push(if present { Some(u32::from_le_bytes(take(4)?.try_into().unwrap())) } else { None });
+			}
+		},
+		ParquetColumn::Float32(values) => {
+			for &present in &present {
+				values.push(if present { Some(f32::from_le_bytes(take(4)?.try_into().unwrap())) } else { None });
+			}
+		},
+		ParquetColumn::Boolean(values) => {
+			let bits = take(present.iter().filter(|&&present| present).count().div_ceil(8))?;
+			let mut bit = 0;
+			for &present in &present {
+				values.push(match present {
+					true => {
+						bit += 1;
+
+						Some(bits[(bit - 1) / 8] >> ((bit - 1) % 8) & 1 == 1)
+					},
+					false => None
+				});
+			}
+		}
+	}
+
+	return Ok(());
+}
+
+/// decode the rle hybrid encoding of definition levels with a bit width of 1
+fn read_definition_levels(bytes: &[u8], count: usize) -> Result<Vec<bool>, String> {
+	let mut reader = CompactReader { bytes: bytes, position: 0 };
+	let mut levels = Vec::with_capacity(count);
+	while levels.len() < count {
+		let header = reader.varint()? as usize;
+		if header & 1 == 0 {
+			// a value repeated
+			let level = reader.byte()? == 1;
+			levels.extend(std::iter::repeat_n(level, header >> 1));
+		} else {
+			// groups of 8 bit-packed values
+			for byte in reader.take(header >> 1)? {
+				levels.extend((0..8).map(|bit| byte >> bit & 1 == 1));
+			}
+		}
+	}
+	levels.truncate(count);
+
+	return Ok(levels);
+}
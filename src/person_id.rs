@@ -0,0 +1,420 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use uuid::Uuid;
+
+/// the longest identity kept in a `PersonId` itself rather than in the table of long identities
+const SHORT: usize = 15;
+
+/// how many long identities the table holds at once, their slots take 3 of the 4 bytes of a `Repr::Long`
+const SLOTS: usize = 1 << 24;
+
+/// an identity as a 17-byte copyable handle, so candidates and blacklists compare and hash
+/// bytes instead of uuid strings. a uuid in its canonical form, lowercase and hyphenated, is kept
+/// as its 16 bytes and an identity of up to 15 bytes, e.g. "m0", as it is, so neither is stored
+/// anywhere else and both go away with whatever holds them. longer identities that are not uuids
+/// are kept in a table shared by the process, each until the last `Sample` holding it is dropped,
+/// see `Sample::rebuild_index`. one made into an id with `PersonId::new` since a sample last took hold of it
+/// stays until the next sample holding it is dropped, so that a sample read on one thread does not lose
+/// its identities to a sample dropped on another. once they are gone the string of such an id can
+/// no longer be read and `PersonId::as_str` panics. serde writes and reads the string, ids are ordered by their strings
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct PersonId(Repr);
+
+/// every identity has exactly one of these, so the derived `Eq` agrees with the strings
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Repr {
+	Uuid([u8; 16]),
+	// the length and the bytes, padded with zeros
+	Short(u8, [u8; SHORT]),
+	// the slot in the table of long identities as 3 little-endian bytes and the generation of the slot,
+	// so that the id needs no alignment and packs into 17 bytes, and an id of a freed identity
+	// is not taken for the one that took its slot
+	Long([u8; 4])
+}
+
+/// a slot of the table, empty once the last sample holding its identity was dropped
+#[derive(Default)]
+struct Slot {
+	identity: Option<Arc<str>>,
+	generation: u8,
+	// the samples holding the identity
+	holders: u32,
+	// whether `PersonId::new` made it into an id since a sample last took hold of it
+	fresh: AtomicBool
+}
+
+/// the identities longer than `SHORT` bytes that are not uuids, with the slots freed for reuse
+#[derive(Default)]
+struct LongIdentities {
+	ids: HashMap<Arc<str>, [u8; 4]>,
+	slots: Vec<Slot>,
+	free: Vec<u32>
+}
+
+impl LongIdentities {
+	/// the identity of `id`, `None` once it was freed
+	fn get(&self, id: [u8; 4]) -> Option<&Arc<str>> {
+		let slot = self.slots.get(slot_of(id))?;
+
+		return slot.identity.as_ref().filter(|_| slot.generation == id[3]);
+	}
+
+	fn identity(&self, id: [u8; 4]) -> &Arc<str> {
+		return self.get(id).expect("the identity of a `PersonId` was read after the last sample holding it was dropped");
+	}
+}
+
+fn slot_of(id: [u8; 4]) -> usize {
+	return u32::from_le_bytes([id[0], id[1], id[2], 0]) as usize;
+}
+
+fn long_identities() -> &'static RwLock<LongIdentities> {
+	static LONG_IDENTITIES: OnceLock<RwLock<LongIdentities>> = OnceLock::new();
+
+	return LONG_IDENTITIES.get_or_init(|| RwLock::new(LongIdentities::default()));
+}
+
+fn read_table() -> std::sync::RwLockReadGuard<'static, LongIdentities> {
+	return long_identities().read().unwrap_or_else(|poisoned| poisoned.into_inner());
+}
+
+fn write_table() -> std::sync::RwLockWriteGuard<'static, LongIdentities> {
+	return long_identities().write().unwrap_or_else(|poisoned| poisoned.into_inner());
+}
+
+/// the id `identity` has without the table, `None` when it has to be kept there
+fn inline(identity: &str) -> Option<PersonId> {
+	if identity.len() <= SHORT {
+		let mut bytes = [0; SHORT];
+		bytes[..identity.len()].copy_from_slice(identity.as_bytes());
+
+		return Some(PersonId(Repr::Short(identity.len() as u8, bytes)));
+	}
+
+	// anything but the canonical form would not be written back as it was read
+	let uuid = Uuid::try_parse(identity).ok()?;
+	if *uuid.hyphenated().encode_lower(&mut Uuid::encode_buffer()) != *identity {
+		return None;
+	}
+
+	return Some(PersonId::from_uuid(uuid));
+}
+
+impl PersonId {
+	/// the id of `identity`, putting it into the table if it is long and not a uuid. 
+	/// panics when the table already holds 2^24 long identities
+	pub fn new(identity: &str) -> Self {
+		if let Some(id) = inline(identity) {
+			return id;
+		}
+		{
+			let table = read_table();
+			if let Some(&id) = table.ids.get(identity) {
+				table.slots[slot_of(id)].fresh.store(true, Ordering::Relaxed);
+
+				return PersonId(Repr::Long(id));
+			}
+		}
+
+		let mut table = write_table();
+		// another thread may have added it in the meantime
+		if let Some(&id) = table.ids.get(identity) {
+			table.slots[slot_of(id)].fresh.store(true, Ordering::Relaxed);
+
+			return PersonId(Repr::Long(id));
+		}
+
+		let slot = match table.free.pop() {
+			Some(slot) => slot as usize,
+			None => {
+				assert!(table.slots.len() < SLOTS, "more than {} long identities at once", SLOTS);
+				table.slots.push(Slot::default());
+
+				table.slots.len() - 1
+			}
+		};
+		let identity: Arc<str> = Arc::from(identity);
+		table.slots[slot].identity = Some(identity.clone());
+		table.slots[slot].fresh.store(true, Ordering::Relaxed);
+		let [first, second, third, _] = (slot as u32).to_le_bytes();
+		let id = [first, second, third, table.slots[slot].generation];
+		table.ids.insert(identity, id);
+
+		return PersonId(Repr::Long(id));
+	}
+
+	/// the id of a uuid, which needs neither its string nor the table
+	pub fn from_uuid(uuid: Uuid) -> Self {
+		return PersonId(Repr::Uuid(uuid.into_bytes()));
+	}
+
+	/// the id of `identity` if it needs no table or is in it, without adding it
+	pub fn get(identity: &str) -> Option<Self> {
+		if let Some(id) = inline(identity) {
+			return Some(id);
+		}
+
+		return read_table()
+			.ids
+			.get(identity)
+			.map(|&id| PersonId(Repr::Long(id)));
+	}
+
+	/// the identity this id stands for
+	pub fn as_str(&self) -> IdentityStr {
+		return match self.0 {
+			Repr::Uuid(bytes) => {
+				let mut text = [0; 36];
+				Uuid::from_bytes(bytes).hyphenated().encode_lower(&mut text);
+
+				IdentityStr::Inline(36, text)
+			},
+			Repr::Short(length, bytes) => {
+				let mut text = [0; 36];
+				text[..SHORT].copy_from_slice(&bytes);
+
+				IdentityStr::Inline(length, text)
+			},
+			Repr::Long(id) => IdentityStr::Long(read_table().identity(id).clone())
+		};
+	}
+
+	/// the first 8 characters of the identity, the first 8 hex digits of a uuid,
+	/// enough to tell the individuals of a report apart
+	pub fn short_id(&self) -> IdentityStr {
+		let identity = self.as_str();
+		let end = identity.char_indices().nth(8).map_or(identity.len(), |(end, _)| end);
+		let mut text = [0; 36];
+		text[..end].copy_from_slice(&identity.as_bytes()[..end]);
+
+		return IdentityStr::Inline(end as u8, text);
+	}
+
+	/// the bytes a long identity takes in the table: its string with the counts of its `Arc`, its slot
+	/// and its entry in the map from string to slot. 0 for the ones kept in the id and freed ones
+	pub(crate) fn table_bytes(&self) -> usize {
+		let Repr::Long(id) = self.0 else {
+			return 0;
+		};
+
+		return read_table().get(id).map_or(0, |identity| {
+			identity.len() + 2 * std::mem::size_of::<usize>()
+				+ std::mem::size_of::<Slot>()
+				+ std::mem::size_of::<(Arc<str>, [u8; 4])>() + 1
+		});
+	}
+}
+
+/// the long identities a `Sample` holds, each kept in the table until the last sample holding it is dropped.
+/// a copy holds them once more
+#[derive(Debug, Default)]
+pub(crate) struct HeldIdentities {
+	ids: HashSet<[u8; 4]>
+}
+
+impl HeldIdentities {
+	/// hold the long ones among `ids` that are not held yet
+	pub(crate) fn hold(&mut self, ids: impl Iterator<Item = PersonId>) {
+		let new: Vec<[u8; 4]> = ids
+			.filter_map(|id| match id.0 {
+				Repr::Long(id) => Some(id),
+				_ => None
+			})
+			.filter(|id| !self.ids.contains(id))
+			.collect();
+		if new.is_empty() {
+			return;
+		}
+
+		let mut table = write_table();
+		for id in new {
+			// a freed identity cannot be held again
+			if table.get(id).is_some() && self.ids.insert(id) {
+				let slot = &mut table.slots[slot_of(id)];
+				slot.holders += 1;
+				*slot.fresh.get_mut() = false;
+			}
+		}
+	}
+}
+
+impl Clone for HeldIdentities {
+	fn clone(&self) -> Self {
+		let mut held = HeldIdentities::default();
+		held.hold(self.ids.iter().map(|&id| PersonId(Repr::Long(id))));
+
+		return held;
+	}
+}
+
+impl Drop for HeldIdentities {
+	fn drop(&mut self) {
+		if self.ids.is_empty() {
+			return;
+		}
+
+		let mut table = write_table();
+		for &id in &self.ids {
+			let slot = &mut table.slots[slot_of(id)];
+			slot.holders -= 1;
+			if slot.holders > 0 || *slot.fresh.get_mut() {
+				continue;
+			}
+
+			let identity = slot.identity.take().expect("a held identity is in the table");
+			slot.generation = slot.generation.wrapping_add(1);
+			table.ids.remove(&identity);
+			table.free.push(slot_of(id) as u32);
+		}
+	}
+}
+
+/// the string of a `PersonId`, which derefs to `str`. uuids and short identities are
+/// written out on the stack, long ones shared with the table
+#[derive(Clone)]
+pub enum IdentityStr {
+	Inline(u8, [u8; 36]),
+	Long(Arc<str>)
+}
+
+impl std::ops::Deref for IdentityStr {
+	type Target = str;
+
+	fn deref(&self) -> &str {
+		return match self {
+			IdentityStr::Inline(length, text) => std::str::from_utf8(&text[..*length as usize]).expect("an identity is utf-8"),
+			IdentityStr::Long(identity) => identity
+		};
+	}
+}
+
+impl AsRef<str> for IdentityStr {
+	fn as_ref(&self) -> &str {
+		return self;
+	}
+}
+
+impl PartialEq for IdentityStr {
+	fn eq(&self, other: &Self) -> bool {
+		return **self == **other;
+	}
+}
+
+impl PartialEq<str> for IdentityStr {
+	fn eq(&self, other: &str) -> bool {
+		return **self == *other;
+	}
+}
+
+impl PartialEq<&str> for IdentityStr {
+	fn eq(&self, other: &&str) -> bool {
+		return **self == **other;
+	}
+}
+
+impl std::fmt::Display for IdentityStr {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return f.write_str(self);
+	}
+}
+
+impl std::fmt::Debug for IdentityStr {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return write!(f, "{:?}", &**self);
+	}
+}
+
+impl From<&str> for PersonId {
+	fn from(identity: &str) -> Self {
+		return PersonId::new(identity);
+	}
+}
+
+impl From<Uuid> for PersonId {
+	fn from(uuid: Uuid) -> Self {
+		return PersonId::from_uuid(uuid);
+	}
+}
+
+impl PartialEq<str> for PersonId {
+	fn eq(&self, other: &str) -> bool {
+		return *self.as_str() == *other;
+	}
+}
+
+impl PartialEq<&str> for PersonId {
+	fn eq(&self, other: &&str) -> bool {
+		return *self.as_str() == **other;
+	}
+}
+
+/// one word per id, which hashes about as fast as the 4-byte handles of before. the halves of a uuid
+/// are random enough to be folded together, ids that collide across kinds are told apart by `Eq`
+impl std::hash::Hash for PersonId {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+		let word = |bytes: &[u8]| -> u64 {
+			return u64::from_le_bytes(bytes.try_into().unwrap());
+		};
+		state.write_u64(match &self.0 {
+			Repr::Uuid(bytes) => word(&bytes[..8]) ^ word(&bytes[8..]),
+			Repr::Short(length, bytes) => word(&bytes[..8]) ^ (word(&bytes[7..]) << 8) ^ *length as u64,
+			Repr::Long(id) => u32::from_le_bytes(*id) as u64
+		});
+	}
+}
+
+impl PartialOrd for PersonId {
+	fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+		return Some(self.cmp(other));
+	}
+}
+
+impl Ord for PersonId {
+	fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+		return match (&self.0, &other.0) {
+			// the big-endian bytes of two uuids order as their lowercase hex digits do
+			(Repr::Uuid(first), Repr::Uuid(second)) => first.cmp(second),
+			(Repr::Short(first_length, first), Repr::Short(second_length, second)) => {
+				first[..*first_length as usize].cmp(&second[..*second_length as usize])
+			},
+			(Repr::Long(first), Repr::Long(second)) if first == second => std::cmp::Ordering::Equal,
+			(Repr::Long(first), Repr::Long(second)) => {
+				let table = read_table();
+
+				table.identity(*first).cmp(table.identity(*second))
+			},
+			// ids of different kinds are compared by their strings, so that the order does not depend
+			// on how an identity is kept. populations rarely mix them
+			_ => (*self.as_str()).cmp(&*other.as_str())
+		};
+	}
+}
+
+impl std::fmt::Display for PersonId {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return f.write_str(&self.as_str());
+	}
+}
+
+impl std::fmt::Debug for PersonId {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return write!(f, "{:?}", self.as_str());
+	}
+}
+
+impl Serialize for PersonId {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		return serializer.serialize_str(&self.as_str());
+	}
+}
+
+impl<'de> Deserialize<'de> for PersonId {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let identity = String::deserialize(deserializer)?;
+
+		return Ok(PersonId::new(&identity));
+	}
+}
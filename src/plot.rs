@@ -0,0 +1,100 @@
+use std::path::{Path, PathBuf};
+
+use plotters::prelude::*;
+
+use crate::error::SimulationError;
+use crate::statistics::RoundStats;
+
+/// a chart that failed to draw, plotters reports it with the error of its backend
+type DrawResult = Result<(), Box<dyn std::error::Error>>;
+
+const SIZE: (u32, u32) = (800, 480);
+
+impl RoundStats {
+	/// draw the match rate of every gender over the rounds to `match_rate.svg` and the mean candidate score
+	/// to `mean_score.svg` in `directory`, which has to exist. returns the two paths
+	pub fn plot(round_stats: &[RoundStats], directory: &Path) -> Result<Vec<PathBuf>, SimulationError> {
+		if !directory.is_dir() {
+			return Err(SimulationError::MissingDirectory(directory.to_path_buf()));
+		}
+
+		let match_rate = directory.join("match_rate.svg");
+		let mean_score = directory.join("mean_score.svg");
+		for (path, draw) in [(&match_rate, draw_match_rate as fn(&[RoundStats], &Path) -> DrawResult), (&mean_score, draw_mean_score)] {
+			draw(round_stats, path).map_err(|error| SimulationError::Plot { path: path.clone(), message: error.to_string() })?;
+		}
+
+		return Ok(vec![match_rate, mean_score]);
+	}
+}
+
+/// the matched and the unmatched of one gender
+type GenderCounts = fn(&RoundStats) -> (usize, usize);
+
+fn draw_match_rate(round_stats: &[RoundStats], path: &Path) -> DrawResult {
+	let root = SVGBackend::new(path, SIZE).into_drawing_area();
+	root.fill(&WHITE)?;
+
+	let last_round = round_stats.last().map_or(1, |round_stats| round_stats.round).max(1);
+	let mut chart = ChartBuilder::on(&root)
+		.caption("Match rate by gender", ("sans-serif", 24))
+		.margin(16)
+		.x_label_area_size(40)
+		.y_label_area_size(50)
+		.build_cartesian_2d(1..last_round, 0.0..1.0)?;
+	chart.configure_mesh().x_desc("round").y_desc("match rate").draw()?;
+
+	let genders: [(&str, RGBColor, GenderCounts); 3] = [
+		("males", BLUE, |round_stats| (round_stats.matched_males, round_stats.unmatched_males)),
+		("females", RED, |round_stats| (round_stats.matched_females, round_stats.unmatched_females)),
+		("non-binary", GREEN, |round_stats| (round_stats.matched_non_binary, round_stats.unmatched_non_binary))
+	];
+	for (name, color, counts) in genders {
+		// a gender nobody belongs to has no rate to draw
+		let points: Vec<(u32, f64)> = round_stats
+			.iter()
+			.filter_map(|round_stats| {
+				let (matched, unmatched) = counts(round_stats);
+
+				return (matched + unmatched > 0).then(|| (round_stats.round, matched as f64 / (matched + unmatched) as f64));
+			})
+			.collect();
+		if points.is_empty() {
+			continue;
+		}
+
+		chart.draw_series(LineSeries::new(points, color))?
+			.label(name)
+			.legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+	}
+	chart.configure_series_labels().background_style(WHITE).border_style(BLACK).draw()?;
+	root.present()?;
+
+	return Ok(());
+}
+
+fn draw_mean_score(round_stats: &[RoundStats], path: &Path) -> DrawResult {
+	let root = SVGBackend::new(path, SIZE).into_drawing_area();
+	root.fill(&WHITE)?;
+
+	let points: Vec<(u32, f64)> = round_stats
+		.iter()
+		.filter_map(|round_stats| round_stats.mean_candidate_score.map(|score| (round_stats.round, score as f64)))
+		.collect();
+	let last_round = round_stats.last().map_or(1, |round_stats| round_stats.round).max(1);
+	let highest = points.iter().map(|&(_, score)| score).fold(0.0, f64::max);
+	// negative weights can give negative scores, the axis starts at 0 otherwise
+	let lowest = points.iter().map(|&(_, score)| score).fold(0.0, f64::min);
+
+	let mut chart = ChartBuilder::on(&root)
+		.caption("Mean candidate score of the matched", ("sans-serif", 24))
+		.margin(16)
+		.x_label_area_size(40)
+		.y_label_area_size(50)
+		.build_cartesian_2d(1..last_round, lowest * 1.1..(highest * 1.1).max(1.0))?;
+	chart.configure_mesh().x_desc("round").y_desc("mean candidate score").draw()?;
+	chart.draw_series(LineSeries::new(points, BLUE))?;
+	root.present()?;
+
+	return Ok(());
+}
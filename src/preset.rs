@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+
+use crate::attributes::{AttributeSchema, AttributeSpec, CorrelationMatrix};
+use crate::individual::{RatingDistribution, WeightDistribution};
+
+/// a ready-made population for `SampleConfig::preset` and `--preset`, for experiments without a config file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Preset {
+	LooksDominated,
+	Balanced,
+	Polarized,
+	HaloEffect
+}
+
+/// what a preset sets up: the attributes with the distribution of their ratings,
+/// the concentrations of the dirichlet weights and how the ratings correlate
+struct PresetSpec {
+	preset: Preset,
+	name: &'static str,
+	description: &'static str,
+	attributes: &'static [(&'static str, RatingDistribution)],
+	concentration: &'static [f64],
+	correlation: Option<&'static [&'static [f64]]>
+}
+
+const UNIFORM: RatingDistribution = RatingDistribution::Uniform { min: 1.0, max: 10.0 };
+const NORMAL: RatingDistribution = RatingDistribution::Normal { mean: 5.5, std_dev: 1.5 };
+
+const PRESETS: [PresetSpec; 4] = [
+	PresetSpec {
+		preset: Preset::LooksDominated,
+		name: "looks-dominated",
+		description: "looks, humor and kindness, with about 70% of everyone's weight on looks",
+		attributes: &[("looks", UNIFORM), ("humor", UNIFORM), ("kindness", UNIFORM)],
+		concentration: &[8.0, 1.5, 1.5],
+		correlation: None
+	},
+	PresetSpec {
+		preset: Preset::Balanced,
+		name: "balanced",
+		description: "looks, humor, kindness and ambition, everyone weights them about equally",
+		attributes: &[("looks", UNIFORM), ("humor", UNIFORM), ("kindness", UNIFORM), ("ambition", UNIFORM)],
+		concentration: &[20.0, 20.0, 20.0, 20.0],
+		correlation: None
+	},
+	PresetSpec {
+		preset: Preset::Polarized,
+		name: "polarized",
+		description: "looks, humor and kindness, almost everyone cares about one of them alone",
+		attributes: &[("looks", UNIFORM), ("humor", UNIFORM), ("kindness", UNIFORM)],
+		concentration: &[0.15, 0.15, 0.15],
+		correlation: None
+	},
+	PresetSpec {
+		preset: Preset::HaloEffect,
+		name: "halo-effect",
+		description: "looks, charm and status rated on a bell curve, correlated by 0.6 with one another",
+		attributes: &[("looks", NORMAL), ("charm", NORMAL), ("status", NORMAL)],
+		concentration: &[2.0, 2.0, 2.0],
+		correlation: Some(&[&[1.0, 0.6, 0.6], &[0.6, 1.0, 0.6], &[0.6, 0.6, 1.0]])
+	}
+];
+
+impl Preset {
+	pub const ALL: [Preset; 4] = [Preset::LooksDominated, Preset::Balanced, Preset::Polarized, Preset::HaloEffect];
+
+	fn spec(&self) -> &'static PresetSpec {
+		return PRESETS
+			.iter()
+			.find(|spec| spec.preset == *self)
+			.expect("every preset is listed in PRESETS");
+	}
+
+	/// the name `--preset` takes, e.g. `looks-dominated`
+	pub fn name(&self) -> &'static str {
+		return self.spec().name;
+	}
+
+	/// what the population of the preset is like, as `--help` lists it
+	pub fn description(&self) -> &'static str {
+		return self.spec().description;
+	}
+
+	pub fn attribute_schema(&self) -> AttributeSchema {
+		let spec = self.spec();
+
+		return AttributeSchema {
+			attributes: spec.attributes
+				.iter()
+				.map(|&(name, distribution)| AttributeSpec::new(name, distribution))
+				.collect(),
+			correlation: spec.correlation.map(|rows| {
+				CorrelationMatrix::new(rows.iter().map(|row| row.to_vec()).collect())
+					.expect("the correlations of the presets are valid")
+			}),
+			categorical: Vec::new()
+		};
+	}
+
+	pub fn weight_distribution(&self) -> WeightDistribution {
+		return WeightDistribution::Dirichlet { concentration: self.spec().concentration.to_vec() };
+	}
+}
+
+impl std::fmt::Display for Preset {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return f.write_str(self.name());
+	}
+}
+
+impl std::str::FromStr for Preset {
+	type Err = String;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		return Preset::ALL
+			.into_iter()
+			.find(|preset| preset.name() == name)
+			.ok_or_else(|| format!(
+				"unknown preset `{}`, expected one of {}",
+				name,
+				Preset::ALL.map(|preset| format!("`{}`", preset)).join(", ")
+			));
+	}
+}
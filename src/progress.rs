@@ -0,0 +1,97 @@
+/// progress of generating or matching a population, drawn on stderr.
+/// a disabled progress draws nothing and never touches stdout or stderr,
+/// so tests and embedding callers stay silent
+#[cfg(feature = "progress")]
+#[derive(Debug, Clone)]
+pub(crate) struct Progress {
+	bar: Option<indicatif::ProgressBar>
+}
+
+#[cfg(feature = "progress")]
+impl Progress {
+	/// progress over `len` individuals, only drawn when `enabled` is set
+	pub fn new(len: u64, enabled: bool) -> Self {
+		return Progress::counting(len, "individuals", enabled);
+	}
+
+	/// progress over `len` of `unit`, e.g. `"runs"`, only drawn when `enabled` is set
+	pub fn counting(len: u64, unit: &str, enabled: bool) -> Self {
+		if !enabled {
+			return Progress::disabled();
+		}
+
+		let bar = indicatif::ProgressBar::new(len);
+		bar.set_style(indicatif::ProgressStyle::with_template(&format!(
+			"{{spinner:.green}} [{{elapsed_precise}}] [{{wide_bar:.cyan/blue}}] {{pos}}/{{len}} {} ({{per_sec}}, {{eta}}) {{msg}}",
+			unit
+		))
+			.unwrap()
+			.with_key(
+				"eta",
+				|state: &indicatif::ProgressState, w: &mut dyn std::fmt::Write| write!(
+					w, "{:.1}s", state.eta().as_secs_f64()).unwrap()
+				)
+			.progress_chars("#>-"));
+
+		return Progress { bar: Some(bar) };
+	}
+
+	pub fn disabled() -> Self {
+		return Progress { bar: None };
+	}
+
+	/// mark `delta` more individuals as done
+	pub fn inc(&self, delta: u64) {
+		if let Some(bar) = &self.bar {
+			bar.inc(delta);
+		}
+	}
+
+	/// show the share of the `len` individuals that are matched,
+	/// `matched` is only counted when the progress is drawn
+	pub fn set_matched(&self, matched: impl FnOnce() -> usize) {
+		if let Some(bar) = &self.bar {
+			let len = bar.length().unwrap_or_default().max(1);
+			bar.set_message(format!("{:.1}% matched", matched() as f64 / len as f64 * 100.0));
+		}
+	}
+
+	/// show `message` next to the bar, it is only built when the progress is drawn
+	pub fn set_message(&self, message: impl FnOnce() -> String) {
+		if let Some(bar) = &self.bar {
+			bar.set_message(message());
+		}
+	}
+
+	/// stop drawing and leave `message` next to the finished bar,
+	/// `message` is only built when the progress is drawn
+	pub fn finish(&self, message: impl FnOnce(f64) -> String) {
+		if let Some(bar) = &self.bar {
+			bar.finish_with_message(message(bar.elapsed().as_secs_f64()));
+		}
+	}
+}
+
+/// without the `progress` feature nothing is ever drawn
+#[cfg(not(feature = "progress"))]
+#[derive(Debug, Clone)]
+pub(crate) struct Progress;
+
+#[cfg(not(feature = "progress"))]
+impl Progress {
+	pub fn new(_len: u64, _enabled: bool) -> Self {
+		return Progress;
+	}
+
+	pub fn counting(_len: u64, _unit: &str, _enabled: bool) -> Self {
+		return Progress;
+	}
+
+	pub fn inc(&self, _delta: u64) {}
+
+	pub fn set_matched(&self, _matched: impl FnOnce() -> usize) {}
+
+	pub fn set_message(&self, _message: impl FnOnce() -> String) {}
+
+	pub fn finish(&self, _message: impl FnOnce(f64) -> String) {}
+}
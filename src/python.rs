@@ -0,0 +1,189 @@
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::AssertUnwindSafe;
+
+use crate::config::SimulationConfig;
+use crate::error::SimulationError;
+use crate::sample::Sample;
+
+thread_local! {
+	// the message of the last call of this thread that failed
+	static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// run `call`, keeping its error or panic for `dating_simulation_last_error` and returning `failed` instead
+fn guard<T>(failed: T, call: impl FnOnce() -> Result<T, SimulationError>) -> T {
+	let message = match std::panic::catch_unwind(AssertUnwindSafe(call)) {
+		Ok(Ok(value)) => return value,
+		Ok(Err(error)) => error.to_string(),
+		Err(panic) => panic
+			.downcast_ref::<&str>()
+			.map(|message| message.to_string())
+			.or_else(|| panic.downcast_ref::<String>().cloned())
+			.unwrap_or_else(|| String::from("the simulation panicked"))
+	};
+	LAST_ERROR.with(|last_error| *last_error.borrow_mut() = CString::new(message.replace('\0', " ")).ok());
+
+	return failed;
+}
+
+fn to_c_string(text: String) -> Result<*mut c_char, SimulationError> {
+	let text = CString::new(text).map_err(|_| SimulationError::InvalidConfig(String::from("the json holds a nul byte")))?;
+
+	return Ok(text.into_raw());
+}
+
+/// the sample of `sample`, which has to come from `dating_simulation_sample_new`
+unsafe fn sample_mut<'a>(sample: *mut Sample) -> Result<&'a mut Sample, SimulationError> {
+	return unsafe { sample.as_mut() }.ok_or_else(|| SimulationError::InvalidConfig(String::from("the sample is null")));
+}
+
+/// the path in `path`, which is a nul terminated string
+#[cfg(feature = "arrow")]
+unsafe fn path_of(path: *const c_char) -> Result<std::path::PathBuf, SimulationError> {
+	if path.is_null() {
+		return Err(SimulationError::InvalidConfig(String::from("the path is null")));
+	}
+
+	return Ok(std::path::PathBuf::from(unsafe { CStr::from_ptr(path) }.to_string_lossy().into_owned()));
+}
+
+/// the message of the last call of this thread that failed, null when none did. owned by the library
+#[no_mangle]
+pub extern "C" fn dating_simulation_last_error() -> *const c_char {
+	return LAST_ERROR.with(|last_error| last_error.borrow().as_ref().map_or(std::ptr::null(), |message| message.as_ptr()));
+}
+
+/// draw the sample of a `SimulationConfig` given as json, null when the config is refused
+///
+/// # Safety
+/// `config` is a nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn dating_simulation_sample_new(config: *const c_char) -> *mut Sample {
+	return guard(std::ptr::null_mut(), || {
+		if config.is_null() {
+			return Err(SimulationError::InvalidConfig(String::from("the config is null")));
+		}
+		let config = unsafe { CStr::from_ptr(config) }.to_string_lossy();
+		let config: SimulationConfig = serde_json::from_str(&config)?;
+		config.validate()?;
+
+		return Ok(Box::into_raw(Box::new(config.generate_sample()?)));
+	});
+}
+
+/// free a sample of `dating_simulation_sample_new`
+///
+/// # Safety
+/// `sample` comes from `dating_simulation_sample_new` and is not used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn dating_simulation_sample_free(sample: *mut Sample) {
+	if !sample.is_null() {
+		drop(unsafe { Box::from_raw(sample) });
+	}
+}
+
+/// run one round of `Sample::match_making`, 0 when it went through and -1 otherwise
+///
+/// # Safety
+/// `sample` comes from `dating_simulation_sample_new`
+#[no_mangle]
+pub unsafe extern "C" fn dating_simulation_match_making(sample: *mut Sample) -> c_int {
+	return guard(-1, || {
+		unsafe { sample_mut(sample) }?.match_making()?;
+
+		return Ok(0);
+	});
+}
+
+/// the `Sample::statistics` as json
+///
+/// # Safety
+/// `sample` comes from `dating_simulation_sample_new`
+#[no_mangle]
+pub unsafe extern "C" fn dating_simulation_statistics(sample: *mut Sample) -> *mut c_char {
+	return guard(std::ptr::null_mut(), || {
+		return to_c_string(serde_json::to_string(&unsafe { sample_mut(sample) }?.statistics())?);
+	});
+}
+
+/// the `Sample::pairs` as a json list of `[proposer_id, reviewer_id, reviewer_score, proposer_score]`
+///
+/// # Safety
+/// `sample` comes from `dating_simulation_sample_new`
+#[no_mangle]
+pub unsafe extern "C" fn dating_simulation_pairs(sample: *mut Sample) -> *mut c_char {
+	return guard(std::ptr::null_mut(), || {
+		let pairs: Vec<(String, String, f32, f32)> = unsafe { sample_mut(sample) }?
+			.pairs()
+			.into_iter()
+			.map(|pair| (pair.proposer_id.to_string(), pair.reviewer_id.to_string(), pair.reviewer_score, pair.proposer_score))
+			.collect();
+
+		return to_c_string(serde_json::to_string(&pairs)?);
+	});
+}
+
+/// the whole sample as `Sample::to_json_file` writes it
+///
+/// # Safety
+/// `sample` comes from `dating_simulation_sample_new`
+#[no_mangle]
+pub unsafe extern "C" fn dating_simulation_to_json(sample: *mut Sample) -> *mut c_char {
+	return guard(std::ptr::null_mut(), || {
+		return to_c_string(serde_json::to_string(&unsafe { sample_mut(sample) }?.with_metadata())?);
+	});
+}
+
+/// write the pairs as `Sample::export_pairs_parquet` does to `path`, 0 when it went through and -1 otherwise
+///
+/// # Safety
+/// `sample` comes from `dating_simulation_sample_new` and `path` is a nul terminated string
+#[cfg(feature = "arrow")]
+#[no_mangle]
+pub unsafe extern "C" fn dating_simulation_export_pairs_parquet(sample: *mut Sample, path: *const c_char) -> c_int {
+	return guard(-1, || {
+		let sample = unsafe { sample_mut(sample) }?;
+		sample.export_pairs_parquet(&unsafe { path_of(path) }?)?;
+
+		return Ok(0);
+	});
+}
+
+/// the `ParquetTable::read` of `path` as json, `{"columns": [[name, [values]]], "metadata": {key: value}}`
+/// with nulls for the values that are missing
+///
+/// # Safety
+/// `path` is a nul terminated string
+#[cfg(feature = "arrow")]
+#[no_mangle]
+pub unsafe extern "C" fn dating_simulation_read_parquet(path: *const c_char) -> *mut c_char {
+	use crate::parquet::{ParquetColumn, ParquetTable};
+
+	return guard(std::ptr::null_mut(), || {
+		let table = ParquetTable::read(&unsafe { path_of(path) }?)?;
+		let columns: Vec<(&str, serde_json::Value)> = table
+			.columns
+			.iter()
+			.map(|(name, values)| (name.as_str(), match values {
+				ParquetColumn::Utf8(values) => serde_json::json!(values),
+				ParquetColumn::UInt32(values) => serde_json::json!(values),
+				ParquetColumn::Float32(values) => serde_json::json!(values),
+				ParquetColumn::Boolean(values) => serde_json::json!(values)
+			}))
+			.collect();
+
+		return to_c_string(serde_json::json!({ "columns": columns, "metadata": table.metadata }).to_string());
+	});
+}
+
+/// free a string returned by the library
+///
+/// # Safety
+/// `text` comes from the library and is not used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn dating_simulation_string_free(text: *mut c_char) {
+	if !text.is_null() {
+		drop(unsafe { CString::from_raw(text) });
+	}
+}
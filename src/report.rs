@@ -0,0 +1,126 @@
+use crate::graph::escape_xml;
+use crate::matching::MatchPair;
+use crate::statistics::{RunReport, ScoreDistribution, Statistics};
+
+/// the figures of a finished run as one html page, see `Sample::report`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+	pub run_report: RunReport,
+	// after the last round
+	pub statistics: Statistics,
+	// the pairs whose two scores sum to the most, highest first, at most `Report::TOP_PAIRS`
+	pub top_pairs: Vec<MatchPair>
+}
+
+/// kept inline so that the page needs nothing but itself, e.g. as an email attachment
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { border: 1px solid #ccc; padding: 0.2em 0.6em; text-align: right; }
+th { background: #eee; }
+td.label { text-align: left; }
+.bar { background: #6a9fd4; height: 1em; }";
+
+impl Report {
+	pub const TOP_PAIRS: usize = 20;
+
+	/// the page, headline statistics first, then the rounds, the score histograms and the top pairs.
+	/// it links to no scripts, stylesheets or images
+	pub fn render(&self) -> String {
+		let statistics = &self.statistics;
+		let mut html = String::new();
+
+		html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+		html.push_str(&format!("<title>Dating simulation, seed {}</title>\n", statistics.seed));
+		html.push_str(&format!("<style>\n{}\n</style>\n</head>\n<body>\n", STYLE));
+		html.push_str(&format!("<h1>Dating simulation, seed {}</h1>\n", statistics.seed));
+
+		html.push_str("<h2>Statistics</h2>\n<table>\n");
+		let matched = statistics.matched_males + statistics.matched_females + statistics.matched_non_binary;
+		let headlines = [
+			("Rounds", format!("{}, {}", self.run_report.rounds_executed, self.run_report.stop_reason)),
+			("Population", statistics.total_population.to_string()),
+			("Matched", matched.to_string()),
+			("Match rate", format_percentage(statistics.match_rate())),
+			("Mean candidate score", format_score(statistics.mean_candidate_score)),
+			("Median candidate score", format_score(statistics.median_candidate_score)),
+			("Total welfare", format!("{:.3}", statistics.total_welfare)),
+			("Blocking pairs", statistics.blocking_pairs.map(|pairs| format!("{:.0}", pairs)).unwrap_or_else(|| String::from("-")))
+		];
+		for (name, value) in headlines {
+			html.push_str(&format!("<tr><td class=\"label\">{}</td><td>{}</td></tr>\n", name, value));
+		}
+		html.push_str("</table>\n");
+
+		html.push_str("<h2>Rounds</h2>\n<table>\n");
+		html.push_str("<tr><th>Round</th><th>Matched</th><th>Match rate</th><th>Mean candidate score</th><th>New matches</th><th>Dissolved</th></tr>\n");
+		for round_stats in &self.run_report.round_stats {
+			let matched = round_stats.matched_males + round_stats.matched_females + round_stats.matched_non_binary;
+			let population = matched + round_stats.unmatched_males + round_stats.unmatched_females + round_stats.unmatched_non_binary;
+			html.push_str(&format!(
+				"<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+				round_stats.round,
+				matched,
+				format_percentage((population > 0).then(|| matched as f64 / population as f64)),
+				format_score(round_stats.mean_candidate_score),
+				round_stats.delta.new_matches,
+				round_stats.delta.dissolved
+			));
+		}
+		html.push_str("</table>\n");
+
+		html.push_str("<h2>Candidate scores</h2>\n");
+		if let Some(distribution) = &statistics.score_distribution {
+			push_histogram(&mut html, "All matched individuals", distribution);
+		}
+		for (gender, distribution) in &statistics.score_distribution_by_gender {
+			push_histogram(&mut html, &format!("Matched {}", gender), distribution);
+		}
+
+		html.push_str(&format!("<h2>Top {} pairs</h2>\n<table>\n", Report::TOP_PAIRS));
+		html.push_str("<tr><th>Proposer</th><th>Reviewer</th><th>Reviewer's score</th><th>Proposer's score</th><th>Round</th></tr>\n");
+		for pair in &self.top_pairs {
+			html.push_str(&format!(
+				"<tr><td class=\"label\">{}</td><td class=\"label\">{}</td><td>{:.3}</td><td>{:.3}</td><td>{}</td></tr>\n",
+				escape_xml(&pair.proposer_id.as_str()),
+				escape_xml(&pair.reviewer_id.as_str()),
+				pair.reviewer_score,
+				pair.proposer_score,
+				pair.matched_in_round.map(|round| round.to_string()).unwrap_or_default()
+			));
+		}
+		html.push_str("</table>\n</body>\n</html>\n");
+
+		return html;
+	}
+}
+
+fn format_percentage(rate: Option<f64>) -> String {
+	return rate.map(|rate| format!("{:.2}%", rate * 100.0)).unwrap_or_else(|| String::from("-"));
+}
+
+fn format_score(score: Option<f32>) -> String {
+	return score.map(|score| format!("{:.3}", score)).unwrap_or_else(|| String::from("-"));
+}
+
+/// a table of the buckets of `distribution`, each with a bar as wide as its share of the largest one
+fn push_histogram(html: &mut String, title: &str, distribution: &ScoreDistribution) {
+	html.push_str(&format!(
+		"<h3>{}</h3>\n<p>min {:.3}, max {:.3}, mean {:.3}, std dev {:.3}</p>\n<table>\n",
+		title,
+		distribution.min,
+		distribution.max,
+		distribution.mean,
+		distribution.std_dev
+	));
+
+	let largest = distribution.histogram.iter().copied().max().unwrap_or_default().max(1);
+	for (bucket, &count) in distribution.histogram.iter().enumerate() {
+		html.push_str(&format!(
+			"<tr><td>{:.3}</td><td class=\"label\" style=\"width: 20em\"><div class=\"bar\" style=\"width: {}%\"></div></td><td>{}</td></tr>\n",
+			distribution.bucket_start(bucket),
+			count * 100 / largest,
+			count
+		));
+	}
+	html.push_str("</table>\n");
+}
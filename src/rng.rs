@@ -0,0 +1,77 @@
+use rand::{RngCore, SeedableRng};
+
+/// the generator populations are drawn with, xoshiro256++. unlike `StdRng` and `rand::rngs::SmallRng`
+/// it is the same algorithm on every platform and in every version of `rand`,
+/// so a seed draws the same population in the browser too.
+/// it is not fit for anything that has to be unpredictable
+#[derive(Debug, Clone)]
+pub(crate) struct Xoshiro256PlusPlus {
+	state: [u64; 4]
+}
+
+impl RngCore for Xoshiro256PlusPlus {
+	fn next_u32(&mut self) -> u32 {
+		// the upper bits are the better ones
+		return (self.next_u64() >> 32) as u32;
+	}
+
+	fn next_u64(&mut self) -> u64 {
+		let [s0, s1, s2, s3] = self.state;
+		let result = s0.wrapping_add(s3).rotate_left(23).wrapping_add(s0);
+
+		let t = s1 << 17;
+		let mut state = [s0, s1, s2 ^ s0, s3 ^ s1];
+		state[1] ^= state[2];
+		state[0] ^= state[3];
+		state[2] ^= t;
+		state[3] = state[3].rotate_left(45);
+		self.state = state;
+
+		return result;
+	}
+
+	fn fill_bytes(&mut self, dest: &mut [u8]) {
+		for chunk in dest.chunks_mut(8) {
+			let bytes = self.next_u64().to_le_bytes();
+			chunk.copy_from_slice(&bytes[..chunk.len()]);
+		}
+	}
+
+	fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+		self.fill_bytes(dest);
+
+		return Ok(());
+	}
+}
+
+impl SeedableRng for Xoshiro256PlusPlus {
+	type Seed = [u8; 32];
+
+	/// an all-zero state would only ever give zeros, that seed starts from `seed_from_u64(0)` instead
+	fn from_seed(seed: [u8; 32]) -> Self {
+		if seed == [0; 32] {
+			return Xoshiro256PlusPlus::seed_from_u64(0);
+		}
+
+		let mut state = [0; 4];
+		for (word, bytes) in state.iter_mut().zip(seed.chunks_exact(8)) {
+			*word = u64::from_le_bytes(bytes.try_into().unwrap());
+		}
+
+		return Xoshiro256PlusPlus { state: state };
+	}
+
+	/// the state from splitmix64, as the authors of xoshiro recommend
+	fn seed_from_u64(mut seed: u64) -> Self {
+		let mut state = [0; 4];
+		for word in &mut state {
+			seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+			let mut z = seed;
+			z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+			z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+			*word = z ^ (z >> 31);
+		}
+
+		return Xoshiro256PlusPlus { state: state };
+	}
+}
@@ -0,0 +1,2477 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use rand::prelude::*;
+use rand::rngs::{OsRng, StdRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::attributes::{AttributeSchema, SchemaError};
+use crate::cancellation::CancellationToken;
+use crate::config::SampleConfig;
+use crate::drift::{NoOp, PreferenceDrift, PreferenceUpdate};
+use crate::encounter::{Encounter, EncounterOutcome, Encounters, VisibilityTable};
+use crate::error::SimulationError;
+use crate::event::{self, EventSink, SimEvent};
+use crate::graph::{self, GraphFormat, GraphKind};
+use crate::individual::{AgeConfig, Gender, GenderDistribution, Individual, Orientation, OrientationDistribution, RatingDistribution, ThresholdDistribution, WeightDistribution};
+use crate::matching::{self, MatchPair, MatchingAlgorithm, MatchingConfig, ScanOrder};
+use crate::memory::{self, MemoryReport};
+use crate::metadata::RunMetadata;
+use crate::progress::Progress;
+use crate::report::Report;
+use crate::rng::Xoshiro256PlusPlus;
+use crate::person_id::{HeldIdentities, PersonId};
+use crate::score_cache::{ScoreCache, ScoreCacheMode, Scorer};
+use crate::scoring::{CategoricalScoring, PercentileScoring, RatingValues, ScoreError, ScoreNoise, ScoringStrategy};
+use crate::statistics::{self, Desirability, DesirabilityReport, RoundDelta, RoundStats, RunReport, Statistics, StopReason};
+use crate::validation::ValidationError;
+
+/// the distributions `Sample::generate` drew the population from, 
+/// kept on the sample so that arrivals are drawn the same way
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenerationParameters {
+	// weights shared by everyone, random weights are drawn when absent
+	pub predefined_weights: Option<Vec<f32>>,
+	// how the random weights are drawn
+	pub weight_distribution: WeightDistribution,
+	pub gender_distribution: GenderDistribution,
+	pub orientation_distribution: OrientationDistribution,
+	pub threshold_distribution: ThresholdDistribution
+}
+
+impl GenerationParameters {
+	/// draw one individual of `attribute_schema`, of `gender` or one drawn from the `gender_distribution`
+	pub(crate) fn draw(
+		&self, 
+		attribute_schema: &AttributeSchema, 
+		age_config: &AgeConfig, 
+		gender: Option<Gender>,
+		rng: &mut impl Rng
+	) -> Result<Individual, SchemaError> {
+		let gender = gender.unwrap_or_else(|| self.gender_distribution.sample(rng));
+		let orientation = self.orientation_distribution.sample(rng);
+		// uniform weights are left to `Individual::new`, which draws them the same way
+		let drawn_weights = match (&self.predefined_weights, &self.weight_distribution) {
+			(None, WeightDistribution::UniformRange { .. } | WeightDistribution::Dirichlet { .. }) => {
+				Some(self.weight_distribution.sample(attribute_schema.len(), rng))
+			},
+			_ => None
+		};
+		
+		return Individual::new(
+			attribute_schema, 
+			self.predefined_weights.as_deref().or(drawn_weights.as_deref()),
+			gender,
+			orientation,
+			&self.threshold_distribution,
+			age_config,
+			rng
+		);
+	}
+}
+
+/// individuals joining and leaving the pool between rounds, the default keeps the pool fixed
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChurnConfig {
+	// new individuals drawn before every round
+	pub arrivals_per_round: u64,
+	// chance that a matched pair leaves the pool after a round, into `Sample::retired`
+	pub exit_on_match_probability: f32,
+	// chance that a matched pair breaks up before a round, both go back to being single
+	pub breakup_probability: f32,
+	// scale the breakup probability of a pair by the mean mutual score of all pairs over its own,
+	// pairs scoring below the mean break up more often, at most always
+	pub breakup_scaled_by_score: bool,
+	// the two who broke up blacklist each other, for `blacklist_duration_rounds` if it is set
+	pub blacklist_on_breakup: bool,
+	// after every this many rounds the matched look at their partner again, `None` never
+	pub divorce_every_rounds: Option<u32>,
+	// a pair divorces when either of them scores the other below this fraction of the mean
+	// candidate score of the matched, what the last round ended with. 0 keeps every pair
+	pub divorce_below: f32,
+	// the two who divorced blacklist each other, for `blacklist_duration_rounds` if it is set
+	pub blacklist_on_divorce: bool
+}
+
+impl ChurnConfig {
+	pub fn validate(&self) -> Result<(), SimulationError> {
+		if !(0.0..=1.0).contains(&self.exit_on_match_probability) {
+			return Err(
+				SimulationError::InvalidConfig(format!("the exit on match probability must be between 0 and 1, got {}", self.exit_on_match_probability))
+			);
+		}
+		if !(0.0..=1.0).contains(&self.breakup_probability) {
+			return Err(
+				SimulationError::InvalidConfig(format!("the breakup probability must be between 0 and 1, got {}", self.breakup_probability))
+			);
+		}
+		if self.divorce_every_rounds == Some(0) {
+			return Err(
+				SimulationError::InvalidConfig(String::from("the matched must look at their partner again after at least 1 round"))
+			);
+		}
+		if !self.divorce_below.is_finite() || self.divorce_below < 0.0 {
+			return Err(
+				SimulationError::InvalidConfig(format!("the fraction of the mean score below which pairs divorce must be a non-negative number, got {}", self.divorce_below))
+			);
+		}
+		
+		return Ok(());
+	}
+}
+
+/// who `Sample::describe_matches` shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisplayFilter {
+	// the pairs, then the unmatched
+	#[default]
+	All,
+	Matched,
+	Unmatched
+}
+
+/// the order `Sample::describe_matches` shows the pairs in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PairOrder {
+	// by descending reviewer score, the order of `Sample::pairs`
+	#[default]
+	ReviewerScore,
+	// by the sum of the scores the two give each other
+	BestFirst,
+	WorstFirst
+}
+
+/// what `Sample::describe_matches` shows, the default shows everyone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DisplayOptions {
+	pub filter: DisplayFilter,
+	// only the pairs scoring each other the highest, `None` for all of them
+	pub limit: Option<usize>,
+	pub order: PairOrder,
+	// name everyone by their full identity rather than "Alice Gray (3f2a91bc)", for output read by programs
+	pub full_ids: bool
+}
+
+/// how picky individuals start out and how they settle for less while they stay unmatched, 
+/// the default keeps everyone's `Individual::selectivity` at 1
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SelectivityConfig {
+	// the selectivity everyone is generated with, arrivals included
+	pub initial: f32,
+	// multiplies the selectivity of everyone still unmatched after a round, 1 keeps it
+	pub decay: f32,
+	// the selectivity never decays below it
+	pub floor: f32
+}
+
+impl Default for SelectivityConfig {
+	fn default() -> Self {
+		return SelectivityConfig {
+			initial: 1.0,
+			decay: 1.0,
+			floor: 0.0
+		};
+	}
+}
+
+impl SelectivityConfig {
+	pub fn validate(&self) -> Result<(), SimulationError> {
+		if !(0.0..).contains(&self.floor) || !(self.floor..).contains(&self.initial) {
+			return Err(
+				SimulationError::InvalidConfig(format!("the selectivity must start at or above its floor of at least 0, got {} and a floor of {}", self.initial, self.floor))
+			);
+		}
+		if !(f32::MIN_POSITIVE..=1.0).contains(&self.decay) {
+			return Err(
+				SimulationError::InvalidConfig(format!("the selectivity decay must be above 0 and at most 1, got {}", self.decay))
+			);
+		}
+		
+		return Ok(());
+	}
+}
+
+/// matched pairs having children who join the pool once they are old enough, the default has none
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReproductionConfig {
+	// children of every matched pair after every `every_rounds` rounds, 0 for none
+	pub births_per_pair: u32,
+	pub every_rounds: u32,
+	// standard deviation of the normal noise on the ratings a child gets from the mean of its parents
+	// and on the weights it gets from one of them. ratings are clamped to their attribute, weights are not
+	pub mutation_std_dev: f32,
+	// rounds a child waits in `Sample::juveniles` before it joins, 0 joins before the next round
+	pub maturity_rounds: u32
+}
+
+impl Default for ReproductionConfig {
+	fn default() -> Self {
+		return ReproductionConfig {
+			births_per_pair: 0,
+			every_rounds: 1,
+			mutation_std_dev: 0.0,
+			maturity_rounds: 0
+		};
+	}
+}
+
+impl ReproductionConfig {
+	pub fn validate(&self) -> Result<(), SimulationError> {
+		if self.every_rounds < 1 {
+			return Err(
+				SimulationError::InvalidConfig(String::from("pairs can have children after every round at the most, every_rounds must be at least 1"))
+			);
+		}
+		if !self.mutation_std_dev.is_finite() || self.mutation_std_dev < 0.0 {
+			return Err(
+				SimulationError::InvalidConfig(format!("the mutation standard deviation must be a non-negative number, got {}", self.mutation_std_dev))
+			);
+		}
+		
+		return Ok(());
+	}
+}
+
+/// a child that has not joined the pool yet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Juvenile {
+	// the 1-based round it joins before
+	pub joins_in_round: u32,
+	pub individual: Individual
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Sample {
+	// individuals of every gender, in the order they were generated
+	pub population: Vec<Individual>,
+	// the seed that the population was generated from, 
+	// pass it back to `Sample::generate` to reproduce this simulation
+	pub seed: u64,
+	// the attributes behind the weights and ratings, with how the ratings were drawn
+	#[serde(default)]
+	pub attribute_schema: AttributeSchema,
+	// number of match-making rounds that have been run on this sample
+	#[serde(default)]
+	pub round: u32,
+	// how `match_making` pairs the population
+	#[serde(default)]
+	pub matching_config: MatchingConfig,
+	// `Sample::run` stops once this many rounds in a row left the pairs unchanged,
+	// `None` always runs every round
+	#[serde(default)]
+	pub convergence_rounds: Option<u32>,
+	// rounds in a row that left the pairs unchanged so far, 
+	// kept in checkpoints so that a resumed run settles when the uninterrupted one would
+	#[serde(default)]
+	pub unchanged_rounds: u32,
+	// how individuals were given an age and how much they age per round
+	#[serde(default)]
+	pub age_config: AgeConfig,
+	// how the population was drawn, arrivals are drawn the same way
+	#[serde(default)]
+	pub generation: GenerationParameters,
+	#[serde(default)]
+	pub churn: ChurnConfig,
+	// see `Sample::set_selectivity`
+	#[serde(default)]
+	pub selectivity: SelectivityConfig,
+	// how the weights move between the rounds of `Sample::run`
+	#[serde(default)]
+	pub drift: PreferenceDrift,
+	#[serde(default)]
+	pub reproduction: ReproductionConfig,
+	// children waiting to come of age, in the order they were born
+	#[serde(default)]
+	pub juveniles: Vec<Juvenile>,
+	// pairs that left the pool, in the order they left
+	#[serde(default)]
+	pub retired: Vec<MatchPair>,
+	// pairs that broke up before a round so far, see `ChurnConfig::breakup_probability`
+	#[serde(default)]
+	pub breakups: usize,
+	// pairs that divorced before a round so far, see `ChurnConfig::divorce_every_rounds`
+	#[serde(default)]
+	pub divorces: usize,
+	// what `Sample::generate` drew the population from, with the seed it used. 
+	// `None` for samples made up of given individuals
+	#[serde(default)]
+	pub sample_config: Option<SampleConfig>,
+	// what the file the sample was read from was written by, `None` for files without it.
+	// a sample is always written with its current `Sample::metadata`
+	#[serde(default, rename = "metadata", skip_serializing)]
+	pub loaded_metadata: Option<RunMetadata>,
+	// whether to draw progress bars (on stderr) while generating and matching 
+	#[serde(skip)]
+	pub show_progress: bool,
+	// replaces `matching_config.scoring`, see `Sample::set_scoring_strategy`
+	#[serde(skip)]
+	scoring_strategy: Option<Arc<dyn ScoringStrategy>>,
+	// replaces `drift`, see `Sample::set_preference_update`
+	#[serde(skip)]
+	preference_update: Option<Arc<dyn PreferenceUpdate>>,
+	// position of each identity in `population`, see `Sample::rebuild_index`
+	#[serde(skip)]
+	index: HashMap<PersonId, usize>,
+	// the long identities of everyone in the population and the retired pairs, kept in the table of
+	// `PersonId` until the last sample holding them is dropped
+	#[serde(skip)]
+	identities: HeldIdentities,
+	// scores kept between rounds with `matching_config.score_cache`
+	#[serde(skip)]
+	score_cache: Option<ScoreCache>,
+	// who is seen by whom under `Encounters::Desirability`, see `Sample::refresh_visibility`
+	#[serde(skip)]
+	visibility: Option<Arc<VisibilityTable>>,
+	// where the events of every round go, see `Sample::set_event_sink`
+	#[serde(skip, default = "event::no_events")]
+	event_sink: Box<dyn EventSink>
+}
+
+/// a copy to run differently from the original, e.g. with another algorithm on the same people. 
+/// the copy sends its events nowhere until it gets a sink of its own, 
+/// its score cache is rebuilt by the first round that needs it
+impl Clone for Sample {
+	fn clone(&self) -> Self {
+		return Sample {
+			population: self.population.clone(),
+			seed: self.seed,
+			attribute_schema: self.attribute_schema.clone(),
+			round: self.round,
+			matching_config: self.matching_config.clone(),
+			convergence_rounds: self.convergence_rounds,
+			unchanged_rounds: self.unchanged_rounds,
+			age_config: self.age_config,
+			generation: self.generation.clone(),
+			churn: self.churn,
+			selectivity: self.selectivity,
+			drift: self.drift,
+			reproduction: self.reproduction,
+			juveniles: self.juveniles.clone(),
+			retired: self.retired.clone(),
+			breakups: self.breakups,
+			divorces: self.divorces,
+			sample_config: self.sample_config.clone(),
+			loaded_metadata: self.loaded_metadata.clone(),
+			show_progress: self.show_progress,
+			scoring_strategy: self.scoring_strategy.clone(),
+			preference_update: self.preference_update.clone(),
+			index: self.index.clone(),
+			identities: self.identities.clone(),
+			score_cache: None,
+			visibility: self.visibility.clone(),
+			event_sink: event::no_events()
+		};
+	}
+}
+
+/// two samples are equal when everything the json snapshot holds is, 
+/// the caches, the event sink, a custom scoring strategy and a custom preference update are left out
+impl PartialEq for Sample {
+	fn eq(&self, other: &Self) -> bool {
+		return self.population == other.population
+			&& self.seed == other.seed
+			&& self.attribute_schema == other.attribute_schema
+			&& self.round == other.round
+			&& self.matching_config == other.matching_config
+			&& self.convergence_rounds == other.convergence_rounds
+			&& self.unchanged_rounds == other.unchanged_rounds
+			&& self.age_config == other.age_config
+			&& self.generation == other.generation
+			&& self.churn == other.churn
+			&& self.selectivity == other.selectivity
+			&& self.drift == other.drift
+			&& self.reproduction == other.reproduction
+			&& self.juveniles == other.juveniles
+			&& self.retired == other.retired
+			&& self.breakups == other.breakups
+			&& self.divorces == other.divorces
+			&& self.sample_config == other.sample_config;
+	}
+}
+
+/// a sample as it is written to a file, its metadata first
+#[derive(Serialize)]
+pub(crate) struct WithMetadata<'a> {
+	metadata: RunMetadata,
+	#[serde(flatten)]
+	sample: &'a Sample
+}
+
+impl std::fmt::Display for Sample {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for gender in Gender::ALL {
+			if self.count(gender) == 0 {
+				continue;
+			}
+			
+			writeln!(f, "{} Population: ", gender)?;
+			for individual in self.of_gender(gender) {
+				writeln!(f, "===================")?;
+				writeln!(f, "{}", individual.describe(&self.attribute_schema))?;
+			}
+		}
+		
+		return Ok(());
+	}
+}
+
+impl Sample {
+	/// the largest population `Sample::statistics_with_blocking_pairs` counts every blocking pair of, larger ones get an estimate
+	pub const EXACT_BLOCKING_PAIRS_MAX: usize = 2000;
+	/// the pairs the estimate of `Sample::statistics_with_blocking_pairs` draws
+	pub const BLOCKING_PAIR_SAMPLES: usize = 1_000_000;
+	/// the largest population `Sample::statistics` checks every unmatched individual of for `Statistics::unmatched_by_threshold`
+	pub const EXACT_UNMATCHED_BY_THRESHOLD_MAX: usize = 2000;
+	/// the unmatched individuals the estimate of `Statistics::unmatched_by_threshold` checks in larger populations
+	pub const UNMATCHED_BY_THRESHOLD_SAMPLES: usize = 200;
+	
+	/// draw a population for simulating match-making as `config` describes, 
+	/// once it passes `SampleConfig::validate`. everyone rates and weights the attributes of its schema 
+	/// and all randomness comes from its seed, a random one is picked when it has none. 
+	/// the seed used is stored in `Sample::seed` and the config in `Sample::sample_config`
+	pub fn generate(config: &SampleConfig) -> Result<Self, SimulationError> {
+		return Sample::generate_with_rng(config, &mut OsRng);
+	}
+	
+	/// `Sample::generate`, picking the seed of a config without one from `rng` instead of the os, 
+	/// for targets like the browser where the os randomness has to be provided
+	pub fn generate_with_rng(config: &SampleConfig, rng: &mut impl RngCore) -> Result<Self, SimulationError> {
+		config.validate()?;
+		
+		return Ok(Sample::draw(config, rng)?);
+	}
+	
+	/// initiate a population for simulating match-making, 
+	/// everyone rates and weights the attributes of `attribute_schema`, 
+	/// the gender and orientation of each individual are drawn from 
+	/// `gender_distribution` and `orientation_distribution`, 
+	/// their minimum acceptable score from `threshold_distribution`, which has to pass 
+	/// `ThresholdDistribution::validate`, and their age from `age_config`. 
+	/// when `seed` is `None` a random seed is picked, 
+	/// either way the seed used is stored in `Sample::seed`
+	/// progress bars are only drawn when `show_progress` is set
+	#[deprecated(note = "use `Sample::generate` with a `SampleConfig`")]
+	#[allow(clippy::too_many_arguments)]
+	pub fn new(
+		population_size: usize,
+		attribute_schema: &AttributeSchema,
+		specified_predefined_weights: Option<Vec<f32>>,
+		gender_distribution: &GenderDistribution,
+		orientation_distribution: &OrientationDistribution,
+		threshold_distribution: &ThresholdDistribution,
+		age_config: &AgeConfig,
+		seed: Option<u64>,
+		show_progress: bool
+	) -> Result<Self, SchemaError> {
+		return Sample::draw(&SampleConfig {
+			population_size: population_size,
+			attribute_schema: attribute_schema.clone(),
+			predefined_weights: specified_predefined_weights,
+			weight_distribution: WeightDistribution::default(),
+			gender_distribution: *gender_distribution,
+			orientation_distribution: *orientation_distribution,
+			threshold_distribution: *threshold_distribution,
+			age: *age_config,
+			gender_counts: None,
+			seed: seed,
+			show_progress: show_progress
+		}, &mut OsRng);
+	}
+	
+	/// draw the population of `config` without checking anything but its schema, 
+	/// `seeds` picks the seed when the config has none
+	fn draw(config: &SampleConfig, seeds: &mut impl RngCore) -> Result<Self, SchemaError> {
+		config.attribute_schema.validate()?;
+		
+		let seed = config.seed.unwrap_or_else(|| seeds.r#gen());
+		let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+		
+		// the size is known up-front, a vector that grows by doubling ends up with spare room for up to as many again
+		let mut population: Vec<Individual> = Vec::with_capacity(config.population_size);
+		let generation = config.generation();
+		
+		let progress = Progress::new(
+			config.population_size as u64, 
+			config.show_progress
+		);
+		let genders = config.gender_counts.map(|counts| counts.shuffled(&mut rng));
+		
+		for position in 0..config.population_size {
+			let gender = genders.as_ref().map(|genders| genders[position]);
+			population.push(
+				generation.draw(&config.attribute_schema, &config.age, gender, &mut rng)?
+			);
+			
+			progress.inc(1);
+		}
+		
+		progress.finish(|elapsed| format!("generated in {:.1}s", elapsed));
+		tracing::info!(population = population.len(), seed = seed, "generated the population");
+		
+		return Ok(Sample::generated(population, config, seed));
+	}
+	
+	/// a sample of `population` as drawn from `config` with `seed`
+	pub(crate) fn generated(population: Vec<Individual>, config: &SampleConfig, seed: u64) -> Self {
+		let mut sample = Sample::from_individuals(population);
+		sample.seed = seed;
+		sample.attribute_schema = config.attribute_schema.clone();
+		sample.age_config = config.age;
+		sample.generation = config.generation();
+		sample.show_progress = config.show_progress;
+		sample.sample_config = Some(SampleConfig {
+			seed: Some(seed),
+			..config.clone()
+		});
+		
+		return sample;
+	}
+	
+	/// how much memory the sample takes, by what takes it
+	pub fn memory_estimate(&self) -> MemoryReport {
+		let mut report = MemoryReport {
+			population: memory::vec_bytes(&self.population),
+			index: memory::map_bytes(&self.index),
+			score_cache: self.score_cache.as_ref().map_or(0, ScoreCache::heap_bytes),
+			rest: memory::vec_bytes(&self.juveniles) + memory::vec_bytes(&self.retired),
+			..MemoryReport::default()
+		};
+		for individual in &self.population {
+			report.add(individual);
+		}
+		
+		return report;
+	}
+	
+	/// give back the room every vector and blacklist of the sample has spare, 
+	/// e.g. after blacklist entries expired or the population shrank through churn
+	pub fn shrink_to_fit(&mut self) {
+		self.population.shrink_to_fit();
+		for individual in &mut self.population {
+			individual.preference_weights.shrink_to_fit();
+			individual.ratings.shrink_to_fit();
+			if let Some(extras) = individual.extras_if_any() {
+				extras.percentile_ratings.shrink_to_fit();
+				extras.blacklist.shrink_to_fit();
+				extras.provisional_candidates.shrink_to_fit();
+				extras.history.shrink_to_fit();
+			}
+		}
+		self.index.shrink_to_fit();
+		self.juveniles.shrink_to_fit();
+		self.retired.shrink_to_fit();
+	}
+	
+	/// a sample of the given individuals, e.g. built with `IndividualBuilder`, in their order. 
+	/// the schema names the attributes of the first individual anonymously and the seed is 0, 
+	/// `Sample::validate` tells whether the individuals are consistent with each other
+	pub fn from_individuals(population: Vec<Individual>) -> Self {
+		let attribute_count = population
+			.first()
+			.map_or(0, |individual| individual.ratings.len());
+		
+		let mut sample = Sample {
+			population: population,
+			seed: 0,
+			attribute_schema: AttributeSchema::anonymous(attribute_count, RatingDistribution::default()),
+			round: 0,
+			matching_config: MatchingConfig::default(),
+			convergence_rounds: None,
+			unchanged_rounds: 0,
+			age_config: AgeConfig::default(),
+			generation: GenerationParameters::default(),
+			churn: ChurnConfig::default(),
+			selectivity: SelectivityConfig::default(),
+			drift: PreferenceDrift::default(),
+			reproduction: ReproductionConfig::default(),
+			juveniles: Vec::new(),
+			retired: Vec::new(),
+			breakups: 0,
+			divorces: 0,
+			sample_config: None,
+			loaded_metadata: None,
+			show_progress: false,
+			scoring_strategy: None,
+			preference_update: None,
+			index: HashMap::new(),
+			identities: HeldIdentities::default(),
+			score_cache: None,
+			visibility: None,
+			event_sink: event::no_events()
+		};
+		sample.rebuild_index();
+		
+		return sample;
+	}
+	
+	/// map every identity to its position in the population, 
+	/// call this after modifying `population` directly
+	pub fn rebuild_index(&mut self) {
+		self.index.clear();
+		// a map grown one insert at a time rehashes everything it holds every time it doubles
+		self.index.reserve(self.population.len());
+		
+		for (position, individual) in self.population.iter().enumerate() {
+			self.index.insert(individual.identity, position);
+		}
+		self.identities.hold(
+			self.population
+				.iter()
+				.map(|individual| individual.identity)
+				.chain(self.retired.iter().flat_map(|pair| [pair.proposer_id, pair.reviewer_id]))
+		);
+	}
+	
+	/// rebuild the index when the population was replaced or resized behind its back
+	fn ensure_index(&mut self) {
+		if self.index.len() != self.population.len() {
+			self.rebuild_index();
+		}
+	}
+	
+	/// look up an individual by identity
+	pub fn get(&self, identity: &str) -> Option<&Individual> {
+		return self.get_by_id(PersonId::get(identity)?);
+	}
+	
+	/// look up an individual by `PersonId`, what candidates and blacklists hold
+	pub fn get_by_id(&self, id: PersonId) -> Option<&Individual> {
+		return self.population.get(*self.index.get(&id)?);
+	}
+	
+	/// the `IndividualExtras::history` of the individual with `identity`, empty unless `MatchingConfig::record_history` is set
+	pub fn history_of(&self, identity: &str) -> Option<&[Encounter]> {
+		return self.get(identity).map(|individual| individual.extras().history.as_slice());
+	}
+	
+	/// look up an individual by identity for modification, 
+	/// changing the identity itself requires `Sample::rebuild_index`
+	pub fn get_mut(&mut self, identity: &str) -> Option<&mut Individual> {
+		return self.population.get_mut(*self.index.get(&PersonId::get(identity)?)?);
+	}
+	
+	/// number of attributes everyone rates and weights, 
+	/// samples saved without a schema go by the first individual
+	pub fn attribute_count(&self) -> usize {
+		if !self.attribute_schema.is_empty() {
+			return self.attribute_schema.len();
+		}
+		
+		return self.population
+			.first()
+			.map(|individual| individual.preference_weights.len())
+			.unwrap_or_default();
+	}
+	
+	/// the individuals of `gender`, in population order
+	pub fn of_gender(&self, gender: Gender) -> impl Iterator<Item = &Individual> {
+		return self.population
+			.iter()
+			.filter(move |individual| individual.gender == gender);
+	}
+	
+	/// number of individuals of `gender`
+	pub fn count(&self, gender: Gender) -> usize {
+		return self.of_gender(gender).count();
+	}
+	
+	/// write the whole sample, including the matching state, as json 
+	/// candidates are kept as identities so they can be resolved again after loading
+	#[cfg(feature = "fs")]
+	pub fn to_json_file(
+		&self, 
+		path: &std::path::Path
+	) -> Result<(), SimulationError> {
+		let file = std::fs::File::create(path)?;
+		self.to_json_writer(std::io::BufWriter::new(file))?;
+		tracing::debug!(path = %path.display(), population = self.population.len(), "wrote the sample");
+		
+		return Ok(());
+	}
+	
+	/// write the json of `Sample::to_json_file` to `writer`
+	pub fn to_json_writer(&self, writer: impl std::io::Write) -> Result<(), SimulationError> {
+		serde_json::to_writer_pretty(writer, &self.with_metadata())?;
+		
+		return Ok(());
+	}
+	
+	/// what produced the sample so far: the crate version, the seed, the config it was generated from,
+	/// the algorithm and the rounds run, as of now. exports and checkpoints are written with it
+	pub fn metadata(&self) -> RunMetadata {
+		return RunMetadata::new(self.seed, self.sample_config.clone(), self.matching_config.algorithm, self.round);
+	}
+	
+	pub(crate) fn with_metadata(&self) -> WithMetadata<'_> {
+		return WithMetadata { metadata: self.metadata(), sample: self };
+	}
+	
+	/// write the full state of a run to `path` so that `Sample::resume` can continue it: 
+	/// the population, the round counter, the configs and the seed. 
+	/// the random draws of a round are seeded from the seed and the round counter, 
+	/// those two are all of the generator state. the snapshot is written next to `path` first
+	/// and moved over it, a crash while writing keeps the previous checkpoint
+	#[cfg(feature = "fs")]
+	pub fn checkpoint(&self, path: &std::path::Path) -> Result<(), SimulationError> {
+		let mut partial = path.as_os_str().to_owned();
+		partial.push(".partial");
+		let partial = std::path::PathBuf::from(partial);
+		
+		let file = std::fs::File::create(&partial)?;
+		let mut writer = std::io::BufWriter::new(file);
+		serde_json::to_writer(&mut writer, &self.with_metadata())?;
+		std::io::Write::flush(&mut writer)?;
+		std::fs::rename(&partial, path)?;
+		tracing::info!(path = %path.display(), round = self.round, "wrote a checkpoint");
+		
+		return Ok(());
+	}
+	
+	/// continue a run from a file written by `Sample::checkpoint`. 
+	/// the next rounds draw what they would have drawn without the interruption, 
+	/// a strategy given to `Sample::set_scoring_strategy` has to be set again
+	#[cfg(feature = "fs")]
+	pub fn resume(path: &std::path::Path) -> Result<Self, SimulationError> {
+		let sample = Sample::from_json_file(path)?;
+		tracing::info!(path = %path.display(), round = sample.round, "resuming from a checkpoint");
+		
+		return Ok(sample);
+	}
+	
+	/// rebuild a sample from a file written by `Sample::to_json_file`, 
+	/// the snapshot is rejected when it references identities that do not exist
+	/// or when an individual's weights and ratings differ in length
+	#[cfg(feature = "fs")]
+	pub fn from_json_file(
+		path: &std::path::Path
+	) -> Result<Self, SimulationError> {
+		let file = std::fs::File::open(path)?;
+		let sample = Sample::from_json_reader(std::io::BufReader::new(file))?;
+		tracing::debug!(path = %path.display(), population = sample.population.len(), round = sample.round, "loaded the sample");
+		
+		return Ok(sample);
+	}
+	
+	/// rebuild a sample from the json of `Sample::to_json_file` read from `reader`, 
+	/// checked like `Sample::from_json_file` checks its file
+	pub fn from_json_reader(
+		reader: impl std::io::Read
+	) -> Result<Self, SimulationError> {
+		let mut sample: Sample = serde_json::from_reader(reader)?;
+		sample.rebuild_index();
+		
+		for individual in &sample.population {
+			if individual.preference_weights.len() != individual.ratings.len() {
+				return Err(SimulationError::WeightRatingLengthMismatch {
+					expected: individual.ratings.len(),
+					actual: individual.preference_weights.len()
+				});
+			}
+			
+			for (candidate, _) in individual.held_candidates() {
+				if sample.get_by_id(candidate).is_none() {
+					return Err(SimulationError::UnknownIdentity(candidate.to_string()));
+				}
+			}
+			
+			for rejected in individual.extras().blacklist.keys() {
+				if sample.get_by_id(*rejected).is_none() {
+					return Err(SimulationError::UnknownIdentity(rejected.to_string()));
+				}
+			}
+		}
+		
+		return Ok(sample);
+	}
+	
+	/// check the invariants of the matching state and report every violation: 
+	/// candidates exist, hold their individual back, are not the individual itself 
+	/// and come with a score, blacklist entries exist, 
+	/// everyone has a weight and a rating per attribute and a category per categorical attribute
+	pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+		let mut errors: Vec<ValidationError> = Vec::new();
+		
+		let expected = self.attribute_count();
+		
+		for individual in &self.population {
+			if individual.preference_weights.len() != expected || individual.ratings.len() != expected {
+				errors.push(ValidationError::AttributeCountMismatch {
+					identity: individual.identity,
+					expected: expected,
+					weights: individual.preference_weights.len(),
+					ratings: individual.ratings.len()
+				});
+			}
+			
+			let categorical = &self.attribute_schema.categorical;
+			let categories = &individual.extras().categories;
+			if categories.len() != categorical.len() || 
+				categorical.iter().zip(categories).any(|(attribute, &category)| category >= attribute.categories.len()) {
+				errors.push(ValidationError::CategoryMismatch {
+					identity: individual.identity,
+					expected: categorical.len(),
+					categories: categories.len()
+				});
+			}
+			
+			if individual.candidate.is_some() != individual.candidate_score.is_some() {
+				errors.push(ValidationError::CandidateScoreMismatch {
+					identity: individual.identity
+				});
+			}
+			
+			if let Some(candidate) = individual.candidate {
+				if candidate == individual.identity {
+					errors.push(ValidationError::SelfCandidate {
+						identity: individual.identity
+					});
+				} else {
+					match self.get_by_id(candidate) {
+						None => errors.push(ValidationError::UnknownCandidate {
+							identity: individual.identity,
+							candidate: candidate
+						}),
+						Some(partner) if !partner.holds(individual.identity) => {
+							errors.push(ValidationError::NotMutual {
+								identity: individual.identity,
+								candidate: candidate
+							});
+						},
+						Some(_) => {}
+					}
+				}
+			}
+			
+			for &(candidate, _) in &individual.extras().provisional_candidates {
+				if self.get_by_id(candidate).is_none() {
+					errors.push(ValidationError::UnknownCandidate {
+						identity: individual.identity,
+						candidate: candidate
+					});
+				}
+			}
+			
+			let mut blacklist: Vec<PersonId> = individual.extras().blacklist.keys().copied().collect();
+			blacklist.sort();
+			for blacklisted in blacklist {
+				if self.get_by_id(blacklisted).is_none() {
+					errors.push(ValidationError::UnknownBlacklisted {
+						identity: individual.identity,
+						blacklisted: blacklisted
+					});
+				}
+			}
+		}
+		
+		if errors.is_empty() {
+			return Ok(());
+		}
+		
+		return Err(errors);
+	}
+	
+	/// the individual's candidate, provided the candidate names them back
+	fn mutual_partner(&self, individual: &Individual) -> Option<&Individual> {
+		return individual.candidate
+			.and_then(|candidate| self.get_by_id(candidate))
+			.filter(|partner| partner.candidate == Some(individual.identity));
+	}
+	
+	/// whether each individual of `population` is part of a mutual pair
+	fn matched(&self) -> Vec<bool> {
+		return self.population
+			.iter()
+			.map(|individual| self.mutual_partner(individual).is_some())
+			.collect();
+	}
+	
+	/// how many unmatched individuals have nobody left to match because they score
+	/// everyone they are compatible with below their minimum acceptable score, and whether it is an estimate.
+	/// each of them is scored against the whole pool, so above `Sample::EXACT_UNMATCHED_BY_THRESHOLD_MAX`
+	/// individuals only `Sample::UNMATCHED_BY_THRESHOLD_SAMPLES` drawn at random are and their share is scaled up
+	fn unmatched_by_threshold(&self, matched: &[bool]) -> (usize, bool) {
+		let scoring = self.scoring_strategy();
+		let refuses_everyone = |individual: &Individual| -> bool {
+			let mut compatible = self.population
+				.iter()
+				.filter(|other| other.identity != individual.identity && individual.compatible_with(other))
+				.peekable();
+			
+			return compatible.peek().is_some() && compatible.all(|other| {
+				scoring
+					.score(individual, other)
+					.is_ok_and(|score| score < individual.demanded_score())
+			});
+		};
+		// without a minimum acceptable score anyone compatible will do
+		let demanding: Vec<&Individual> = self.population
+			.iter()
+			.zip(matched)
+			.filter(|(individual, &is_matched)| !is_matched && individual.demanded_score() > f32::NEG_INFINITY)
+			.map(|(individual, _)| individual)
+			.collect();
+		
+		if self.population.len() <= Sample::EXACT_UNMATCHED_BY_THRESHOLD_MAX || demanding.len() <= Sample::UNMATCHED_BY_THRESHOLD_SAMPLES {
+			return (demanding.into_iter().filter(|individual| refuses_everyone(individual)).count(), false);
+		}
+		
+		let mut rng = StdRng::seed_from_u64(self.seed);
+		let refusing = demanding
+			.choose_multiple(&mut rng, Sample::UNMATCHED_BY_THRESHOLD_SAMPLES)
+			.filter(|individual| refuses_everyone(individual))
+			.count();
+		let estimate = refusing as f64 / Sample::UNMATCHED_BY_THRESHOLD_SAMPLES as f64 * demanding.len() as f64;
+		
+		return (estimate.round() as usize, true);
+	}
+	
+	/// what scores outside of a round go through, the `ScoreCache` while it still fits the population
+	fn scorer<'a>(&'a self, strategy: &'a dyn ScoringStrategy) -> Scorer<'a> {
+		let scoring = match self.scoring_strategy {
+			Some(_) => None,
+			None => Some(self.matching_config.scoring)
+		};
+		
+		// the statistics judge the pairs by what the individuals truly score each other
+		return Scorer {
+			strategy: strategy,
+			cache: self.score_cache.as_ref().filter(|cache| cache.fits(&self.population, scoring, self.matching_config.rating_values)),
+			noise: ScoreNoise::None,
+			noise_seed: 0
+		};
+	}
+	
+	/// the score everyone gives their partner by their own scoring, `None` for the unmatched
+	fn partner_scores(&self, scorer: Scorer) -> Vec<Option<f32>> {
+		return (0..self.population.len())
+			.map(|position| {
+				let partner = self.mutual_partner(&self.population[position])?;
+				
+				return scorer.score(&self.population, position, self.index[&partner.identity]).ok();
+			})
+			.collect();
+	}
+	
+	/// whether the individuals at `first` and `second` would both rather be with each other than
+	/// where they are, each by their own score, single individuals take anyone above their minimum
+	fn blocks(&self, scorer: Scorer, partner_scores: &[Option<f32>], first: usize, second: usize) -> bool {
+		let first_individual = &self.population[first];
+		let second_individual = &self.population[second];
+		let partners = first_individual.candidate == Some(second_individual.identity) &&
+			second_individual.candidate == Some(first_individual.identity);
+		if first == second ||
+			partners ||
+			!first_individual.compatible_with(second_individual) ||
+			!first_individual.accepts(second_individual) ||
+			!second_individual.accepts(first_individual) {
+			return false;
+		}
+		
+		let prefers = |rater: usize, rated: usize| {
+			return scorer.score(&self.population, rater, rated).is_ok_and(|score| {
+				score >= self.population[rater].demanded_score() &&
+					partner_scores[rater].is_none_or(|partner_score| score > partner_score)
+			});
+		};
+		
+		return prefers(first, second) && prefers(second, first);
+	}
+	
+	/// the two of a blocking pair ordered like a `MatchPair`
+	fn blocking_pair(&self, first: usize, second: usize) -> (PersonId, PersonId) {
+		let first_individual = &self.population[first];
+		let second_individual = &self.population[second];
+		
+		if (second_individual.gender, second) < (first_individual.gender, first) {
+			return (second_individual.identity, first_individual.identity);
+		}
+		
+		return (first_individual.identity, second_individual.identity);
+	}
+	
+	/// every pair of compatible individuals who both score each other higher than their partners, 
+	/// or are single and score each other at least their `Individual::demanded_score`, so the matching 
+	/// is not stable. O(n²) scores, through the `ScoreCache` when there is one
+	pub fn blocking_pairs(&self) -> Vec<(PersonId, PersonId)> {
+		let strategy = self.scoring_strategy();
+		let scorer = self.scorer(strategy.as_ref());
+		let partner_scores = self.partner_scores(scorer);
+		
+		return (0..self.population.len())
+			.into_par_iter()
+			.flat_map_iter(|first| {
+				let partner_scores = &partner_scores;
+				
+				return (first + 1..self.population.len())
+					.filter(move |&second| self.blocks(scorer, partner_scores, first, second))
+					.map(move |second| self.blocking_pair(first, second));
+			})
+			.collect();
+	}
+	
+	/// the number of blocking pairs estimated from `samples` pairs of individuals drawn with `seed`,
+	/// for populations too large for `Sample::blocking_pairs`
+	pub fn estimate_blocking_pairs(&self, samples: usize, seed: u64) -> f64 {
+		let len = self.population.len();
+		if len < 2 || samples == 0 {
+			return 0.0;
+		}
+		
+		let strategy = self.scoring_strategy();
+		let scorer = self.scorer(strategy.as_ref());
+		let partner_scores = self.partner_scores(scorer);
+		
+		let mut rng = StdRng::seed_from_u64(seed);
+		let mut blocking = 0;
+		for _ in 0..samples {
+			let first = rng.gen_range(0..len);
+			// any of the others, with the same chance
+			let second = (first + rng.gen_range(1..len)) % len;
+			if self.blocks(scorer, &partner_scores, first, second) {
+				blocking += 1;
+			}
+		}
+		
+		let pairs = len as f64 * (len - 1) as f64 / 2.0;
+		
+		return blocking as f64 / samples as f64 * pairs;
+	}
+	
+	/// the sum of the scores both sides of every pair give each other, 
+	/// pairs that can not be scored count nothing
+	fn total_welfare(&self) -> f64 {
+		let scoring = self.scoring_strategy();
+		
+		return self.pairs()
+			.iter()
+			.map(|pair| {
+				let proposer = &self.population[self.index[&pair.proposer_id]];
+				let reviewer = &self.population[self.index[&pair.reviewer_id]];
+				
+				return [scoring.score(proposer, reviewer), scoring.score(reviewer, proposer)]
+					.into_iter()
+					.filter_map(Result::ok)
+					.map(|score| score as f64)
+					.sum::<f64>();
+			})
+			.sum();
+	}
+	
+	/// summarize the current matching state as the stats of the latest round
+	pub fn round_stats(&self, blacklist_growth: usize) -> RoundStats {
+		return RoundStats::new(
+			self.round,
+			&self.population,
+			&self.matched(),
+			blacklist_growth
+		);
+	}
+	
+	/// run up to `max_rounds` rounds of match-making and collect the stats of every round, 
+	/// stopping early once the pairs settled for `convergence_rounds` rounds, 
+	/// counting the unchanged rounds of earlier runs
+	pub fn run(&mut self, max_rounds: u32) -> Result<RunReport, SimulationError> {
+		return self.run_with(max_rounds, |_, _| {});
+	}
+	
+	/// `Sample::run`, calling `on_round` after every round, e.g. to print or export its stats
+	pub fn run_with(
+		&mut self, 
+		max_rounds: u32, 
+		on_round: impl FnMut(&Sample, &RoundStats)
+	) -> Result<RunReport, SimulationError> {
+		return self.run_until(max_rounds, &CancellationToken::new(), on_round);
+	}
+	
+	/// `Sample::run_with`, stopping after the round in progress once `cancellation` is cancelled. 
+	/// a cancelled run keeps any provisional candidates so that a checkpoint of it resumes the run
+	pub fn run_until(
+		&mut self, 
+		max_rounds: u32, 
+		cancellation: &CancellationToken,
+		mut on_round: impl FnMut(&Sample, &RoundStats)
+	) -> Result<RunReport, SimulationError> {
+		let mut round_stats: Vec<RoundStats> = Vec::new();
+		let mut stop_reason = StopReason::MaxRounds;
+		
+		while round_stats.len() < max_rounds as usize {
+			if cancellation.is_cancelled() {
+				stop_reason = StopReason::Cancelled;
+				break;
+			}
+			
+			let stats = self.match_making()?;
+			self.update_preferences();
+			
+			if stats.delta.changed() {
+				self.unchanged_rounds = 0;
+			} else {
+				self.unchanged_rounds += 1;
+			}
+			on_round(self, &stats);
+			round_stats.push(stats);
+			
+			if self.convergence_rounds.is_some_and(|convergence_rounds| self.unchanged_rounds >= convergence_rounds) {
+				stop_reason = StopReason::Converged;
+				break;
+			}
+		}
+		
+		if self.matching_config.candidate_capacity > 1 && stop_reason != StopReason::Cancelled {
+			self.commit_candidates();
+		}
+		self.event_sink.finish()?;
+		
+		return Ok(RunReport {
+			rounds_executed: round_stats.len() as u32,
+			stop_reason: stop_reason,
+			round_stats: round_stats
+		});
+	}
+	
+	/// collapse everyone to a single candidate, the end of a run with a `candidate_capacity` above 1. 
+	/// every two individuals holding each other are a mutual option, the options are taken
+	/// by the sum of both scores, best first, as long as both of the two are still free. 
+	/// whoever is left without one holds nobody afterwards
+	pub fn commit_candidates(&mut self) {
+		self.ensure_index();
+		
+		let mut options: Vec<(f32, usize, usize, f32, f32)> = Vec::new();
+		for (position, individual) in self.population.iter().enumerate() {
+			for (candidate, score) in individual.held_candidates() {
+				let Some(&partner) = self.index.get(&candidate) else {
+					continue;
+				};
+				if partner <= position {
+					continue;
+				}
+				
+				let partner_score = self.population[partner]
+					.held_candidates()
+					.find(|(held, _)| *held == individual.identity)
+					.map(|(_, partner_score)| partner_score);
+				if let Some(partner_score) = partner_score {
+					options.push((score + partner_score, position, partner, score, partner_score));
+				}
+			}
+		}
+		options.sort_by(|first, second| second.0.total_cmp(&first.0).then((first.1, first.2).cmp(&(second.1, second.2))));
+		
+		let mut committed: Vec<Option<(PersonId, f32)>> = vec![None; self.population.len()];
+		for (_, first, second, first_score, second_score) in options {
+			if committed[first].is_some() || committed[second].is_some() {
+				continue;
+			}
+			
+			committed[first] = Some((self.population[second].identity, first_score));
+			committed[second] = Some((self.population[first].identity, second_score));
+		}
+		
+		for (individual, committed) in self.population.iter_mut().zip(committed) {
+			let (candidate, candidate_score) = committed.unzip();
+			individual.candidate = candidate;
+			individual.candidate_score = candidate_score;
+			if let Some(extras) = individual.extras_if_any() {
+				extras.provisional_candidates.clear();
+			}
+			if individual.candidate.is_none() {
+				individual.matched_in_round = None;
+			}
+		}
+	}
+	
+	/// the current pairs as `(proposer, reviewer)` identities
+	fn pair_set(&self) -> HashSet<(PersonId, PersonId)> {
+		return self.pairs()
+			.into_iter()
+			.map(|pair| (pair.proposer_id, pair.reviewer_id))
+			.collect();
+	}
+	
+	/// compare the pairs before a round with the ones after it
+	pub(crate) fn round_delta(
+		before: &HashSet<(PersonId, PersonId)>, 
+		after: &HashSet<(PersonId, PersonId)>, 
+		rejections: usize
+	) -> RoundDelta {
+		let matched_before: HashSet<PersonId> = before
+			.iter()
+			.flat_map(|&(proposer, reviewer)| [proposer, reviewer])
+			.collect();
+		
+		let mut delta = RoundDelta {
+			rejections: rejections,
+			dissolved: before.difference(after).count(),
+			..RoundDelta::default()
+		};
+		
+		for (proposer, reviewer) in after.difference(before) {
+			if matched_before.contains(proposer) || matched_before.contains(reviewer) {
+				delta.upgrades += 1;
+			} else {
+				delta.new_matches += 1;
+			}
+		}
+		
+		return delta;
+	}
+	
+	/// every mutual pair, by descending reviewer score, then by identities. 
+	/// an individual whose candidate has moved on is a stale pair and not reported
+	pub fn pairs(&self) -> Vec<MatchPair> {
+		let mut pairs: Vec<MatchPair> = self.population
+			.iter()
+			.enumerate()
+			.filter_map(|(position, proposer)| {
+				let reviewer = self.mutual_partner(proposer)?;
+				
+				// report every pair once, from the side of its proposer
+				if (reviewer.gender, self.index[&reviewer.identity]) < (proposer.gender, position) {
+					return None;
+				}
+				
+				return Some(MatchPair {
+					proposer_id: proposer.identity,
+					reviewer_id: reviewer.identity,
+					reviewer_score: reviewer.candidate_score.unwrap_or_default(),
+					proposer_score: proposer.candidate_score.unwrap_or_default(),
+					matched_in_round: proposer.matched_in_round
+				});
+			})
+			.collect();
+		
+		pairs.sort_by(|a, b| b.reviewer_score
+			.total_cmp(&a.reviewer_score)
+			.then_with(|| a.proposer_id.cmp(&b.proposer_id))
+			.then_with(|| a.reviewer_id.cmp(&b.reviewer_id))
+		);
+		
+		return pairs;
+	}
+	
+	/// write one csv row per matched pair, 
+	/// a pair is only reported when both individuals name each other as candidate
+	/// with `include_unmatched` every other individual gets a row with empty partner columns. 
+	/// pairs that left the pool follow the current ones with `retired` set. 
+	/// the rating sums are followed by one column per attribute and side, 
+	/// e.g. `proposer_looks` and `reviewer_looks`. the header follows the `Sample::metadata` as `#` comments
+	pub fn export_matches_csv(
+		&self, 
+		mut writer: impl std::io::Write, 
+		include_unmatched: bool
+	) -> Result<(), SimulationError> {
+		let attribute_count = self.attribute_count();
+		let ratings = |individual: Option<&Individual>| -> String {
+			return (0..attribute_count)
+				.map(|position| individual
+					.and_then(|individual| individual.ratings.get(position))
+					.map(|rating| rating.to_string())
+					.unwrap_or_default()
+				)
+				.collect::<Vec<String>>()
+				.join(",");
+		};
+		
+		let mut header = String::from("proposer_id,reviewer_id,score,proposer_rating_sum,reviewer_rating_sum");
+		for side in ["proposer", "reviewer"] {
+			for position in 0..attribute_count {
+				header.push_str(&format!(",{}_{}", side, self.attribute_schema.name(position)));
+			}
+		}
+		header.push_str(",matched_in_round,proposer_rejections_received,proposer_rejections_given,reviewer_rejections_received,reviewer_rejections_given,proposer_age,reviewer_age,retired,proposer_top_attribute,reviewer_top_attribute");
+		self.metadata().write_csv_comment(&mut writer)?;
+		writeln!(writer, "{}", header)?;
+		
+		for pair in self.pairs() {
+			// both are in the index, `pairs` resolved them through it
+			let proposer = self.get_by_id(pair.proposer_id).unwrap();
+			let reviewer = self.get_by_id(pair.reviewer_id).unwrap();
+			
+			writeln!(
+				writer, 
+				"{},{},{},{},{},{},{},{},{},{},{},{},{},{},false,{},{}", 
+				proposer.identity, 
+				reviewer.identity, 
+				pair.reviewer_score, 
+				proposer.ratings.iter().sum::<f32>(), 
+				reviewer.ratings.iter().sum::<f32>(),
+				ratings(Some(proposer)),
+				ratings(Some(reviewer)),
+				pair.matched_in_round.map(|round| round.to_string()).unwrap_or_default(),
+				proposer.rejections_received,
+				proposer.rejections_given,
+				reviewer.rejections_received,
+				reviewer.rejections_given,
+				proposer.age,
+				reviewer.age,
+				self.top_attribute_name(proposer, reviewer)?,
+				self.top_attribute_name(reviewer, proposer)?
+			)?;
+		}
+		
+		// retired pairs left the pool, only their identities and scores are kept
+		for pair in &self.retired {
+			writeln!(
+				writer, 
+				"{},{},{},,,{},{},{},,,,,,,true,,", 
+				pair.proposer_id, 
+				pair.reviewer_id, 
+				pair.reviewer_score, 
+				ratings(None),
+				ratings(None),
+				pair.matched_in_round.map(|round| round.to_string()).unwrap_or_default()
+			)?;
+		}
+		
+		if include_unmatched {
+			for (individual, matched) in self.population.iter().zip(self.matched()) {
+				if matched {
+					continue;
+				}
+				
+				writeln!(
+					writer, 
+					"{},,,{},,{},{},,{},{},,,{},,false,,", 
+					individual.identity, 
+					individual.ratings.iter().sum::<f32>(),
+					ratings(Some(individual)),
+					ratings(None),
+					individual.rejections_received,
+					individual.rejections_given,
+					individual.age
+				)?;
+			}
+		}
+		
+		return Ok(());
+	}
+
+	/// the html `Report` of a run of this sample that ended with `run_report`,
+	/// with the statistics and the highest scoring pairs as they are now
+	pub fn report(&self, run_report: &RunReport) -> Report {
+		let mut pairs = self.pairs();
+		pairs.sort_by(|a, b| (b.reviewer_score + b.proposer_score).total_cmp(&(a.reviewer_score + a.proposer_score)));
+		pairs.truncate(Report::TOP_PAIRS);
+		
+		return Report {
+			run_report: run_report.clone(),
+			statistics: self.statistics_with_blocking_pairs(),
+			top_pairs: pairs
+		};
+	}
+
+	/// write the population as a graph with one node per individual, colored by whether they are matched,
+	/// and one edge per mutual pair or per blacklist entry depending on `kind`.
+	/// with `max_nodes` only that many individuals are drawn, picked by the sample's seed,
+	/// and only edges between two drawn individuals are kept
+	pub fn export_graph(
+		&self,
+		writer: impl std::io::Write,
+		kind: GraphKind,
+		format: GraphFormat,
+		max_nodes: Option<usize>
+	) -> Result<(), SimulationError> {
+		let mut nodes: Vec<graph::Node> = self.population
+			.iter()
+			.zip(self.matched())
+			.map(|(individual, matched)| graph::Node { individual: individual, matched: matched })
+			.collect();
+
+		if let Some(max_nodes) = max_nodes {
+			if nodes.len() > max_nodes {
+				let mut rng = StdRng::seed_from_u64(self.seed);
+				nodes = nodes.choose_multiple(&mut rng, max_nodes).copied().collect();
+				// keep the population order so the same seed always writes the same file
+				nodes.sort_by_key(|node| self.index[&node.individual.identity]);
+			}
+		}
+
+		let drawn: HashSet<PersonId> = nodes
+			.iter()
+			.map(|node| node.individual.identity)
+			.collect();
+
+		let pairs = self.pairs();
+		let mut edges: Vec<(PersonId, PersonId)> = match kind {
+			GraphKind::Matches => pairs
+				.iter()
+				.map(|pair| (pair.proposer_id, pair.reviewer_id))
+				.collect(),
+			GraphKind::Blacklist => self.population
+				.iter()
+				.flat_map(|individual| {
+					let mut blacklist: Vec<PersonId> = individual.extras().blacklist.keys().copied().collect();
+					blacklist.sort();
+
+					return blacklist
+						.into_iter()
+						.map(move |blacklisted| (individual.identity, blacklisted));
+				})
+				.collect()
+		};
+		edges.retain(|(from, to)| drawn.contains(from) && drawn.contains(to));
+
+		return match format {
+			GraphFormat::Dot => graph::write_dot(writer, kind, &nodes, &edges),
+			GraphFormat::GraphMl => graph::write_graphml(writer, kind, &nodes, &edges)
+		};
+	}
+
+	/// process the action after the two gets matched
+	/// each side keeps the score they gave to the other. 
+	/// the pair is stamped with `round` unless the two already were a pair
+	pub fn liked(
+		reviewer: &mut Individual, 
+		proposer: &mut Individual, 
+		reviewer_score: f32,
+		proposer_score: f32,
+		round: u32
+	) {
+		let already_paired = reviewer.candidate == Some(proposer.identity) && 
+			proposer.candidate == Some(reviewer.identity);
+		if !already_paired {
+			reviewer.matched_in_round = Some(round);
+			proposer.matched_in_round = Some(round);
+		}
+		
+		reviewer.candidate = Some(
+			proposer.identity
+		);
+		reviewer.candidate_score = Some(
+			reviewer_score
+		);
+		
+		proposer.candidate = Some(
+			reviewer.identity
+		);
+		proposer.candidate_score = Some(
+			proposer_score
+		);
+	}
+	
+	/// score with `strategy` instead of the built-in `matching_config.scoring`, 
+	/// a custom strategy is not part of the json snapshot
+	pub fn set_scoring_strategy(&mut self, strategy: Arc<dyn ScoringStrategy>) {
+		self.scoring_strategy = Some(strategy);
+		self.score_cache = None;
+	}
+	
+	/// move the weights with `update` between the rounds of `Sample::run` instead of the built-in `drift`, 
+	/// a custom update is not part of the json snapshot
+	pub fn set_preference_update(&mut self, update: Arc<dyn PreferenceUpdate>) {
+		self.preference_update = Some(update);
+	}
+	
+	/// the update `Sample::run` moves the weights with after every round, `NoOp` without drift
+	pub fn preference_update(&self) -> Arc<dyn PreferenceUpdate> {
+		return match (&self.preference_update, self.drift.is_active()) {
+			(Some(update), _) => Arc::clone(update),
+			(None, true) => Arc::new(self.drift),
+			(None, false) => Arc::new(NoOp)
+		};
+	}
+	
+	/// run the `preference_update` on the round that just ended and forget the cached scores 
+	/// of everyone whose weights it changed
+	fn update_preferences(&mut self) {
+		if self.preference_update.is_none() && !self.drift.is_active() {
+			return;
+		}
+		
+		// the drift of a round draws from its own seed
+		let mut rng = StdRng::seed_from_u64(
+			self.seed ^ (self.round as u64).wrapping_mul(0xA24B_AED4_963E_E407)
+		);
+		let weights_before: Vec<Vec<f32>> = match self.score_cache {
+			Some(_) => self.population.iter().map(|individual| individual.preference_weights.clone()).collect(),
+			None => Vec::new()
+		};
+		
+		self.preference_update().update(&mut self.population, self.round, &mut rng);
+		
+		if let Some(cache) = &self.score_cache {
+			for (rater, (individual, before)) in self.population.iter().zip(&weights_before).enumerate() {
+				if individual.preference_weights != *before {
+					cache.invalidate_rater(rater);
+				}
+			}
+		}
+	}
+	
+	/// send the events of every round of `match_making` to `sink`, a sink is not part of the json snapshot
+	pub fn set_event_sink(&mut self, sink: Box<dyn EventSink>) {
+		self.event_sink = sink;
+	}
+	
+	/// the strategy `match_making` scores with, wrapped in a `PercentileScoring` under `RatingValues::Percentile` 
+	/// and in a `CategoricalScoring` when the schema has categorical attributes
+	pub fn scoring_strategy(&self) -> Arc<dyn ScoringStrategy> {
+		let mut strategy = match &self.scoring_strategy {
+			Some(strategy) => strategy.clone(),
+			None => self.matching_config.scoring.strategy()
+		};
+		if self.matching_config.rating_values == RatingValues::Percentile {
+			strategy = Arc::new(PercentileScoring { strategy: strategy });
+		}
+		if self.attribute_schema.categorical.is_empty() {
+			return strategy;
+		}
+		
+		return Arc::new(CategoricalScoring { strategy: strategy, attribute_schema: self.attribute_schema.clone() });
+	}
+	
+	/// rank every rating among the population into the `Individual::percentile_ratings` that 
+	/// `RatingValues::Percentile` scores. a round ranks the population itself while anyone is not ranked, 
+	/// e.g. after arrivals, rank it again after changing ratings by hand
+	pub fn rank_ratings(&mut self) {
+		let ranks: Vec<Vec<f32>> = (0..self.attribute_count())
+			.map(|position| {
+				let ratings: Vec<f32> = self.population
+					.iter()
+					.map(|individual| individual.ratings.get(position).copied().unwrap_or(f32::NAN))
+					.collect();
+				
+				return statistics::percentile_ranks(&ratings);
+			})
+			.collect();
+		
+		for (index, individual) in self.population.iter_mut().enumerate() {
+			let percentile_ratings = (0..individual.ratings.len().min(ranks.len()))
+				.map(|position| Individual::MIN_RATING + ranks[position][index] * (Individual::MAX_RATING - Individual::MIN_RATING))
+				.collect();
+			individual.extras_mut().percentile_ratings = percentile_ratings;
+		}
+		self.score_cache = None;
+	}
+	
+	/// the scores kept between rounds, `None` until a round built them with `matching_config.score_cache`
+	pub fn score_cache(&self) -> Option<&ScoreCache> {
+		return self.score_cache.as_ref();
+	}
+	
+	/// forget the kept scores, e.g. after changing weights or ratings by hand. 
+	/// changes to the population or the strategy are noticed without it
+	pub fn clear_score_cache(&mut self) {
+		self.score_cache = None;
+	}
+	
+	/// keep the score cache in step with the population and the strategy before a round
+	fn refresh_score_cache(&mut self, strategy: &dyn ScoringStrategy) -> Result<(), ScoreError> {
+		let scoring = match self.scoring_strategy {
+			Some(_) => None,
+			None => Some(self.matching_config.scoring)
+		};
+		
+		if self.matching_config.score_cache == ScoreCacheMode::Off {
+			self.score_cache = None;
+		} else if !self.score_cache.as_ref().is_some_and(|cache| cache.fits(&self.population, scoring, self.matching_config.rating_values)) {
+			self.score_cache = ScoreCache::new(
+				&self.population, 
+				self.matching_config.score_cache, 
+				self.matching_config.score_cache_max_entries, 
+				strategy, 
+				scoring,
+				self.matching_config.rating_values
+			)?;
+		}
+		
+		return Ok(());
+	}
+	
+	/// the `VisibilityTable` of `Encounters::Desirability`, rebuilt when the population changed since the last round
+	fn refresh_visibility(&mut self) -> Option<Arc<VisibilityTable>> {
+		if self.matching_config.encounters != Encounters::Desirability {
+			self.visibility = None;
+			return None;
+		}
+		
+		if !self.visibility.as_ref().is_some_and(|visibility| visibility.fits(&self.population)) {
+			self.visibility = VisibilityTable::new(&self.population, &self.mean_weights()).map(Arc::new);
+		}
+		
+		return self.visibility.clone();
+	}
+	
+	/// the `SimulationError::EmptyPopulation` of a pool where no proposer of this round is compatible with anyone
+	/// they would propose to, while nobody else arrives or comes of age. greedy has everyone propose,
+	/// the other algorithms the proposing gender to the others. it names the missing gender when everyone is of one,
+	/// the proposing gender for an empty pool
+	fn unpairable(&self) -> Option<SimulationError> {
+		if self.churn.arrivals_per_round > 0 || !self.juveniles.is_empty() {
+			return None;
+		}
+		let proposing = self.matching_config.proposer_side.proposing_gender(self.round + 1);
+		if self.population.is_empty() {
+			return Some(SimulationError::EmptyPopulation { gender: Some(proposing) });
+		}
+		
+		// who is attracted to whom only depends on gender and orientation
+		let mut counts: HashMap<(Gender, Orientation), usize> = HashMap::new();
+		for individual in &self.population {
+			*counts.entry((individual.gender, individual.orientation)).or_default() += 1;
+		}
+		let everyone_proposes = self.matching_config.algorithm == MatchingAlgorithm::Greedy;
+		let pairable = counts.iter().any(|(&(gender, orientation), &count)| {
+			let proposes = everyone_proposes || gender == proposing;
+			
+			return proposes && counts.keys().any(|&(other_gender, other_orientation)| {
+				(everyone_proposes || other_gender != proposing) &&
+					orientation.attracted_to(gender, other_gender) &&
+					other_orientation.attracted_to(other_gender, gender) &&
+					((gender, orientation) != (other_gender, other_orientation) || count > 1)
+			});
+		});
+		if pairable {
+			return None;
+		}
+		
+		let only = self.population[0].gender;
+		let mut missing = None;
+		if counts.keys().all(|&(gender, _)| gender == only) {
+			missing = [proposing].into_iter().chain(Gender::ALL).find(|&missing| {
+				missing != only && counts.keys().any(|&(gender, orientation)| orientation.attracted_to(gender, missing))
+			});
+		}
+		
+		return Some(SimulationError::EmptyPopulation { gender: missing });
+	}
+	
+	/// run one round of match-making and return the summary of the round, 
+	/// its `delta` tells how the pairs changed. a pool where nobody can be paired, e.g. for lack of anyone
+	/// of a gender, fails with `SimulationError::EmptyPopulation` before anything changes
+	pub fn match_making(&mut self) -> Result<RoundStats, SimulationError> {
+		// the span lasts the whole round, a subscriber can report its duration when it closes
+		let span = tracing::info_span!(
+			"round", 
+			round = self.round + 1, 
+			algorithm = %self.matching_config.algorithm, 
+			population = self.population.len(), 
+			matched = tracing::field::Empty, 
+			new_matches = tracing::field::Empty, 
+			dissolved = tracing::field::Empty
+		);
+		let _entered = span.enter();
+		self.matching_config.validate()?;
+		if let Some(error) = self.unpairable() {
+			return Err(error);
+		}
+		
+		let listening = self.event_sink.enabled();
+		if listening {
+			self.event_sink.emit(&SimEvent::RoundStarted { round: self.round + 1, population: self.population.len() })?;
+		}
+		
+		// arrivals and exits of a round draw from their own seed, 
+		// so a snapshot resumes with the same churn
+		let mut churn_rng = StdRng::seed_from_u64(
+			self.seed ^ (self.round as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+		);
+		let arrivals = self.arrive(&mut churn_rng)? + self.come_of_age();
+		
+		self.ensure_index();
+		// and so do the breakups, before the round takes stock of the pairs
+		let mut breakup_rng = StdRng::seed_from_u64(
+			self.seed ^ (self.round as u64 + 1).wrapping_mul(0x2545_F491_4F6C_DD1D)
+		);
+		let pairs_at_start = if listening { self.pair_set() } else { HashSet::new() };
+		let divorces = self.divorce();
+		let breakups = self.break_up(&mut breakup_rng);
+		for extras in self.population.iter_mut().filter_map(Individual::extras_if_any) {
+			extras.best_rejected_score = None;
+		}
+		
+		let blacklist_size_before = self.blacklist_size();
+		let pairs_before = self.pair_set();
+		if self.matching_config.rating_values == RatingValues::Percentile && 
+			self.population.iter().any(|individual| individual.extras().percentile_ratings.len() != individual.ratings.len()) {
+			self.rank_ratings();
+		}
+		let scoring = self.scoring_strategy();
+		self.refresh_score_cache(scoring.as_ref())?;
+		let visibility = self.refresh_visibility();
+		// the proposals of the round are taken from the histories of the proposers, 
+		// recorded for the round alone when only the sink listens
+		let matching_config = MatchingConfig {
+			record_history: self.matching_config.record_history || listening,
+			..self.matching_config.clone()
+		};
+		let history_lengths: Vec<usize> = if listening {
+			self.population.iter().map(|individual| individual.extras().history.len()).collect()
+		} else {
+			Vec::new()
+		};
+		// the noise on the scores of a round draws from its own seed as well
+		let scorer = Scorer {
+			strategy: scoring.as_ref(),
+			cache: self.score_cache.as_ref(),
+			noise: self.matching_config.score_noise,
+			noise_seed: self.seed ^ (self.round as u64 + 1).wrapping_mul(0xBF58_476D_1CE4_E5B9)
+		};
+		
+		let progress = Progress::new(
+			self.population.len() as u64, 
+			self.show_progress
+		);
+		
+		// the scan order of a round draws from its own seed too
+		let mut order = ScanOrder::new(
+			self.population.len(), 
+			&self.matching_config, 
+			self.seed ^ (self.round as u64 + 1).wrapping_mul(0xD1B5_4A32_D192_ED03),
+			visibility
+		);
+		
+		let dealbreaker_rejections = match self.matching_config.algorithm {
+			MatchingAlgorithm::Greedy => matching::greedy(
+				&mut self.population, 
+				&self.index,
+				&matching_config,
+				scorer,
+				self.round + 1,
+				&mut order,
+				&progress
+			)?,
+			MatchingAlgorithm::DeferredAcceptance => matching::deferred_acceptance(
+				&mut self.population, 
+				&matching_config,
+				scorer,
+				self.round + 1,
+				&order,
+				&progress
+			)?,
+			MatchingAlgorithm::ParallelProposal => matching::parallel_proposal(
+				&mut self.population, 
+				&self.index,
+				&matching_config,
+				scorer,
+				self.round + 1,
+				&order,
+				&progress
+			)?,
+			MatchingAlgorithm::Random => matching::random(
+				&mut self.population, 
+				&self.index,
+				&matching_config,
+				scorer,
+				self.round + 1,
+				self.seed ^ (self.round as u64 + 1).wrapping_mul(0x94D0_49BB_1331_11EB),
+				&progress
+			)?,
+			MatchingAlgorithm::SpeedDating => matching::speed_dating(
+				&mut self.population, 
+				&self.index,
+				&matching_config,
+				scorer,
+				self.round + 1,
+				// only one algorithm draws per round, speed dating can share the seed of random matching
+				self.seed ^ (self.round as u64 + 1).wrapping_mul(0x94D0_49BB_1331_11EB),
+				&progress
+			)?,
+			MatchingAlgorithm::Optimal => matching::optimal(
+				&mut self.population, 
+				&matching_config,
+				scorer,
+				self.round + 1,
+				&progress
+			)?
+		};
+		
+		progress.finish(|elapsed| format!(
+			"{:.1}% matched in {:.1}s", 
+			self.matched().iter().filter(|&&matched| matched).count() as f64 / self.population.len().max(1) as f64 * 100.0,
+			elapsed
+		));
+		let proposals = if listening { self.round_proposals(&history_lengths) } else { Vec::new() };
+		if listening && !self.matching_config.record_history {
+			for (individual, &length) in self.population.iter_mut().zip(&history_lengths) {
+				if let Some(extras) = individual.extras_if_any() {
+					extras.history.truncate(length);
+				}
+			}
+		}
+		
+		self.round += 1;
+		for individual in &mut self.population {
+			individual.age += self.age_config.step;
+		}
+		self.decay_selectivity();
+		
+		let blacklist_growth = self.blacklist_size() - blacklist_size_before;
+		let pairs_after = self.pair_set();
+		let delta = Sample::round_delta(
+			&pairs_before, 
+			&pairs_after, 
+			blacklist_growth + dealbreaker_rejections
+		);
+		// the children of a round draw from their own seed, they are born before their parents may leave
+		let mut reproduction_rng = StdRng::seed_from_u64(
+			self.seed ^ (self.round as u64).wrapping_mul(0x3C79_AC49_2BA7_B653)
+		);
+		let births = self.reproduce(&mut reproduction_rng);
+		let exits = self.exit(&mut churn_rng);
+		
+		if cfg!(debug_assertions) && self.matching_config.validate_rounds {
+			if let Err(errors) = self.validate() {
+				return Err(SimulationError::InconsistentRound { round: self.round, errors: errors });
+			}
+		}
+		
+		let mut round_stats = self.round_stats(blacklist_growth);
+		round_stats.dealbreaker_rejections = dealbreaker_rejections;
+		round_stats.delta = delta;
+		round_stats.arrivals = arrivals;
+		round_stats.retired_pairs = exits;
+		round_stats.breakups = breakups;
+		round_stats.divorces = divorces;
+		round_stats.births = births;
+		round_stats.juveniles = self.juveniles.len();
+		
+		if listening {
+			let formed: Vec<MatchPair> = self.pairs()
+				.into_iter()
+				.filter(|pair| !pairs_at_start.contains(&(pair.proposer_id, pair.reviewer_id)))
+				.collect();
+			let mut broken: Vec<&(PersonId, PersonId)> = pairs_at_start.difference(&pairs_after).collect();
+			broken.sort();
+			
+			let round = self.round;
+			let events = proposals
+				.into_iter()
+				.chain(formed.into_iter().map(|pair| SimEvent::MatchFormed {
+					round: round,
+					proposer: pair.proposer_id,
+					reviewer: pair.reviewer_id,
+					reviewer_score: pair.reviewer_score,
+					proposer_score: pair.proposer_score
+				}))
+				.chain(broken.into_iter().map(|&(proposer, reviewer)| SimEvent::MatchBroken { round: round, proposer: proposer, reviewer: reviewer }))
+				.chain(std::iter::once(SimEvent::RoundCompleted { round: round, stats: round_stats.clone() }));
+			for event in events {
+				self.event_sink.emit(&event)?;
+			}
+			self.event_sink.flush()?;
+		}
+		
+		span.record("matched", round_stats.matched_males + round_stats.matched_females + round_stats.matched_non_binary);
+		span.record("new_matches", round_stats.delta.new_matches);
+		span.record("dissolved", round_stats.delta.dissolved);
+		tracing::debug!(
+			arrivals = arrivals, 
+			retired_pairs = exits, 
+			breakups = breakups, 
+			divorces = divorces, 
+			blacklist_growth = blacklist_growth, 
+			dealbreaker_rejections = dealbreaker_rejections, 
+			upgrades = round_stats.delta.upgrades, 
+			"finished the round"
+		);
+		
+		return Ok(round_stats);
+	}
+	
+	/// the proposals and rejections of the round just run, from what every proposer's history
+	/// gained past `history_lengths`, proposer by proposer
+	fn round_proposals(&self, history_lengths: &[usize]) -> Vec<SimEvent> {
+		let mut events = Vec::new();
+		
+		for (individual, &length) in self.population.iter().zip(history_lengths) {
+			for encounter in individual.extras().history[length..].iter().filter(|encounter| encounter.proposed) {
+				if let Some(score) = encounter.score {
+					events.push(SimEvent::Proposal {
+						round: encounter.round,
+						proposer: individual.identity,
+						reviewer: encounter.other,
+						score: score
+					});
+				}
+				if encounter.outcome != EncounterOutcome::Accepted {
+					events.push(SimEvent::Rejection {
+						round: encounter.round,
+						proposer: individual.identity,
+						reviewer: encounter.other,
+						reason: encounter.outcome
+					});
+				}
+			}
+		}
+		
+		return events;
+	}
+	
+	/// forget every pair, candidate, blacklist entry and proposal so far and start again at round 0, 
+	/// with everyone at `selectivity.initial`: the same people can be matched again from scratch, 
+	/// e.g. with another `MatchingConfig`. who arrived or left through churn stays so
+	pub fn reset_matches(&mut self) {
+		for individual in &mut self.population {
+			individual.candidate = None;
+			individual.candidate_score = None;
+			individual.matched_in_round = None;
+			individual.rejections_received = 0;
+			individual.rejections_given = 0;
+			individual.times_evaluated = 0;
+			individual.proposals_received = 0;
+			if let Some(extras) = individual.extras_if_any() {
+				extras.provisional_candidates.clear();
+				extras.blacklist.clear();
+				extras.divorced_in_round = None;
+				extras.best_rejected_score = None;
+				extras.history.clear();
+			}
+			individual.selectivity = self.selectivity.initial;
+		}
+		self.round = 0;
+		self.unchanged_rounds = 0;
+		self.breakups = 0;
+		self.divorces = 0;
+	}
+	
+	/// give everyone `selectivity.initial` and keep `selectivity` for the arrivals and the decay of later rounds
+	pub fn set_selectivity(&mut self, selectivity: SelectivityConfig) {
+		for individual in &mut self.population {
+			individual.selectivity = selectivity.initial;
+		}
+		self.selectivity = selectivity;
+	}
+	
+	/// everyone who ends the round unmatched settles for a bit less
+	fn decay_selectivity(&mut self) {
+		if self.selectivity.decay == 1.0 {
+			return;
+		}
+		
+		let matched = self.matched();
+		for (individual, matched) in self.population.iter_mut().zip(matched) {
+			if !matched {
+				individual.selectivity = (individual.selectivity * self.selectivity.decay).max(self.selectivity.floor);
+			}
+		}
+	}
+	
+	/// draw `churn.arrivals_per_round` new individuals into the pool, returns how many joined
+	fn arrive(&mut self, rng: &mut StdRng) -> Result<usize, SchemaError> {
+		for _ in 0..self.churn.arrivals_per_round {
+			let mut individual = self.generation.draw(&self.attribute_schema, &self.age_config, None, rng)?;
+			individual.selectivity = self.selectivity.initial;
+			self.population.push(individual);
+		}
+		
+		return Ok(self.churn.arrivals_per_round as usize);
+	}
+	
+	/// move the juveniles whose round has come into the pool, returns how many joined
+	fn come_of_age(&mut self) -> usize {
+		let round = self.round + 1;
+		let (joining, waiting): (Vec<Juvenile>, Vec<Juvenile>) = std::mem::take(&mut self.juveniles)
+			.into_iter()
+			.partition(|juvenile| juvenile.joins_in_round <= round);
+		self.juveniles = waiting;
+		
+		let joined = joining.len();
+		for juvenile in joining {
+			let mut individual = juvenile.individual;
+			individual.selectivity = self.selectivity.initial;
+			self.population.push(individual);
+		}
+		
+		return joined;
+	}
+	
+	/// after every `reproduction.every_rounds` rounds every matched pair has `reproduction.births_per_pair` children
+	/// into `juveniles`. a child is drawn like an arrival, its gender from the gender distribution, 
+	/// then gets the mean ratings of its parents and the weights of either of them, both with mutation. 
+	/// returns how many were born
+	fn reproduce(&mut self, rng: &mut StdRng) -> usize {
+		if self.reproduction.births_per_pair == 0 || !self.round.is_multiple_of(self.reproduction.every_rounds) {
+			return 0;
+		}
+		
+		let mutation = rand_distr::Normal::new(0.0, self.reproduction.mutation_std_dev).unwrap();
+		let joins_in_round = self.round + self.reproduction.maturity_rounds + 1;
+		let mut births = 0;
+		for pair in self.pairs() {
+			let parents = [&self.population[self.index[&pair.proposer_id]], &self.population[self.index[&pair.reviewer_id]]];
+			// children only come of parents that were drawn for the same attributes
+			if parents[0].ratings.len() != self.attribute_schema.len() || parents[1].ratings.len() != self.attribute_schema.len() {
+				continue;
+			}
+			
+			for _ in 0..self.reproduction.births_per_pair {
+				// the schema was drawn from before, it cannot fail
+				let Ok(mut child) = self.generation.draw(&self.attribute_schema, &self.age_config, None, rng) else {
+					continue;
+				};
+				for (position, rating) in child.ratings.iter_mut().enumerate() {
+					let attribute = &self.attribute_schema.attributes[position];
+					let mean = (parents[0].ratings[position] + parents[1].ratings[position]) / 2.0;
+					*rating = (mean + mutation.sample(rng)).clamp(attribute.min, attribute.max);
+				}
+				let parent = parents[rng.gen_range(0..2)];
+				child.preference_weights = parent.preference_weights
+					.iter()
+					.map(|weight| weight + mutation.sample(rng))
+					.collect();
+				if let ThresholdDistribution::OwnScore { factor } = self.generation.threshold_distribution {
+					child.min_acceptable_score = Some(factor * child.score(&child).unwrap_or_default());
+				}
+				child.generation = parents[0].generation.max(parents[1].generation) + 1;
+				
+				self.juveniles.push(Juvenile { joins_in_round: joins_in_round, individual: child });
+				births += 1;
+			}
+		}
+		
+		return births;
+	}
+	
+	/// dissolve every matched pair with `churn.breakup_probability`, both sides drop the other
+	/// and with `churn.blacklist_on_breakup` blacklist them in the coming round. returns how many pairs broke up
+	fn break_up(&mut self, rng: &mut StdRng) -> usize {
+		if self.churn.breakup_probability <= 0.0 {
+			return 0;
+		}
+		
+		let pairs = self.pairs();
+		let mutual_score = |pair: &MatchPair| (pair.reviewer_score + pair.proposer_score) / 2.0;
+		let mean_score = statistics::mean(&pairs.iter().map(mutual_score).collect::<Vec<f32>>());
+		
+		let breaking: Vec<MatchPair> = pairs
+			.into_iter()
+			.filter(|pair| {
+				let probability = match mean_score {
+					// a pair or a mean at or below 0 has no ratio to scale by
+					Some(mean_score) if self.churn.breakup_scaled_by_score && mean_score > 0.0 && mutual_score(pair) > 0.0 => {
+						self.churn.breakup_probability * mean_score / mutual_score(pair)
+					},
+					_ => self.churn.breakup_probability
+				};
+				
+				return rng.gen_bool(probability.min(1.0) as f64);
+			})
+			.collect();
+		
+		for pair in &breaking {
+			self.separate(pair, self.churn.blacklist_on_breakup);
+		}
+		self.breakups += breaking.len();
+		
+		return breaking.len();
+	}
+	
+	/// after every `churn.divorce_every_rounds` rounds, dissolve every pair in which either side scores the other 
+	/// below `churn.divorce_below` times the mean candidate score of the matched. unlike breakups only the worst 
+	/// pairs go, and only while that mean is above 0. with `churn.blacklist_on_divorce` the two blacklist each other 
+	/// in the coming round, returns how many pairs divorced
+	fn divorce(&mut self) -> usize {
+		let Some(every_rounds) = self.churn.divorce_every_rounds else {
+			return 0;
+		};
+		if self.round == 0 || !self.round.is_multiple_of(every_rounds) || self.churn.divorce_below <= 0.0 {
+			return 0;
+		}
+		
+		let pairs = self.pairs();
+		// the mean candidate score the last round ended with, the divorces come before the breakups
+		let scores: Vec<f32> = pairs.iter().flat_map(|pair| [pair.reviewer_score, pair.proposer_score]).collect();
+		let Some(mean_score) = statistics::mean(&scores).filter(|mean_score| *mean_score > 0.0) else {
+			return 0;
+		};
+		
+		let threshold = mean_score * self.churn.divorce_below;
+		let divorcing: Vec<MatchPair> = pairs
+			.into_iter()
+			.filter(|pair| pair.reviewer_score.min(pair.proposer_score) < threshold)
+			.collect();
+		
+		let round = self.round + 1;
+		for pair in &divorcing {
+			self.separate(pair, self.churn.blacklist_on_divorce);
+			for identity in [pair.proposer_id, pair.reviewer_id] {
+				self.population[self.index[&identity]].extras_mut().divorced_in_round = Some(round);
+			}
+		}
+		self.divorces += divorcing.len();
+		
+		return divorcing.len();
+	}
+	
+	/// both sides of `pair` drop each other before the coming round, with `blacklist` they blacklist each other in it
+	fn separate(&mut self, pair: &MatchPair, blacklist: bool) {
+		let round = self.round + 1;
+		for (identity, partner) in [(pair.proposer_id, pair.reviewer_id), (pair.reviewer_id, pair.proposer_id)] {
+			let individual = &mut self.population[self.index[&identity]];
+			individual.drop_candidate(partner);
+			if individual.candidate.is_none() {
+				individual.matched_in_round = None;
+			}
+			if blacklist {
+				individual.extras_mut().blacklist.insert(partner, round);
+			}
+		}
+	}
+	
+	/// move every matched pair into `retired` with `churn.exit_on_match_probability`, 
+	/// nobody who stays keeps pointing at or blacklisting them. returns how many pairs left
+	fn exit(&mut self, rng: &mut StdRng) -> usize {
+		if self.churn.exit_on_match_probability <= 0.0 {
+			return 0;
+		}
+		
+		let leaving: Vec<MatchPair> = self.pairs()
+			.into_iter()
+			.filter(|_| rng.gen_bool(self.churn.exit_on_match_probability as f64))
+			.collect();
+		if leaving.is_empty() {
+			return 0;
+		}
+		
+		let departed: HashSet<PersonId> = leaving
+			.iter()
+			.flat_map(|pair| [pair.proposer_id, pair.reviewer_id])
+			.collect();
+		
+		self.population.retain(|individual| !departed.contains(&individual.identity));
+		for individual in &mut self.population {
+			if let Some(extras) = individual.extras_if_any() {
+				extras.blacklist.retain(|identity, _| !departed.contains(identity));
+				extras.provisional_candidates.retain(|(candidate, _)| !departed.contains(candidate));
+			}
+			if let Some(candidate) = individual.candidate.filter(|candidate| departed.contains(candidate)) {
+				individual.drop_candidate(candidate);
+				if individual.candidate.is_none() {
+					individual.matched_in_round = None;
+				}
+			}
+		}
+		self.rebuild_index();
+		
+		let exits = leaving.len();
+		self.retired.extend(leaving);
+		
+		return exits;
+	}
+	
+	/// total number of blacklist entries across the population
+	fn blacklist_size(&self) -> usize {
+		return self.population
+			.iter()
+			.map(|individual| individual.extras().blacklist.len())
+			.sum();
+	}
+	
+	// display matched pairs
+	pub fn display_matches(&self) {
+		self.display_matches_with(&DisplayOptions::default());
+	}
+	
+	/// print what `Sample::write_matches` writes for `options`, stdout is locked once for all of it
+	pub fn display_matches_with(&self, options: &DisplayOptions) {
+		self.write_matches(&mut std::io::stdout().lock(), options)
+			.expect("failed printing to stdout");
+	}
+	
+	/// the pairs `options` shows, the `limit` best by the sum of both scores in the `order` it asks for
+	pub fn displayed_pairs(&self, options: &DisplayOptions) -> Vec<MatchPair> {
+		if options.filter == DisplayFilter::Unmatched {
+			return Vec::new();
+		}
+		
+		// with the position in `pairs` to go back to its order
+		let mut pairs: Vec<(usize, MatchPair)> = self.pairs().into_iter().enumerate().collect();
+		let total = |pair: &MatchPair| pair.reviewer_score + pair.proposer_score;
+		if let Some(limit) = options.limit {
+			pairs.sort_by(|(_, first), (_, second)| total(second).total_cmp(&total(first)));
+			pairs.truncate(limit);
+		}
+		match options.order {
+			PairOrder::ReviewerScore => pairs.sort_by_key(|(position, _)| *position),
+			PairOrder::BestFirst => pairs.sort_by(|(_, first), (_, second)| total(second).total_cmp(&total(first))),
+			PairOrder::WorstFirst => pairs.sort_by(|(_, first), (_, second)| total(first).total_cmp(&total(second)))
+		}
+		
+		return pairs.into_iter().map(|(_, pair)| pair).collect();
+	}
+	
+	/// everyone without a mutual partner, in population order
+	pub fn unmatched_individuals(&self) -> Vec<&Individual> {
+		return self.population
+			.iter()
+			.zip(self.matched())
+			.filter(|(_, matched)| !matched)
+			.map(|(individual, _)| individual)
+			.collect();
+	}
+	
+	/// what `Sample::write_matches` writes for `options`, as a string
+	pub fn describe_matches(&self, options: &DisplayOptions) -> String {
+		let mut matches: Vec<u8> = Vec::new();
+		// writing to a `Vec` does not fail
+		self.write_matches(&mut matches, options).unwrap();
+		
+		return String::from_utf8(matches).unwrap();
+	}
+	
+	/// write the matched pairs `options` shows with both descriptions and what each likes most about the other, 
+	/// then an "Unmatched" section with everyone left single unless `options` leaves them out
+	pub fn write_matches<W: std::io::Write>(&self, writer: &mut W, options: &DisplayOptions) -> Result<(), SimulationError> {
+		for pair in self.displayed_pairs(options) {
+			// both are in the index, `pairs` resolved them through it
+			let proposer = self.get_by_id(pair.proposer_id).unwrap();
+			let reviewer = self.get_by_id(pair.reviewer_id).unwrap();
+			
+			// the proposer's information 
+			writeln!(writer, "============================================")?;
+			writeln!(writer, "Matches of {} {}", proposer.gender, proposer.label(options.full_ids))?;
+			writeln!(writer, "Below is {} {}'s information", proposer.gender, proposer.label(options.full_ids))?;
+			writeln!(writer, "{}", proposer.describe(&self.attribute_schema))?;
+			writeln!(writer)?;
+			
+			// the individual the proposer is matched with
+			writeln!(writer, "{}", reviewer.describe(&self.attribute_schema))?;
+			for (rater, rated) in [(proposer, reviewer), (reviewer, proposer)] {
+				if let Ok(name) = self.top_attribute_name(rater, rated) {
+					writeln!(writer, "{} likes {} most for their {}", rater.label(options.full_ids), rated.label(options.full_ids), name)?;
+				}
+			}
+		}
+		
+		if options.filter == DisplayFilter::Matched {
+			return Ok(());
+		}
+		
+		let unmatched = self.unmatched_individuals();
+		if !unmatched.is_empty() {
+			writeln!(writer, "============================================")?;
+			writeln!(writer, "Unmatched ({})", unmatched.len())?;
+		}
+		for individual in unmatched {
+			writeln!(writer, "============================================")?;
+			writeln!(writer, "{} {}", individual.gender, individual.label(options.full_ids))?;
+			writeln!(writer, "{}", individual.describe(&self.attribute_schema))?;
+		}
+		
+		return Ok(());
+	}
+	
+	/// a readable account of every proposal the individual with `identity` made or received, round by round,
+	/// from their `Individual::history`, ending with their partner. empty rounds are left out
+	pub fn trace(&self, identity: &str) -> Result<String, SimulationError> {
+		let individual = self.get(identity)
+			.ok_or_else(|| SimulationError::UnknownIdentity(identity.to_string()))?;
+		
+		let mut trace = String::new();
+		trace.push_str(&format!("Trace of {} {}, {}\n", individual.gender, individual.label(false), individual.orientation));
+		trace.push_str(&format!("Preference Weights: {}\n", self.attribute_schema.label(&individual.preference_weights)));
+		trace.push_str(&format!("Ratings: {}\n", self.attribute_schema.label(&individual.ratings)));
+		let extras = individual.extras();
+		if !extras.categories.is_empty() {
+			trace.push_str(&format!("Categories: {}\n", self.attribute_schema.label_categories(&extras.categories)));
+		}
+		if extras.history.is_empty() {
+			trace.push_str("No proposals recorded, the history is only kept with `record_history`\n");
+		}
+		
+		let mut round = None;
+		for encounter in &extras.history {
+			if round != Some(encounter.round) {
+				round = Some(encounter.round);
+				trace.push_str(&format!("Round {}\n", encounter.round));
+			}
+			
+			// the other side may have left the pool since
+			let other = self.get_by_id(encounter.other);
+			let other_name = match other {
+				Some(other) => format!("{} {}", other.gender, other.label(false)),
+				None => format!("{} (left the pool)", encounter.other)
+			};
+			let (proposer, reviewer) = if encounter.proposed { (Some(individual), other) } else { (other, Some(individual)) };
+			let reason = match (encounter.score, proposer, reviewer) {
+				(Some(score), Some(proposer), Some(reviewer)) => match self.top_attribute_name(reviewer, proposer) {
+					Ok(name) if !name.is_empty() => format!("scored {:.2}, most for their {}", score, name),
+					_ => format!("scored {:.2}", score)
+				},
+				(Some(score), _, _) => format!("scored {:.2}", score),
+				(None, _, _) => String::from("not scored")
+			};
+			
+			let line = match (encounter.proposed, encounter.outcome) {
+				(true, EncounterOutcome::Accepted) => format!("proposed to {}, {}, and was accepted", other_name, reason),
+				(true, EncounterOutcome::RejectedBelowCandidate) => 
+					format!("proposed to {}, {}, and was turned down below their candidate or minimum", other_name, reason),
+				(true, EncounterOutcome::Tied) => format!("proposed to {}, {}, and tied with their candidate, who stayed", other_name, reason),
+				(true, EncounterOutcome::Dealbreaker) => format!("passed over {}, one of the two fails a dealbreaker of the other", other_name),
+				(true, EncounterOutcome::Blacklisted) => format!("passed over {}, one of the two blacklisted the other", other_name),
+				(false, EncounterOutcome::Accepted) => format!("was proposed to by {}, {}, and accepted them", other_name, reason),
+				(false, EncounterOutcome::RejectedBelowCandidate) => 
+					format!("was proposed to by {}, {}, and turned them down below their candidate or minimum", other_name, reason),
+				(false, EncounterOutcome::Tied) => format!("was proposed to by {}, {}, and kept their candidate, the two tied", other_name, reason),
+				(false, EncounterOutcome::Dealbreaker) => format!("was passed over by {}, one of the two fails a dealbreaker of the other", other_name),
+				(false, EncounterOutcome::Blacklisted) => format!("was passed over by {}, one of the two blacklisted the other", other_name)
+			};
+			trace.push_str(&format!("  {}\n", line));
+		}
+		
+		match self.mutual_partner(individual) {
+			Some(partner) => trace.push_str(&format!(
+				"Matched with {} {} since round {}, scoring them {:.2}\n",
+				partner.gender,
+				partner.label(false),
+				individual.matched_in_round.unwrap_or_default(),
+				individual.candidate_score.unwrap_or_default()
+			)),
+			None => trace.push_str("Unmatched\n")
+		}
+		
+		return Ok(trace);
+	}
+	
+	/// summarize the matching state, 
+	/// an individual counts as matched when their candidate names them back
+	pub fn statistics(&self) -> Statistics {
+		let matched = self.matched();
+		
+		let mut statistics = Statistics::new(
+			self.seed,
+			&self.population,
+			&matched
+		);
+		(statistics.unmatched_by_threshold, statistics.unmatched_by_threshold_estimated) = self.unmatched_by_threshold(&matched);
+		statistics.retired_pairs = self.retired.len();
+		statistics.breakups = self.breakups;
+		statistics.divorces = self.divorces;
+		let desirability_report = self.desirability_report();
+		statistics.partner_desirability_correlation = desirability_report.partner_correlation;
+		let desirability: Vec<f32> = desirability_report.individuals
+			.iter()
+			.map(|individual| individual.desirability)
+			.collect();
+		statistics.desirability_gini = statistics::gini(&desirability);
+		statistics.by_decile = statistics::decile_stats(&desirability, &matched);
+		statistics.total_welfare = self.total_welfare();
+		statistics.attribute_score_shares = self.attribute_score_shares();
+		
+		return statistics;
+	}
+	
+	/// the `Sample::statistics` with the blocking pairs counted, or estimated above `Sample::EXACT_BLOCKING_PAIRS_MAX`.
+	/// they are left out of the plain statistics as counting them scores every pair
+	pub fn statistics_with_blocking_pairs(&self) -> Statistics {
+		let mut statistics = self.statistics();
+		if self.population.len() <= Sample::EXACT_BLOCKING_PAIRS_MAX {
+			statistics.blocking_pairs = Some(self.blocking_pairs().len() as f64);
+		} else {
+			statistics.blocking_pairs = Some(self.estimate_blocking_pairs(Sample::BLOCKING_PAIR_SAMPLES, self.seed));
+			statistics.blocking_pairs_estimated = true;
+		}
+		
+		return statistics;
+	}
+	
+	/// the preference weights averaged over the population, 
+	/// one per attribute and all 0 for an empty population
+	pub fn mean_weights(&self) -> Vec<f32> {
+		let mut mean_weights = vec![0.0; self.attribute_count()];
+		if self.population.is_empty() {
+			return mean_weights;
+		}
+		
+		for individual in &self.population {
+			for (mean_weight, weight) in mean_weights.iter_mut().zip(&individual.preference_weights) {
+				*mean_weight += weight;
+			}
+		}
+		for mean_weight in &mut mean_weights {
+			*mean_weight /= self.population.len() as f32;
+		}
+		
+		return mean_weights;
+	}
+	
+	/// the name of the attribute that adds the most to `rater`'s score of `rated`, empty without attributes
+	fn top_attribute_name(&self, rater: &Individual, rated: &Individual) -> Result<String, ScoreError> {
+		return Ok(
+			rater.top_attribute(rated)?
+				.map(|position| self.attribute_schema.name(position))
+				.unwrap_or_default()
+		);
+	}
+	
+	/// the mean share every attribute has in the score matched individuals give their partner,
+	/// by attribute name in attribute order. scores of 0 and scores that fail are left out
+	pub fn attribute_score_shares(&self) -> Vec<(String, f32)> {
+		let mut totals = vec![0.0; self.attribute_count()];
+		let mut scores = 0;
+		
+		for individual in &self.population {
+			let Some(partner) = self.mutual_partner(individual) else {
+				continue;
+			};
+			let Ok(breakdown) = individual.score_breakdown(partner) else {
+				continue;
+			};
+			let score: f32 = breakdown.iter().map(|(_, contribution)| contribution).sum();
+			if score == 0.0 {
+				continue;
+			}
+			
+			for (position, contribution) in breakdown {
+				if let Some(total) = totals.get_mut(position) {
+					*total += contribution / score;
+				}
+			}
+			scores += 1;
+		}
+		
+		return totals
+			.into_iter()
+			.enumerate()
+			.map(|(position, total)| (self.attribute_schema.name(position), if scores == 0 { 0.0 } else { total / scores as f32 }))
+			.collect();
+	}
+	
+	/// everyone's `Individual::desirability` under the mean weights, 
+	/// with the correlation between the desirabilities of partners
+	pub fn desirability_report(&self) -> DesirabilityReport {
+		let mean_weights = self.mean_weights();
+		
+		let individuals: Vec<Desirability> = self.population
+			.iter()
+			.map(|individual| Desirability {
+				identity: individual.identity,
+				gender: individual.gender,
+				desirability: individual.desirability(&mean_weights),
+				partner: self.mutual_partner(individual).map(|partner| partner.identity)
+			})
+			.collect();
+		
+		let partners: Vec<(f32, f32)> = self.pairs()
+			.iter()
+			.map(|pair| (
+				individuals[self.index[&pair.proposer_id]].desirability, 
+				individuals[self.index[&pair.reviewer_id]].desirability
+			))
+			.collect();
+		
+		return DesirabilityReport {
+			mean_weights: mean_weights,
+			individuals: individuals,
+			partner_correlation: statistics::pearson_correlation(&partners)
+		};
+	}
+	
+	pub fn display_statistics(&self) {
+		self.write_statistics(&mut std::io::stdout().lock())
+			.expect("failed printing to stdout");
+	}
+	
+	/// write the `Sample::statistics` as `Sample::display_statistics` prints them
+	pub fn write_statistics<W: std::io::Write>(&self, writer: &mut W) -> Result<(), SimulationError> {
+		writeln!(writer, "{}", self.statistics())?;
+		
+		return Ok(());
+	}
+}
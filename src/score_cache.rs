@@ -0,0 +1,202 @@
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::individual::Individual;
+use crate::person_id::PersonId;
+use crate::scoring::{RatingValues, ScoreError, ScoreNoise, Scoring, ScoringStrategy};
+
+/// whether and when `Sample::match_making` keeps the scores it computed for later rounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScoreCacheMode {
+	// score every pair whenever it is looked at
+	#[default]
+	Off,
+	// keep each score the first time it is computed
+	Lazy,
+	// score every compatible pair up-front, in parallel
+	Eager
+}
+
+impl std::fmt::Display for ScoreCacheMode {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			ScoreCacheMode::Off => "off",
+			ScoreCacheMode::Lazy => "lazy",
+			ScoreCacheMode::Eager => "eager"
+		};
+
+		return write!(f, "{}", name);
+	}
+}
+
+impl std::str::FromStr for ScoreCacheMode {
+	type Err = String;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		return match name {
+			"off" => Ok(ScoreCacheMode::Off),
+			"lazy" => Ok(ScoreCacheMode::Lazy),
+			"eager" => Ok(ScoreCacheMode::Eager),
+			_ => Err(format!("unknown score cache mode `{}`, expected `off`, `lazy` or `eager`", name))
+		};
+	}
+}
+
+/// the bits of a score that has not been computed yet, a nan no strategy produces by arithmetic
+const UNSCORED: u32 = u32::MAX;
+
+/// the score of every individual by every other one, a dense matrix with a row per rater
+/// and a column per rated individual in population order. ratings never change during a run and weights
+/// only under a `PreferenceUpdate`, which drops the rows of whoever it moved, so a score otherwise
+/// stays valid until the population or the strategy changes.
+/// the matrix takes 4 bytes per pair, `population × population` in total: 400MB for 10k individuals
+pub struct ScoreCache {
+	// the population order the matrix was built for
+	identities: Vec<PersonId>,
+	// the built-in strategy the scores came from, `None` for a `Sample::set_scoring_strategy` one
+	scoring: Option<Scoring>,
+	rating_values: RatingValues,
+	scores: Vec<AtomicU32>,
+	filled: AtomicUsize
+}
+
+impl std::fmt::Debug for ScoreCache {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return f.debug_struct("ScoreCache")
+			.field("population", &self.identities.len())
+			.field("scoring", &self.scoring)
+			.field("filled", &self.filled())
+			.finish();
+	}
+}
+
+impl ScoreCache {
+	/// 25 million scores, 100MB, a population of 5000.
+	/// `MatchingConfig::score_cache_max_entries` defaults to it
+	pub const DEFAULT_MAX_ENTRIES: usize = 25_000_000;
+
+	/// an empty matrix for `population`, filled up-front with `strategy` in `ScoreCacheMode::Eager`.
+	/// `None` when `mode` is off or the population needs more than `max_entries` scores
+	pub(crate) fn new(
+		population: &[Individual],
+		mode: ScoreCacheMode,
+		max_entries: usize,
+		strategy: &dyn ScoringStrategy,
+		scoring: Option<Scoring>,
+		rating_values: RatingValues
+	) -> Result<Option<Self>, ScoreError> {
+		let entries = population.len().saturating_mul(population.len());
+		if mode == ScoreCacheMode::Off {
+			return Ok(None);
+		}
+		if entries > max_entries {
+			tracing::warn!(entries, max_entries, "the population is too large for the score cache, scoring without it");
+			return Ok(None);
+		}
+
+		let cache = ScoreCache {
+			identities: population.iter().map(|individual| individual.identity).collect(),
+			scoring: scoring,
+			rating_values: rating_values,
+			scores: (0..entries).map(|_| AtomicU32::new(UNSCORED)).collect(),
+			filled: AtomicUsize::new(0)
+		};
+
+		if mode == ScoreCacheMode::Eager {
+			(0..population.len())
+				.into_par_iter()
+				.try_for_each(|rater| -> Result<(), ScoreError> {
+					for rated in 0..population.len() {
+						if rater != rated && population[rater].compatible_with(&population[rated]) {
+							cache.score(strategy, population, rater, rated)?;
+						}
+					}
+
+					return Ok(());
+				})?;
+		}
+		tracing::debug!(population = population.len(), %mode, filled = cache.filled(), "built the score cache");
+
+		return Ok(Some(cache));
+	}
+
+	/// whether the matrix still belongs to `population` scored with `scoring` on `rating_values`
+	pub(crate) fn fits(&self, population: &[Individual], scoring: Option<Scoring>, rating_values: RatingValues) -> bool {
+		return self.scoring == scoring &&
+			self.rating_values == rating_values &&
+			self.identities.len() == population.len() &&
+			self.identities
+				.iter()
+				.zip(population)
+				.all(|(identity, individual)| *identity == individual.identity);
+	}
+
+	/// the bytes of the matrix and of the identities it was built for
+	pub(crate) fn heap_bytes(&self) -> usize {
+		return crate::memory::vec_bytes(&self.identities) + crate::memory::vec_bytes(&self.scores);
+	}
+
+	/// number of scores computed so far
+	pub fn filled(&self) -> usize {
+		return self.filled.load(Ordering::Relaxed);
+	}
+
+	/// forget every score given by `population[rater]`, e.g. after their weights changed
+	pub(crate) fn invalidate_rater(&self, rater: usize) {
+		let row = &self.scores[rater * self.identities.len()..(rater + 1) * self.identities.len()];
+		let forgotten = row
+			.iter()
+			.filter(|entry| entry.swap(UNSCORED, Ordering::Relaxed) != UNSCORED)
+			.count();
+		self.filled.fetch_sub(forgotten, Ordering::Relaxed);
+	}
+
+	/// how much `population[rater]` likes `population[rated]`, computed with `strategy` the first time
+	pub(crate) fn score(
+		&self,
+		strategy: &dyn ScoringStrategy,
+		population: &[Individual],
+		rater: usize,
+		rated: usize
+	) -> Result<f32, ScoreError> {
+		let entry = &self.scores[rater * self.identities.len() + rated];
+
+		let bits = entry.load(Ordering::Relaxed);
+		if bits != UNSCORED {
+			return Ok(f32::from_bits(bits));
+		}
+
+		// two threads may score the same pair at once, both store the same score
+		let score = strategy.score(&population[rater], &population[rated])?;
+		if entry.swap(score.to_bits(), Ordering::Relaxed) == UNSCORED {
+			self.filled.fetch_add(1, Ordering::Relaxed);
+		}
+
+		return Ok(score);
+	}
+}
+
+/// what the algorithms of a round score with, through the sample's `ScoreCache` when there is one
+#[derive(Clone, Copy)]
+pub(crate) struct Scorer<'a> {
+	pub strategy: &'a dyn ScoringStrategy,
+	pub cache: Option<&'a ScoreCache>,
+	// added on top of the cached or computed score, drawn with `noise_seed`
+	pub noise: ScoreNoise,
+	pub noise_seed: u64
+}
+
+impl Scorer<'_> {
+	/// how much `population[rater]` likes `population[rated]`, the cache only ever holds the score without noise
+	pub fn score(&self, population: &[Individual], rater: usize, rated: usize) -> Result<f32, ScoreError> {
+		let score = match self.cache {
+			Some(cache) => cache.score(self.strategy, population, rater, rated)?,
+			None => self.strategy.score(&population[rater], &population[rated])?
+		};
+
+		return Ok(self.noise.apply(score, self.noise_seed, rater, rated));
+	}
+}
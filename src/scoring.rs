@@ -0,0 +1,364 @@
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::attributes::AttributeSchema;
+use crate::error::SimulationError;
+use crate::individual::Individual;
+
+/// a rater and a rated individual that cannot be scored against each other
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScoreError {
+	// the rater weights a different number of attributes than the rated individual has ratings
+	AttributeCountMismatch { rater: String, rated: String, weights: usize, ratings: usize }
+}
+
+impl std::fmt::Display for ScoreError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return match self {
+			ScoreError::AttributeCountMismatch { rater, rated, weights, ratings } => write!(
+				f, "{} has {} preference weights but {} has {} ratings", rater, weights, rated, ratings
+			)
+		};
+	}
+}
+
+impl std::error::Error for ScoreError {}
+
+/// how much `rater` likes `rated`, higher is better.
+/// every matching algorithm compares scores given by the same rater,
+/// so a strategy only has to be consistent for one rater
+pub trait ScoringStrategy: std::fmt::Debug + Send + Sync {
+	fn score(&self, rater: &Individual, rated: &Individual) -> Result<f32, ScoreError>;
+
+	/// the score with `ratings` in place of the ratings of `rated`, e.g. its `Individual::percentile_ratings`.
+	/// a strategy that does not override it ignores `ratings` and scores as `score` does
+	fn score_ratings(&self, rater: &Individual, rated: &Individual, ratings: &[f32]) -> Result<f32, ScoreError> {
+		let _ = ratings;
+
+		return self.score(rater, rated);
+	}
+}
+
+/// an error unless the rater has a weight for every one of `ratings`
+fn check_attribute_count(rater: &Individual, rated: &Individual, ratings: &[f32]) -> Result<(), ScoreError> {
+	if rater.preference_weights.len() != ratings.len() {
+		return Err(ScoreError::AttributeCountMismatch {
+			rater: rater.identity.to_string(),
+			rated: rated.identity.to_string(),
+			weights: rater.preference_weights.len(),
+			ratings: ratings.len()
+		});
+	}
+
+	return Ok(());
+}
+
+/// the rater's weights paired with `ratings`, those of `rated` or values standing in for them
+pub(crate) fn weighted_ratings<'a>(
+	rater: &'a Individual,
+	rated: &'a Individual,
+	ratings: &'a [f32]
+) -> Result<impl Iterator<Item = (f32, f32)> + 'a, ScoreError> {
+	check_attribute_count(rater, rated, ratings)?;
+
+	return Ok(
+		rater.preference_weights
+			.iter()
+			.copied()
+			.zip(ratings.iter().copied())
+	);
+}
+
+/// how many products `dot` adds up side by side
+const LANES: usize = 8;
+
+/// from this many attributes on `dot` keeps `LANES` partial sums, which compile to vector instructions.
+/// below it the plain loop is as fast, and the scores stay the same to the bit as they always were
+const CHUNKED_FROM: usize = 32;
+
+/// the dot product of `weights` and `ratings` of the same length. over `CHUNKED_FROM` attributes or more
+/// the products are added up in another order, which can differ from the plain sum in the last bits
+pub(crate) fn dot(weights: &[f32], ratings: &[f32]) -> f32 {
+	if weights.len() < CHUNKED_FROM {
+		return weights.iter().zip(ratings).map(|(weight, rating)| weight * rating).sum();
+	}
+
+	let (weight_chunks, rating_chunks) = (weights.chunks_exact(LANES), ratings.chunks_exact(LANES));
+	let remainder: f32 = weight_chunks.remainder()
+		.iter()
+		.zip(rating_chunks.remainder())
+		.map(|(weight, rating)| weight * rating)
+		.sum();
+
+	let mut sums = [0.0f32; LANES];
+	for (weights, ratings) in weight_chunks.zip(rating_chunks) {
+		// as arrays the lengths are known, so the loop unrolls without bounds checks
+		let (weights, ratings): (&[f32; LANES], &[f32; LANES]) = (weights.try_into().unwrap(), ratings.try_into().unwrap());
+		for ((sum, weight), rating) in sums.iter_mut().zip(weights).zip(ratings) {
+			*sum += weight * rating;
+		}
+	}
+
+	return sums.iter().sum::<f32>() + remainder;
+}
+
+/// which values of the rated individual the strategies score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RatingValues {
+	// the ratings as they were drawn
+	#[default]
+	Raw,
+	// where every rating ranks among the population, see `Individual::percentile_ratings`,
+	// so that how the population is spread on an attribute does not weigh into the scores
+	Percentile
+}
+
+impl std::fmt::Display for RatingValues {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return match self {
+			RatingValues::Raw => write!(f, "raw"),
+			RatingValues::Percentile => write!(f, "percentile")
+		};
+	}
+}
+
+impl std::str::FromStr for RatingValues {
+	type Err = String;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		return match name {
+			"raw" => Ok(RatingValues::Raw),
+			"percentile" => Ok(RatingValues::Percentile),
+			_ => Err(format!("unknown rating values `{}`, expected `raw` or `percentile`", name))
+		};
+	}
+}
+
+/// the dot product of the rater's weights and the rated individual's ratings
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WeightedSum;
+
+impl ScoringStrategy for WeightedSum {
+	fn score(&self, rater: &Individual, rated: &Individual) -> Result<f32, ScoreError> {
+		return self.score_ratings(rater, rated, &rated.ratings);
+	}
+
+	fn score_ratings(&self, rater: &Individual, rated: &Individual, ratings: &[f32]) -> Result<f32, ScoreError> {
+		check_attribute_count(rater, rated, ratings)?;
+
+		return Ok(dot(&rater.preference_weights, ratings));
+	}
+}
+
+/// closeness of the rated individual's ratings to the rater's ideal, as `1 / (1 + distance)`.
+/// the ideal maps each weight onto the rating scale, a weight of 0 asks for
+/// `Individual::MIN_RATING` and a weight of 1 for `Individual::MAX_RATING`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EuclideanSimilarity;
+
+impl ScoringStrategy for EuclideanSimilarity {
+	fn score(&self, rater: &Individual, rated: &Individual) -> Result<f32, ScoreError> {
+		return self.score_ratings(rater, rated, &rated.ratings);
+	}
+
+	fn score_ratings(&self, rater: &Individual, rated: &Individual, ratings: &[f32]) -> Result<f32, ScoreError> {
+		let distance = weighted_ratings(rater, rated, ratings)?
+			.map(|(weight, rating)| {
+				let ideal = Individual::MIN_RATING + weight * (Individual::MAX_RATING - Individual::MIN_RATING);
+
+				return (ideal - rating).powi(2);
+			})
+			.sum::<f32>()
+			.sqrt();
+
+		return Ok(1.0 / (1.0 + distance));
+	}
+}
+
+/// the weakest weighted rating, so one poor attribute cannot be made up for by the others
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MinAttribute;
+
+impl ScoringStrategy for MinAttribute {
+	fn score(&self, rater: &Individual, rated: &Individual) -> Result<f32, ScoreError> {
+		return self.score_ratings(rater, rated, &rated.ratings);
+	}
+
+	fn score_ratings(&self, rater: &Individual, rated: &Individual, ratings: &[f32]) -> Result<f32, ScoreError> {
+		return Ok(
+			weighted_ratings(rater, rated, ratings)?
+				.map(|(weight, rating)| weight * rating)
+				.reduce(f32::min)
+				.unwrap_or_default()
+		);
+	}
+}
+
+/// `strategy` with what the categorical attributes of `attribute_schema` add on top of its score,
+/// see `AttributeSchema::category_score`. the sample scores with it whenever its schema has any
+#[derive(Debug, Clone)]
+pub struct CategoricalScoring {
+	pub strategy: std::sync::Arc<dyn ScoringStrategy>,
+	pub attribute_schema: AttributeSchema
+}
+
+impl ScoringStrategy for CategoricalScoring {
+	fn score(&self, rater: &Individual, rated: &Individual) -> Result<f32, ScoreError> {
+		return Ok(self.strategy.score(rater, rated)? + self.attribute_schema.category_score(rater, rated));
+	}
+
+	fn score_ratings(&self, rater: &Individual, rated: &Individual, ratings: &[f32]) -> Result<f32, ScoreError> {
+		return Ok(self.strategy.score_ratings(rater, rated, ratings)? + self.attribute_schema.category_score(rater, rated));
+	}
+}
+
+/// `strategy` scoring the `Individual::percentile_ratings` of the rated individual instead of the ratings,
+/// what the sample scores with under `RatingValues::Percentile`. someone not ranked yet is scored by their ratings
+#[derive(Debug, Clone)]
+pub struct PercentileScoring {
+	pub strategy: std::sync::Arc<dyn ScoringStrategy>
+}
+
+impl ScoringStrategy for PercentileScoring {
+	fn score(&self, rater: &Individual, rated: &Individual) -> Result<f32, ScoreError> {
+		let percentile_ratings = &rated.extras().percentile_ratings;
+		if percentile_ratings.is_empty() {
+			return self.strategy.score(rater, rated);
+		}
+
+		return self.strategy.score_ratings(rater, rated, percentile_ratings);
+	}
+}
+
+/// the built-in strategies by name, so configs and the command line can pick one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Scoring {
+	#[default]
+	WeightedSum,
+	EuclideanSimilarity,
+	MinAttribute
+}
+
+impl Scoring {
+	pub fn strategy(&self) -> std::sync::Arc<dyn ScoringStrategy> {
+		return match self {
+			Scoring::WeightedSum => std::sync::Arc::new(WeightedSum),
+			Scoring::EuclideanSimilarity => std::sync::Arc::new(EuclideanSimilarity),
+			Scoring::MinAttribute => std::sync::Arc::new(MinAttribute)
+		};
+	}
+}
+
+impl std::fmt::Display for Scoring {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			Scoring::WeightedSum => "weighted-sum",
+			Scoring::EuclideanSimilarity => "euclidean-similarity",
+			Scoring::MinAttribute => "min-attribute"
+		};
+
+		return write!(f, "{}", name);
+	}
+}
+
+impl std::str::FromStr for Scoring {
+	type Err = String;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		return match name {
+			"weighted-sum" => Ok(Scoring::WeightedSum),
+			"euclidean-similarity" => Ok(Scoring::EuclideanSimilarity),
+			"min-attribute" => Ok(Scoring::MinAttribute),
+			_ => Err(
+				format!(
+					"unknown scoring `{}`, expected `weighted-sum`, `euclidean-similarity` or `min-attribute`",
+					name
+				)
+			)
+		};
+	}
+}
+
+/// the error on every score an individual gives during a round, so that meeting someone again
+/// can leave a different impression. a rater gives the same score to the same individual
+/// throughout a round and a new one the next round, drawn from the sample's seed
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum ScoreNoise {
+	// every score is the one the strategy computes
+	#[default]
+	None,
+	// centered on the computed score
+	Gaussian { std_dev: f32 }
+}
+
+impl std::fmt::Display for ScoreNoise {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return match self {
+			ScoreNoise::None => write!(f, "none"),
+			ScoreNoise::Gaussian { std_dev } => write!(f, "gaussian:{}", std_dev)
+		};
+	}
+}
+
+impl std::str::FromStr for ScoreNoise {
+	type Err = String;
+
+	/// parse `none` or `gaussian:<std-dev>`
+	fn from_str(description: &str) -> Result<Self, Self::Err> {
+		let usage = || format!("unknown score noise `{}`, expected `none` or `gaussian:<std-dev>`", description);
+
+		if description == "none" {
+			return Ok(ScoreNoise::None);
+		}
+
+		return match description.split_once(':') {
+			Some(("gaussian", std_dev)) => Ok(ScoreNoise::Gaussian { std_dev: std_dev.trim().parse::<f32>().map_err(|_| usage())? }),
+			_ => Err(usage())
+		};
+	}
+}
+
+impl ScoreNoise {
+	/// the standard deviation must be finite and not negative
+	pub fn validate(&self) -> Result<(), SimulationError> {
+		let valid = match *self {
+			ScoreNoise::None => true,
+			ScoreNoise::Gaussian { std_dev } => std_dev.is_finite() && std_dev >= 0.0
+		};
+
+		if !valid {
+			return Err(SimulationError::InvalidConfig(format!("invalid score noise `{}`", self)));
+		}
+
+		return Ok(());
+	}
+
+	/// how far a score typically strays from the computed one, 0 without noise
+	pub fn std_dev(&self) -> f32 {
+		return match *self {
+			ScoreNoise::None => 0.0,
+			ScoreNoise::Gaussian { std_dev } => std_dev
+		};
+	}
+
+	/// `score` as the rater at position `rater` perceives the one at `rated` in the round seeded with `seed`.
+	/// every pair draws from its own rng, so the draw does not depend on the order pairs are scored in.
+	/// panics when `ScoreNoise::validate` fails
+	pub(crate) fn apply(&self, score: f32, seed: u64, rater: usize, rated: usize) -> f32 {
+		let std_dev = self.std_dev();
+		if std_dev == 0.0 {
+			return score;
+		}
+
+		let mut rng = StdRng::seed_from_u64(seed ^ ((rater as u64) << 32 | rated as u64));
+		let noise: f32 = rand_distr::Normal::new(0.0, std_dev)
+			.unwrap()
+			.sample(&mut rng);
+
+		return score + noise;
+	}
+}
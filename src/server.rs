@@ -0,0 +1,354 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use rand::Rng;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::config::SampleConfig;
+use crate::error::SimulationError;
+use crate::sample::Sample;
+
+// a body larger than this is refused rather than read
+const MAX_BODY: usize = 1 << 20;
+// likewise the request line and headers together
+const MAX_HEAD: usize = 16 << 10;
+// what is still read and thrown away after refusing a request, so that the client gets the answer
+// rather than a reset of the connection with its unread bytes
+const MAX_DRAINED: u64 = 8 << 20;
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// a request as the router sees it, `path` keeps its query string
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpRequest {
+	pub method: String,
+	pub path: String,
+	pub body: Vec<u8>
+}
+
+/// a json response of the router
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+	pub status: u16,
+	pub body: String
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RoundsRequest {
+	rounds: u32
+}
+
+impl Default for RoundsRequest {
+	fn default() -> Self {
+		return RoundsRequest { rounds: 1 };
+	}
+}
+
+impl HttpRequest {
+	pub fn new(method: &str, path: &str, body: &str) -> Self {
+		return HttpRequest { method: method.to_string(), path: path.to_string(), body: body.as_bytes().to_vec() };
+	}
+
+	/// read one http/1.1 request, `None` when the connection closed before a request line.
+	/// a request that is not http, or larger than the limits, is an `InvalidRequest` and its body is left unread
+	pub fn read_from(reader: &mut impl BufRead) -> Result<Option<Self>, SimulationError> {
+		let mut head = Read::take(reader, MAX_HEAD as u64);
+		let mut line = String::new();
+		if read_head_line(&mut head, &mut line)? == 0 {
+			return Ok(None);
+		}
+		let mut parts = line.split_whitespace();
+		let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+			return Err(SimulationError::InvalidRequest(format!("malformed request line {:?}", line.trim_end())));
+		};
+		let (method, path) = (method.to_string(), path.to_string());
+
+		let mut content_length = 0;
+		loop {
+			line.clear();
+			if read_head_line(&mut head, &mut line)? == 0 || line.trim_end().is_empty() {
+				break;
+			}
+			if let Some((name, value)) = line.split_once(':') {
+				if name.trim().eq_ignore_ascii_case("content-length") {
+					content_length = value.trim().parse()
+						.map_err(|_| SimulationError::InvalidRequest(format!("bad content-length {:?}", value.trim())))?;
+				}
+			}
+		}
+		if content_length > MAX_BODY {
+			return Err(SimulationError::InvalidRequest(format!("a body of {} bytes is over the limit of {}", content_length, MAX_BODY)));
+		}
+
+		let mut body = vec![0; content_length];
+		head.into_inner().read_exact(&mut body)
+			.map_err(|_| SimulationError::InvalidRequest(format!("the body ended before its content-length of {}", content_length)))?;
+
+		return Ok(Some(HttpRequest { method: method, path: path, body: body }));
+	}
+
+	/// the value of `name` in the query string
+	fn query(&self, name: &str) -> Option<&str> {
+		let (_, query) = self.path.split_once('?')?;
+
+		return query
+			.split('&')
+			.filter_map(|pair| pair.split_once('='))
+			.find(|(key, _)| *key == name)
+			.map(|(_, value)| value);
+	}
+}
+
+/// one line of the request line and headers, which fail once they run over `MAX_HEAD` together
+fn read_head_line(head: &mut std::io::Take<impl BufRead>, line: &mut String) -> Result<usize, SimulationError> {
+	let read = head.read_line(line)
+		.map_err(|_| SimulationError::InvalidRequest(String::from("the request line and headers are not utf-8")))?;
+	if head.limit() == 0 && !line.ends_with('\n') {
+		return Err(SimulationError::InvalidRequest(format!("the request line and headers are over the limit of {} bytes", MAX_HEAD)));
+	}
+
+	return Ok(read);
+}
+
+impl HttpResponse {
+	fn json(status: u16, body: &impl serde::Serialize) -> Self {
+		return match serde_json::to_string(body) {
+			Ok(body) => HttpResponse { status: status, body: body },
+			Err(error) => HttpResponse::error(500, &error.to_string())
+		};
+	}
+
+	fn error(status: u16, message: &str) -> Self {
+		return HttpResponse { status: status, body: serde_json::json!({ "error": message }).to_string() };
+	}
+
+	/// the response for an error of the simulation: refused input is the client's fault, the rest the server's
+	fn from_error(error: SimulationError) -> Self {
+		let status = match error {
+			SimulationError::InvalidConfig(_)
+			| SimulationError::InvalidRequest(_)
+			| SimulationError::Json(_)
+			| SimulationError::Schema(_)
+			| SimulationError::EmptyPopulation { .. }
+			| SimulationError::WeightRatingLengthMismatch { .. } => 400,
+			_ => 500
+		};
+
+		return HttpResponse::error(status, &error.to_string());
+	}
+
+	fn reason(&self) -> &'static str {
+		return match self.status {
+			200 => "OK",
+			201 => "Created",
+			400 => "Bad Request",
+			404 => "Not Found",
+			405 => "Method Not Allowed",
+			_ => "Internal Server Error"
+		};
+	}
+
+	/// write the response as http/1.1, closing the connection after it
+	pub fn write_to(&self, writer: &mut impl Write) -> Result<(), SimulationError> {
+		write!(
+			writer,
+			"HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+			self.status, self.reason(), self.body.len(), self.body
+		)?;
+		writer.flush()?;
+
+		return Ok(());
+	}
+}
+
+/// the simulations of `dating-simulation serve`, kept in memory until the server stops.
+/// every connection is handled on its own thread, so a long run of rounds
+/// only holds up the requests for its own simulation
+#[derive(Debug)]
+pub struct SimulationServer {
+	simulations: RwLock<HashMap<Uuid, Arc<Mutex<Sample>>>>,
+	// the largest population a request can create
+	pub max_population: usize,
+	// the most rounds one request can run
+	pub max_rounds: u32
+}
+
+impl Default for SimulationServer {
+	fn default() -> Self {
+		return SimulationServer {
+			simulations: RwLock::default(),
+			max_population: SimulationServer::MAX_POPULATION,
+			max_rounds: SimulationServer::MAX_ROUNDS
+		};
+	}
+}
+
+impl SimulationServer {
+	pub const MAX_POPULATION: usize = 100_000;
+	pub const MAX_ROUNDS: u32 = 1000;
+
+	pub fn new() -> Self {
+		return SimulationServer::default();
+	}
+
+	/// answer one request:
+	/// `POST /simulations` with a `SampleConfig` as json gives the id of the new simulation,
+	/// `POST /simulations/{id}/rounds` with `{"rounds": n}` runs n rounds, one without a body,
+	/// `GET /simulations/{id}/statistics`, `GET /simulations/{id}/pairs?limit=n`
+	/// and `GET /simulations/{id}/individuals/{uuid}`
+	pub fn handle(&self, request: &HttpRequest) -> HttpResponse {
+		let path = request.path.split('?').next().unwrap_or_default();
+		let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+		let allowed = match segments.as_slice() {
+			["simulations"] | ["simulations", _, "rounds"] => "POST",
+			["simulations", _, "statistics"] | ["simulations", _, "pairs"] | ["simulations", _, "individuals", _] => "GET",
+			_ => return HttpResponse::error(404, &format!("no route for {}", path))
+		};
+		if request.method != allowed {
+			return HttpResponse::error(405, &format!("{} takes {}", path, allowed));
+		}
+
+		if let ["simulations"] = segments.as_slice() {
+			return match self.create(&request.body) {
+				Ok(id) => HttpResponse::json(201, &serde_json::json!({ "id": id.to_string() })),
+				Err(error) => HttpResponse::from_error(error)
+			};
+		}
+
+		let Some((id, simulation)) = Uuid::parse_str(segments[1]).ok().and_then(|id| Some((id, self.get(id)?))) else {
+			return HttpResponse::error(404, &format!("no simulation {}", segments[1]));
+		};
+
+		// a panic can leave the sample halfway through a round, so the simulation is dropped rather than read again
+		let answered = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| -> Option<HttpResponse> {
+			let mut sample = simulation.lock().ok()?;
+
+			return Some(self.answer_for(&mut sample, request, &segments));
+		}));
+		return match answered {
+			Ok(Some(response)) => response,
+			Ok(None) | Err(_) => {
+				self.simulations.write().unwrap_or_else(|poisoned| poisoned.into_inner()).remove(&id);
+				tracing::error!(%id, "a request panicked, dropped the simulation");
+
+				HttpResponse::error(500, &format!("simulation {} panicked and was dropped", id))
+			}
+		};
+	}
+
+	/// the response of a route of one simulation
+	fn answer_for(&self, sample: &mut Sample, request: &HttpRequest, segments: &[&str]) -> HttpResponse {
+		return match segments {
+			[_, _, "rounds"] => {
+				let rounds: Result<RoundsRequest, serde_json::Error> = match request.body.is_empty() {
+					true => Ok(RoundsRequest::default()),
+					false => serde_json::from_slice(&request.body)
+				};
+				let rounds = match rounds {
+					Ok(rounds) if rounds.rounds > self.max_rounds => {
+						return HttpResponse::error(400, &format!("{} rounds are over the limit of {} a request", rounds.rounds, self.max_rounds));
+					},
+					rounds => rounds
+				};
+				match rounds.map_err(SimulationError::from).and_then(|rounds| sample.run(rounds.rounds)) {
+					Ok(report) => HttpResponse::json(200, &report),
+					Err(error) => HttpResponse::from_error(error)
+				}
+			},
+			[_, _, "statistics"] => HttpResponse::json(200, &sample.statistics()),
+			[_, _, "pairs"] => {
+				let limit = match request.query("limit").map(str::parse::<usize>) {
+					None => usize::MAX,
+					Some(Ok(limit)) => limit,
+					Some(Err(_)) => return HttpResponse::error(400, "limit is not a number")
+				};
+				let pairs: Vec<_> = sample.pairs().into_iter().take(limit).collect();
+
+				HttpResponse::json(200, &pairs)
+			},
+			[_, _, "individuals", identity] => match sample.get(identity) {
+				Some(individual) => HttpResponse::json(200, individual),
+				None => HttpResponse::error(404, &format!("no individual {}", identity))
+			},
+			_ => unreachable!("every route was matched above")
+		};
+	}
+
+	/// generate the sample of a `SampleConfig` given as json and keep it under a new id
+	fn create(&self, body: &[u8]) -> Result<Uuid, SimulationError> {
+		let config: SampleConfig = match body.is_empty() {
+			true => SampleConfig::default(),
+			false => serde_json::from_slice(body)?
+		};
+		if config.population_size > self.max_population {
+			return Err(SimulationError::InvalidRequest(format!(
+				"a population of {} is over the limit of {}",
+				config.population_size,
+				self.max_population
+			)));
+		}
+		let sample = Sample::generate(&config)?;
+
+		return Ok(self.insert(sample));
+	}
+
+	/// keep `sample` under a new id, e.g. one built with `Sample::from_individuals`
+	pub fn insert(&self, sample: Sample) -> Uuid {
+		let id = uuid::Builder::from_random_bytes(rand::rngs::OsRng.r#gen()).into_uuid();
+		tracing::info!(%id, population = sample.population.len(), "created a simulation");
+
+		self.simulations.write().unwrap_or_else(|poisoned| poisoned.into_inner()).insert(id, Arc::new(Mutex::new(sample)));
+
+		return id;
+	}
+
+	fn get(&self, id: Uuid) -> Option<Arc<Mutex<Sample>>> {
+		return self.simulations.read().unwrap_or_else(|poisoned| poisoned.into_inner()).get(&id).cloned();
+	}
+
+	/// answer the requests of `listener` until it fails, one thread per connection
+	pub fn serve(self: Arc<Self>, listener: TcpListener) -> Result<(), SimulationError> {
+		tracing::info!(address = %listener.local_addr()?, "serving the simulations");
+
+		for stream in listener.incoming() {
+			let stream = stream?;
+			let server = Arc::clone(&self);
+			std::thread::spawn(move || {
+				if let Err(error) = server.answer(stream) {
+					tracing::warn!(%error, "dropped a connection");
+				}
+			});
+		}
+
+		return Ok(());
+	}
+
+	fn answer(&self, stream: TcpStream) -> Result<(), SimulationError> {
+		let mut reader = BufReader::new(stream.try_clone()?);
+		let response = match HttpRequest::read_from(&mut reader) {
+			Ok(Some(request)) => {
+				let response = self.handle(&request);
+				tracing::debug!(method = %request.method, path = %request.path, status = response.status, "answered a request");
+
+				response
+			},
+			Ok(None) => return Ok(()),
+			Err(error) => {
+				HttpResponse::from_error(error).write_to(&mut &stream)?;
+				// closing with the rest of the request unread would reset the connection and lose the answer
+				stream.shutdown(Shutdown::Write)?;
+				stream.set_read_timeout(Some(DRAIN_TIMEOUT))?;
+				let _ = std::io::copy(&mut reader.take(MAX_DRAINED), &mut std::io::sink());
+
+				return Ok(());
+			}
+		};
+
+		return response.write_to(&mut &stream);
+	}
+}
@@ -0,0 +1,408 @@
+use std::collections::BTreeMap;
+use std::ffi::{c_int, CStr, CString};
+use std::path::{Path, PathBuf};
+use std::ptr::NonNull;
+use std::sync::Mutex;
+
+use crate::error::SimulationError;
+use crate::event::{EventSink, SimEvent};
+use crate::person_id::{IdentityStr, PersonId};
+use crate::sample::Sample;
+
+/// the bindings of the system libsqlite3 that `SqliteSink` needs, rusqlite would be the usual choice
+/// but the plain c api keeps the build down to the library itself
+mod ffi {
+	use std::ffi::{c_char, c_double, c_int, c_void};
+
+	pub const SQLITE_OK: c_int = 0;
+	pub const SQLITE_ROW: c_int = 100;
+	pub const SQLITE_DONE: c_int = 101;
+
+	#[repr(C)]
+	pub struct Database {
+		_private: [u8; 0]
+	}
+
+	#[repr(C)]
+	pub struct Statement {
+		_private: [u8; 0]
+	}
+
+	#[link(name = "sqlite3")]
+	extern "C" {
+		pub fn sqlite3_open(filename: *const c_char, database: *mut *mut Database) -> c_int;
+		pub fn sqlite3_close(database: *mut Database) -> c_int;
+		pub fn sqlite3_errmsg(database: *mut Database) -> *const c_char;
+		pub fn sqlite3_exec(
+			database: *mut Database,
+			sql: *const c_char,
+			callback: *const c_void,
+			argument: *mut c_void,
+			error: *mut *mut c_char
+		) -> c_int;
+		pub fn sqlite3_prepare_v2(
+			database: *mut Database,
+			sql: *const c_char,
+			length: c_int,
+			statement: *mut *mut Statement,
+			tail: *mut *const c_char
+		) -> c_int;
+		pub fn sqlite3_bind_int64(statement: *mut Statement, position: c_int, value: i64) -> c_int;
+		pub fn sqlite3_bind_double(statement: *mut Statement, position: c_int, value: c_double) -> c_int;
+		pub fn sqlite3_bind_null(statement: *mut Statement, position: c_int) -> c_int;
+		// the destructor is `SQLITE_STATIC`, the text has to outlive the step
+		pub fn sqlite3_bind_text(
+			statement: *mut Statement,
+			position: c_int,
+			text: *const c_char,
+			length: c_int,
+			destructor: *const c_void
+		) -> c_int;
+		pub fn sqlite3_step(statement: *mut Statement) -> c_int;
+		pub fn sqlite3_reset(statement: *mut Statement) -> c_int;
+		pub fn sqlite3_column_int64(statement: *mut Statement, column: c_int) -> i64;
+		pub fn sqlite3_finalize(statement: *mut Statement) -> c_int;
+		pub fn sqlite3_last_insert_rowid(database: *mut Database) -> i64;
+	}
+}
+
+/// bumped whenever the tables change, a database of another version is refused rather than appended to
+const SCHEMA_VERSION: i64 = 1;
+
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS runs (
+	run_id INTEGER PRIMARY KEY,
+	seed INTEGER NOT NULL,
+	metadata TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS individuals (
+	run_id INTEGER NOT NULL REFERENCES runs (run_id),
+	id TEXT NOT NULL,
+	gender TEXT NOT NULL,
+	orientation TEXT NOT NULL,
+	age REAL NOT NULL,
+	weights TEXT NOT NULL,
+	ratings TEXT NOT NULL,
+	PRIMARY KEY (run_id, id)
+);
+CREATE TABLE IF NOT EXISTS matches (
+	run_id INTEGER NOT NULL REFERENCES runs (run_id),
+	round INTEGER NOT NULL,
+	proposer_id TEXT NOT NULL,
+	reviewer_id TEXT NOT NULL,
+	reviewer_score REAL NOT NULL,
+	proposer_score REAL NOT NULL
+);
+CREATE TABLE IF NOT EXISTS rounds (
+	run_id INTEGER NOT NULL REFERENCES runs (run_id),
+	round INTEGER NOT NULL,
+	matched INTEGER NOT NULL,
+	unmatched INTEGER NOT NULL,
+	mean_candidate_score REAL,
+	new_matches INTEGER NOT NULL,
+	dissolved INTEGER NOT NULL,
+	breakups INTEGER NOT NULL,
+	blacklist_growth INTEGER NOT NULL,
+	PRIMARY KEY (run_id, round)
+);";
+
+/// a value bound to a statement
+enum Value<'a> {
+	Integer(i64),
+	Real(Option<f64>),
+	Text(&'a str)
+}
+
+/// an open database, closed when dropped
+struct Connection {
+	database: NonNull<ffi::Database>,
+	path: PathBuf
+}
+
+// SAFETY: the handle is owned by the connection alone and libsqlite3 lets a handle move between threads
+// when it is used by one thread at a time, which `&mut` or the mutex of `SqliteSink` make sure of.
+// it is not `Sync`, a library built single threaded would not take two threads calling through `&self`
+unsafe impl Send for Connection {}
+
+impl Connection {
+	fn open(path: &Path) -> Result<Self, SimulationError> {
+		let filename = CString::new(path.as_os_str().as_encoded_bytes())
+			.map_err(|_| SimulationError::Sqlite { path: path.to_path_buf(), message: String::from("the path holds a nul byte") })?;
+		let mut database = std::ptr::null_mut();
+		let code = unsafe { ffi::sqlite3_open(filename.as_ptr(), &mut database) };
+
+		// a database is handed out even when opening fails, to tell why
+		let Some(database) = NonNull::new(database) else {
+			return Err(SimulationError::Sqlite { path: path.to_path_buf(), message: String::from("out of memory") });
+		};
+		let connection = Connection { database: database, path: path.to_path_buf() };
+		if code != ffi::SQLITE_OK {
+			return Err(connection.error());
+		}
+
+		return Ok(connection);
+	}
+
+	/// the last error of the connection
+	fn error(&self) -> SimulationError {
+		let message = unsafe { CStr::from_ptr(ffi::sqlite3_errmsg(self.database.as_ptr())) };
+
+		return SimulationError::Sqlite { path: self.path.clone(), message: message.to_string_lossy().into_owned() };
+	}
+
+	/// run `sql`, any number of statements without parameters
+	fn execute_batch(&self, sql: &str) -> Result<(), SimulationError> {
+		let sql = CString::new(sql).map_err(|_| SimulationError::Sqlite { path: self.path.clone(), message: String::from("the sql holds a nul byte") })?;
+		let code = unsafe {
+			ffi::sqlite3_exec(self.database.as_ptr(), sql.as_ptr(), std::ptr::null(), std::ptr::null_mut(), std::ptr::null_mut())
+		};
+		if code != ffi::SQLITE_OK {
+			return Err(self.error());
+		}
+
+		return Ok(());
+	}
+
+	/// run the single statement `sql` once for every row of `rows`,
+	/// returns the first column of the last row it yielded, if any
+	fn execute(&self, sql: &str, rows: &[Vec<Value>]) -> Result<Option<i64>, SimulationError> {
+		let sql = CString::new(sql).map_err(|_| SimulationError::Sqlite { path: self.path.clone(), message: String::from("the sql holds a nul byte") })?;
+		let mut statement = std::ptr::null_mut();
+		let code = unsafe {
+			ffi::sqlite3_prepare_v2(self.database.as_ptr(), sql.as_ptr(), -1, &mut statement, std::ptr::null_mut())
+		};
+		if code != ffi::SQLITE_OK {
+			return Err(self.error());
+		}
+
+		let result = self.step_rows(statement, rows);
+		unsafe { ffi::sqlite3_finalize(statement) };
+
+		return result;
+	}
+
+	fn step_rows(&self, statement: *mut ffi::Statement, rows: &[Vec<Value>]) -> Result<Option<i64>, SimulationError> {
+		let mut first_column = None;
+		let no_parameters = [Vec::new()];
+		let rows = if rows.is_empty() { &no_parameters[..] } else { rows };
+
+		for row in rows {
+			let mut texts = Vec::new();
+			for (position, value) in row.iter().enumerate() {
+				let position = position as c_int + 1;
+				let code = match value {
+					Value::Integer(value) => unsafe { ffi::sqlite3_bind_int64(statement, position, *value) },
+					Value::Real(Some(value)) => unsafe { ffi::sqlite3_bind_double(statement, position, *value) },
+					Value::Real(None) => unsafe { ffi::sqlite3_bind_null(statement, position) },
+					Value::Text(text) => {
+						// kept until the row is stepped, sqlite does not copy it
+						let text = CString::new(*text).map_err(|_| SimulationError::Sqlite { path: self.path.clone(), message: String::from("a text holds a nul byte") })?;
+						let code = unsafe { ffi::sqlite3_bind_text(statement, position, text.as_ptr(), -1, std::ptr::null()) };
+						texts.push(text);
+
+						code
+					}
+				};
+				if code != ffi::SQLITE_OK {
+					return Err(self.error());
+				}
+			}
+
+			loop {
+				match unsafe { ffi::sqlite3_step(statement) } {
+					ffi::SQLITE_ROW => first_column = Some(unsafe { ffi::sqlite3_column_int64(statement, 0) }),
+					ffi::SQLITE_DONE => break,
+					_ => return Err(self.error())
+				}
+			}
+			// a reset statement keeps its bindings until they are bound again
+			unsafe { ffi::sqlite3_reset(statement) };
+		}
+
+		return Ok(first_column);
+	}
+
+	/// run `work` in a transaction, rolled back when it fails
+	fn transaction<T>(&self, work: impl FnOnce() -> Result<T, SimulationError>) -> Result<T, SimulationError> {
+		self.execute_batch("BEGIN")?;
+		let result = work();
+		match &result {
+			Ok(_) => self.execute_batch("COMMIT")?,
+			Err(_) => {
+				let _ = self.execute_batch("ROLLBACK");
+			}
+		}
+
+		return result;
+	}
+
+	/// the error of a connection whose mutex a panic poisoned
+	fn poisoned(&self) -> SimulationError {
+		return SimulationError::Sqlite { path: self.path.clone(), message: String::from("a panic left the connection in an unknown state") };
+	}
+
+	fn last_insert_rowid(&self) -> i64 {
+		return unsafe { ffi::sqlite3_last_insert_rowid(self.database.as_ptr()) };
+	}
+}
+
+impl Drop for Connection {
+	fn drop(&mut self) {
+		unsafe { ffi::sqlite3_close(self.database.as_ptr()) };
+	}
+}
+
+impl std::fmt::Debug for Connection {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return f.debug_struct("Connection").field("path", &self.path).finish();
+	}
+}
+
+/// writes a run to a sqlite database: the population to `individuals` when it is created,
+/// the pairs after every round to `matches` and the stats of every round to `rounds`, one transaction per round.
+/// every run gets a new `run_id` in `runs`, with its `Sample::metadata` as json, so a database can hold many.
+/// individuals who arrive during the run are not written
+#[derive(Debug)]
+pub struct SqliteSink {
+	// only locked by `count`, `emit` goes through `&mut`
+	connection: Mutex<Connection>,
+	run_id: i64,
+	// the pairs as of the last event, with the reviewer's and the proposer's score
+	pairs: BTreeMap<(PersonId, PersonId), (f32, f32)>
+}
+
+impl SqliteSink {
+	pub const SCHEMA_VERSION: i64 = SCHEMA_VERSION;
+
+	/// open or create the database at `path`, make its tables if they are missing and write a new run
+	/// with the population of `sample`. a database of another schema version is refused
+	pub fn create(path: &Path, sample: &Sample) -> Result<Self, SimulationError> {
+		let connection = Connection::open(path)?;
+
+		let version = connection.execute("PRAGMA user_version", &[])?.unwrap_or_default();
+		if version != 0 && version != SCHEMA_VERSION {
+			return Err(SimulationError::Sqlite {
+				path: path.to_path_buf(),
+				message: format!("the database has schema version {}, expected {}, write to a new file", version, SCHEMA_VERSION)
+			});
+		}
+		connection.execute_batch(&format!("BEGIN;\n{}\nPRAGMA user_version = {};\nCOMMIT;", SCHEMA, SCHEMA_VERSION))?;
+
+		let metadata = serde_json::to_string(&sample.metadata())?;
+		let mut columns = Vec::with_capacity(sample.population.len());
+		for individual in &sample.population {
+			columns.push((
+				individual.identity.as_str(),
+				individual.gender.to_string(),
+				individual.orientation.to_string(),
+				serde_json::to_string(&individual.preference_weights)?,
+				serde_json::to_string(&individual.ratings)?
+			));
+		}
+		let run_id = connection.transaction(|| {
+			connection.execute("INSERT INTO runs (seed, metadata) VALUES (?1, ?2)", &[vec![Value::Integer(sample.seed as i64), Value::Text(&metadata)]])?;
+			let run_id = connection.last_insert_rowid();
+
+			let rows: Vec<Vec<Value>> = sample.population
+				.iter()
+				.zip(&columns)
+				.map(|(individual, (identity, gender, orientation, weights, ratings))| vec![
+					Value::Integer(run_id),
+					Value::Text(identity),
+					Value::Text(gender),
+					Value::Text(orientation),
+					Value::Real(Some(individual.age as f64)),
+					Value::Text(weights),
+					Value::Text(ratings)
+				])
+				.collect();
+			if !rows.is_empty() {
+				connection.execute("INSERT INTO individuals (run_id, id, gender, orientation, age, weights, ratings) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)", &rows)?;
+			}
+
+			return Ok(run_id);
+		})?;
+		tracing::debug!(path = %path.display(), run_id = run_id, "started a run in the database");
+
+		// the pairs the sample starts with, later rounds only tell what changed
+		let pairs = sample.pairs()
+			.into_iter()
+			.map(|pair| ((pair.proposer_id, pair.reviewer_id), (pair.reviewer_score, pair.proposer_score)))
+			.collect();
+
+		return Ok(SqliteSink { connection: Mutex::new(connection), run_id: run_id, pairs: pairs });
+	}
+
+	/// the run this sink writes, the largest in the database
+	pub fn run_id(&self) -> i64 {
+		return self.run_id;
+	}
+
+	/// the rows of `table` over every run, e.g. to check what a run wrote
+	pub fn count(&self, table: &str) -> Result<i64, SimulationError> {
+		let connection = self.connection.lock().map_err(|poisoned| poisoned.into_inner().poisoned())?;
+
+		return Ok(connection.execute(&format!("SELECT COUNT(*) FROM \"{}\"", table.replace('"', "\"\"")), &[])?.unwrap_or_default());
+	}
+}
+
+impl EventSink for SqliteSink {
+	fn emit(&mut self, event: &SimEvent) -> Result<(), SimulationError> {
+		match event {
+			SimEvent::MatchFormed { proposer, reviewer, reviewer_score, proposer_score, .. } => {
+				self.pairs.insert((*proposer, *reviewer), (*reviewer_score, *proposer_score));
+			},
+			SimEvent::MatchBroken { proposer, reviewer, .. } => {
+				self.pairs.remove(&(*proposer, *reviewer));
+			},
+			SimEvent::RoundCompleted { round, stats } => {
+				let round = *round as i64;
+				let matched = stats.matched_males + stats.matched_females + stats.matched_non_binary;
+				let unmatched = stats.unmatched_males + stats.unmatched_females + stats.unmatched_non_binary;
+
+				let identities: Vec<(IdentityStr, IdentityStr)> = self.pairs
+					.keys()
+					.map(|(proposer, reviewer)| (proposer.as_str(), reviewer.as_str()))
+					.collect();
+				let pairs: Vec<Vec<Value>> = self.pairs
+					.values()
+					.zip(&identities)
+					.map(|((reviewer_score, proposer_score), (proposer, reviewer))| vec![
+						Value::Integer(self.run_id),
+						Value::Integer(round),
+						Value::Text(proposer),
+						Value::Text(reviewer),
+						Value::Real(Some(*reviewer_score as f64)),
+						Value::Real(Some(*proposer_score as f64))
+					])
+					.collect();
+				let run_stats = vec![
+					Value::Integer(self.run_id),
+					Value::Integer(round),
+					Value::Integer(matched as i64),
+					Value::Integer(unmatched as i64),
+					Value::Real(stats.mean_candidate_score.map(|score| score as f64)),
+					Value::Integer(stats.delta.new_matches as i64),
+					Value::Integer(stats.delta.dissolved as i64),
+					Value::Integer(stats.breakups as i64),
+					Value::Integer(stats.blacklist_growth as i64)
+				];
+
+				let connection = self.connection.get_mut().map_err(|poisoned| poisoned.into_inner().poisoned())?;
+				connection.transaction(|| {
+					if !pairs.is_empty() {
+						connection.execute("INSERT INTO matches (run_id, round, proposer_id, reviewer_id, reviewer_score, proposer_score) VALUES (?1, ?2, ?3, ?4, ?5, ?6)", &pairs)?;
+					}
+					connection.execute(
+						"INSERT INTO rounds (run_id, round, matched, unmatched, mean_candidate_score, new_matches, dissolved, breakups, blacklist_growth) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+						&[run_stats]
+					)?;
+
+					return Ok(());
+				})?;
+			},
+			SimEvent::RoundStarted { .. } | SimEvent::Proposal { .. } | SimEvent::Rejection { .. } => {}
+		}
+
+		return Ok(());
+	}
+}
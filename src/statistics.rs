@@ -0,0 +1,1222 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::SimulationError;
+use crate::individual::{Gender, Individual, Orientation};
+use crate::person_id::PersonId;
+
+/// size and number of matched individuals of `gender`, 
+/// `matched` tells for every individual of `population` whether they are matched
+fn gender_counts(population: &[Individual], matched: &[bool], gender: Gender) -> (usize, usize) {
+	let mut size = 0;
+	let mut matched_count = 0;
+
+	for (individual, &is_matched) in population.iter().zip(matched) {
+		if individual.gender == gender {
+			size += 1;
+			if is_matched {
+				matched_count += 1;
+			}
+		}
+	}
+
+	return (size, matched_count);
+}
+
+/// the candidate scores of the matched individuals
+fn matched_scores(population: &[Individual], matched: &[bool]) -> Vec<f32> {
+	return population
+		.iter()
+		.zip(matched)
+		.filter(|(_, &is_matched)| is_matched)
+		.filter_map(|(individual, _)| individual.candidate_score)
+		.filter(|score| !score.is_nan())
+		.collect();
+}
+
+/// the individuals of every generation, with their mean ratings
+fn generation_stats(population: &[Individual], matched: &[bool]) -> BTreeMap<u32, GenerationStats> {
+	let mut by_generation: BTreeMap<u32, (GenerationStats, Vec<f64>)> = BTreeMap::new();
+	for (individual, &is_matched) in population.iter().zip(matched) {
+		let (stats, rating_sums) = by_generation.entry(individual.generation).or_default();
+		stats.population += 1;
+		if is_matched {
+			stats.matched += 1;
+		}
+		if rating_sums.len() < individual.ratings.len() {
+			rating_sums.resize(individual.ratings.len(), 0.0);
+		}
+		for (sum, &rating) in rating_sums.iter_mut().zip(&individual.ratings) {
+			*sum += rating as f64;
+		}
+	}
+
+	return by_generation
+		.into_iter()
+		.map(|(generation, (mut stats, rating_sums))| {
+			stats.mean_ratings = rating_sums.into_iter().map(|sum| (sum / stats.population as f64) as f32).collect();
+
+			return (generation, stats);
+		})
+		.collect();
+}
+
+/// the share of the individuals who divorced that is matched, `None` when nobody did
+fn divorced_rematch_rate(population: &[Individual], matched: &[bool]) -> Option<f64> {
+	let divorced: Vec<bool> = population
+		.iter()
+		.zip(matched)
+		.filter(|(individual, _)| individual.extras().divorced_in_round.is_some())
+		.map(|(_, &is_matched)| is_matched)
+		.collect();
+	if divorced.is_empty() {
+		return None;
+	}
+
+	return Some(divorced.iter().filter(|&&is_matched| is_matched).count() as f64 / divorced.len() as f64);
+}
+
+/// the mean `Individual::selectivity` of the population, `None` when it is empty
+fn mean_selectivity(population: &[Individual]) -> Option<f32> {
+	let selectivities: Vec<f32> = population
+		.iter()
+		.map(|individual| individual.selectivity)
+		.collect();
+
+	return mean(&selectivities);
+}
+
+/// mean rejection counts of the individuals that are (or are not) matched
+fn mean_rejections(population: &[Individual], matched: &[bool], of_matched: bool) -> MeanRejections {
+	return rejections_of(
+		population
+			.iter()
+			.zip(matched)
+			.filter(|(_, &is_matched)| is_matched == of_matched)
+			.map(|(individual, _)| individual)
+	);
+}
+
+fn rejections_of<'a>(individuals: impl Iterator<Item = &'a Individual>) -> MeanRejections {
+	let individuals: Vec<&Individual> = individuals.collect();
+	let received: Vec<f32> = individuals.iter().map(|individual| individual.rejections_received as f32).collect();
+	let given: Vec<f32> = individuals.iter().map(|individual| individual.rejections_given as f32).collect();
+
+	return MeanRejections {
+		received: mean(&received),
+		given: mean(&given)
+	};
+}
+
+/// how every gender present in `population` fared
+fn gender_stats(population: &[Individual], matched: &[bool]) -> BTreeMap<Gender, GenderStats> {
+	let mut by_gender: BTreeMap<Gender, GenderStats> = BTreeMap::new();
+	for gender in Gender::ALL {
+		let (size, matched_count) = gender_counts(population, matched, gender);
+		if size == 0 {
+			continue;
+		}
+		let scores: Vec<f32> = population
+			.iter()
+			.zip(matched)
+			.filter(|(individual, &is_matched)| is_matched && individual.gender == gender)
+			.filter_map(|(individual, _)| individual.candidate_score)
+			.filter(|score| !score.is_nan())
+			.collect();
+
+		by_gender.insert(gender, GenderStats {
+			population: size,
+			matched: matched_count,
+			match_rate: Some(matched_count as f64 / size as f64),
+			mean_matched_score: mean(&scores),
+			mean_rejections: rejections_of(population.iter().filter(|individual| individual.gender == gender))
+		});
+	}
+
+	return by_gender;
+}
+
+/// the match rate of every tenth of the population by `desirability`, least desirable first.
+/// a population of fewer than 10 leaves some deciles empty
+pub(crate) fn decile_stats(desirability: &[f32], matched: &[bool]) -> [DecileStats; 10] {
+	let mut ranked: Vec<(f32, bool)> = desirability.iter().copied().zip(matched.iter().copied()).collect();
+	ranked.sort_by(|(first, _), (second, _)| first.total_cmp(second));
+
+	let mut deciles = [DecileStats::default(); 10];
+	for (rank, &(desirability, is_matched)) in ranked.iter().enumerate() {
+		let decile = &mut deciles[rank * 10 / ranked.len()];
+		decile.population += 1;
+		if is_matched {
+			decile.matched += 1;
+		}
+		decile.min_desirability = Some(decile.min_desirability.map_or(desirability, |min| min.min(desirability)));
+		decile.max_desirability = Some(decile.max_desirability.map_or(desirability, |max| max.max(desirability)));
+	}
+	for decile in &mut deciles {
+		decile.match_rate = (decile.population > 0).then(|| decile.matched as f64 / decile.population as f64);
+	}
+
+	return deciles;
+}
+
+pub(crate) fn mean(values: &[f32]) -> Option<f32> {
+	if values.is_empty() {
+		return None;
+	}
+
+	return Some(values.iter().sum::<f32>() / values.len() as f32);
+}
+
+/// where every value ranks among all of them, from 0 for the lowest to 1 for the highest, in the order
+/// of `values`. equal values share the mean of their ranks, a single value ranks 0.5
+pub(crate) fn percentile_ranks(values: &[f32]) -> Vec<f32> {
+	if values.len() < 2 {
+		return vec![0.5; values.len()];
+	}
+
+	let mut order: Vec<usize> = (0..values.len()).collect();
+	order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+	let mut ranks = vec![0.0; values.len()];
+	let mut start = 0;
+	while start < order.len() {
+		// a nan equals nothing, not even itself, and ranks on its own
+		let end = start + 1 + order[start + 1..].iter().take_while(|&&position| values[position] == values[order[start]]).count();
+		let rank = (start + end - 1) as f32 / 2.0 / (values.len() - 1) as f32;
+		for &position in &order[start..end] {
+			ranks[position] = rank;
+		}
+		start = end;
+	}
+
+	return ranks;
+}
+
+/// the spread of the candidate scores of a group of matched individuals
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreDistribution {
+	pub min: f32,
+	pub max: f32,
+	pub mean: f32,
+	pub median: f32,
+	// population standard deviation
+	pub std_dev: f32,
+	// `ScoreDistribution::BUCKETS` equally wide buckets from `min` to `max`,
+	// the last one includes `max`
+	pub histogram: Vec<usize>
+}
+
+impl ScoreDistribution {
+	pub const BUCKETS: usize = 10;
+
+	/// `None` when there are no scores, NaN scores are left out
+	pub fn new(scores: &[f32]) -> Option<Self> {
+		let mut sorted: Vec<f32> = scores.iter().copied().filter(|score| !score.is_nan()).collect();
+		// added up in the order of `scores`, like every other mean of them
+		let mean = mean(&sorted)?;
+		sorted.sort_by(|a, b| a.total_cmp(b));
+
+		let min = sorted[0];
+		let max = sorted[sorted.len() - 1];
+		let variance = sorted.iter().map(|score| (score - mean).powi(2)).sum::<f32>() / sorted.len() as f32;
+
+		let mut histogram = vec![0; ScoreDistribution::BUCKETS];
+		for score in &sorted {
+			histogram[ScoreDistribution::bucket(*score, min, max)] += 1;
+		}
+
+		return Some(ScoreDistribution {
+			min: min,
+			max: max,
+			mean: mean,
+			median: median(&sorted)?,
+			std_dev: variance.sqrt(),
+			histogram: histogram
+		});
+	}
+
+	/// the bucket `score` falls into, every score lands in the first bucket when `min == max`
+	fn bucket(score: f32, min: f32, max: f32) -> usize {
+		if max <= min {
+			return 0;
+		}
+
+		let position = (score - min) / (max - min) * ScoreDistribution::BUCKETS as f32;
+		return (position as usize).min(ScoreDistribution::BUCKETS - 1);
+	}
+
+	/// the lower edge of `bucket`
+	pub fn bucket_start(&self, bucket: usize) -> f32 {
+		return self.min + (self.max - self.min) * bucket as f32 / ScoreDistribution::BUCKETS as f32;
+	}
+}
+
+/// pearson correlation of the paired values, `None` for fewer than two pairs 
+/// or when either side does not vary
+pub fn pearson_correlation(pairs: &[(f32, f32)]) -> Option<f32> {
+	if pairs.len() < 2 {
+		return None;
+	}
+
+	let count = pairs.len() as f64;
+	let mean_x = pairs.iter().map(|&(x, _)| x as f64).sum::<f64>() / count;
+	let mean_y = pairs.iter().map(|&(_, y)| y as f64).sum::<f64>() / count;
+
+	let mut covariance = 0.0;
+	let mut variance_x = 0.0;
+	let mut variance_y = 0.0;
+	for &(x, y) in pairs {
+		let (dx, dy) = (x as f64 - mean_x, y as f64 - mean_y);
+		covariance += dx * dy;
+		variance_x += dx * dx;
+		variance_y += dy * dy;
+	}
+
+	if variance_x <= 0.0 || variance_y <= 0.0 {
+		return None;
+	}
+
+	let correlation = covariance / (variance_x * variance_y).sqrt();
+	if !correlation.is_finite() {
+		return None;
+	}
+
+	return Some(correlation as f32);
+}
+
+/// the gini coefficient of the values, 0 when they are all the same and towards 1 when one holds
+/// everything. values below 0 count as 0, 0 for fewer than two values or when they sum to 0
+pub fn gini(values: &[f32]) -> f32 {
+	let mut sorted: Vec<f64> = values.iter().map(|&value| (value as f64).max(0.0)).collect();
+	let total: f64 = sorted.iter().sum();
+	if sorted.len() < 2 || total <= 0.0 || !total.is_finite() {
+		return 0.0;
+	}
+
+	sorted.sort_by(|a, b| a.total_cmp(b));
+	// the sum of (2i - n - 1) × the i-th smallest value, 1-based
+	let n = sorted.len() as f64;
+	let weighted: f64 = sorted
+		.iter()
+		.enumerate()
+		.map(|(position, value)| (2.0 * (position as f64 + 1.0) - n - 1.0) * value)
+		.sum();
+
+	return (weighted / (n * total)) as f32;
+}
+
+/// one individual of `DesirabilityReport`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Desirability {
+	pub identity: PersonId,
+	pub gender: Gender,
+	pub desirability: f32,
+	// their partner in a mutual pair
+	pub partner: Option<PersonId>
+}
+
+/// how desirable everyone is and whether desirable individuals pair up with each other, 
+/// see `Sample::desirability_report`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DesirabilityReport {
+	// the preference weights averaged over the population
+	pub mean_weights: Vec<f32>,
+	// in the order of the population
+	pub individuals: Vec<Desirability>,
+	// between the desirability of each proposer and that of their reviewer,
+	// `None` with fewer than two pairs or without any variance
+	pub partner_correlation: Option<f32>
+}
+
+/// how one round of `Sample::match_making` changed the pairs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct RoundDelta {
+	// pairs formed by two individuals that were both single before the round
+	pub new_matches: usize,
+	// pairs formed by at least one individual that left another partner for it
+	pub upgrades: usize,
+	// pairs of the previous round that no longer exist
+	pub dissolved: usize,
+	// proposals turned down during the round, by blacklisting or over a dealbreaker
+	pub rejections: usize
+}
+
+impl RoundDelta {
+	/// whether the round formed or broke any pair
+	pub fn changed(&self) -> bool {
+		return self.new_matches + self.upgrades + self.dissolved > 0;
+	}
+}
+
+/// summary of the matching state after one round of `Sample::match_making`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoundStats {
+	// 1-based index of the round this summary was taken after
+	pub round: u32,
+	pub matched_males: usize,
+	pub matched_females: usize,
+	pub unmatched_males: usize,
+	pub unmatched_females: usize,
+	// over the candidate scores of all matched individuals, `None` when nobody is matched
+	pub mean_candidate_score: Option<f32>,
+	// number of blacklist entries added during the round
+	pub blacklist_growth: usize,
+	#[serde(default)]
+	pub matched_non_binary: usize,
+	#[serde(default)]
+	pub unmatched_non_binary: usize,
+	// pairs skipped during the round because one of them failed a dealbreaker of the other,
+	// filled in by `Sample::match_making`
+	#[serde(default)]
+	pub dealbreaker_rejections: usize,
+	// filled in by `Sample::match_making`
+	#[serde(default)]
+	pub delta: RoundDelta,
+	// individuals that joined before the round, drawn or come of age, and matched pairs that left after it
+	#[serde(default)]
+	pub arrivals: usize,
+	#[serde(default)]
+	pub retired_pairs: usize,
+	// over everyone in the pool, `None` for an empty pool
+	#[serde(default)]
+	pub mean_selectivity: Option<f32>,
+	// matched pairs that broke up before the round, filled in by `Sample::match_making`
+	#[serde(default)]
+	pub breakups: usize,
+	// matched pairs that divorced before the round, filled in by `Sample::match_making`
+	#[serde(default)]
+	pub divorces: usize,
+	// everyone in the pool after the round, matched or not
+	#[serde(default)]
+	pub population: usize,
+	// children born after the round and all those yet to join the pool, filled in by `Sample::match_making`
+	#[serde(default)]
+	pub births: usize,
+	#[serde(default)]
+	pub juveniles: usize
+}
+
+impl RoundStats {
+	pub const CSV_HEADER: &'static str = "round,matched_males,matched_females,unmatched_males,unmatched_females,mean_candidate_score,blacklist_growth,matched_non_binary,unmatched_non_binary,dealbreaker_rejections,new_matches,upgrades,dissolved,arrivals,retired_pairs,mean_selectivity,breakups,divorces,population,births,juveniles";
+
+	/// summarize the population after `round`, 
+	/// `matched` tells for every individual whether they are matched
+	pub fn new(
+		round: u32,
+		population: &[Individual],
+		matched: &[bool],
+		blacklist_growth: usize
+	) -> Self {
+		let (males, matched_males) = gender_counts(population, matched, Gender::Male);
+		let (females, matched_females) = gender_counts(population, matched, Gender::Female);
+		let (non_binary, matched_non_binary) = gender_counts(population, matched, Gender::NonBinary);
+
+		return RoundStats {
+			round: round,
+			matched_males: matched_males,
+			matched_females: matched_females,
+			unmatched_males: males - matched_males,
+			unmatched_females: females - matched_females,
+			mean_candidate_score: mean(&matched_scores(population, matched)),
+			blacklist_growth: blacklist_growth,
+			matched_non_binary: matched_non_binary,
+			unmatched_non_binary: non_binary - matched_non_binary,
+			dealbreaker_rejections: 0,
+			delta: RoundDelta::default(),
+			arrivals: 0,
+			retired_pairs: 0,
+			mean_selectivity: mean_selectivity(population),
+			breakups: 0,
+			divorces: 0,
+			population: population.len(),
+			births: 0,
+			juveniles: 0
+		};
+	}
+
+	/// append this round as one csv row, write `RoundStats::CSV_HEADER` first
+	pub fn export_csv(
+		&self,
+		mut writer: impl std::io::Write
+	) -> Result<(), SimulationError> {
+		writeln!(
+			writer,
+			"{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+			self.round,
+			self.matched_males,
+			self.matched_females,
+			self.unmatched_males,
+			self.unmatched_females,
+			self.mean_candidate_score.map(|score| score.to_string()).unwrap_or_default(),
+			self.blacklist_growth,
+			self.matched_non_binary,
+			self.unmatched_non_binary,
+			self.dealbreaker_rejections,
+			self.delta.new_matches,
+			self.delta.upgrades,
+			self.delta.dissolved,
+			self.arrivals,
+			self.retired_pairs,
+			self.mean_selectivity.map(|selectivity| selectivity.to_string()).unwrap_or_default(),
+			self.breakups,
+			self.divorces,
+			self.population,
+			self.births,
+			self.juveniles
+		)?;
+
+		return Ok(());
+	}
+}
+
+/// why `Sample::run` stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StopReason {
+	// every one of the requested rounds was run
+	MaxRounds,
+	// the pairs did not change for `Sample::convergence_rounds` rounds in a row
+	Converged,
+	// the `CancellationToken` of the run was cancelled
+	Cancelled,
+	// a round failed with `SimulationError::EmptyPopulation`, only `MonteCarlo` and `SweepRunner` go on after it
+	Unpairable
+}
+
+impl StopReason {
+	/// the name serde writes, e.g. `max-rounds`
+	pub fn as_str(&self) -> &'static str {
+		return match self {
+			StopReason::MaxRounds => "max-rounds",
+			StopReason::Converged => "converged",
+			StopReason::Cancelled => "cancelled",
+			StopReason::Unpairable => "unpairable"
+		};
+	}
+}
+
+impl std::fmt::Display for StopReason {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return match self {
+			StopReason::MaxRounds => write!(f, "reached the maximum number of rounds"),
+			StopReason::Converged => write!(f, "the pairs stopped changing"),
+			StopReason::Cancelled => write!(f, "the run was cancelled"),
+			StopReason::Unpairable => write!(f, "nobody in the pool could be paired")
+		};
+	}
+}
+
+/// outcome of `Sample::run`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunReport {
+	pub rounds_executed: u32,
+	pub stop_reason: StopReason,
+	// the stats of every executed round
+	pub round_stats: Vec<RoundStats>
+}
+
+/// the individuals of one generation in the pool, see `ReproductionConfig`
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct GenerationStats {
+	pub population: usize,
+	pub matched: usize,
+	// the mean of every rating over the generation, in the order of the attributes
+	pub mean_ratings: Vec<f32>
+}
+
+/// size and number of matched individuals of one group of the population
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct GroupCounts {
+	pub population: usize,
+	pub matched: usize
+}
+
+impl GroupCounts {
+	/// share of the group that is matched, `None` for an empty group
+	pub fn match_rate(&self) -> Option<f64> {
+		if self.population == 0 {
+			return None;
+		}
+
+		return Some(self.matched as f64 / self.population as f64);
+	}
+}
+
+/// average `Individual::rejections_received` and `Individual::rejections_given` of a group,
+/// `None` for an empty group
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct MeanRejections {
+	pub received: Option<f32>,
+	pub given: Option<f32>
+}
+
+/// how the individuals of one gender fared, see `Statistics::by_gender`
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct GenderStats {
+	pub population: usize,
+	pub matched: usize,
+	pub match_rate: Option<f64>,
+	// over the candidate scores of the matched, `None` when none of them is
+	pub mean_matched_score: Option<f32>,
+	pub mean_rejections: MeanRejections
+}
+
+/// how a tenth of the population by desirability fared, see `Statistics::by_decile`
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct DecileStats {
+	pub population: usize,
+	pub matched: usize,
+	// `None` for an empty decile
+	pub match_rate: Option<f64>,
+	// the range of desirability it covers, `None` for an empty decile
+	pub min_desirability: Option<f32>,
+	pub max_desirability: Option<f32>
+}
+
+/// summary of the matching state of a whole sample,
+/// its `Display` is the report printed by `Sample::display_statistics`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Statistics {
+	pub seed: u64,
+	pub male_population: usize,
+	pub female_population: usize,
+	pub total_population: usize,
+	// individuals whose candidate names them back
+	pub matched_males: usize,
+	pub matched_females: usize,
+	pub unmatched_males: usize,
+	pub unmatched_females: usize,
+	pub unmatched_percentage: f64,
+	// over the candidate scores of all matched individuals, `None` when nobody is matched
+	pub mean_candidate_score: Option<f32>,
+	pub median_candidate_score: Option<f32>,
+	// males minus females, negative when females are the majority
+	pub gender_imbalance: i64,
+	#[serde(default)]
+	pub non_binary_population: usize,
+	#[serde(default)]
+	pub matched_non_binary: usize,
+	#[serde(default)]
+	pub unmatched_non_binary: usize,
+	// every orientation that occurs in the population
+	#[serde(default)]
+	pub by_orientation: BTreeMap<Orientation, GroupCounts>,
+	// unmatched individuals who score everyone they are compatible with below their
+	// `Individual::demanded_score`, filled in by `Sample::statistics`.
+	// above `Sample::EXACT_UNMATCHED_BY_THRESHOLD_MAX` individuals it is estimated from some of them
+	#[serde(default)]
+	pub unmatched_by_threshold: usize,
+	#[serde(default)]
+	pub unmatched_by_threshold_estimated: bool,
+	// rounds the matched individuals spent on the market, their `Individual::matched_in_round`
+	#[serde(default)]
+	pub mean_time_to_match: Option<f32>,
+	#[serde(default)]
+	pub median_time_to_match: Option<f32>,
+	// number of matched individuals by the round their pair formed in
+	#[serde(default)]
+	pub time_to_match_histogram: BTreeMap<u32, usize>,
+	#[serde(default)]
+	pub rejections_of_matched: MeanRejections,
+	#[serde(default)]
+	pub rejections_of_unmatched: MeanRejections,
+	// the mean `Individual::best_rejected_score` of the unmatched who were turned down in the last round,
+	// the best offers they missed. `None` when none of them were
+	#[serde(default)]
+	pub mean_unmatched_best_rejected_score: Option<f32>,
+	// `mean_candidate_score` minus the above: near 0 the unmatched only just missed the bar, large they are far from it
+	#[serde(default)]
+	pub unmatched_score_gap: Option<f32>,
+	// by the decade of their age, e.g. 20 for everyone from 20 to just under 30
+	#[serde(default)]
+	pub by_age: BTreeMap<u32, GroupCounts>,
+	// by `Individual::generation`, how the ratings of the offspring change from one generation to the next
+	#[serde(default)]
+	pub by_generation: BTreeMap<u32, GenerationStats>,
+	// pairs that matched and left the pool in earlier rounds, the other counts only cover
+	// the individuals still in the pool. filled in by `Sample::statistics`
+	#[serde(default)]
+	pub retired_pairs: usize,
+	// pairs that broke up before a round over the whole run, filled in by `Sample::statistics`
+	#[serde(default)]
+	pub breakups: usize,
+	// pairs that divorced before a round over the whole run, filled in by `Sample::statistics`
+	#[serde(default)]
+	pub divorces: usize,
+	// the share of the divorced still in the pool who are matched again, `None` while nobody divorced
+	#[serde(default)]
+	pub divorced_rematch_rate: Option<f64>,
+	// candidate scores of all matched individuals, `None` when nobody is matched
+	#[serde(default)]
+	pub score_distribution: Option<ScoreDistribution>,
+	// the same for every gender with matched individuals
+	#[serde(default)]
+	pub score_distribution_by_gender: BTreeMap<Gender, ScoreDistribution>,
+	// assortative mating, see `DesirabilityReport::partner_correlation`, filled in by `Sample::statistics`
+	#[serde(default)]
+	pub partner_desirability_correlation: Option<f32>,
+	// candidates held next to everyone's best one with a `MatchingConfig::candidate_capacity` above 1,
+	// 0 after `Sample::commit_candidates`. the matched counts only look at the best one
+	#[serde(default)]
+	pub provisional_candidates: usize,
+	// the sum of the scores both sides of every pair give each other, what `MatchingAlgorithm::Optimal`
+	// maximizes. unlike the candidate scores it does not depend on the algorithm, filled in by `Sample::statistics`
+	#[serde(default)]
+	pub total_welfare: f64,
+	// pairs who would both rather be with each other than with their partners, see `Sample::blocking_pairs`.
+	// above `Sample::EXACT_BLOCKING_PAIRS_MAX` individuals it is estimated from random pairs of individuals.
+	// only `Sample::statistics_with_blocking_pairs` counts them
+	#[serde(default)]
+	pub blocking_pairs: Option<f64>,
+	#[serde(default)]
+	pub blocking_pairs_estimated: bool,
+	// the mean share of every attribute in the score matched individuals give their partner,
+	// see `Sample::attribute_score_shares`
+	#[serde(default)]
+	pub attribute_score_shares: Vec<(String, f32)>,
+	// over everyone in the pool, `None` for an empty pool
+	#[serde(default)]
+	pub mean_selectivity: Option<f32>,
+	// between the selectivity of the matched individuals and the score they give their partner,
+	// whether those who settled ended up with less
+	#[serde(default)]
+	pub selectivity_score_correlation: Option<f32>,
+	// the gini coefficient of everyone's `Individual::times_evaluated`, 0 when everyone got the same attention
+	// and towards 1 when a few got all of it. `None` while nobody was evaluated
+	#[serde(default)]
+	pub attention_gini: Option<f32>,
+	// the gini coefficient of everyone's desirability under the mean weights, filled in by `Sample::statistics`
+	#[serde(default)]
+	pub desirability_gini: f32,
+	// the gini coefficient of everyone's `Individual::proposals_received`
+	#[serde(default)]
+	pub proposals_gini: f32,
+	// the gini coefficient of the score everyone gives their partner, 0 for the unmatched
+	#[serde(default)]
+	pub matched_score_gini: f32,
+	// every gender present in the population
+	#[serde(default)]
+	pub by_gender: BTreeMap<Gender, GenderStats>,
+	// by desirability under the mean weights, the least desirable tenth first, filled in by `Sample::statistics`.
+	// whether the algorithm favours some over others of the same side
+	#[serde(default)]
+	pub by_decile: [DecileStats; 10]
+}
+
+impl Statistics {
+	/// build the summary of `population`, 
+	/// `matched` tells for every individual whether they are matched
+	pub fn new(
+		seed: u64,
+		population: &[Individual],
+		matched: &[bool]
+	) -> Self {
+		let (male_population, matched_males) = gender_counts(population, matched, Gender::Male);
+		let (female_population, matched_females) = gender_counts(population, matched, Gender::Female);
+		let (non_binary_population, matched_non_binary) = gender_counts(population, matched, Gender::NonBinary);
+
+		let total_population = population.len();
+		let unmatched_males = male_population - matched_males;
+		let unmatched_females = female_population - matched_females;
+		let unmatched_non_binary = non_binary_population - matched_non_binary;
+
+		// an empty population has nobody left unmatched
+		let unmatched_percentage = if total_population == 0 {
+			0.0
+		} else {
+			(unmatched_males + unmatched_females + unmatched_non_binary) as f64 / total_population as f64 * 100.0
+		};
+
+		let mut matched_scores = matched_scores(population, matched);
+		matched_scores.sort_by(|a, b| a.total_cmp(b));
+
+		let mut score_distribution_by_gender: BTreeMap<Gender, ScoreDistribution> = BTreeMap::new();
+		for gender in Gender::ALL {
+			let scores: Vec<f32> = population
+				.iter()
+				.zip(matched)
+				.filter(|(individual, &is_matched)| is_matched && individual.gender == gender)
+				.filter_map(|(individual, _)| individual.candidate_score)
+				.collect();
+			if let Some(distribution) = ScoreDistribution::new(&scores) {
+				score_distribution_by_gender.insert(gender, distribution);
+			}
+		}
+
+		let mut times_to_match: Vec<f32> = Vec::new();
+		let mut time_to_match_histogram: BTreeMap<u32, usize> = BTreeMap::new();
+		for (individual, &is_matched) in population.iter().zip(matched) {
+			if let Some(round) = individual.matched_in_round.filter(|_| is_matched) {
+				times_to_match.push(round as f32);
+				*time_to_match_histogram.entry(round).or_default() += 1;
+			}
+		}
+		times_to_match.sort_by(|a, b| a.total_cmp(b));
+
+		let best_rejected_scores: Vec<f32> = population
+			.iter()
+			.zip(matched)
+			.filter(|(_, &is_matched)| !is_matched)
+			.filter_map(|(individual, _)| individual.extras().best_rejected_score)
+			.collect();
+		let mean_unmatched_best_rejected_score = mean(&best_rejected_scores);
+		let mean_candidate_score = mean(&matched_scores);
+
+		let mut by_orientation: BTreeMap<Orientation, GroupCounts> = BTreeMap::new();
+		let mut by_age: BTreeMap<u32, GroupCounts> = BTreeMap::new();
+		for (individual, &is_matched) in population.iter().zip(matched) {
+			let age_bucket = (individual.age.max(0.0) as u32) / 10 * 10;
+			for counts in [
+				by_orientation.entry(individual.orientation).or_default(),
+				by_age.entry(age_bucket).or_default()
+			] {
+				counts.population += 1;
+				if is_matched {
+					counts.matched += 1;
+				}
+			}
+		}
+
+		return Statistics {
+			seed: seed,
+			male_population: male_population,
+			female_population: female_population,
+			total_population: total_population,
+			matched_males: matched_males,
+			matched_females: matched_females,
+			unmatched_males: unmatched_males,
+			unmatched_females: unmatched_females,
+			unmatched_percentage: unmatched_percentage,
+			mean_candidate_score: mean_candidate_score,
+			median_candidate_score: median(&matched_scores),
+			gender_imbalance: male_population as i64 - female_population as i64,
+			non_binary_population: non_binary_population,
+			matched_non_binary: matched_non_binary,
+			unmatched_non_binary: unmatched_non_binary,
+			by_orientation: by_orientation,
+			unmatched_by_threshold: 0,
+			unmatched_by_threshold_estimated: false,
+			mean_time_to_match: mean(&times_to_match),
+			median_time_to_match: median(&times_to_match),
+			time_to_match_histogram: time_to_match_histogram,
+			rejections_of_matched: mean_rejections(population, matched, true),
+			rejections_of_unmatched: mean_rejections(population, matched, false),
+			mean_unmatched_best_rejected_score: mean_unmatched_best_rejected_score,
+			unmatched_score_gap: mean_candidate_score
+				.zip(mean_unmatched_best_rejected_score)
+				.map(|(matched, rejected)| matched - rejected),
+			by_age: by_age,
+			by_generation: generation_stats(population, matched),
+			retired_pairs: 0,
+			breakups: 0,
+			divorces: 0,
+			divorced_rematch_rate: divorced_rematch_rate(population, matched),
+			score_distribution: ScoreDistribution::new(&matched_scores),
+			score_distribution_by_gender: score_distribution_by_gender,
+			partner_desirability_correlation: None,
+			provisional_candidates: population
+				.iter()
+				.map(|individual| individual.extras().provisional_candidates.len())
+				.sum(),
+			total_welfare: 0.0,
+			blocking_pairs: None,
+			blocking_pairs_estimated: false,
+			attribute_score_shares: Vec::new(),
+			mean_selectivity: mean_selectivity(population),
+			selectivity_score_correlation: pearson_correlation(
+				&population
+					.iter()
+					.zip(matched)
+					.filter(|(_, &is_matched)| is_matched)
+					.filter_map(|(individual, _)| individual.candidate_score.map(|score| (individual.selectivity, score)))
+					.collect::<Vec<(f32, f32)>>()
+			),
+			attention_gini: population
+				.iter()
+				.any(|individual| individual.times_evaluated > 0)
+				.then(|| gini(&population.iter().map(|individual| individual.times_evaluated as f32).collect::<Vec<f32>>())),
+			desirability_gini: 0.0,
+			proposals_gini: gini(&population.iter().map(|individual| individual.proposals_received as f32).collect::<Vec<f32>>()),
+			matched_score_gini: gini(
+				&population
+					.iter()
+					.zip(matched)
+					.map(|(individual, &is_matched)| if is_matched { individual.candidate_score.unwrap_or(0.0) } else { 0.0 })
+					.collect::<Vec<f32>>()
+			),
+			by_gender: gender_stats(population, matched),
+			by_decile: [DecileStats::default(); 10]
+		};
+	}
+
+	/// write the statistics, histograms included, as json
+	#[cfg(feature = "fs")]
+	pub fn to_json_file(
+		&self, 
+		path: &std::path::Path
+	) -> Result<(), SimulationError> {
+		let file = std::fs::File::create(path)?;
+		serde_json::to_writer_pretty(
+			std::io::BufWriter::new(file), 
+			self
+		)?;
+
+		return Ok(());
+	}
+
+	/// share of the population that is matched, `None` for an empty population
+	pub fn match_rate(&self) -> Option<f64> {
+		if self.total_population == 0 {
+			return None;
+		}
+
+		let matched = self.matched_males + self.matched_females + self.matched_non_binary;
+
+		return Some(matched as f64 / self.total_population as f64);
+	}
+
+	/// the mean score matched individuals of `gender` give their partner, 
+	/// e.g. to compare how well each side does under a `ProposerSide`
+	pub fn mean_matched_score(&self, gender: Gender) -> Option<f32> {
+		return self.score_distribution_by_gender
+			.get(&gender)
+			.map(|distribution| distribution.mean);
+	}
+
+	/// the score histogram of every gender as ascii bars, one line per bucket
+	pub fn render_histogram(&self) -> String {
+		const WIDTH: usize = 40;
+
+		let mut rendered = String::new();
+		for (gender, distribution) in &self.score_distribution_by_gender {
+			rendered.push_str(&format!(
+				"Candidate scores of matched {}: min {:.3}, max {:.3}, std dev {:.3}\n", 
+				gender, 
+				distribution.min, 
+				distribution.max, 
+				distribution.std_dev
+			));
+
+			let largest = distribution.histogram.iter().copied().max().unwrap_or_default().max(1);
+			for (bucket, &count) in distribution.histogram.iter().enumerate() {
+				rendered.push_str(&format!(
+					"{:>10.3} | {:<width$} {}\n", 
+					distribution.bucket_start(bucket), 
+					"#".repeat(count * WIDTH / largest), 
+					count, 
+					width = WIDTH
+				));
+			}
+		}
+
+		return rendered;
+	}
+}
+
+/// a share as a percentage for `Statistics`, "n/a" where there is nobody to take it of
+fn format_rate(rate: Option<f64>) -> String {
+	return rate.map(|rate| format!("{:.2}%", rate * 100.0)).unwrap_or_else(|| String::from("n/a"));
+}
+
+fn format_mean(mean: Option<f32>, precision: usize) -> String {
+	return mean.map(|mean| format!("{:.*}", precision, mean)).unwrap_or_else(|| String::from("-"));
+}
+
+/// median of already sorted values
+fn median(sorted: &[f32]) -> Option<f32> {
+	if sorted.is_empty() {
+		return None;
+	}
+
+	let middle = sorted.len() / 2;
+	if sorted.len().is_multiple_of(2) {
+		return Some((sorted[middle - 1] + sorted[middle]) / 2.0);
+	}
+
+	return Some(sorted[middle]);
+}
+
+impl std::fmt::Display for Statistics {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "Statistics:")?;
+		writeln!(f, "Seed: {}", self.seed)?;
+		writeln!(f, "Males that do not have a match: {}/{}", self.unmatched_males, self.male_population)?;
+		writeln!(f, "Females that do not have a match: {}/{}", self.unmatched_females, self.female_population)?;
+		writeln!(f, "Males that have a match: {}/{}", self.matched_males, self.male_population)?;
+		writeln!(f, "Females that have a match: {}/{}", self.matched_females, self.female_population)?;
+		if self.non_binary_population > 0 {
+			writeln!(f, "Non-binary individuals that do not have a match: {}/{}", self.unmatched_non_binary, self.non_binary_population)?;
+			writeln!(f, "Non-binary individuals that have a match: {}/{}", self.matched_non_binary, self.non_binary_population)?;
+		}
+		// a breakdown is only worth printing when there is more than one orientation
+		if self.by_orientation.len() > 1 {
+			for (orientation, counts) in &self.by_orientation {
+				writeln!(
+					f, 
+					"{} individuals that have a match: {}/{} ({:.2}%)", 
+					orientation, 
+					counts.matched, 
+					counts.population, 
+					counts.match_rate().unwrap_or_default() * 100.0
+				)?;
+			}
+		}
+		if self.by_age.len() > 1 {
+			for (decade, counts) in &self.by_age {
+				writeln!(
+					f, 
+					"Individuals aged {}-{} that have a match: {}/{} ({:.2}%)", 
+					decade, 
+					decade + 9, 
+					counts.matched, 
+					counts.population, 
+					counts.match_rate().unwrap_or_default() * 100.0
+				)?;
+			}
+		}
+		if self.by_generation.len() > 1 {
+			for (generation, stats) in &self.by_generation {
+				let ratings: Vec<String> = stats.mean_ratings.iter().map(|rating| format!("{:.2}", rating)).collect();
+				writeln!(
+					f, 
+					"Generation {}: {} individuals, {} matched, mean ratings {}", 
+					generation, 
+					stats.population, 
+					stats.matched, 
+					ratings.join(", ")
+				)?;
+			}
+		}
+		if !self.by_gender.is_empty() {
+			writeln!(f, "{:<12}{:>12}{:>10}{:>12}{:>12}{:>12}{:>12}", "Gender", "Population", "Matched", "Match rate", "Mean score", "Rejected", "Rejecting")?;
+			for (gender, stats) in &self.by_gender {
+				writeln!(
+					f, 
+					"{:<12}{:>12}{:>10}{:>12}{:>12}{:>12}{:>12}", 
+					gender.to_string(), 
+					stats.population, 
+					stats.matched, 
+					format_rate(stats.match_rate), 
+					format_mean(stats.mean_matched_score, 3), 
+					format_mean(stats.mean_rejections.received, 2), 
+					format_mean(stats.mean_rejections.given, 2)
+				)?;
+			}
+		}
+		if self.by_decile.iter().any(|decile| decile.population > 0) {
+			writeln!(f, "{:<8}{:>20}{:>12}{:>10}{:>12}", "Decile", "Desirability", "Population", "Matched", "Match rate")?;
+			for (position, decile) in self.by_decile.iter().enumerate() {
+				let range = match (decile.min_desirability, decile.max_desirability) {
+					(Some(min), Some(max)) => format!("{:.3}-{:.3}", min, max),
+					_ => String::from("-")
+				};
+				writeln!(
+					f, 
+					"{:<8}{:>20}{:>12}{:>10}{:>12}", 
+					position + 1, 
+					range, 
+					decile.population, 
+					decile.matched, 
+					format_rate(decile.match_rate)
+				)?;
+			}
+		}
+		if let Some(correlation) = self.partner_desirability_correlation {
+			writeln!(f, "Desirability correlation between partners: {:.3}", correlation)?;
+		}
+		if self.retired_pairs > 0 {
+			writeln!(f, "Pairs that matched and left the pool: {} ({} individuals)", self.retired_pairs, self.retired_pairs * 2)?;
+		}
+		if self.breakups > 0 {
+			writeln!(f, "Pairs that broke up: {}", self.breakups)?;
+		}
+		if self.divorces > 0 {
+			writeln!(f, "Pairs that divorced: {}", self.divorces)?;
+		}
+		if let Some(rate) = self.divorced_rematch_rate {
+			writeln!(f, "Divorced individuals matched again: {:.1}%", rate * 100.0)?;
+		}
+		if self.provisional_candidates > 0 {
+			writeln!(f, "Provisional candidates held besides the best one: {}", self.provisional_candidates)?;
+		}
+		if self.unmatched_by_threshold > 0 {
+			let about = if self.unmatched_by_threshold_estimated { "about " } else { "" };
+			writeln!(f, "Individuals unmatched because of their minimum acceptable score: {}{}", about, self.unmatched_by_threshold)?;
+		}
+		if let (Some(mean), Some(median)) = (self.mean_candidate_score, self.median_candidate_score) {
+			writeln!(f, "Candidate score of matched individuals: mean {:.3}, median {:.3}", mean, median)?;
+			writeln!(f, "Total welfare, the scores partners give each other: {:.3}", self.total_welfare)?;
+		}
+		if self.matched_males + self.matched_females + self.matched_non_binary > 0 && !self.attribute_score_shares.is_empty() {
+			let shares: Vec<String> = self.attribute_score_shares
+				.iter()
+				.map(|(name, share)| format!("{} {:.1}%", name, share * 100.0))
+				.collect();
+			writeln!(f, "Share of the partner score by attribute: {}", shares.join(", "))?;
+		}
+		if let Some(mean_selectivity) = self.mean_selectivity.filter(|&selectivity| selectivity != 1.0) {
+			writeln!(f, "Mean selectivity: {:.3}", mean_selectivity)?;
+			if let Some(correlation) = self.selectivity_score_correlation {
+				writeln!(f, "Correlation between selectivity and the partner score: {:.3}", correlation)?;
+			}
+		}
+		if let Some(gini) = self.attention_gini {
+			writeln!(f, "Inequality of attention, the gini coefficient of the times everyone was evaluated: {:.3}", gini)?;
+		}
+		writeln!(f, "Gini coefficient of desirability: {:.3}", self.desirability_gini)?;
+		writeln!(f, "Gini coefficient of the proposals received: {:.3}", self.proposals_gini)?;
+		writeln!(f, "Gini coefficient of the partner score, 0 for the unmatched: {:.3}", self.matched_score_gini)?;
+		if let Some(blocking_pairs) = self.blocking_pairs {
+			if self.blocking_pairs_estimated {
+				writeln!(f, "Pairs who would rather be with each other than their partners: about {:.0}", blocking_pairs)?;
+			} else {
+				writeln!(f, "Pairs who would rather be with each other than their partners: {}", blocking_pairs)?;
+			}
+		}
+		if let (Some(mean), Some(median)) = (self.mean_time_to_match, self.median_time_to_match) {
+			writeln!(f, "Round in which the matches formed: mean {:.2}, median {:.1}", mean, median)?;
+			let histogram: Vec<String> = self.time_to_match_histogram
+				.iter()
+				.map(|(round, count)| format!("{}: {}", round, count))
+				.collect();
+			writeln!(f, "Matched individuals by round: {}", histogram.join(", "))?;
+		}
+		for (group, rejections) in [("matched", self.rejections_of_matched), ("unmatched", self.rejections_of_unmatched)] {
+			if let (Some(received), Some(given)) = (rejections.received, rejections.given) {
+				writeln!(f, "Rejections of {} individuals: mean {:.2} received, {:.2} given", group, received, given)?;
+			}
+		}
+		if let Some(best) = self.mean_unmatched_best_rejected_score {
+			writeln!(f, "Best score a rejection gave the unmatched in the last round: mean {:.3}", best)?;
+			if let Some(gap) = self.unmatched_score_gap {
+				writeln!(f, "Gap between the mean candidate score and the best rejected one of the unmatched: {:.3}", gap)?;
+			}
+		}
+
+		writeln!(f, "Descriptions:")?;
+		match self.gender_imbalance.cmp(&0) {
+			std::cmp::Ordering::Greater => 
+				writeln!(f, "In this simulation, male population EXCEEDED that of female by {}", self.gender_imbalance)?,
+			std::cmp::Ordering::Less => 
+				writeln!(f, "In this simulation, male population FELL SHORT OF that of female by {}", -self.gender_imbalance)?,
+			std::cmp::Ordering::Equal => writeln!(f, "In this simulation, male population EQUALED that of female")?
+		}
+
+		// an empty pool has no share that was never matched
+		let unmatched = (self.total_population > 0).then_some(self.unmatched_percentage / 100.0);
+		write!(f, "{} of individuals were never matched.", format_rate(unmatched))?;
+
+		return Ok(());
+	}
+}
+
+/// two-sided 97.5% quantiles of student's t distribution by degrees of freedom,
+/// from 31 on the normal quantile is close enough
+const T_QUANTILES: [f64; 30] = [
+	12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228,
+	2.201, 2.179, 2.160, 2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086,
+	2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042
+];
+
+/// a quantity measured once per run: its mean over the runs, the sample standard deviation
+/// and the 95% confidence interval of the mean
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Estimate {
+	pub mean: f64,
+	pub std_dev: f64,
+	pub confidence_low: f64,
+	pub confidence_high: f64
+}
+
+impl Estimate {
+	/// `None` without values, a single value has no spread and an interval of just itself
+	pub fn new(values: &[f64]) -> Option<Self> {
+		if values.is_empty() {
+			return None;
+		}
+
+		let count = values.len() as f64;
+		let mean = values.iter().sum::<f64>() / count;
+		if values.len() == 1 {
+			return Some(Estimate { mean: mean, std_dev: 0.0, confidence_low: mean, confidence_high: mean });
+		}
+
+		let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / (count - 1.0);
+		let std_dev = variance.sqrt();
+		let quantile = T_QUANTILES.get(values.len() - 2).copied().unwrap_or(1.96);
+		let margin = quantile * std_dev / count.sqrt();
+
+		return Some(Estimate {
+			mean: mean,
+			std_dev: std_dev,
+			confidence_low: mean - margin,
+			confidence_high: mean + margin
+		});
+	}
+}
+
+impl std::fmt::Display for Estimate {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return write!(
+			f, 
+			"{:.4} ± {:.4} (95% CI {:.4} to {:.4})", 
+			self.mean, 
+			self.std_dev, 
+			self.confidence_low, 
+			self.confidence_high
+		);
+	}
+}
+
+/// the final statistics of independent runs summarized, see `MonteCarlo`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AggregateStatistics {
+	pub runs: usize,
+	// the runs left out because nobody in their pool could be paired, see `StopReason::Unpairable`
+	#[serde(default)]
+	pub unpairable_runs: usize,
+	// share of the population that is matched, over the runs with a population
+	pub match_rate: Option<Estimate>,
+	// the `Statistics::mean_candidate_score` of the runs in which anybody matched
+	pub mean_matched_score: Option<Estimate>
+}
+
+impl AggregateStatistics {
+	/// the summary of `statistics`, `unpairable_runs` stays 0 for the caller to fill in
+	pub fn new(statistics: &[Statistics]) -> Self {
+		let match_rates: Vec<f64> = statistics
+			.iter()
+			.filter_map(|statistics| statistics.match_rate())
+			.collect();
+		let mean_matched_scores: Vec<f64> = statistics
+			.iter()
+			.filter_map(|statistics| statistics.mean_candidate_score)
+			.map(f64::from)
+			.collect();
+
+		return AggregateStatistics {
+			runs: statistics.len(),
+			unpairable_runs: 0,
+			match_rate: Estimate::new(&match_rates),
+			mean_matched_score: Estimate::new(&mean_matched_scores)
+		};
+	}
+}
+
+impl std::fmt::Display for AggregateStatistics {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "Aggregate statistics of {} runs:", self.runs)?;
+		if self.unpairable_runs > 0 {
+			writeln!(f, "Left out {} runs in which nobody could be paired", self.unpairable_runs)?;
+		}
+		if let Some(match_rate) = self.match_rate {
+			writeln!(f, "Match rate: {}", match_rate)?;
+		}
+		if let Some(mean_matched_score) = self.mean_matched_score {
+			writeln!(f, "Mean matched score: {}", mean_matched_score)?;
+		}
+
+		return Ok(());
+	}
+}
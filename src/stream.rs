@@ -0,0 +1,448 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use rand::rngs::OsRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::config::SampleConfig;
+use crate::error::SimulationError;
+use crate::individual::{Gender, Individual};
+use crate::matching::{self, MatchingAlgorithm, MatchingConfig, ProposerSide, ScanOrder};
+use crate::person_id::PersonId;
+use crate::progress::Progress;
+use crate::rng::Xoshiro256PlusPlus;
+use crate::sample::Sample;
+use crate::score_cache::Scorer;
+use crate::scoring::{RatingValues, ScoreNoise};
+use crate::statistics::{self, RoundStats};
+
+/// what `Sample::generate_to_disk` writes next to the chunks, everything of the sample but its population
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Manifest {
+	seed: u64,
+	sample_config: SampleConfig,
+	// what the last `Sample::stream_match_making` paired with
+	matching_config: MatchingConfig,
+	round: u32,
+	chunk_size: usize,
+	chunks: usize
+}
+
+impl Manifest {
+	fn path(directory: &Path) -> PathBuf {
+		return directory.join("manifest.json");
+	}
+
+	fn read(directory: &Path) -> Result<Self, SimulationError> {
+		let file = std::fs::File::open(Manifest::path(directory))?;
+
+		return Ok(serde_json::from_reader(std::io::BufReader::new(file))?);
+	}
+
+	fn write(&self, directory: &Path) -> Result<(), SimulationError> {
+		return replace(&Manifest::path(directory), |writer| Ok(serde_json::to_writer_pretty(writer, self)?));
+	}
+
+	fn chunk_path(directory: &Path, chunk: usize) -> PathBuf {
+		return directory.join(format!("chunk-{:05}.ndjson", chunk));
+	}
+}
+
+/// write `path` through `write`, next to it first and moved over it once complete
+fn replace(
+	path: &Path,
+	write: impl FnOnce(&mut std::io::BufWriter<std::fs::File>) -> Result<(), SimulationError>
+) -> Result<(), SimulationError> {
+	let mut partial = path.as_os_str().to_owned();
+	partial.push(".partial");
+	let partial = PathBuf::from(partial);
+
+	let mut writer = std::io::BufWriter::new(std::fs::File::create(&partial)?);
+	write(&mut writer)?;
+	writer.flush()?;
+	std::fs::rename(&partial, path)?;
+
+	return Ok(());
+}
+
+/// the individuals of a chunk, one json object per line
+fn read_chunk(path: &Path) -> Result<Vec<Individual>, SimulationError> {
+	let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+
+	let mut individuals = Vec::new();
+	for line in reader.lines() {
+		individuals.push(serde_json::from_str(&line?)?);
+	}
+
+	return Ok(individuals);
+}
+
+fn write_chunk<'a>(path: &Path, individuals: impl Iterator<Item = &'a Individual>) -> Result<(), SimulationError> {
+	return replace(path, |writer| {
+		for individual in individuals {
+			serde_json::to_writer(&mut *writer, individual)?;
+			writeln!(writer)?;
+		}
+
+		return Ok(());
+	});
+}
+
+/// the gender streamed from disk under `config`, an error for what the streamed greedy scan cannot run
+fn streamed_gender(config: &MatchingConfig) -> Result<Gender, SimulationError> {
+	config.validate()?;
+
+	if config.algorithm != MatchingAlgorithm::Greedy || config.candidate_capacity != 1 {
+		return Err(
+			SimulationError::InvalidConfig(format!(
+				"streamed match-making runs the greedy scan with a candidate capacity of 1, not {} with {}",
+				config.algorithm,
+				config.candidate_capacity
+			))
+		);
+	}
+	if config.shuffle_proposers || config.shuffle_reviewers || config.max_evaluations_per_round.is_some() {
+		return Err(
+			SimulationError::InvalidConfig(String::from("streamed match-making scans in population order, without shuffling or an evaluation budget"))
+		);
+	}
+	if config.score_noise != ScoreNoise::None || config.rating_values != RatingValues::Raw {
+		return Err(
+			SimulationError::InvalidConfig(String::from("streamed match-making scores the raw ratings without noise"))
+		);
+	}
+
+	return match config.proposer_side {
+		ProposerSide::Male => Ok(Gender::Male),
+		ProposerSide::Female => Ok(Gender::Female),
+		ProposerSide::Alternate => Err(
+			SimulationError::InvalidConfig(String::from("streamed match-making needs the same side to propose every round"))
+		)
+	};
+}
+
+/// release a streamed individual whose partner moved on while it was on disk,
+/// what `matching::release` does to those in memory
+fn settle(individual: &mut Individual, resident: &[Individual], index: &HashMap<PersonId, usize>) {
+	let Some(candidate) = individual.candidate else {
+		return;
+	};
+
+	let held = index
+		.get(&candidate)
+		.is_some_and(|&position| resident[position].candidate == Some(individual.identity));
+	if !held && individual.drop_candidate(candidate) && individual.candidate.is_none() {
+		individual.matched_in_round = None;
+	}
+}
+
+/// a pair by its two identities in order, both sides of the pair name it the same
+fn pair_key(first: PersonId, second: PersonId) -> (PersonId, PersonId) {
+	return (first.min(second), first.max(second));
+}
+
+/// the pairs of everyone held in memory. a streamed individual only ever pairs with one of them,
+/// and someone in memory only holds a streamed individual that holds them back
+fn resident_pairs(resident: &[Individual], index: &HashMap<PersonId, usize>) -> HashSet<(PersonId, PersonId)> {
+	return resident
+		.iter()
+		.filter_map(|individual| {
+			let candidate = individual.candidate?;
+			let mutual = index
+				.get(&candidate)
+				.is_none_or(|&position| resident[position].candidate == Some(individual.identity));
+
+			return mutual.then(|| pair_key(individual.identity, candidate));
+		})
+		.collect();
+}
+
+fn blacklist_size<'a>(individuals: impl Iterator<Item = &'a Individual>) -> usize {
+	return individuals.map(|individual| individual.extras().blacklist.len()).sum();
+}
+
+/// the reviewing side of a population on disk, held in memory while the proposing side streams past
+struct Resident {
+	individuals: Vec<Individual>,
+	// the position of each of them in the whole population, ascending
+	positions: Vec<usize>,
+	index: HashMap<PersonId, usize>
+}
+
+impl Resident {
+	/// the individuals of `range` of the whole population, with their positions among the resident
+	fn within(&self, range: std::ops::Range<usize>) -> std::ops::Range<usize> {
+		return self.positions.partition_point(|&position| position < range.start)..
+			self.positions.partition_point(|&position| position < range.end);
+	}
+}
+
+impl Sample {
+	/// the individuals `Sample::generate_to_disk` writes into one chunk unless told otherwise
+	pub const DEFAULT_CHUNK_SIZE: usize = 100_000;
+
+	/// draw the population of `config` into `directory` instead of memory, `chunk_size` individuals
+	/// at a time as one json object per line, for `Sample::stream_match_making`. the individuals are
+	/// the ones `Sample::generate` draws from the same config
+	pub fn generate_to_disk(config: &SampleConfig, directory: &Path, chunk_size: usize) -> Result<(), SimulationError> {
+		config.validate()?;
+		config.attribute_schema.validate()?;
+		if chunk_size == 0 {
+			return Err(SimulationError::InvalidConfig(String::from("a chunk needs to hold at least 1 individual")));
+		}
+		if !directory.is_dir() {
+			return Err(SimulationError::MissingDirectory(directory.to_path_buf()));
+		}
+
+		let seed = config.seed.unwrap_or_else(|| OsRng.r#gen());
+		let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+		let generation = config.generation();
+		let progress = Progress::new(config.population_size as u64, config.show_progress);
+
+		let genders = config.gender_counts.map(|counts| counts.shuffled(&mut rng));
+
+		let mut chunks = 0;
+		let mut chunk: Vec<Individual> = Vec::with_capacity(chunk_size.min(config.population_size));
+		for drawn in 1..=config.population_size {
+			let gender = genders.as_ref().map(|genders| genders[drawn - 1]);
+			chunk.push(generation.draw(&config.attribute_schema, &config.age, gender, &mut rng)?);
+			progress.inc(1);
+
+			if chunk.len() == chunk_size || drawn == config.population_size {
+				write_chunk(&Manifest::chunk_path(directory, chunks), chunk.iter())?;
+				chunk.clear();
+				chunks += 1;
+			}
+		}
+
+		progress.finish(|elapsed| format!("generated in {:.1}s", elapsed));
+		tracing::info!(population = config.population_size, chunks = chunks, seed = seed, path = %directory.display(), "generated the population to disk");
+
+		return Manifest {
+			seed: seed,
+			sample_config: SampleConfig { seed: Some(seed), ..config.clone() },
+			matching_config: MatchingConfig::default(),
+			round: 0,
+			chunk_size: chunk_size,
+			chunks: chunks
+		}.write(directory);
+	}
+
+	/// run `rounds` rounds of the greedy scan on the population `Sample::generate_to_disk` wrote into
+	/// `directory`, and write it back there. everyone but the proposing gender is held in memory,
+	/// the proposers are read, scanned and written back a chunk at a time. the rounds pair as
+	/// `Sample::match_making` of the same population does, on the conditions that the proposing side is
+	/// heterosexual and that `config` scans in population order, see `MatchingConfig`. churn, breakups,
+	/// selectivity and drift are left out, the population only ages
+	pub fn stream_match_making(directory: &Path, config: &MatchingConfig, rounds: u32) -> Result<Vec<RoundStats>, SimulationError> {
+		let proposing = streamed_gender(config)?;
+		let mut manifest = Manifest::read(directory)?;
+		let population_size = manifest.sample_config.population_size;
+
+		let mut resident = Resident { individuals: Vec::new(), positions: Vec::new(), index: HashMap::new() };
+		for chunk in 0..manifest.chunks {
+			for (offset, individual) in read_chunk(&Manifest::chunk_path(directory, chunk))?.into_iter().enumerate() {
+				if individual.gender == proposing {
+					continue;
+				}
+
+				resident.index.insert(individual.identity, resident.individuals.len());
+				resident.positions.push(chunk * manifest.chunk_size + offset);
+				resident.individuals.push(individual);
+			}
+		}
+		tracing::info!(resident = resident.individuals.len(), streamed = population_size - resident.individuals.len(), "loaded the reviewing side");
+
+		// the same strategy as a sample in memory, without a score cache or noise
+		let mut shell = Sample::from_individuals(Vec::new());
+		shell.attribute_schema = manifest.sample_config.attribute_schema.clone();
+		shell.matching_config = config.clone();
+		let strategy = shell.scoring_strategy();
+		let scorer = Scorer { strategy: strategy.as_ref(), cache: None, noise: ScoreNoise::None, noise_seed: 0 };
+		let step = manifest.sample_config.age.step;
+
+		let mut all_stats = Vec::new();
+		for _ in 0..rounds {
+			let round = manifest.round + 1;
+			let progress = Progress::counting(manifest.chunks as u64, "chunks", manifest.sample_config.show_progress);
+
+			for extras in resident.individuals.iter_mut().filter_map(Individual::extras_if_any) {
+				extras.best_rejected_score = None;
+			}
+			let pairs_before = resident_pairs(&resident.individuals, &resident.index);
+			let mut blacklist_growth = 0;
+			let mut dealbreaker_rejections = 0;
+			// by position in the whole population, so that the means add up in its order
+			let mut selectivities: Vec<f32> = vec![0.0; population_size];
+			// the streamed individuals holding someone in memory, by who they hold
+			let mut streamed_holding: HashMap<PersonId, (PersonId, usize, Option<f32>)> = HashMap::new();
+			let mut streamed_count = 0;
+
+			let blacklist_before = blacklist_size(resident.individuals.iter());
+			for chunk in 0..manifest.chunks {
+				let path = Manifest::chunk_path(directory, chunk);
+				let start = chunk * manifest.chunk_size;
+				let mut streamed: Vec<(usize, Individual)> = Vec::new();
+				for (offset, mut individual) in read_chunk(&path)?.into_iter().enumerate() {
+					if individual.gender != proposing {
+						continue;
+					}
+					if individual.orientation.attracted_to(proposing, proposing) {
+						return Err(
+							SimulationError::InvalidConfig(format!(
+								"streamed match-making needs the proposing side to be heterosexual, {} is {}",
+								individual.identity,
+								individual.orientation
+							))
+						);
+					}
+
+					settle(&mut individual, &resident.individuals, &resident.index);
+					if let Some(extras) = individual.extras_if_any() {
+						extras.best_rejected_score = None;
+					}
+					streamed.push((start + offset, individual));
+				}
+				let blacklist_of_chunk = blacklist_size(streamed.iter().map(|(_, individual)| individual));
+
+				// the proposers of the chunk in population order, the streamed ones after the resident
+				let within = resident.within(start..start + manifest.chunk_size);
+				let resident_count = resident.individuals.len();
+				let mut proposers: Vec<(usize, usize)> = within
+					.clone()
+					.map(|position| (resident.positions[position], position))
+					.chain(streamed.iter().enumerate().map(|(offset, (position, _))| (*position, resident_count + offset)))
+					.collect();
+				proposers.sort_unstable();
+
+				let positions: Vec<usize> = streamed.iter().map(|(position, _)| *position).collect();
+				for (offset, (_, individual)) in streamed.into_iter().enumerate() {
+					resident.index.insert(individual.identity, resident_count + offset);
+					resident.individuals.push(individual);
+				}
+				let mut order = ScanOrder::fixed(proposers.into_iter().map(|(_, position)| position).collect(), resident.individuals.len());
+				dealbreaker_rejections += matching::greedy(
+					&mut resident.individuals,
+					&resident.index,
+					config,
+					scorer,
+					round,
+					&mut order,
+					&Progress::new(0, false)
+				)?;
+				let mut streamed = resident.individuals.split_off(resident_count);
+
+				for (individual, &position) in streamed.iter_mut().zip(&positions) {
+					resident.index.remove(&individual.identity);
+					if let Some(candidate) = individual.candidate {
+						streamed_holding.insert(candidate, (individual.identity, position, individual.candidate_score));
+					}
+					selectivities[position] = individual.selectivity;
+					individual.age += step;
+				}
+				blacklist_growth += blacklist_size(streamed.iter()) - blacklist_of_chunk;
+				streamed_count += streamed.len();
+
+				// the resident individuals of the chunk as they are now, they are written again at the end
+				let mut chunk_individuals: Vec<(usize, &Individual)> = within
+					.map(|position| (resident.positions[position], &resident.individuals[position]))
+					.chain(positions.iter().copied().zip(&streamed))
+					.collect();
+				chunk_individuals.sort_unstable_by_key(|(position, _)| *position);
+				write_chunk(&path, chunk_individuals.into_iter().map(|(_, individual)| individual))?;
+				progress.inc(1);
+			}
+			blacklist_growth += blacklist_size(resident.individuals.iter()) - blacklist_before;
+
+			for individual in &mut resident.individuals {
+				individual.age += step;
+			}
+			manifest.round = round;
+			progress.finish(|elapsed| format!("round {} streamed in {:.1}s", round, elapsed));
+
+			// the scores of the matched by position in the whole population, as `RoundStats::new` takes them
+			let mut scores: Vec<Option<f32>> = vec![None; population_size];
+			let mut matched = Vec::with_capacity(resident.individuals.len());
+			let mut streamed_matched = 0;
+			for (individual, &position) in resident.individuals.iter().zip(&resident.positions) {
+				selectivities[position] = individual.selectivity;
+
+				let streamed_partner = streamed_holding
+					.get(&individual.identity)
+					.filter(|(holder, _, _)| Some(*holder) == individual.candidate);
+				let is_matched = match individual.candidate.and_then(|candidate| resident.index.get(&candidate)) {
+					Some(&other) => resident.individuals[other].candidate == Some(individual.identity),
+					None => streamed_partner.is_some()
+				};
+				if is_matched {
+					scores[position] = individual.candidate_score;
+				}
+				if let Some(&(_, streamed_position, score)) = streamed_partner {
+					scores[streamed_position] = score;
+					streamed_matched += 1;
+				}
+				matched.push(is_matched);
+			}
+
+			let mut stats = RoundStats::new(round, &resident.individuals, &matched, blacklist_growth);
+			let (matched_streamed, unmatched_streamed) = match proposing {
+				Gender::Female => (&mut stats.matched_females, &mut stats.unmatched_females),
+				_ => (&mut stats.matched_males, &mut stats.unmatched_males)
+			};
+			*matched_streamed += streamed_matched;
+			*unmatched_streamed += streamed_count - streamed_matched;
+			let scores: Vec<f32> = scores.into_iter().flatten().filter(|score| !score.is_nan()).collect();
+			stats.mean_candidate_score = statistics::mean(&scores);
+			stats.mean_selectivity = statistics::mean(&selectivities);
+			stats.population = population_size;
+			stats.dealbreaker_rejections = dealbreaker_rejections;
+			stats.delta = Sample::round_delta(
+				&pairs_before,
+				&resident_pairs(&resident.individuals, &resident.index),
+				blacklist_growth + dealbreaker_rejections
+			);
+			tracing::info!(round = round, matched = stats.matched_males + stats.matched_females + stats.matched_non_binary, "streamed a round");
+			all_stats.push(stats);
+		}
+
+		// the chunks get the resident individuals as the last round left them
+		// and let go of partners that moved on after they were written
+		if rounds > 0 {
+			for chunk in 0..manifest.chunks {
+				let path = Manifest::chunk_path(directory, chunk);
+				let mut individuals = read_chunk(&path)?;
+				for individual in &mut individuals {
+					if individual.gender == proposing {
+						settle(individual, &resident.individuals, &resident.index);
+					} else {
+						*individual = resident.individuals[resident.index[&individual.identity]].clone();
+					}
+				}
+				write_chunk(&path, individuals.iter())?;
+			}
+		}
+		manifest.matching_config = config.clone();
+		manifest.write(directory)?;
+
+		return Ok(all_stats);
+	}
+
+	/// read the population `Sample::generate_to_disk` wrote into `directory` back into memory,
+	/// after the rounds `Sample::stream_match_making` ran on it
+	pub fn from_disk(directory: &Path) -> Result<Self, SimulationError> {
+		let manifest = Manifest::read(directory)?;
+
+		let mut population = Vec::with_capacity(manifest.sample_config.population_size);
+		for chunk in 0..manifest.chunks {
+			population.extend(read_chunk(&Manifest::chunk_path(directory, chunk))?);
+		}
+
+		let mut sample = Sample::generated(population, &manifest.sample_config, manifest.seed);
+		sample.matching_config = manifest.matching_config;
+		sample.round = manifest.round;
+
+		return Ok(sample);
+	}
+}
@@ -0,0 +1,169 @@
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use rayon::prelude::*;
+
+use crate::config::SimulationConfig;
+use crate::error::SimulationError;
+use crate::progress::Progress;
+use crate::statistics::{RoundStats, RunReport, Statistics, StopReason};
+
+/// one combination of a sweep with one of its seeds
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepPoint {
+	pub population: usize,
+	pub complexity: usize,
+	pub seed: u64,
+	// the base config of the sweep with the three above filled in
+	pub config: SimulationConfig
+}
+
+/// the outcome of the full simulation of one `SweepPoint`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepResult {
+	pub point: SweepPoint,
+	pub report: RunReport,
+	// the statistics after the last round
+	pub statistics: Statistics
+}
+
+impl SweepResult {
+	/// the columns of `SweepResult::export_csv`, the point followed by `RoundStats::CSV_HEADER`
+	pub fn csv_header() -> String {
+		return format!("population,complexity,seed,{}", RoundStats::CSV_HEADER);
+	}
+
+	/// append one csv row per round of the run, write `SweepResult::csv_header` first
+	pub fn export_csv(
+		&self,
+		mut writer: impl std::io::Write
+	) -> Result<(), SimulationError> {
+		for round_stats in &self.report.round_stats {
+			write!(writer, "{},{},{},", self.point.population, self.point.complexity, self.point.seed)?;
+			round_stats.export_csv(&mut writer)?;
+		}
+
+		return Ok(());
+	}
+}
+
+/// the full simulation of `config` for every combination of `populations` and `complexities`,
+/// each with `seeds_per_point` seeds. the seeds are drawn from `config.seed` and shared by all
+/// combinations, so two combinations differ by their parameters rather than by their luck
+#[derive(Debug, Clone)]
+pub struct SweepRunner {
+	pub config: SimulationConfig,
+	// population sizes to simulate, `config.population_size` when empty
+	pub populations: Vec<usize>,
+	// numbers of anonymous attributes to simulate, `config.preference_complexity` when empty
+	pub complexities: Vec<usize>,
+	pub seeds_per_point: usize,
+	// whether to draw one progress bar over the rounds of all runs, the runs themselves draw none
+	pub show_progress: bool
+}
+
+impl SweepRunner {
+	pub fn new(config: SimulationConfig, populations: Vec<usize>, complexities: Vec<usize>) -> Self {
+		return SweepRunner {
+			config: config,
+			populations: populations,
+			complexities: complexities,
+			seeds_per_point: 1,
+			show_progress: false
+		};
+	}
+
+	/// every combination and seed, by population, then complexity, then seed
+	pub fn points(&self) -> Result<Vec<SweepPoint>, SimulationError> {
+		if self.seeds_per_point < 1 {
+			return Err(SimulationError::InvalidConfig(String::from("a sweep needs at least one seed per point")));
+		}
+		if !self.complexities.is_empty() && !self.config.attributes.is_empty() {
+			return Err(
+				SimulationError::InvalidConfig(String::from("the complexity can only be swept over anonymous attributes, the config lists named ones"))
+			);
+		}
+
+		let mut populations = self.populations.clone();
+		if populations.is_empty() {
+			populations.push(self.config.population_size);
+		}
+		let mut complexities = self.complexities.clone();
+		if complexities.is_empty() {
+			complexities.push(self.config.preference_complexity);
+		}
+
+		let master_seed = self.config.seed.unwrap_or_else(|| rand::rngs::OsRng.r#gen());
+		let mut rng = StdRng::seed_from_u64(master_seed);
+		let seeds: Vec<u64> = (0..self.seeds_per_point).map(|_| rng.r#gen()).collect();
+
+		let mut points = Vec::new();
+		for &population in &populations {
+			for &complexity in &complexities {
+				for &seed in &seeds {
+					let config = SimulationConfig {
+						population_size: population,
+						preference_complexity: complexity,
+						seed: Some(seed),
+						..self.config.clone()
+					};
+					config.validate()?;
+
+					points.push(SweepPoint {
+						population: population,
+						complexity: complexity,
+						seed: seed,
+						config: config
+					});
+				}
+			}
+		}
+
+		return Ok(points);
+	}
+
+	/// simulate every point in parallel, the results come in the order of `SweepRunner::points`
+	pub fn run(&self) -> Result<Vec<SweepResult>, SimulationError> {
+		let points = self.points()?;
+
+		// a round scores up to every pair of individuals, each score takes one product per attribute
+		let products: f64 = points
+			.iter()
+			.map(|point| (point.population as f64).powi(2) * point.complexity as f64 * point.config.rounds as f64)
+			.sum();
+		tracing::info!(simulations = points.len(), "sweeping, an estimated {:.1e} weight-rating products at most", products);
+
+		let progress = Progress::counting(
+			points.iter().map(|point| point.config.rounds as u64).sum(),
+			"rounds",
+			self.show_progress
+		);
+		let results = points
+			.into_par_iter()
+			.map(|point| -> Result<SweepResult, SimulationError> {
+				let mut sample = point.config.generate_sample()?;
+				let report = match sample.run_with(point.config.rounds, |_, _| progress.inc(1)) {
+					Ok(report) => report,
+					// one point that cannot be paired does not fail the others, it ends without its rounds
+					Err(error @ SimulationError::EmptyPopulation { .. }) => {
+						tracing::warn!(population = point.population, complexity = point.complexity, seed = point.seed, "{}, the point has no rounds", error);
+
+						RunReport { rounds_executed: sample.round, stop_reason: StopReason::Unpairable, round_stats: Vec::new() }
+					},
+					Err(error) => return Err(error)
+				};
+				// a converged run leaves rounds it never needed
+				progress.inc((point.config.rounds - report.rounds_executed) as u64);
+				tracing::debug!(population = point.population, complexity = point.complexity, seed = point.seed, "finished a sweep point");
+
+				return Ok(SweepResult {
+					statistics: sample.statistics(),
+					point: point,
+					report: report
+				});
+			})
+			.collect::<Result<Vec<SweepResult>, SimulationError>>()?;
+		progress.finish(|elapsed| format!("{} simulations in {:.1}s", results.len(), elapsed));
+
+		return Ok(results);
+	}
+}
@@ -0,0 +1,64 @@
+use proptest::collection::{btree_set, vec, SizeRange};
+use proptest::prelude::*;
+
+use crate::individual::{Gender, Individual, Orientation};
+use crate::names;
+use crate::sample::Sample;
+
+pub fn gender() -> impl Strategy<Value = Gender> {
+	return proptest::sample::select(Gender::ALL.to_vec());
+}
+
+pub fn orientation() -> impl Strategy<Value = Orientation> {
+	return proptest::sample::select(Orientation::ALL.to_vec());
+}
+
+/// weights between 0 and 1, one per attribute
+pub fn preference_weights(attributes: usize) -> impl Strategy<Value = Vec<f32>> {
+	return vec(0.0f32..=1.0, attributes);
+}
+
+/// ratings between `Individual::MIN_RATING` and `Individual::MAX_RATING`, one per attribute
+pub fn ratings(attributes: usize) -> impl Strategy<Value = Vec<f32>> {
+	return vec(Individual::MIN_RATING..=Individual::MAX_RATING, attributes);
+}
+
+/// an individual without matching state, named like `Individual::new` names them
+fn individual_of(identity: [u8; 16], (gender, orientation, preference_weights, ratings): (Gender, Orientation, Vec<f32>, Vec<f32>)) -> Individual {
+	let uuid = uuid::Builder::from_random_bytes(identity).into_uuid();
+
+	return Individual::builder()
+		.identity(&uuid.to_string())
+		.display_name(&names::name_for(uuid.as_bytes()))
+		.gender(gender)
+		.orientation(orientation)
+		.preference_weights(preference_weights)
+		.ratings(ratings)
+		.build()
+		.expect("the weights and ratings are drawn for the same attributes");
+}
+
+fn traits(attributes: usize) -> impl Strategy<Value = (Gender, Orientation, Vec<f32>, Vec<f32>)> {
+	return (gender(), orientation(), preference_weights(attributes), ratings(attributes));
+}
+
+/// an individual with a random uuid who weights and rates `attributes` attributes
+pub fn individual(attributes: usize) -> impl Strategy<Value = Individual> {
+	return (any::<[u8; 16]>(), traits(attributes)).prop_map(|(identity, traits)| individual_of(identity, traits));
+}
+
+/// `size` individuals of distinct uuids who all weight and rate `attributes` attributes
+pub fn population(size: impl Into<SizeRange>, attributes: usize) -> impl Strategy<Value = Vec<Individual>> {
+	return btree_set(any::<[u8; 16]>(), size)
+		.prop_flat_map(move |identities| {
+			let count = identities.len();
+
+			return (Just(identities), vec(traits(attributes), count));
+		})
+		.prop_map(|(identities, traits)| identities.into_iter().zip(traits).map(|(identity, traits)| individual_of(identity, traits)).collect());
+}
+
+/// the `Sample::from_individuals` of a `population`, nobody matched yet
+pub fn sample(size: impl Into<SizeRange>, attributes: usize) -> impl Strategy<Value = Sample> {
+	return population(size, attributes).prop_map(Sample::from_individuals);
+}
@@ -0,0 +1,50 @@
+use crate::person_id::PersonId;
+
+/// an inconsistency found by `Sample::validate`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+	// the candidate is not part of the sample
+	UnknownCandidate { identity: PersonId, candidate: PersonId },
+	// the candidate does not name the individual back
+	NotMutual { identity: PersonId, candidate: PersonId },
+	// `candidate_score` is set without a candidate or the other way around
+	CandidateScoreMismatch { identity: PersonId },
+	// weights or ratings differ in length from the rest of the population
+	AttributeCountMismatch { identity: PersonId, expected: usize, weights: usize, ratings: usize },
+	// the individual lacks a category of the schema's categorical attributes, or has one outside of them
+	CategoryMismatch { identity: PersonId, expected: usize, categories: usize },
+	// the individual is their own candidate
+	SelfCandidate { identity: PersonId },
+	// a blacklist entry is not part of the sample
+	UnknownBlacklisted { identity: PersonId, blacklisted: PersonId }
+}
+
+impl std::fmt::Display for ValidationError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		return match self {
+			ValidationError::UnknownCandidate { identity, candidate } => write!(
+				f, "{} has candidate {} which is not part of the sample", identity, candidate
+			),
+			ValidationError::NotMutual { identity, candidate } => write!(
+				f, "{} has candidate {} who does not name them back", identity, candidate
+			),
+			ValidationError::CandidateScoreMismatch { identity } => write!(
+				f, "{} has a candidate score without a candidate or a candidate without a score", identity
+			),
+			ValidationError::AttributeCountMismatch { identity, expected, weights, ratings } => write!(
+				f, "{} has {} weights and {} ratings, expected {} of each", identity, weights, ratings, expected
+			),
+			ValidationError::CategoryMismatch { identity, expected, categories } => write!(
+				f, "{} has {} categories, expected one within each of the {} categorical attributes", identity, categories, expected
+			),
+			ValidationError::SelfCandidate { identity } => write!(
+				f, "{} is their own candidate", identity
+			),
+			ValidationError::UnknownBlacklisted { identity, blacklisted } => write!(
+				f, "{} has blacklisted {} which is not part of the sample", identity, blacklisted
+			)
+		};
+	}
+}
+
+impl std::error::Error for ValidationError {}
@@ -0,0 +1,64 @@
+use wasm_bindgen::prelude::*;
+
+use crate::config::SimulationConfig;
+use crate::error::SimulationError;
+use crate::sample::Sample;
+
+fn to_js_error(error: SimulationError) -> JsError {
+	return JsError::new(&error.to_string());
+}
+
+/// a generated population and its matching state, for javascript
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct WasmSample {
+	sample: Sample
+}
+
+#[wasm_bindgen]
+impl WasmSample {
+	/// draw the sample of a `SimulationConfig` given as json, anything left out keeps its default.
+	/// the seed of a config without one comes from `crypto.getRandomValues`
+	#[wasm_bindgen(constructor)]
+	pub fn new(config_json: &str) -> Result<WasmSample, JsError> {
+		return WasmSample::from_config(config_json).map_err(to_js_error);
+	}
+
+	/// run one round and give its `RoundStats` as json
+	pub fn step(&mut self) -> Result<String, JsError> {
+		return self.step_json().map_err(to_js_error);
+	}
+
+	/// the `Sample::pairs` as a json list of `[proposer_id, reviewer_id, reviewer_score, proposer_score]`
+	pub fn pairs_json(&self) -> Result<String, JsError> {
+		return self.pairs_to_json().map_err(to_js_error);
+	}
+}
+
+// the bindings without `JsError`, which only exists inside a javascript engine
+impl WasmSample {
+	pub fn from_config(config_json: &str) -> Result<WasmSample, SimulationError> {
+		let config: SimulationConfig = serde_json::from_str(config_json)?;
+		config.validate()?;
+
+		return Ok(WasmSample { sample: config.generate_sample()? });
+	}
+
+	pub fn step_json(&mut self) -> Result<String, SimulationError> {
+		return Ok(serde_json::to_string(&self.sample.match_making()?)?);
+	}
+
+	pub fn pairs_to_json(&self) -> Result<String, SimulationError> {
+		let pairs: Vec<(String, String, f32, f32)> = self.sample
+			.pairs()
+			.into_iter()
+			.map(|pair| (pair.proposer_id.to_string(), pair.reviewer_id.to_string(), pair.reviewer_score, pair.proposer_score))
+			.collect();
+
+		return Ok(serde_json::to_string(&pairs)?);
+	}
+
+	pub fn sample(&self) -> &Sample {
+		return &self.sample;
+	}
+}
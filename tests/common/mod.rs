@@ -0,0 +1,22 @@
+#![allow(dead_code, clippy::needless_return, clippy::redundant_field_names)]
+
+use dating_simulation::{Gender, Individual, PersonId};
+
+/// an individual with fixed weights and ratings and no matching state
+pub fn individual(identity: &str, gender: Gender, preference_weights: Vec<f32>, ratings: Vec<f32>) -> Individual {
+	return Individual::builder()
+		.identity(identity)
+		.gender(gender)
+		.preference_weights(preference_weights)
+		.ratings(ratings)
+		.build()
+		.unwrap();
+}
+
+/// point `individual` at `candidate`, holding `score`
+pub fn with_candidate(mut individual: Individual, candidate: &str, score: f32) -> Individual {
+	individual.candidate = Some(PersonId::new(candidate));
+	individual.candidate_score = Some(score);
+	
+	return individual;
+}
@@ -0,0 +1,471 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::{individual, with_candidate};
+use dating_simulation::{diff_runs, ChurnConfig, Gender, GraphFormat, GraphKind, Individual, MatchingAlgorithm, MatchingConfig, PersonId, RoundStats, RunExport, Sample, SampleConfig, SimulationError};
+
+#[test]
+fn json_export_contains_the_population() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(50).complexity(3).seed(11).build().unwrap()).unwrap();
+	sample.match_making().unwrap();
+	
+	let path = std::env::temp_dir().join("dating_simulation_json_export.json");
+	sample.to_json_file(&path).unwrap();
+	
+	let content = std::fs::read_to_string(&path).unwrap();
+	std::fs::remove_file(&path).unwrap();
+	let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+	
+	assert_eq!(json["seed"], 11);
+	assert_eq!(
+		json["population"].as_array().unwrap().len(), 
+		sample.population.len()
+	);
+	
+	let individual = &json["population"][0];
+	assert_eq!(
+		individual["candidate"].as_str().map(String::from), 
+		sample.population[0].candidate.map(|candidate| candidate.to_string())
+	);
+	assert!(individual.get("gender").is_some());
+	assert!(individual.get("blacklist").is_some());
+	assert!(individual.get("candidate_score").is_some());
+}
+
+#[test]
+fn exports_and_checkpoints_carry_the_run_metadata() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(30).complexity(2).seed(70).build().unwrap()).unwrap();
+	sample.matching_config.algorithm = MatchingAlgorithm::DeferredAcceptance;
+	sample.run(2).unwrap();
+	
+	let metadata = sample.metadata();
+	assert_eq!(metadata.crate_version, env!("CARGO_PKG_VERSION"));
+	assert_eq!((metadata.seed, metadata.algorithm, metadata.rounds_executed), (70, MatchingAlgorithm::DeferredAcceptance, 2));
+	assert!(metadata.timestamp > 0);
+	
+	let path = std::env::temp_dir().join("dating_simulation_metadata_export.json");
+	sample.to_json_file(&path).unwrap();
+	let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+	assert_eq!(json["metadata"]["seed"], 70);
+	let loaded = Sample::from_json_file(&path).unwrap();
+	std::fs::remove_file(&path).unwrap();
+	assert_eq!(loaded.loaded_metadata.unwrap().sample_config, sample.sample_config);
+	
+	let path = std::env::temp_dir().join("dating_simulation_metadata_checkpoint.json");
+	sample.checkpoint(&path).unwrap();
+	let resumed = Sample::resume(&path).unwrap();
+	std::fs::remove_file(&path).unwrap();
+	let resumed_metadata = resumed.loaded_metadata.clone().unwrap();
+	assert_eq!(resumed_metadata.sample_config, sample.sample_config);
+	assert_eq!((resumed_metadata.algorithm, resumed_metadata.rounds_executed), (MatchingAlgorithm::DeferredAcceptance, 2));
+	
+	// the csv comments hold the config as json
+	let mut output: Vec<u8> = Vec::new();
+	sample.export_matches_csv(&mut output, false).unwrap();
+	let output = String::from_utf8(output).unwrap();
+	assert!(output.starts_with(&format!("# crate_version: {}\n", env!("CARGO_PKG_VERSION"))));
+	assert!(output.lines().any(|line| line == "# algorithm: deferred-acceptance"));
+	let config = output.lines().find_map(|line| line.strip_prefix("# sample_config: ")).unwrap();
+	assert_eq!(serde_json::from_str::<SampleConfig>(config).unwrap(), sample.sample_config.unwrap());
+}
+
+#[test]
+fn json_export_writes_identities_as_uuids() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(50).complexity(3).seed(15).build().unwrap()).unwrap();
+	sample.match_making().unwrap();
+	
+	let json = serde_json::to_value(&sample).unwrap();
+	for individual in json["population"].as_array().unwrap() {
+		let identity = individual["identity"].as_str().unwrap();
+		assert!(uuid::Uuid::parse_str(identity).is_ok(), "{}", identity);
+		assert!(sample.get(identity).is_some());
+		
+		for (blacklisted, round) in individual["blacklist"].as_object().unwrap() {
+			assert!(uuid::Uuid::parse_str(blacklisted).is_ok());
+			assert!(round.as_u64().unwrap() >= 1);
+		}
+	}
+	
+	let matched = sample.population.iter().find(|individual| individual.candidate.is_some()).unwrap();
+	let position = sample.population.iter().position(|individual| individual == matched).unwrap();
+	assert_eq!(json["population"][position]["candidate"], &*matched.candidate.unwrap().as_str());
+}
+
+#[test]
+fn json_snapshot_round_trips() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(50).complexity(3).seed(12).build().unwrap()).unwrap();
+	sample.match_making().unwrap();
+	
+	let path = std::env::temp_dir().join("dating_simulation_json_round_trip.json");
+	sample.to_json_file(&path).unwrap();
+	let mut loaded = Sample::from_json_file(&path).unwrap();
+	std::fs::remove_file(&path).unwrap();
+	
+	assert_eq!(loaded.seed, sample.seed);
+	assert_eq!(loaded.population, sample.population);
+	
+	loaded.match_making().unwrap();
+}
+
+#[test]
+fn a_resumed_run_ends_like_an_uninterrupted_one() {
+	let config = SampleConfig::builder().population(80).complexity(3).seed(16).build().unwrap();
+	let prepare = || {
+		let mut sample = Sample::generate(&config).unwrap();
+		sample.matching_config.shuffle_proposers = true;
+		sample.matching_config.shuffle_reviewers = true;
+		sample.churn = ChurnConfig { arrivals_per_round: 5, exit_on_match_probability: 0.2, ..ChurnConfig::default() };
+	
+		return sample;
+	};
+	
+	let mut uninterrupted = prepare();
+	uninterrupted.run(4).unwrap();
+	
+	let path = std::env::temp_dir().join("dating_simulation_checkpoint.json");
+	let mut interrupted = prepare();
+	interrupted.run(2).unwrap();
+	interrupted.checkpoint(&path).unwrap();
+	drop(interrupted);
+	
+	let mut resumed = Sample::resume(&path).unwrap();
+	std::fs::remove_file(&path).unwrap();
+	assert_eq!(resumed.round, 2);
+	resumed.run(2).unwrap();
+	
+	assert_eq!(resumed.round, uninterrupted.round);
+	assert_eq!(resumed.pairs(), uninterrupted.pairs());
+	assert_eq!(resumed.retired, uninterrupted.retired);
+	assert_eq!(resumed.population, uninterrupted.population);
+}
+
+#[test]
+fn a_blacklist_listed_without_rounds_still_loads() {
+	let individual = Individual::builder().identity("m0").blacklist(["f1"]).build().unwrap();
+	let mut json = serde_json::to_value(&individual).unwrap();
+	assert_eq!(json["blacklist"], serde_json::json!({ "f1": 0 }));
+	
+	// snapshots from before rejections had rounds
+	json["blacklist"] = serde_json::json!(["f1"]);
+	let loaded: Individual = serde_json::from_value(json).unwrap();
+	
+	assert_eq!(loaded, individual);
+}
+
+#[test]
+fn json_snapshot_with_unknown_candidate_is_rejected() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(20).complexity(3).seed(13).build().unwrap()).unwrap();
+	sample.population[0].candidate = Some(PersonId::from("no-such-identity"));
+	
+	let path = std::env::temp_dir().join("dating_simulation_json_unknown_candidate.json");
+	sample.to_json_file(&path).unwrap();
+	let result = Sample::from_json_file(&path);
+	std::fs::remove_file(&path).unwrap();
+	
+	let error = result.unwrap_err();
+	assert!(error.to_string().contains("no-such-identity"), "{}", error);
+	assert!(matches!(error, SimulationError::UnknownIdentity(identity) if identity == "no-such-identity"));
+}
+
+#[test]
+fn matches_csv_only_reports_mutual_pairs() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(60).complexity(3).seed(14).build().unwrap()).unwrap();
+	sample.match_making().unwrap();
+	
+	let mutual_pairs = sample.population
+		.iter()
+		.filter(|individual| sample.population.iter().any(|partner| {
+			individual.candidate.as_ref() == Some(&partner.identity) &&
+			partner.candidate.as_ref() == Some(&individual.identity)
+		}))
+		.count() / 2;
+	
+	let mut output: Vec<u8> = Vec::new();
+	sample.export_matches_csv(&mut output, false).unwrap();
+	let output = String::from_utf8(output).unwrap();
+	let mut lines = output.lines().skip_while(|line| line.starts_with('#'));
+	
+	assert_eq!(
+		lines.next(), 
+		Some("proposer_id,reviewer_id,score,proposer_rating_sum,reviewer_rating_sum,proposer_attribute_1,proposer_attribute_2,proposer_attribute_3,reviewer_attribute_1,reviewer_attribute_2,reviewer_attribute_3,matched_in_round,proposer_rejections_received,proposer_rejections_given,reviewer_rejections_received,reviewer_rejections_given,proposer_age,reviewer_age,retired,proposer_top_attribute,reviewer_top_attribute")
+	);
+	assert_eq!(lines.count(), mutual_pairs);
+	
+	let mut output: Vec<u8> = Vec::new();
+	sample.export_matches_csv(&mut output, true).unwrap();
+	let rows = String::from_utf8(output).unwrap().lines().filter(|line| !line.starts_with('#')).count() - 1;
+	
+	assert_eq!(
+		rows, 
+		sample.population.len() - mutual_pairs
+	);
+}
+
+/// `m0` and `f0` are a pair
+fn matched_fixture() -> Sample {
+	let sample = Sample::from_individuals(vec![
+		with_candidate(individual("m0", Gender::Male, vec![1.0], vec![1.0]), "f0", 4.0),
+		with_candidate(individual("f0", Gender::Female, vec![1.0], vec![1.0]), "m0", 6.0)
+	]);
+	
+	return sample;
+}
+
+#[test]
+fn matches_csv_names_the_attribute_that_drove_each_side() {
+	let sample = Sample::from_individuals(vec![
+		with_candidate(individual("m0", Gender::Male, vec![1.0, 1.0], vec![2.0, 8.0]), "f0", 6.0),
+		with_candidate(individual("f0", Gender::Female, vec![1.0, 1.0], vec![5.0, 1.0]), "m0", 10.0)
+	]);
+	
+	let mut output: Vec<u8> = Vec::new();
+	sample.export_matches_csv(&mut output, false).unwrap();
+	let output = String::from_utf8(output).unwrap();
+	let row = output.lines().filter(|line| !line.starts_with('#')).nth(1).unwrap();
+	
+	assert!(row.ends_with(",false,attribute_1,attribute_2"), "{}", row);
+	
+	let shares = sample.statistics().attribute_score_shares;
+	assert_eq!(shares[0].0, "attribute_1");
+	// `m0` owes 5 of 6 to the first attribute, `f0` 2 of 10
+	assert!((shares[0].1 - (5.0 / 6.0 + 0.2) / 2.0).abs() < 1e-6, "{:?}", shares);
+	assert!((shares[1].1 - (1.0 / 6.0 + 0.8) / 2.0).abs() < 1e-6, "{:?}", shares);
+}
+
+#[test]
+fn match_graph_has_one_edge_per_pair() {
+	let sample = matched_fixture();
+	
+	let mut dot = Vec::new();
+	sample.export_graph(&mut dot, GraphKind::Matches, GraphFormat::Dot, None).unwrap();
+	let dot = String::from_utf8(dot).unwrap();
+	
+	let edges: Vec<&str> = dot.lines().filter(|line| line.contains("--")).collect();
+	assert_eq!(edges, vec!["\t\"m0\" -- \"f0\";"]);
+	assert!(dot.contains("\"m0\" [label=\"m0 (M)\", fillcolor=palegreen];"));
+	assert!(dot.contains("\"f0\" [label=\"f0 (F)\", fillcolor=palegreen];"));
+	
+	let mut graphml = Vec::new();
+	sample.export_graph(&mut graphml, GraphKind::Matches, GraphFormat::GraphMl, None).unwrap();
+	let graphml = String::from_utf8(graphml).unwrap();
+	
+	assert_eq!(graphml.matches("<edge ").count(), 1);
+	assert!(graphml.contains("<edge source=\"m0\" target=\"f0\"/>"));
+}
+
+#[test]
+fn graph_sampling_keeps_at_most_max_nodes() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(40).complexity(2).seed(8).build().unwrap()).unwrap();
+	sample.match_making().unwrap();
+	
+	let mut dot = Vec::new();
+	sample.export_graph(&mut dot, GraphKind::Blacklist, GraphFormat::Dot, Some(10)).unwrap();
+	let dot = String::from_utf8(dot).unwrap();
+	
+	assert_eq!(dot.lines().filter(|line| line.contains("[label=")).count(), 10);
+	assert!(dot.starts_with("digraph blacklist {"));
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn a_run_written_to_sqlite_has_a_row_per_individual_pair_and_round() {
+	use dating_simulation::{NoEvents, SqliteSink};
+	
+	let path = std::env::temp_dir().join("dating_simulation_run.db");
+	let _ = std::fs::remove_file(&path);
+	
+	let mut sample = Sample::generate(&SampleConfig::builder().population(40).complexity(2).seed(71).build().unwrap()).unwrap();
+	let sink = SqliteSink::create(&path, &sample).unwrap();
+	assert_eq!(sink.run_id(), 1);
+	sample.set_event_sink(Box::new(sink));
+	let mut pairs = 0;
+	for _ in 0..3 {
+		sample.match_making().unwrap();
+		pairs += sample.pairs().len();
+	}
+	// closes the database
+	sample.set_event_sink(Box::new(NoEvents));
+	
+	// running again on the same file appends a run
+	let again = SqliteSink::create(&path, &sample).unwrap();
+	assert_eq!(again.run_id(), 2);
+	assert_eq!(again.count("runs").unwrap(), 2);
+	assert_eq!(again.count("individuals").unwrap(), 80);
+	assert_eq!(again.count("matches").unwrap(), pairs as i64);
+	assert_eq!(again.count("rounds").unwrap(), 3);
+	drop(again);
+	std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn pairs_and_round_stats_survive_a_parquet_round_trip() {
+	use dating_simulation::{MatchPair, ParquetColumn, ParquetTable, RoundStats};
+	
+	let mut sample = Sample::generate(&SampleConfig::builder().population(60).complexity(3).seed(72).build().unwrap()).unwrap();
+	sample.churn = ChurnConfig { exit_on_match_probability: 0.3, ..ChurnConfig::default() };
+	let run_report = sample.run(4).unwrap();
+	let pairs = sample.pairs();
+	assert!(!pairs.is_empty() && !sample.retired.is_empty());
+	
+	let path = std::env::temp_dir().join("dating_simulation_pairs.parquet");
+	sample.export_pairs_parquet(&path).unwrap();
+	let table = ParquetTable::read(&path).unwrap();
+	std::fs::remove_file(&path).unwrap();
+	
+	let names: Vec<&str> = table.columns.iter().map(|(name, _)| name.as_str()).collect();
+	assert_eq!(names, Sample::PAIRS_PARQUET_COLUMNS);
+	assert_eq!(table.rows(), pairs.len() + sample.retired.len());
+	let expected: Vec<&MatchPair> = pairs.iter().chain(&sample.retired).collect();
+	assert_eq!(table.column("proposer_id"), Some(&ParquetColumn::Utf8(expected.iter().map(|pair| Some(pair.proposer_id.to_string())).collect())));
+	assert_eq!(table.column("reviewer_score"), Some(&ParquetColumn::Float32(expected.iter().map(|pair| Some(pair.reviewer_score)).collect())));
+	assert_eq!(table.column("matched_in_round"), Some(&ParquetColumn::UInt32(expected.iter().map(|pair| pair.matched_in_round).collect())));
+	assert_eq!(table.column("retired"), Some(&ParquetColumn::Boolean((0..expected.len()).map(|row| Some(row >= pairs.len())).collect())));
+	assert!(table.metadata["dating_simulation.metadata"].contains("\"seed\":72"));
+	
+	// a round without anyone matched has a null mean score
+	let mut round_stats = run_report.round_stats.clone();
+	round_stats[0].mean_candidate_score = None;
+	let path = std::env::temp_dir().join("dating_simulation_round_stats.parquet");
+	RoundStats::export_parquet(&round_stats, &path).unwrap();
+	let table = ParquetTable::read(&path).unwrap();
+	std::fs::remove_file(&path).unwrap();
+	
+	assert_eq!(table.columns.iter().map(|(name, _)| name.as_str()).collect::<Vec<&str>>().join(","), RoundStats::CSV_HEADER);
+	assert_eq!(table.column("round"), Some(&ParquetColumn::UInt32(vec![Some(1), Some(2), Some(3), Some(4)])));
+	assert_eq!(table.column("mean_candidate_score"), Some(&ParquetColumn::Float32(round_stats.iter().map(|stats| stats.mean_candidate_score).collect())));
+	assert_eq!(table.column("breakups"), Some(&ParquetColumn::UInt32(round_stats.iter().map(|stats| Some(stats.breakups as u32)).collect())));
+}
+
+/// the json export without the lines that change from one build or second to the next
+fn without_build_details(export: &str) -> String {
+	return export
+		.lines()
+		.filter(|line| !["\"timestamp\":", "\"crate_version\":", "\"git_describe\":"].iter().any(|key| line.trim_start().starts_with(key)))
+		.map(|line| format!("{}\n", line))
+		.collect();
+}
+
+/// regenerate the snapshot with `UPDATE_SNAPSHOTS=1 cargo test --test export`
+#[test]
+fn a_seeded_run_writes_the_checked_in_json_export() {
+	let export = || -> String {
+		let mut sample = Sample::generate(&SampleConfig::builder().population(500).complexity(3).seed(81).build().unwrap()).unwrap();
+		sample.run(3).unwrap();
+		let mut json: Vec<u8> = Vec::new();
+		sample.to_json_writer(&mut json).unwrap();
+		
+		return without_build_details(&String::from_utf8(json).unwrap());
+	};
+	let first = export();
+	assert!(first == export(), "two runs with the same seed wrote different exports");
+	
+	let snapshot = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots/seeded_500_3_rounds.json");
+	if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+		std::fs::write(&snapshot, &first).unwrap();
+	}
+	let expected = std::fs::read_to_string(&snapshot).unwrap();
+	let mismatch = first.lines().zip(expected.lines()).position(|(line, expected)| line != expected);
+	assert!(first == expected, "the export differs from {} at line {:?}", snapshot.display(), mismatch.map(|line| line + 1));
+}
+
+#[test]
+fn diffing_two_runs_reports_the_pairs_and_partners_that_changed() {
+	let mut greedy = Sample::generate(&SampleConfig::builder().population(80).complexity(3).seed(82).build().unwrap()).unwrap();
+	let mut deferred = greedy.clone();
+	deferred.matching_config.algorithm = MatchingAlgorithm::DeferredAcceptance;
+	greedy.run(5).unwrap();
+	deferred.run(5).unwrap();
+	
+	let mut json: Vec<u8> = Vec::new();
+	greedy.to_json_writer(&mut json).unwrap();
+	let exported = RunExport::from_json_reader(json.as_slice()).unwrap();
+	assert_eq!(exported.metadata.as_ref().map(|metadata| metadata.seed), Some(82));
+	
+	let same = diff_runs(&exported, &RunExport::from_sample(greedy.clone()));
+	assert!(same.is_empty(), "{}", same);
+	assert!(same.to_string().starts_with("The runs ended the same"));
+	
+	let diff = diff_runs(&RunExport::from_sample(greedy.clone()), &RunExport::from_sample(deferred.clone()));
+	assert!(!diff.is_empty());
+	assert!(!diff.only_in_a.is_empty() || !diff.only_in_b.is_empty());
+	assert_eq!(diff.individuals_only_in_a, 0);
+	assert_eq!(diff.pairs_in_a - diff.only_in_a.len(), diff.pairs_in_b - diff.only_in_b.len());
+	for change in &diff.partner_changes {
+		assert_ne!(change.in_a, change.in_b);
+		let partner_in = |sample: &Sample| sample.pairs().iter().find_map(|pair| match change.identity {
+			identity if identity == pair.proposer_id => Some(pair.reviewer_id),
+			identity if identity == pair.reviewer_id => Some(pair.proposer_id),
+			_ => None
+		});
+		assert_eq!(change.in_a, partner_in(&greedy));
+		assert_eq!(change.in_b, partner_in(&deferred));
+	}
+	let rounds = diff.statistics.iter().find(|statistic| statistic.name == "rounds").unwrap();
+	assert_eq!(rounds.delta(), Some(0.0));
+	
+	let json: serde_json::Value = serde_json::to_value(&diff).unwrap();
+	assert_eq!(json["only_in_a"].as_array().unwrap().len(), diff.only_in_a.len());
+	assert!(diff.to_string().contains("Statistic"));
+}
+
+/// a fresh directory under the temporary one
+fn scratch_directory(name: &str) -> std::path::PathBuf {
+	let directory = std::env::temp_dir().join(name);
+	let _ = std::fs::remove_dir_all(&directory);
+	std::fs::create_dir_all(&directory).unwrap();
+	
+	return directory;
+}
+
+#[test]
+fn a_population_streamed_from_disk_pairs_as_it_does_in_memory() {
+	let config = SampleConfig::builder().population(300).complexity(3).seed(97).build().unwrap();
+	let mut sample = Sample::generate(&config).unwrap();
+	let stats: Vec<RoundStats> = (0..3).map(|_| sample.match_making().unwrap()).collect();
+	
+	let directory = scratch_directory("dating_simulation_streamed");
+	Sample::generate_to_disk(&config, &directory, 64).unwrap();
+	assert_eq!(Sample::from_disk(&directory).unwrap().population, Sample::generate(&config).unwrap().population);
+	
+	// and so are exact gender counts
+	let counted = SampleConfig::builder().gender_counts(120, 180).complexity(3).seed(97).build().unwrap();
+	let counted_directory = scratch_directory("dating_simulation_streamed_counts");
+	Sample::generate_to_disk(&counted, &counted_directory, 64).unwrap();
+	assert_eq!(Sample::from_disk(&counted_directory).unwrap().population, Sample::generate(&counted).unwrap().population);
+	std::fs::remove_dir_all(&counted_directory).unwrap();
+	
+	// two calls continue where the first left off
+	let mut streamed_stats = Sample::stream_match_making(&directory, &MatchingConfig::default(), 2).unwrap();
+	streamed_stats.extend(Sample::stream_match_making(&directory, &MatchingConfig::default(), 1).unwrap());
+	let streamed = Sample::from_disk(&directory).unwrap();
+	std::fs::remove_dir_all(&directory).unwrap();
+	
+	// partners moved on to someone else, also of streamed proposers already written back
+	assert!(sample.pairs().len() > 40);
+	assert!(stats.iter().map(|stats| stats.delta.upgrades).sum::<usize>() > 10);
+	assert_eq!(streamed.round, 3);
+	assert_eq!(streamed.population, sample.population);
+	assert_eq!(streamed.pairs(), sample.pairs());
+	assert_eq!(streamed_stats, stats);
+	
+	let shuffled = MatchingConfig { shuffle_proposers: true, ..MatchingConfig::default() };
+	assert!(matches!(Sample::stream_match_making(&directory, &shuffled, 1), Err(SimulationError::InvalidConfig(_))));
+}
+
+/// the 5 million of a machine with 16 GB, about half of them held in memory at a time. 
+/// the greedy scan takes hours at that size, run with `cargo test --release --test export -- --ignored`
+#[test]
+#[ignore]
+fn five_million_individuals_stream_through_a_round() {
+	let config = SampleConfig::builder().population(5_000_000).complexity(3).seed(5).build().unwrap();
+	let directory = scratch_directory("dating_simulation_streamed_5m");
+	
+	Sample::generate_to_disk(&config, &directory, Sample::DEFAULT_CHUNK_SIZE).unwrap();
+	let stats = Sample::stream_match_making(&directory, &MatchingConfig::default(), 1).unwrap();
+	std::fs::remove_dir_all(&directory).unwrap();
+	
+	assert_eq!(stats[0].population, 5_000_000);
+	assert!(stats[0].matched_males > 0);
+}
@@ -0,0 +1,1219 @@
+#![allow(clippy::needless_return, clippy::redundant_field_names)]
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use common::{individual, with_candidate};
+use dating_simulation::{pearson_correlation, EncounterOutcome, Encounters, EventSink, Gender, Individual, JsonLinesSink, MatchingAlgorithm, Orientation, PersonId, ProposerSide, Sample, SampleConfig, ScoreCacheMode, ScoreNoise, SimEvent, SimulationError, StopReason, ThresholdDistribution};
+
+/// two males who both appeal most to `f0`, while `m1` would rather be with `f1`
+fn unstable_fixture() -> Sample {
+	let sample = Sample::from_individuals(vec![
+		individual("m0", Gender::Male, vec![1.0, 0.0], vec![1.0, 1.0]),
+		individual("m1", Gender::Male, vec![0.0, 1.0], vec![9.0, 9.0]),
+		individual("f0", Gender::Female, vec![1.0, 1.0], vec![9.0, 1.0]),
+		individual("f1", Gender::Female, vec![1.0, 1.0], vec![1.0, 9.0])
+	]);
+	
+	return sample;
+}
+
+/// the score an individual gives to their current partner, `None` when unmatched
+fn partner_score(sample: &Sample, individual: &Individual) -> Option<f32> {
+	let partner = sample.population
+		.iter()
+		.find(|other| {
+			individual.candidate == Some(other.identity) && 
+			other.candidate == Some(individual.identity)
+		})?;
+	
+	return Some(individual.score(partner).unwrap());
+}
+
+/// pairs that would both rather be with each other than with their current partners
+fn blocking_pairs(sample: &Sample) -> Vec<(PersonId, PersonId)> {
+	let mut blocking_pairs = Vec::new();
+	
+	for male_individual in sample.of_gender(Gender::Male) {
+		for female_individual in sample.of_gender(Gender::Female) {
+			let male_prefers = partner_score(sample, male_individual)
+				.is_none_or(|score| male_individual.score(female_individual).unwrap() > score);
+			let female_prefers = partner_score(sample, female_individual)
+				.is_none_or(|score| female_individual.score(male_individual).unwrap() > score);
+			
+			if male_prefers && female_prefers && male_individual.candidate != Some(female_individual.identity) {
+				blocking_pairs.push((male_individual.identity, female_individual.identity));
+			}
+		}
+	}
+	
+	return blocking_pairs;
+}
+
+#[test]
+fn greedy_leaves_a_blocking_pair() {
+	let mut sample = unstable_fixture();
+	
+	sample.match_making().unwrap();
+	
+	assert!(blocking_pairs(&sample).contains(&(PersonId::from("m1"), PersonId::from("f1"))));
+}
+
+#[test]
+fn deferred_acceptance_is_stable() {
+	let mut sample = unstable_fixture();
+	sample.matching_config.algorithm = MatchingAlgorithm::DeferredAcceptance;
+	
+	let round_stats = sample.match_making().unwrap();
+	
+	assert!(blocking_pairs(&sample).is_empty());
+	assert_eq!(round_stats.matched_males, 2);
+	assert_eq!(sample.get("m0").unwrap().candidate, Some(PersonId::from("f0")));
+	assert_eq!(sample.get("m1").unwrap().candidate, Some(PersonId::from("f1")));
+}
+
+#[test]
+fn deferred_acceptance_is_stable_on_a_generated_population() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(80).complexity(3).seed(21).build().unwrap()).unwrap();
+	sample.matching_config.algorithm = MatchingAlgorithm::DeferredAcceptance;
+	
+	sample.match_making().unwrap();
+	
+	assert!(blocking_pairs(&sample).is_empty());
+}
+
+/// `f0` wants `m0`, but `m0` already holds `f1` whom he scores higher
+fn declined_fixture() -> Sample {
+	let mut m0 = individual("m0", Gender::Male, vec![1.0, 0.0], vec![5.0, 5.0]);
+	m0.candidate = Some(PersonId::from("f1"));
+	m0.candidate_score = Some(9.0);
+	
+	let mut f1 = individual("f1", Gender::Female, vec![1.0, 1.0], vec![9.0, 1.0]);
+	f1.candidate = Some(PersonId::from("m0"));
+	f1.candidate_score = Some(10.0);
+	
+	let sample = Sample::from_individuals(vec![
+		m0,
+		individual("f0", Gender::Female, vec![1.0, 1.0], vec![1.0, 9.0]),
+		f1
+	]);
+	
+	return sample;
+}
+
+#[test]
+fn male_declines_a_female_below_his_candidate() {
+	let mut sample = declined_fixture();
+	sample.matching_config.mutual_acceptance = true;
+	
+	sample.match_making().unwrap();
+	
+	assert_eq!(sample.get("m0").unwrap().candidate, Some(PersonId::from("f1")));
+	assert_eq!(sample.get("f0").unwrap().candidate, None);
+}
+
+#[test]
+fn male_accepts_anyone_without_mutual_acceptance() {
+	let mut sample = declined_fixture();
+	
+	sample.match_making().unwrap();
+	
+	assert_eq!(sample.get("m0").unwrap().candidate, Some(PersonId::from("f0")));
+	assert_eq!(sample.get("f0").unwrap().candidate, Some(PersonId::from("m0")));
+}
+
+#[test]
+fn mutual_acceptance_keeps_each_sides_own_score() {
+	let mut sample = Sample::from_individuals(vec![
+		individual("m0", Gender::Male, vec![1.0, 0.0], vec![2.0, 3.0]),
+		individual("f0", Gender::Female, vec![0.0, 1.0], vec![4.0, 5.0])
+	]);
+	sample.matching_config.mutual_acceptance = true;
+	
+	sample.match_making().unwrap();
+	
+	assert_eq!(sample.get("m0").unwrap().candidate_score, Some(4.0));
+	assert_eq!(sample.get("f0").unwrap().candidate_score, Some(3.0));
+}
+
+#[test]
+fn parallel_proposal_does_not_depend_on_thread_count() {
+	let run = |threads: usize| {
+		let mut sample = Sample::generate(&SampleConfig::builder().population(300).complexity(3).seed(33).build().unwrap()).unwrap();
+		sample.matching_config.algorithm = MatchingAlgorithm::ParallelProposal;
+		
+		rayon::ThreadPoolBuilder::new()
+			.num_threads(threads)
+			.build()
+			.unwrap()
+			.install(|| sample.run(3).unwrap());
+		
+		return sample;
+	};
+	
+	let single_threaded = run(1);
+	let multi_threaded = run(4);
+	
+	assert_eq!(single_threaded.population, multi_threaded.population);
+	assert!(single_threaded.of_gender(Gender::Female).any(|female_individual| female_individual.candidate.is_some()));
+}
+
+/// `f0` prefers `m0`, so `m1` is rejected once `m0` holds her
+fn rejection_fixture() -> Sample {
+	let sample = Sample::from_individuals(vec![
+		individual("m0", Gender::Male, vec![1.0], vec![9.0]),
+		individual("m1", Gender::Male, vec![1.0], vec![1.0]),
+		individual("f0", Gender::Female, vec![1.0], vec![5.0])
+	]);
+	
+	return sample;
+}
+
+#[test]
+fn rejection_is_recorded_on_both_sides_with_symmetric_blacklist() {
+	let mut sample = rejection_fixture();
+	sample.matching_config.symmetric_blacklist = true;
+	
+	sample.match_making().unwrap();
+	
+	assert!(sample.get("m1").unwrap().extras().blacklist.contains_key(&PersonId::from("f0")));
+	assert!(sample.get("f0").unwrap().extras().blacklist.contains_key(&PersonId::from("m1")));
+}
+
+#[test]
+fn rejection_is_recorded_by_the_proposer_only_by_default() {
+	let mut sample = rejection_fixture();
+	
+	sample.match_making().unwrap();
+	
+	assert!(sample.get("m1").unwrap().extras().blacklist.contains_key(&PersonId::from("f0")));
+	assert!(sample.get("f0").unwrap().extras().blacklist.is_empty());
+}
+
+#[test]
+fn displaced_male_is_released_when_the_female_upgrades() {
+	// `f0` takes `m0` first and switches as soon as `m1` proposes
+	let mut sample = Sample::from_individuals(vec![
+		individual("m0", Gender::Male, vec![1.0], vec![1.0]),
+		individual("m1", Gender::Male, vec![1.0], vec![9.0]),
+		individual("f0", Gender::Female, vec![1.0], vec![5.0])
+	]);
+	
+	let round_stats = sample.match_making().unwrap();
+	
+	assert_eq!(sample.get("m0").unwrap().candidate, None);
+	assert_eq!(sample.get("m0").unwrap().candidate_score, None);
+	assert_eq!(sample.get("f0").unwrap().candidate, Some(PersonId::from("m1")));
+	assert_eq!(round_stats.matched_males, 1);
+	assert_eq!(sample.statistics().unmatched_males, 1);
+}
+
+#[test]
+fn gay_males_are_matched_with_each_other() {
+	// `f0` is heterosexual, so the two males are each other's only compatible option
+	let mut m0 = individual("m0", Gender::Male, vec![1.0], vec![1.0]);
+	m0.orientation = Orientation::Homosexual;
+	let mut m1 = individual("m1", Gender::Male, vec![1.0], vec![2.0]);
+	m1.orientation = Orientation::Homosexual;
+	let mut sample = Sample::from_individuals(vec![
+		m0,
+		individual("f0", Gender::Female, vec![1.0], vec![9.0]),
+		m1
+	]);
+	
+	sample.match_making().unwrap();
+	
+	assert_eq!(sample.get("m0").unwrap().candidate, Some(PersonId::from("m1")));
+	assert_eq!(sample.get("m1").unwrap().candidate, Some(PersonId::from("m0")));
+	assert_eq!(sample.get("f0").unwrap().candidate, None);
+	
+	let statistics = sample.statistics();
+	assert_eq!(statistics.matched_males, 2);
+	assert_eq!(statistics.by_orientation[&Orientation::Homosexual].match_rate(), Some(1.0));
+	assert_eq!(statistics.by_orientation[&Orientation::Heterosexual].match_rate(), Some(0.0));
+}
+
+#[test]
+fn an_impossible_threshold_leaves_everyone_single() {
+	let threshold = ThresholdDistribution::Uniform { min: 1e9, max: 1e9 };
+	
+	for algorithm in [MatchingAlgorithm::Greedy, MatchingAlgorithm::DeferredAcceptance, MatchingAlgorithm::ParallelProposal] {
+		let mut sample = Sample::generate(&SampleConfig::builder().population(200).complexity(3).threshold_distribution(threshold).seed(5).build().unwrap()).unwrap();
+		sample.matching_config.algorithm = algorithm;
+		
+		let round_stats = sample.match_making().unwrap();
+		
+		assert_eq!(round_stats.matched_males + round_stats.matched_females, 0, "{}", algorithm);
+		assert!(sample.pairs().is_empty(), "{}", algorithm);
+		assert_eq!(sample.statistics().unmatched_by_threshold, 200, "{}", algorithm);
+	}
+}
+
+#[test]
+fn a_single_reviewer_turns_down_proposals_below_their_threshold() {
+	let mut f0 = individual("f0", Gender::Female, vec![1.0], vec![5.0]);
+	f0.min_acceptable_score = Some(5.0);
+	let mut sample = Sample::from_individuals(vec![
+		individual("m0", Gender::Male, vec![1.0], vec![4.0]),
+		individual("m1", Gender::Male, vec![1.0], vec![6.0]),
+		f0
+	]);
+	
+	sample.match_making().unwrap();
+	
+	assert!(sample.get("m0").unwrap().extras().blacklist.contains_key(&PersonId::from("f0")));
+	assert_eq!(sample.get("f0").unwrap().candidate, Some(PersonId::from("m1")));
+}
+
+#[test]
+fn a_dealbreaker_passes_over_the_best_candidate() {
+	// `m0` is the best by far, but `f0` demands at least 6 on the second attribute
+	for algorithm in [MatchingAlgorithm::Greedy, MatchingAlgorithm::DeferredAcceptance, MatchingAlgorithm::ParallelProposal] {
+		let mut f0 = individual("f0", Gender::Female, vec![1.0, 0.1], vec![5.0, 5.0]);
+		f0.extras_mut().dealbreakers = vec![None, Some(6.0)];
+		let mut sample = Sample::from_individuals(vec![
+			individual("m0", Gender::Male, vec![1.0, 1.0], vec![10.0, 5.0]),
+			individual("m1", Gender::Male, vec![1.0, 1.0], vec![4.0, 7.0]),
+			f0
+		]);
+		sample.matching_config.algorithm = algorithm;
+		
+		let round_stats = sample.match_making().unwrap();
+		
+		assert_eq!(sample.get("f0").unwrap().candidate, Some(PersonId::from("m1")), "{}", algorithm);
+		assert_eq!(sample.get("m0").unwrap().candidate, None, "{}", algorithm);
+		assert_eq!(round_stats.dealbreaker_rejections, 1, "{}", algorithm);
+	}
+}
+
+#[test]
+fn a_pair_keeps_the_round_it_formed_in() {
+	for algorithm in [MatchingAlgorithm::Greedy, MatchingAlgorithm::DeferredAcceptance, MatchingAlgorithm::ParallelProposal] {
+		let mut sample = Sample::from_individuals(vec![
+			individual("m0", Gender::Male, vec![1.0], vec![9.0]),
+			individual("f0", Gender::Female, vec![1.0], vec![9.0])
+		]);
+		sample.matching_config.algorithm = algorithm;
+		sample.match_making().unwrap();
+		
+		// `m1` and `f1` only join for the second round
+		sample.population.push(individual("m1", Gender::Male, vec![1.0], vec![5.0]));
+		sample.population.push(individual("f1", Gender::Female, vec![1.0], vec![5.0]));
+		sample.match_making().unwrap();
+		sample.match_making().unwrap();
+		
+		for (identity, round) in [("m0", 1), ("f0", 1), ("m1", 2), ("f1", 2)] {
+			assert_eq!(sample.get(identity).unwrap().matched_in_round, Some(round), "{} {}", algorithm, identity);
+		}
+		
+		let statistics = sample.statistics();
+		assert_eq!(statistics.mean_time_to_match, Some(1.5), "{}", algorithm);
+		assert_eq!(statistics.time_to_match_histogram.into_iter().collect::<Vec<(u32, usize)>>(), vec![(1, 2), (2, 2)], "{}", algorithm);
+	}
+}
+
+#[test]
+fn every_female_rejects_the_low_rated_male() {
+	for algorithm in [MatchingAlgorithm::Greedy, MatchingAlgorithm::DeferredAcceptance, MatchingAlgorithm::ParallelProposal] {
+		// `m3` proposes first and every female already holds a better male
+		let mut sample = Sample::from_individuals(vec![
+			individual("m3", Gender::Male, vec![1.0], vec![1.0]),
+			with_candidate(individual("f0", Gender::Female, vec![1.0], vec![5.0]), "m0", 9.0),
+			with_candidate(individual("f1", Gender::Female, vec![1.0], vec![5.0]), "m1", 9.0),
+			with_candidate(individual("f2", Gender::Female, vec![1.0], vec![5.0]), "m2", 9.0),
+			with_candidate(individual("m0", Gender::Male, vec![1.0], vec![9.0]), "f0", 5.0),
+			with_candidate(individual("m1", Gender::Male, vec![1.0], vec![9.0]), "f1", 5.0),
+			with_candidate(individual("m2", Gender::Male, vec![1.0], vec![9.0]), "f2", 5.0)
+		]);
+		sample.matching_config.algorithm = algorithm;
+		
+		sample.match_making().unwrap();
+		
+		let m3 = sample.get("m3").unwrap();
+		assert_eq!(m3.rejections_received, 3, "{}", algorithm);
+		assert_eq!(m3.rejections_given, 0, "{}", algorithm);
+		assert_eq!(m3.candidate, None, "{}", algorithm);
+		
+		let given: u32 = sample.population.iter().map(|individual| individual.rejections_given).sum();
+		let received: u32 = sample.population.iter().map(|individual| individual.rejections_received).sum();
+		assert_eq!(given, received, "{}", algorithm);
+	}
+}
+
+#[test]
+fn a_maximum_age_gap_skips_partners_that_are_too_old() {
+	let mut m0 = individual("m0", Gender::Male, vec![1.0], vec![9.0]);
+	m0.age = 50.0;
+	let mut f0 = individual("f0", Gender::Female, vec![1.0], vec![5.0]);
+	f0.extras_mut().max_age_gap = Some(10.0);
+	let mut sample = Sample::from_individuals(vec![
+		m0,
+		individual("m1", Gender::Male, vec![1.0], vec![2.0]),
+		f0
+	]);
+	
+	let round_stats = sample.match_making().unwrap();
+	
+	assert_eq!(sample.get("f0").unwrap().candidate, Some(PersonId::from("m1")));
+	assert_eq!(round_stats.dealbreaker_rejections, 1);
+}
+
+/// the share of matched individuals of `gender` in the first and in the second half of the population order
+fn matched_share_by_half(sample: &Sample, gender: Gender) -> (f32, f32) {
+	let positions: Vec<usize> = (0..sample.population.len())
+		.filter(|&position| sample.population[position].gender == gender)
+		.collect();
+	let share = |positions: &[usize]| positions
+		.iter()
+		.filter(|&&position| sample.population[position].candidate.is_some())
+		.count() as f32 / positions.len() as f32;
+	let (first, second) = positions.split_at(positions.len() / 2);
+	
+	return (share(first), share(second));
+}
+
+#[test]
+fn shuffled_scans_remove_the_positional_bias() {
+	let config = SampleConfig::builder().population(400).complexity(3).seed(2).build().unwrap();
+	
+	// in the fixed order every male stops at the first females, the later ones are never reached
+	let mut fixed = Sample::generate(&config).unwrap();
+	fixed.match_making().unwrap();
+	let (first, second) = matched_share_by_half(&fixed, Gender::Female);
+	assert!(first > 0.2 && second == 0.0, "{} {}", first, second);
+	
+	let mut shuffled = Sample::generate(&config).unwrap();
+	shuffled.matching_config.shuffle_proposers = true;
+	shuffled.matching_config.shuffle_reviewers = true;
+	shuffled.match_making().unwrap();
+	let (first, second) = matched_share_by_half(&shuffled, Gender::Female);
+	assert!(first > 0.5 && second > 0.5 && (first - second).abs() < 0.2, "{} {}", first, second);
+	let (first, second) = matched_share_by_half(&shuffled, Gender::Male);
+	assert!((first - second).abs() < 0.2, "{} {}", first, second);
+	
+	// the shuffle draws from the seed, so it is reproducible
+	let mut again = Sample::generate(&config).unwrap();
+	again.matching_config = shuffled.matching_config.clone();
+	again.match_making().unwrap();
+	assert_eq!(again.pairs(), shuffled.pairs());
+}
+
+/// every male prefers a different female than the one who prefers him
+fn crossed_preferences() -> Sample {
+	return Sample::from_individuals(vec![
+		individual("m0", Gender::Male, vec![1.0, 0.0], vec![9.0, 1.0]),
+		individual("m1", Gender::Male, vec![0.0, 1.0], vec![1.0, 9.0]),
+		individual("f0", Gender::Female, vec![0.0, 1.0], vec![9.0, 1.0]),
+		individual("f1", Gender::Female, vec![1.0, 0.0], vec![1.0, 9.0])
+	]);
+}
+
+#[test]
+fn the_proposing_side_gets_its_preferred_partners() {
+	for algorithm in [MatchingAlgorithm::DeferredAcceptance, MatchingAlgorithm::ParallelProposal] {
+		let mut males_propose = crossed_preferences();
+		males_propose.matching_config.algorithm = algorithm;
+		males_propose.match_making().unwrap();
+		
+		let mut females_propose = crossed_preferences();
+		females_propose.matching_config.algorithm = algorithm;
+		females_propose.matching_config.proposer_side = ProposerSide::Female;
+		females_propose.match_making().unwrap();
+		
+		assert_eq!(males_propose.get("m0").unwrap().candidate, Some(PersonId::from("f0")), "{}", algorithm);
+		assert_eq!(females_propose.get("m0").unwrap().candidate, Some(PersonId::from("f1")), "{}", algorithm);
+		
+		let (males, females) = (males_propose.statistics(), females_propose.statistics());
+		assert_eq!(males.mean_matched_score(Gender::Male), Some(9.0), "{}", algorithm);
+		assert_eq!(males.mean_matched_score(Gender::Female), Some(1.0), "{}", algorithm);
+		assert_eq!(females.mean_matched_score(Gender::Male), Some(1.0), "{}", algorithm);
+		assert_eq!(females.mean_matched_score(Gender::Female), Some(9.0), "{}", algorithm);
+	}
+}
+
+#[test]
+fn alternating_sides_start_with_the_males() {
+	assert_eq!(ProposerSide::Alternate.proposing_gender(1), Gender::Male);
+	assert_eq!(ProposerSide::Alternate.proposing_gender(2), Gender::Female);
+	assert_eq!(ProposerSide::Female.proposing_gender(1), Gender::Female);
+	assert_eq!("alternate".parse::<ProposerSide>(), Ok(ProposerSide::Alternate));
+}
+
+/// `f0` appeals to both males, who appeal to her in turn, `m0` more than `m1`
+fn two_suitors() -> Sample {
+	return Sample::from_individuals(vec![
+		individual("m0", Gender::Male, vec![1.0], vec![6.0]),
+		individual("m1", Gender::Male, vec![1.0], vec![4.0]),
+		individual("f0", Gender::Female, vec![1.0], vec![5.0])
+	]);
+}
+
+#[test]
+fn a_capacity_of_two_holds_both_suitors_until_the_commit() {
+	let mut sample = two_suitors();
+	sample.matching_config.candidate_capacity = 2;
+	sample.match_making().unwrap();
+	
+	let f0 = sample.get("f0").unwrap();
+	let held: Vec<(PersonId, f32)> = f0.held_candidates().collect();
+	assert_eq!(held, vec![(PersonId::from("m0"), 6.0), (PersonId::from("m1"), 4.0)]);
+	assert_eq!(sample.statistics().provisional_candidates, 1);
+	assert!(sample.validate().is_ok());
+	
+	sample.commit_candidates();
+	
+	let f0 = sample.get("f0").unwrap();
+	assert_eq!(f0.held_candidates().count(), 1);
+	assert_eq!(f0.candidate, Some(PersonId::from("m0")));
+	assert_eq!(sample.get("m0").unwrap().candidate, Some(PersonId::from("f0")));
+	assert_eq!(sample.get("m1").unwrap().candidate, None);
+	assert_eq!(sample.statistics().provisional_candidates, 0);
+	assert!(sample.validate().is_ok());
+}
+
+#[test]
+fn a_run_commits_and_a_capacity_of_one_holds_one() {
+	let mut committed = two_suitors();
+	committed.matching_config.candidate_capacity = 2;
+	committed.run(2).unwrap();
+	assert!(committed.population.iter().all(|individual| individual.extras().provisional_candidates.is_empty()));
+	assert_eq!(committed.pairs().len(), 1);
+	
+	let mut single = two_suitors();
+	single.match_making().unwrap();
+	assert_eq!(single.get("f0").unwrap().held_candidates().count(), 1);
+}
+
+#[test]
+fn candidate_capacity_is_checked_before_a_round() {
+	let mut empty = two_suitors();
+	empty.matching_config.candidate_capacity = 0;
+	assert!(empty.match_making().is_err());
+	
+	let mut deferred = two_suitors();
+	deferred.matching_config.candidate_capacity = 2;
+	deferred.matching_config.algorithm = MatchingAlgorithm::DeferredAcceptance;
+	assert!(deferred.match_making().is_err());
+}
+
+#[test]
+fn the_score_cache_does_not_change_the_outcome() {
+	let config = SampleConfig::builder().population(200).complexity(4).seed(21).build().unwrap();
+	
+	for algorithm in [MatchingAlgorithm::Greedy, MatchingAlgorithm::DeferredAcceptance, MatchingAlgorithm::ParallelProposal] {
+		let mut uncached = Sample::generate(&config).unwrap();
+		uncached.matching_config.algorithm = algorithm;
+		uncached.run(5).unwrap();
+		assert!(uncached.score_cache().is_none());
+		
+		for mode in [ScoreCacheMode::Lazy, ScoreCacheMode::Eager] {
+			let mut cached = Sample::generate(&config).unwrap();
+			cached.matching_config.algorithm = algorithm;
+			cached.matching_config.score_cache = mode;
+			cached.run(5).unwrap();
+			
+			assert_eq!(cached.pairs(), uncached.pairs(), "{} {}", algorithm, mode);
+			assert!(cached.score_cache().unwrap().filled() > 0, "{} {}", algorithm, mode);
+		}
+	}
+}
+
+#[test]
+fn the_score_cache_is_skipped_above_its_limit() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(100).complexity(2).seed(4).build().unwrap()).unwrap();
+	sample.matching_config.score_cache = ScoreCacheMode::Eager;
+	sample.matching_config.score_cache_max_entries = 100 * 100 - 1;
+	sample.match_making().unwrap();
+	assert!(sample.score_cache().is_none());
+	
+	sample.matching_config.score_cache_max_entries = 100 * 100;
+	sample.match_making().unwrap();
+	assert!(sample.score_cache().is_some());
+	
+	sample.set_scoring_strategy(std::sync::Arc::new(dating_simulation::MinAttribute));
+	assert!(sample.score_cache().is_none());
+}
+
+#[test]
+fn random_matching_pairs_everyone_it_can_and_scores_below_greedy() {
+	let config = SampleConfig::builder().population(1000).complexity(3).seed(52).build().unwrap();
+	
+	let mut random = Sample::generate(&config).unwrap();
+	random.matching_config.algorithm = MatchingAlgorithm::Random;
+	random.match_making().unwrap();
+	
+	let males = random.of_gender(Gender::Male).count();
+	let females = random.of_gender(Gender::Female).count();
+	assert_eq!(random.pairs().len(), males.min(females));
+	
+	let mut greedy = Sample::generate(&config).unwrap();
+	greedy.match_making().unwrap();
+	
+	let random_score = random.statistics().mean_candidate_score.unwrap();
+	let greedy_score = greedy.statistics().mean_candidate_score.unwrap();
+	assert!(random_score < greedy_score, "{} {}", random_score, greedy_score);
+}
+
+/// every male scores the females and the other way round, the sums of the two scores are
+///
+/// |    | f0 | f1 | f2 | f3 |
+/// |----|----|----|----|----|
+/// | m0 | 14 |  1 | 10 | 10 |
+/// | m1 | 47 | 30 | 54 | 55 |
+/// | m2 | 19 |  1 |  7 |  9 |
+/// | m3 | 34 | 30 | 57 | 56 |
+///
+/// m0-f1, m1-f3, m2-f0 and m3-f2 sum to 132, the best of the 24 pairings, the next best sums to 130
+fn assignment_fixture() -> Sample {
+	let sample = Sample::from_individuals(vec![
+		individual("m0", Gender::Male, vec![1.0, 0.0], vec![5.0, 2.0]),
+		individual("m1", Gender::Male, vec![3.0, 3.0], vec![8.0, 7.0]),
+		individual("m2", Gender::Male, vec![1.0, 0.0], vec![8.0, 1.0]),
+		individual("m3", Gender::Male, vec![3.0, 3.0], vec![1.0, 8.0]),
+		individual("f0", Gender::Female, vec![2.0, 1.0], vec![2.0, 6.0]),
+		individual("f1", Gender::Female, vec![0.0, 0.0], vec![1.0, 9.0]),
+		individual("f2", Gender::Female, vec![0.0, 3.0], vec![4.0, 7.0]),
+		individual("f3", Gender::Female, vec![0.0, 1.0], vec![8.0, 8.0])
+	]);
+	
+	return sample;
+}
+
+#[test]
+fn optimal_matching_maximizes_the_total_welfare() {
+	let mut sample = assignment_fixture();
+	sample.matching_config.algorithm = MatchingAlgorithm::Optimal;
+	sample.match_making().unwrap();
+	
+	for (male, female) in [("m0", "f1"), ("m1", "f3"), ("m2", "f0"), ("m3", "f2")] {
+		assert_eq!(sample.get(male).unwrap().candidate, Some(PersonId::from(female)), "{}", male);
+		assert_eq!(sample.get(female).unwrap().candidate, Some(PersonId::from(male)), "{}", female);
+	}
+	assert_eq!(sample.statistics().total_welfare, 132.0);
+	
+	let mut greedy = assignment_fixture();
+	greedy.match_making().unwrap();
+	assert!(greedy.statistics().total_welfare < 132.0);
+}
+
+#[test]
+fn optimal_matching_beats_greedy_on_a_generated_population() {
+	let config = SampleConfig::builder().population(300).complexity(3).seed(53).build().unwrap();
+	
+	let mut optimal = Sample::generate(&config).unwrap();
+	optimal.matching_config.algorithm = MatchingAlgorithm::Optimal;
+	optimal.run(3).unwrap();
+	
+	let mut greedy = Sample::generate(&config).unwrap();
+	greedy.run(3).unwrap();
+	
+	let optimal_welfare = optimal.statistics().total_welfare;
+	let greedy_welfare = greedy.statistics().total_welfare;
+	assert!(optimal_welfare >= greedy_welfare, "{} {}", optimal_welfare, greedy_welfare);
+	assert!(optimal.validate().is_ok());
+	
+	optimal.matching_config.optimal_max_population = 299;
+	let error = optimal.match_making().unwrap_err();
+	assert!(error.to_string().contains("optimal_max_population"), "{}", error);
+}
+
+#[test]
+fn a_rejection_expires_after_the_blacklist_duration() {
+	for duration in [Some(2), None] {
+		// `f0` turns `m0` down for `m1` in the first round, then `m1` leaves her
+		let mut sample = Sample::from_individuals(vec![
+			individual("m1", Gender::Male, vec![1.0], vec![9.0]),
+			individual("m0", Gender::Male, vec![1.0], vec![1.0]),
+			individual("f0", Gender::Female, vec![1.0], vec![5.0])
+		]);
+		sample.matching_config.blacklist_duration_rounds = duration;
+		sample.match_making().unwrap();
+		assert_eq!(sample.get("m0").unwrap().extras().blacklist.get(&PersonId::from("f0")), Some(&1));
+		
+		sample.population.retain(|individual| individual.identity != "m1");
+		sample.rebuild_index();
+		let f0 = sample.get_mut("f0").unwrap();
+		f0.candidate = None;
+		f0.candidate_score = None;
+		
+		sample.match_making().unwrap();
+		assert_eq!(sample.get("m0").unwrap().candidate, None, "{:?}", duration);
+		
+		sample.match_making().unwrap();
+		let expected = duration.map(|_| PersonId::from("f0"));
+		assert_eq!(sample.get("m0").unwrap().candidate, expected, "{:?}", duration);
+	}
+}
+
+#[test]
+fn zero_score_noise_matches_a_run_without_noise() {
+	for algorithm in [MatchingAlgorithm::Greedy, MatchingAlgorithm::DeferredAcceptance, MatchingAlgorithm::ParallelProposal] {
+		let config = SampleConfig::builder().population(200).complexity(3).seed(58).build().unwrap();
+		let mut without_noise = Sample::generate(&config).unwrap();
+		let mut zero_noise = Sample::generate(&config).unwrap();
+		without_noise.matching_config.algorithm = algorithm;
+		zero_noise.matching_config.algorithm = algorithm;
+		zero_noise.matching_config.score_noise = ScoreNoise::Gaussian { std_dev: 0.0 };
+		
+		let without_noise_stats = without_noise.run(5).unwrap();
+		let zero_noise_stats = zero_noise.run(5).unwrap();
+		
+		assert_eq!(zero_noise.population, without_noise.population, "{}", algorithm);
+		assert_eq!(zero_noise_stats, without_noise_stats, "{}", algorithm);
+	}
+}
+
+#[test]
+fn noisy_scores_repeat_under_the_same_seed() {
+	let config = SampleConfig::builder().population(200).complexity(3).seed(59).build().unwrap();
+	let noisy = || {
+		let mut sample = Sample::generate(&config).unwrap();
+		sample.matching_config.score_noise = ScoreNoise::Gaussian { std_dev: 0.5 };
+		sample.run(3).unwrap();
+		
+		return sample;
+	};
+	let first = noisy();
+	assert_eq!(first.population, noisy().population);
+	
+	// the partners are held with a score that strays from what either of them computes
+	let perceived_differently = first.population
+		.iter()
+		.filter(|individual| individual.candidate_score.is_some())
+		.any(|individual| {
+			let partner = first.get_by_id(individual.candidate.unwrap()).unwrap();
+			let computed = [individual.score(partner).unwrap(), partner.score(individual).unwrap()];
+			
+			return !computed.contains(&individual.candidate_score.unwrap());
+		});
+	assert!(perceived_differently);
+}
+
+#[test]
+fn a_rejection_within_the_noise_does_not_blacklist() {
+	// `f0` demands 5 and `m0` scores 4.5 with her, half a point short
+	let fixture = |std_dev: f32| {
+		let mut f0 = individual("f0", Gender::Female, vec![1.0], vec![5.0]);
+		f0.min_acceptable_score = Some(5.0);
+		let mut sample = Sample::from_individuals(vec![
+			individual("m0", Gender::Male, vec![1.0], vec![4.5]),
+			f0
+		]);
+		sample.seed = 1;
+		sample.matching_config.score_noise = ScoreNoise::Gaussian { std_dev: std_dev };
+		sample.match_making().unwrap();
+		
+		return sample;
+	};
+	
+	let noisy = fixture(1.0);
+	let m0 = noisy.get("m0").unwrap();
+	assert_eq!(m0.rejections_received, 1);
+	assert!(m0.extras().blacklist.is_empty());
+	
+	let precise = fixture(0.01);
+	let m0 = precise.get("m0").unwrap();
+	assert_eq!(m0.rejections_received, 1);
+	assert!(m0.extras().blacklist.contains_key(&PersonId::from("f0")));
+}
+
+#[test]
+fn a_smaller_evaluation_budget_takes_longer_to_converge() {
+	// proposers that accept anyone would trade their partner for whoever they draw next,
+	// with mutual acceptance they only move on to someone better
+	for algorithm in [MatchingAlgorithm::Greedy, MatchingAlgorithm::ParallelProposal] {
+		let mut rounds = Vec::new();
+		for budget in [None, Some(5), Some(1)] {
+			let mut sample = Sample::generate(&SampleConfig::builder().population(40).complexity(3).seed(60).build().unwrap()).unwrap();
+			sample.matching_config.algorithm = algorithm;
+			sample.matching_config.max_evaluations_per_round = budget;
+			sample.matching_config.mutual_acceptance = true;
+			sample.matching_config.validate_rounds = true;
+			sample.convergence_rounds = Some(3);
+			
+			let report = sample.run(1000).unwrap();
+			assert_eq!(report.stop_reason, StopReason::Converged, "{} {:?}", algorithm, budget);
+			assert!(!sample.pairs().is_empty());
+			rounds.push(report.rounds_executed);
+		}
+		
+		assert!(rounds.windows(2).all(|pair| pair[0] <= pair[1]), "{} {:?}", algorithm, rounds);
+	}
+}
+
+#[test]
+fn leaving_out_the_matched_pairs_the_same_as_scanning_everyone() {
+	// a matched proposer only walks past the reviewers who turned them down back to their partner,
+	// someone who broke up or was left for another is single and proposes again
+	for breakup_probability in [0.0, 0.3] {
+		let config = SampleConfig::builder().population(200).complexity(3).seed(98).build().unwrap();
+		let mut everyone = Sample::generate(&config).unwrap();
+		everyone.churn.breakup_probability = breakup_probability;
+		let mut only_unmatched = everyone.clone();
+		only_unmatched.matching_config.only_unmatched = true;
+		only_unmatched.matching_config.validate_rounds = true;
+		
+		for round in 1..=10 {
+			let (all_stats, unmatched_stats) = (everyone.match_making().unwrap(), only_unmatched.match_making().unwrap());
+			assert_eq!(all_stats.delta, unmatched_stats.delta, "round {} {}", round, breakup_probability);
+			assert_eq!(all_stats.breakups, unmatched_stats.breakups);
+		}
+		assert_eq!(everyone.pairs(), only_unmatched.pairs());
+		
+		let evaluations = |sample: &Sample| sample.population.iter().map(|individual| individual.times_evaluated).sum::<u64>();
+		assert!(evaluations(&only_unmatched) < evaluations(&everyone), "{}", breakup_probability);
+	}
+	
+	let mut deferred = unstable_fixture();
+	deferred.matching_config.algorithm = MatchingAlgorithm::DeferredAcceptance;
+	deferred.matching_config.only_unmatched = true;
+	assert!(matches!(deferred.match_making(), Err(SimulationError::InvalidConfig(_))));
+}
+
+#[test]
+fn an_evaluation_budget_needs_an_algorithm_that_samples() {
+	let mut sample = unstable_fixture();
+	sample.matching_config.algorithm = MatchingAlgorithm::DeferredAcceptance;
+	sample.matching_config.max_evaluations_per_round = Some(1);
+	
+	assert!(matches!(sample.match_making(), Err(SimulationError::InvalidConfig(_))));
+}
+
+#[test]
+fn speed_dating_pairs_those_who_take_each_other() {
+	// `m0` meets the only female, she demands more than he brings
+	let mut f0 = individual("f0", Gender::Female, vec![1.0], vec![5.0]);
+	f0.min_acceptable_score = Some(5.0);
+	let mut sample = Sample::from_individuals(vec![
+		individual("m0", Gender::Male, vec![1.0], vec![4.0]),
+		f0
+	]);
+	sample.matching_config.algorithm = MatchingAlgorithm::SpeedDating;
+	sample.match_making().unwrap();
+	
+	assert!(sample.pairs().is_empty());
+	let m0 = sample.get("m0").unwrap();
+	assert_eq!(m0.rejections_received, 1);
+	assert!(m0.extras().blacklist.contains_key(&PersonId::from("f0")));
+	
+	// the one left over sits the round out
+	let mut sample = Sample::from_individuals(vec![
+		individual("m0", Gender::Male, vec![1.0], vec![4.0]),
+		individual("m1", Gender::Male, vec![1.0], vec![4.0]),
+		individual("f0", Gender::Female, vec![1.0], vec![5.0])
+	]);
+	sample.matching_config.algorithm = MatchingAlgorithm::SpeedDating;
+	let round_stats = sample.match_making().unwrap();
+	
+	assert_eq!(sample.pairs().len(), 1);
+	assert_eq!(round_stats.blacklist_growth, 0);
+	assert_eq!(sample.population.iter().map(|individual| individual.rejections_received).sum::<u32>(), 0);
+}
+
+#[test]
+fn speed_dating_takes_longer_to_settle_than_the_greedy_scan() {
+	// with a minimum to clear, a single meeting per round rarely finds someone who takes them
+	let rounds_to_converge = |algorithm: MatchingAlgorithm| {
+		let config = SampleConfig::builder()
+			.population(60)
+			.complexity(3)
+			.threshold_distribution(ThresholdDistribution::OwnScore { factor: 0.8 })
+			.seed(61)
+			.build()
+			.unwrap();
+		let mut sample = Sample::generate(&config).unwrap();
+		sample.matching_config.algorithm = algorithm;
+		sample.matching_config.validate_rounds = true;
+		sample.convergence_rounds = Some(5);
+		
+		let report = sample.run(1000).unwrap();
+		assert_eq!(report.stop_reason, StopReason::Converged, "{}", algorithm);
+		
+		return report.rounds_executed;
+	};
+	
+	assert!(rounds_to_converge(MatchingAlgorithm::SpeedDating) > rounds_to_converge(MatchingAlgorithm::Greedy));
+}
+
+#[test]
+fn desirable_reviewers_get_more_of_the_attention() {
+	let attention = |encounters: Encounters| {
+		let mut sample = Sample::generate(&SampleConfig::builder().population(300).complexity(3).seed(62).build().unwrap()).unwrap();
+		sample.matching_config.max_evaluations_per_round = Some(3);
+		sample.matching_config.mutual_acceptance = true;
+		sample.matching_config.encounters = encounters;
+		sample.run(5).unwrap();
+		
+		let report = sample.desirability_report();
+		let females: Vec<(f32, f32)> = sample.population
+			.iter()
+			.zip(&report.individuals)
+			.filter(|(individual, _)| individual.gender == Gender::Female)
+			.map(|(individual, desirability)| (desirability.desirability, individual.times_evaluated as f32))
+			.collect();
+		
+		return (sample.statistics().attention_gini.unwrap(), pearson_correlation(&females).unwrap());
+	};
+	
+	let (uniform_gini, uniform_correlation) = attention(Encounters::Uniform);
+	let (weighted_gini, weighted_correlation) = attention(Encounters::Desirability);
+	assert!(weighted_gini > uniform_gini, "{} {}", weighted_gini, uniform_gini);
+	assert!(weighted_correlation > 0.5, "{}", weighted_correlation);
+	assert!(weighted_correlation > uniform_correlation);
+	
+	// weighted encounters only mean something when not everyone is met
+	let mut sample = unstable_fixture();
+	sample.matching_config.encounters = Encounters::Desirability;
+	assert!(matches!(sample.match_making(), Err(SimulationError::InvalidConfig(_))));
+}
+
+#[test]
+fn the_history_keeps_every_proposal_in_order() {
+	let mut sample = Sample::from_individuals(vec![
+		individual("m0", Gender::Male, vec![1.0, 1.0], vec![10.0, 5.0]),
+		individual("m1", Gender::Male, vec![1.0, 1.0], vec![4.0, 7.0]),
+		individual("f0", Gender::Female, vec![1.0, 0.1], vec![5.0, 5.0])
+	]);
+	sample.matching_config.record_history = true;
+	
+	// `m1` falls short of `m0`, then `f0` starts demanding more than `m0` has on the second attribute
+	sample.match_making().unwrap();
+	sample.get_mut("f0").unwrap().extras_mut().dealbreakers = vec![None, Some(6.0)];
+	sample.match_making().unwrap();
+	
+	let outcomes = |identity: &str| -> Vec<(u32, String, bool, EncounterOutcome)> {
+		return sample.history_of(identity)
+			.unwrap()
+			.iter()
+			.map(|encounter| (encounter.round, encounter.other.to_string(), encounter.proposed, encounter.outcome))
+			.collect();
+	};
+	assert_eq!(outcomes("m1"), vec![
+		(1, String::from("f0"), true, EncounterOutcome::RejectedBelowCandidate),
+		(2, String::from("f0"), true, EncounterOutcome::Blacklisted)
+	]);
+	assert_eq!(outcomes("f0"), vec![
+		(1, String::from("m0"), false, EncounterOutcome::Accepted),
+		(1, String::from("m1"), false, EncounterOutcome::RejectedBelowCandidate),
+		(2, String::from("m0"), false, EncounterOutcome::Dealbreaker),
+		(2, String::from("m1"), false, EncounterOutcome::Blacklisted)
+	]);
+	
+	// the reviewer's score of the proposer, none where it never came to scoring
+	let scores: Vec<Option<f32>> = sample.history_of("m0").unwrap().iter().map(|encounter| encounter.score).collect();
+	assert_eq!(scores, vec![Some(10.5), None]);
+	
+	let json = serde_json::to_value(&sample).unwrap();
+	assert_eq!(json["population"][2]["history"].as_array().unwrap().len(), 4);
+	assert_eq!(json["population"][2]["history"][1]["outcome"], "rejected-below-candidate");
+}
+
+#[test]
+fn a_trace_tells_the_proposals_of_one_individual() {
+	let mut sample = Sample::from_individuals(vec![
+		individual("m0", Gender::Male, vec![1.0, 1.0], vec![10.0, 5.0]),
+		individual("m1", Gender::Male, vec![1.0, 1.0], vec![4.0, 7.0]),
+		individual("f0", Gender::Female, vec![1.0, 0.1], vec![5.0, 5.0])
+	]);
+	sample.matching_config.record_history = true;
+	sample.match_making().unwrap();
+	
+	let trace = sample.trace("m1").unwrap();
+	assert!(trace.contains("Round 1\n"), "{}", trace);
+	assert!(trace.contains("proposed to Female f0, scored 4.70, most for their attribute_1, and was turned down"), "{}", trace);
+	assert!(trace.ends_with("Unmatched\n"), "{}", trace);
+	assert!(sample.trace("f0").unwrap().contains("Matched with Male m0 since round 1"));
+	
+	assert!(matches!(sample.trace("nobody"), Err(SimulationError::UnknownIdentity(identity)) if identity == "nobody"));
+}
+
+/// keeps every event where the test can still read them once the sample owns the sink
+#[derive(Debug, Default)]
+struct MemorySink {
+	events: Arc<Mutex<Vec<SimEvent>>>
+}
+
+impl EventSink for MemorySink {
+	fn emit(&mut self, event: &SimEvent) -> Result<(), SimulationError> {
+		self.events.lock().unwrap().push(event.clone());
+		
+		return Ok(());
+	}
+}
+
+#[test]
+fn a_round_emits_its_events_in_order() {
+	let mut sample = Sample::from_individuals(vec![
+		individual("m0", Gender::Male, vec![1.0, 1.0], vec![10.0, 5.0]),
+		individual("m1", Gender::Male, vec![1.0, 1.0], vec![4.0, 7.0]),
+		individual("f0", Gender::Female, vec![1.0, 0.1], vec![5.0, 5.0])
+	]);
+	let sink = MemorySink::default();
+	let events = sink.events.clone();
+	sample.set_event_sink(Box::new(sink));
+	
+	let round_stats = sample.match_making().unwrap();
+	
+	let (m0, m1, f0) = (PersonId::from("m0"), PersonId::from("m1"), PersonId::from("f0"));
+	assert_eq!(*events.lock().unwrap(), vec![
+		SimEvent::RoundStarted { round: 1, population: 3 },
+		SimEvent::Proposal { round: 1, proposer: m0, reviewer: f0, score: 10.5 },
+		SimEvent::Proposal { round: 1, proposer: m1, reviewer: f0, score: 4.7 },
+		SimEvent::Rejection { round: 1, proposer: m1, reviewer: f0, reason: EncounterOutcome::RejectedBelowCandidate },
+		SimEvent::MatchFormed { round: 1, proposer: m0, reviewer: f0, reviewer_score: 10.5, proposer_score: 10.5 },
+		SimEvent::RoundCompleted { round: 1, stats: round_stats }
+	]);
+	// the histories were only kept for the round
+	assert!(sample.population.iter().all(|individual| individual.extras().history.is_empty()));
+}
+
+/// a file that has run out of space
+#[derive(Debug)]
+struct FullDisk;
+
+impl std::io::Write for FullDisk {
+	fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+		return Err(std::io::Error::other("no space left"));
+	}
+	
+	fn flush(&mut self) -> std::io::Result<()> {
+		return Ok(());
+	}
+}
+
+#[test]
+fn an_event_that_can_not_be_written_fails_the_round() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(20).complexity(2).seed(66).build().unwrap()).unwrap();
+	sample.set_event_sink(Box::new(JsonLinesSink::new(FullDisk)));
+	
+	assert!(matches!(sample.match_making(), Err(SimulationError::Io(_))));
+}
+
+#[test]
+fn a_cloned_sample_runs_another_algorithm_on_the_same_people() {
+	let mut greedy = Sample::generate(&SampleConfig::builder().population(80).complexity(3).seed(76).build().unwrap()).unwrap();
+	let mut deferred = greedy.clone();
+	assert!(greedy == deferred);
+	
+	deferred.matching_config.algorithm = MatchingAlgorithm::DeferredAcceptance;
+	greedy.run(5).unwrap();
+	deferred.run(5).unwrap();
+	
+	assert_eq!(greedy.population.len(), deferred.population.len());
+	for (first, second) in greedy.population.iter().zip(&deferred.population) {
+		assert_eq!(first.identity, second.identity);
+		assert_eq!(first.gender, second.gender);
+		assert_eq!(first.orientation, second.orientation);
+		assert_eq!(first.preference_weights, second.preference_weights);
+		assert_eq!(first.ratings, second.ratings);
+		assert_eq!(first.age, second.age);
+	}
+	assert!(greedy != deferred);
+	
+	let mut rematched = greedy.clone();
+	rematched.reset_matches();
+	assert_eq!(rematched.round, 0);
+	assert!(rematched.pairs().is_empty());
+	assert!(rematched.population.iter().all(|individual| individual.extras().blacklist.is_empty() && individual.candidate_score.is_none()));
+	
+	rematched.run(5).unwrap();
+	assert!(rematched == greedy);
+}
+
+#[test]
+fn displayed_matches_filter_limit_and_sort_the_pairs_and_list_the_unmatched_once() {
+	use dating_simulation::{DisplayFilter, DisplayOptions, PairOrder};
+	
+	let sample = Sample::from_individuals(vec![
+		with_candidate(individual("m0", Gender::Male, vec![1.0], vec![5.0]), "f0", 8.0),
+		with_candidate(individual("m1", Gender::Male, vec![1.0], vec![5.0]), "f1", 4.0),
+		with_candidate(individual("m2", Gender::Male, vec![1.0], vec![5.0]), "f2", 6.0),
+		individual("m3", Gender::Male, vec![1.0], vec![5.0]),
+		with_candidate(individual("f0", Gender::Female, vec![1.0], vec![5.0]), "m0", 9.0),
+		with_candidate(individual("f1", Gender::Female, vec![1.0], vec![5.0]), "m1", 3.0),
+		with_candidate(individual("f2", Gender::Female, vec![1.0], vec![5.0]), "m2", 6.0),
+		individual("f3", Gender::Female, vec![1.0], vec![5.0])
+	]);
+	let proposers = |options: DisplayOptions| -> Vec<String> {
+		return sample.displayed_pairs(&options).iter().map(|pair| pair.proposer_id.to_string()).collect();
+	};
+	
+	assert_eq!(proposers(DisplayOptions::default()), vec!["m0", "m2", "m1"]);
+	assert_eq!(proposers(DisplayOptions { order: PairOrder::WorstFirst, ..DisplayOptions::default() }), vec!["m1", "m2", "m0"]);
+	assert_eq!(proposers(DisplayOptions { limit: Some(2), order: PairOrder::WorstFirst, ..DisplayOptions::default() }), vec!["m2", "m0"]);
+	assert!(proposers(DisplayOptions { filter: DisplayFilter::Unmatched, ..DisplayOptions::default() }).is_empty());
+	
+	let unmatched: Vec<String> = sample.unmatched_individuals().iter().map(|individual| individual.identity.to_string()).collect();
+	assert_eq!(unmatched, vec!["m3", "f3"]);
+	
+	let everyone = sample.describe_matches(&DisplayOptions::default());
+	assert_eq!(everyone.matches("Matches of").count(), 3);
+	assert!(everyone.contains("Unmatched (2)"));
+	assert_eq!(everyone.matches(" f3\n").count(), 1);
+	
+	let matched = sample.describe_matches(&DisplayOptions { filter: DisplayFilter::Matched, limit: Some(1), ..DisplayOptions::default() });
+	assert_eq!(matched.matches("Matches of").count(), 1);
+	assert!(matched.contains("Matches of Male m0") && !matched.contains("Unmatched"));
+	
+	let single = sample.describe_matches(&DisplayOptions { filter: DisplayFilter::Unmatched, ..DisplayOptions::default() });
+	assert!(!single.contains("Matches of") && single.contains("Unmatched (2)"));
+}
+
+#[test]
+fn matches_and_statistics_can_be_written_to_any_writer() {
+	use dating_simulation::DisplayOptions;
+	
+	let sample = Sample::from_individuals(vec![
+		with_candidate(individual("m0", Gender::Male, vec![1.0], vec![5.0]), "f0", 8.0),
+		with_candidate(individual("f0", Gender::Female, vec![1.0], vec![5.0]), "m0", 9.0),
+		individual("f1", Gender::Female, vec![1.0], vec![5.0])
+	]);
+	
+	let mut matches: Vec<u8> = Vec::new();
+	sample.write_matches(&mut matches, &DisplayOptions::default()).unwrap();
+	let matches = String::from_utf8(matches).unwrap();
+	assert!(matches.starts_with("============================================\nMatches of Male m0\n"), "{}", matches);
+	assert!(matches.contains("Candidate Score: Some(9.0)"));
+	assert!(matches.contains("Unmatched (1)\n============================================\nFemale f1\n"));
+	assert_eq!(matches, sample.describe_matches(&DisplayOptions::default()));
+	
+	let mut statistics: Vec<u8> = Vec::new();
+	sample.write_statistics(&mut statistics).unwrap();
+	assert_eq!(String::from_utf8(statistics).unwrap(), format!("{}\n", sample.statistics()));
+}
+
+#[test]
+fn a_negative_weight_flips_which_candidate_is_preferred() {
+	// f0 likes the first attribute, with a negative second weight she dislikes the second one
+	let fixture = |weights: Vec<f32>| Sample::from_individuals(vec![
+		individual("m0", Gender::Male, vec![1.0, 1.0], vec![8.0, 9.0]),
+		individual("m1", Gender::Male, vec![1.0, 1.0], vec![6.0, 2.0]),
+		individual("f0", Gender::Female, weights, vec![5.0, 5.0])
+	]);
+	let partner_of_f0 = |sample: &Sample| sample.get("f0").unwrap().candidate;
+	
+	for algorithm in [MatchingAlgorithm::Greedy, MatchingAlgorithm::DeferredAcceptance, MatchingAlgorithm::ParallelProposal] {
+		let mut liking = fixture(vec![1.0, 1.0]);
+		liking.matching_config.algorithm = algorithm;
+		liking.run(3).unwrap();
+		assert_eq!(partner_of_f0(&liking), Some(PersonId::from("m0")), "{:?}", algorithm);
+		
+		let mut disliking = fixture(vec![1.0, -1.0]);
+		disliking.matching_config.algorithm = algorithm;
+		disliking.run(3).unwrap();
+		assert_eq!(partner_of_f0(&disliking), Some(PersonId::from("m1")), "{:?}", algorithm);
+		
+		// every score she gives is negative and without a minimum she still takes the least disliked
+		let mut negative = fixture(vec![-1.0, -1.0]);
+		negative.matching_config.algorithm = algorithm;
+		negative.run(3).unwrap();
+		assert_eq!(partner_of_f0(&negative), Some(PersonId::from("m1")), "{:?}", algorithm);
+		assert_eq!(negative.get("f0").unwrap().candidate_score, Some(-8.0));
+		
+		let statistics = negative.statistics();
+		let distribution = &statistics.score_distribution_by_gender[&Gender::Female];
+		assert_eq!(distribution.min, -8.0);
+		assert_eq!(distribution.histogram.iter().sum::<usize>(), 1);
+		assert!(statistics.render_histogram().contains("-8.000"));
+	}
+}
+
+#[test]
+fn selectivity_demands_more_of_negative_scores_too() {
+	let mut picky = individual("f0", Gender::Female, vec![-1.0], vec![5.0]);
+	picky.min_acceptable_score = Some(-4.0);
+	picky.selectivity = 2.0;
+	
+	assert_eq!(picky.demanded_score(), -2.0);
+	assert!(picky.turns_down(-3.0, 1));
+	assert!(!picky.turns_down(-1.0, 1));
+	
+	picky.min_acceptable_score = None;
+	assert!(!picky.turns_down(-1000.0, 1));
+	assert_eq!(picky.selective(3.0), 6.0);
+}
+
+#[test]
+fn scores_within_the_epsilon_keep_the_incumbent_and_blacklist_nobody() {
+	// `m1` scores a hair above `m0` with `f0`, far less than the epsilon
+	let fixture = || -> Sample {
+		return Sample::from_individuals(vec![
+			individual("m0", Gender::Male, vec![1.0], vec![1.0]),
+			individual("m1", Gender::Male, vec![1.0], vec![1.000_000_2]),
+			individual("f0", Gender::Female, vec![1.0], vec![1.0])
+		]);
+	};
+	let partner_of_f0 = |sample: &Sample| sample.get("f0").unwrap().candidate.map(|candidate| candidate.to_string());
+	let blacklisted = |sample: &Sample| sample.population.iter().map(|individual| individual.extras().blacklist.len()).sum::<usize>();
+	
+	// without one the newcomer takes over
+	let mut sample = fixture();
+	sample.match_making().unwrap();
+	assert_eq!(partner_of_f0(&sample), Some(String::from("m1")));
+	
+	for algorithm in [MatchingAlgorithm::Greedy, MatchingAlgorithm::DeferredAcceptance, MatchingAlgorithm::ParallelProposal] {
+		let mut sample = fixture();
+		sample.matching_config.algorithm = algorithm;
+		sample.matching_config.score_epsilon = 1e-6;
+		sample.matching_config.record_history = true;
+		sample.match_making().unwrap();
+		
+		// `m0` proposed first and stays, in parallel proposal both are new and the lower identity wins
+		assert_eq!(partner_of_f0(&sample), Some(String::from("m0")), "{}", algorithm);
+		assert_eq!(blacklisted(&sample), 0, "{}", algorithm);
+		let outcomes: Vec<EncounterOutcome> = sample.history_of("m1").unwrap().iter().map(|encounter| encounter.outcome).collect();
+		assert_eq!(outcomes, vec![EncounterOutcome::Tied], "{}", algorithm);
+		
+		// nor does `m1` get further the round after
+		sample.match_making().unwrap();
+		assert_eq!(partner_of_f0(&sample), Some(String::from("m0")), "{}", algorithm);
+		assert_eq!(blacklisted(&sample), 0, "{}", algorithm);
+	}
+	
+	let mut sample = fixture();
+	sample.matching_config.score_epsilon = -1.0;
+	assert!(matches!(sample.match_making(), Err(SimulationError::InvalidConfig(_))));
+}
+
+#[test]
+fn a_pool_where_nobody_can_be_paired_fails_the_round() {
+	let female = |identity: &str| individual(identity, Gender::Female, vec![1.0], vec![1.0]);
+	let male = |identity: &str| individual(identity, Gender::Male, vec![1.0], vec![1.0]);
+	// `Some` with the missing gender, if any, when the round fails
+	let missing_with = |algorithm: MatchingAlgorithm, population: Vec<Individual>| -> Option<Option<Gender>> {
+		let mut sample = Sample::from_individuals(population);
+		sample.matching_config.algorithm = algorithm;
+		return match sample.match_making() {
+			Err(SimulationError::EmptyPopulation { gender }) => {
+				assert_eq!(sample.round, 0, "the failed round changed the sample");
+				Some(gender)
+			},
+			result => {
+				result.unwrap();
+				None
+			}
+		};
+	};
+	let missing = |population: Vec<Individual>| missing_with(MatchingAlgorithm::Greedy, population);
+	
+	assert_eq!(missing(vec![female("f0"), female("f1")]), Some(Some(Gender::Male)));
+	assert_eq!(missing(vec![male("m0"), male("m1")]), Some(Some(Gender::Female)));
+	// the proposers are missing from an empty pool
+	assert_eq!(missing(Vec::new()), Some(Some(Gender::Male)));
+	assert_eq!(missing(vec![male("m0"), female("f0")]), None);
+	
+	// two homosexual females can be paired without anyone else, a lone one is not into anyone there
+	let homosexual = |mut individual: Individual| -> Individual {
+		individual.orientation = Orientation::Homosexual;
+		
+		return individual;
+	};
+	assert_eq!(missing(vec![homosexual(female("f2")), homosexual(female("f3"))]), None);
+	assert_eq!(missing(vec![homosexual(female("f2"))]), Some(None));
+	// both genders are there, but nobody is into anyone who would have them
+	assert_eq!(missing(vec![homosexual(male("m0")), female("f0"), female("f1")]), Some(None));
+	// the two-sided algorithms only pair the proposing gender with the others
+	assert_eq!(missing_with(MatchingAlgorithm::DeferredAcceptance, vec![homosexual(female("f2")), homosexual(female("f3"))]), Some(None));
+	assert_eq!(missing_with(MatchingAlgorithm::DeferredAcceptance, vec![male("m0"), female("f0")]), None);
+}
@@ -0,0 +1,105 @@
+#![allow(clippy::needless_return, clippy::redundant_field_names)]
+
+use std::collections::HashSet;
+
+use dating_simulation::testing;
+use dating_simulation::{Individual, MatchingAlgorithm, SimulationError};
+use proptest::prelude::*;
+
+fn two_individuals() -> impl Strategy<Value = (Individual, Individual)> {
+	return (1usize..6, 1usize..6).prop_flat_map(|(a, b)| (testing::individual(a), testing::individual(b)));
+}
+
+fn algorithm() -> impl Strategy<Value = MatchingAlgorithm> {
+	return proptest::sample::select(vec![
+		MatchingAlgorithm::Greedy,
+		MatchingAlgorithm::DeferredAcceptance,
+		MatchingAlgorithm::ParallelProposal,
+		MatchingAlgorithm::Random,
+		MatchingAlgorithm::SpeedDating,
+		MatchingAlgorithm::Optimal
+	]);
+}
+
+proptest! {
+	#[test]
+	fn a_score_fails_exactly_when_the_attribute_counts_differ((a, b) in two_individuals()) {
+		let mismatched = a.preference_weights.len() != b.ratings.len();
+		
+		prop_assert_eq!(a.score(&b).is_err(), mismatched);
+		prop_assert_eq!(b.score(&a).is_err(), mismatched);
+	}
+	
+	#[test]
+	fn a_score_of_non_negative_weights_and_ratings_is_non_negative(a in testing::individual(4), b in testing::individual(4)) {
+		prop_assert!(a.score(&b).unwrap() >= 0.0);
+	}
+	
+	#[test]
+	fn a_score_scales_linearly_with_the_weights(a in testing::individual(4), b in testing::individual(4), factor in 0.0f32..10.0) {
+		let mut scaled = a.clone();
+		scaled.preference_weights = a.preference_weights.iter().map(|weight| weight * factor).collect();
+		let (score, scaled_score) = (a.score(&b).unwrap(), scaled.score(&b).unwrap());
+		
+		prop_assert!((scaled_score - factor * score).abs() <= 1e-4 * (1.0 + factor * score), "{} against {} times {}", scaled_score, factor, score);
+	}
+	
+	#[test]
+	fn a_wide_score_matches_the_plain_sum((a, b) in (0usize..130).prop_flat_map(|attributes| (testing::individual(attributes), testing::individual(attributes)))) {
+		let plain: f32 = a.preference_weights.iter().zip(&b.ratings).map(|(weight, rating)| weight * rating).sum();
+		let score = a.score(&b).unwrap();
+		
+		prop_assert!((score - plain).abs() <= wide_score_tolerance(a.ratings.len(), plain), "{} against {} over {} attributes", score, plain, a.ratings.len());
+	}
+}
+
+/// how far two sums of `attributes` non-negative products can drift apart as they add them up in different orders,
+/// each one rounds at most once per addition and once per product
+fn wide_score_tolerance(attributes: usize, sum: f32) -> f32 {
+	return attributes as f32 * f32::EPSILON * sum;
+}
+
+/// one past a multiple of the lanes, so the chunks and the remainder are both added up
+#[test]
+fn a_33_attribute_score_stays_within_the_tolerance_of_the_exact_sum() {
+	let weights: Vec<f32> = (0..33).map(|attribute| 0.1 * (attribute + 1) as f32).collect();
+	let ratings: Vec<f32> = (0..33).map(|attribute| Individual::MIN_RATING + 0.3 * (attribute % 7) as f32).collect();
+	let individual = |identity: &str| Individual::builder()
+		.identity(identity)
+		.preference_weights(weights.clone())
+		.ratings(ratings.clone())
+		.build()
+		.unwrap();
+
+	let exact: f64 = weights.iter().zip(&ratings).map(|(weight, rating)| *weight as f64 * *rating as f64).sum();
+	let score = individual("a").score(&individual("b")).unwrap();
+
+	assert!((score as f64 - exact).abs() <= wide_score_tolerance(33, exact as f32) as f64, "{} against {}", score, exact);
+}
+
+proptest! {
+	#![proptest_config(ProptestConfig::with_cases(64))]
+	
+	#[test]
+	fn every_round_leaves_the_sample_consistent(mut sample in testing::sample(0..40, 3), algorithm in algorithm(), rounds in 1u32..5) {
+		sample.matching_config.algorithm = algorithm;
+		
+		for _ in 0..rounds {
+			let round = sample.round;
+			match sample.match_making() {
+				Ok(_) => {},
+				// a pool where nobody can be paired fails the round before anything changes
+				Err(SimulationError::EmptyPopulation { .. }) => prop_assert_eq!(sample.round, round),
+				Err(error) => prop_assert!(false, "{}", error)
+			}
+			let validation = sample.validate();
+			prop_assert!(validation.is_ok(), "{:?}", validation);
+			
+			let mut paired = HashSet::new();
+			for pair in sample.pairs() {
+				prop_assert_ne!(pair.proposer_id, pair.reviewer_id);
+				prop_assert!(paired.insert(pair.proposer_id) && paired.insert(pair.reviewer_id), "{:?} is in more than one pair", pair);
+			}
+		}
+	}
+}
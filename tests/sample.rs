@@ -0,0 +1,978 @@
+#![allow(clippy::needless_return, clippy::redundant_field_names)]
+
+use dating_simulation::{AgeConfig, AttributeSchema, AttributeSpec, CancellationToken, CategoricalAttribute, CategoryPreference, ChurnConfig, CorrelationMatrix, Gender, GenderDistribution, Individual, MatchingConfig, NoOp, Orientation, OrientationDistribution, PersonId, PreferenceDrift, Preset, RatingDistribution, ReproductionConfig, Sample, SampleConfig, SchemaError, ScoreCacheMode, SelectivityConfig, SimulationConfig, SimulationError, StopReason, ThresholdDistribution, WeightDistribution};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+#[test]
+fn same_seed_generates_identical_populations() {
+	let first = Sample::generate(&SampleConfig::builder().population(200).complexity(3).seed(42).build().unwrap()).unwrap();
+	let second = Sample::generate(&SampleConfig::builder().population(200).complexity(3).seed(42).build().unwrap()).unwrap();
+	
+	assert_eq!(first.seed, second.seed);
+	assert_eq!(first.population, second.population);
+}
+
+#[test]
+fn gender_distribution_decides_the_realized_ratio() {
+	let gender_distribution = GenderDistribution {
+		male: 70.0,
+		female: 30.0,
+		non_binary: 0.0
+	};
+	let sample = Sample::generate(&SampleConfig::builder().population(10_000).complexity(1).gender_distribution(gender_distribution).seed(19).build().unwrap()).unwrap();
+	
+	let male_share = sample.count(Gender::Male) as f64 / sample.population.len() as f64;
+	
+	assert_eq!(sample.population.len(), 10_000);
+	assert!((male_share - 0.7).abs() < 0.02, "male share {}", male_share);
+	assert_eq!(sample.count(Gender::NonBinary), 0);
+}
+
+#[test]
+fn non_binary_individuals_are_matched() {
+	let gender_distribution = GenderDistribution {
+		male: 1.0,
+		female: 1.0,
+		non_binary: 1.0
+	};
+	let mut sample = Sample::generate(&SampleConfig::builder().population(300).complexity(3).gender_distribution(gender_distribution).seed(20).build().unwrap()).unwrap();
+	
+	let round_stats = sample.match_making().unwrap();
+	let statistics = sample.statistics();
+	
+	assert!(round_stats.matched_non_binary > 0);
+	assert_eq!(statistics.matched_non_binary + statistics.unmatched_non_binary, sample.count(Gender::NonBinary));
+	assert_eq!(sample.validate(), Ok(()));
+}
+
+#[test]
+fn match_making_runs_without_progress() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(100).complexity(3).seed(7).build().unwrap()).unwrap();
+	
+	sample.match_making().unwrap();
+	
+	let matched = sample.of_gender(Gender::Female)
+		.filter(|female_individual| female_individual.candidate.is_some())
+		.count();
+	assert!(matched > 0);
+}
+
+#[test]
+fn run_collects_stats_for_every_round() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(100).complexity(3).seed(8).build().unwrap()).unwrap();
+	
+	let round_stats = sample.run(3).unwrap().round_stats;
+	
+	assert_eq!(sample.round, 3);
+	assert_eq!(
+		round_stats.iter().map(|stats| stats.round).collect::<Vec<u32>>(), 
+		vec![1, 2, 3]
+	);
+	for stats in &round_stats {
+		assert_eq!(stats.matched_males + stats.unmatched_males, sample.count(Gender::Male));
+		assert_eq!(stats.matched_females + stats.unmatched_females, sample.count(Gender::Female));
+	}
+	
+	let mut output: Vec<u8> = Vec::new();
+	for stats in &round_stats {
+		stats.export_csv(&mut output).unwrap();
+	}
+	assert_eq!(String::from_utf8(output).unwrap().lines().count(), 3);
+}
+
+#[test]
+fn individuals_can_be_looked_up_by_identity() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(50).complexity(3).seed(9).build().unwrap()).unwrap();
+	let first_identity = sample.population[3].identity;
+	let second_identity = sample.population[5].identity;
+	
+	assert_eq!(sample.get(&first_identity.as_str()), Some(&sample.population[3]));
+	assert_eq!(sample.get_by_id(second_identity), Some(&sample.population[5]));
+	assert_eq!(sample.get("no-such-identity"), None);
+	
+	sample.get_mut(&second_identity.as_str()).unwrap().candidate = Some(first_identity);
+	assert_eq!(sample.population[5].candidate, Some(first_identity));
+}
+
+#[test]
+fn normal_ratings_have_the_configured_mean_and_spread() {
+	let rating_distribution = RatingDistribution::Normal {
+		mean: 5.5,
+		std_dev: 1.5
+	};
+	let sample = Sample::generate(&SampleConfig::builder().population(100_000).attribute_schema(AttributeSchema::anonymous(1, rating_distribution)).seed(21).build().unwrap()).unwrap();
+	
+	let ratings: Vec<f64> = sample.population
+		.iter()
+		.map(|individual| individual.ratings[0] as f64)
+		.collect();
+	let mean = ratings.iter().sum::<f64>() / ratings.len() as f64;
+	let variance = ratings.iter().map(|rating| (rating - mean).powi(2)).sum::<f64>() / ratings.len() as f64;
+	
+	assert!((mean - 5.5).abs() < 0.02, "mean {}", mean);
+	assert!((variance.sqrt() - 1.5).abs() < 0.03, "standard deviation {}", variance.sqrt());
+	assert_eq!(sample.attribute_schema.attributes[0].distribution, rating_distribution);
+}
+
+/// `looks` on the usual scale and `humor` on a narrower one
+fn named_schema() -> AttributeSchema {
+	let mut humor = AttributeSpec::new("humor", RatingDistribution::Normal { mean: 4.0, std_dev: 2.0 });
+	humor.min = 2.0;
+	humor.max = 6.0;
+	
+	return AttributeSchema {
+		attributes: vec![
+			AttributeSpec::new("looks", RatingDistribution::default()),
+			humor
+		],
+		correlation: None,
+		categorical: Vec::new()
+	};
+}
+
+#[test]
+fn ratings_follow_the_attribute_schema() {
+	let sample = Sample::generate(&SampleConfig::builder().population(200).attribute_schema(named_schema()).seed(22).build().unwrap()).unwrap();
+	
+	for individual in &sample.population {
+		assert_eq!(individual.ratings.len(), 2);
+		assert!((2.0..=6.0).contains(&individual.ratings[1]));
+	}
+	
+	let description = sample.population[0].describe(&sample.attribute_schema);
+	assert!(description.contains(&format!("Ratings: looks: {:.2}, humor: {:.2}", sample.population[0].ratings[0], sample.population[0].ratings[1])));
+}
+
+#[test]
+#[allow(deprecated)]
+fn predefined_weights_must_cover_the_schema() {
+	let result = Sample::new(10, &named_schema(), Some(vec![1.0]), &GenderDistribution::default(), &OrientationDistribution::default(), &ThresholdDistribution::default(), &AgeConfig::default(), Some(22), false);
+	
+	assert_eq!(result.unwrap_err(), SchemaError::WeightCountMismatch { attributes: 2, weights: 1 });
+}
+
+#[test]
+fn run_stops_once_the_pairs_settle() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(20).complexity(3).seed(3).build().unwrap()).unwrap();
+	sample.convergence_rounds = Some(2);
+	
+	let report = sample.run(100).unwrap();
+	
+	assert_eq!(report.stop_reason, StopReason::Converged);
+	assert!(report.rounds_executed < 20);
+	assert_eq!(report.rounds_executed, sample.round);
+	assert_eq!(report.round_stats.len() as u32, report.rounds_executed);
+	
+	// the last two rounds changed nothing, the one before did
+	let (changing, settled) = report.round_stats.split_at(report.round_stats.len() - 2);
+	assert!(settled.iter().all(|stats| !stats.delta.changed()));
+	assert!(changing.last().unwrap().delta.changed());
+}
+
+#[test]
+fn a_cancelled_run_stops_after_the_current_round() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(50).complexity(3).seed(4).build().unwrap()).unwrap();
+	let cancellation = CancellationToken::new();
+	
+	let report = sample.run_until(10, &cancellation, |_, _| cancellation.cancel()).unwrap();
+	
+	assert_eq!(report.stop_reason, StopReason::Cancelled);
+	assert_eq!(report.rounds_executed, 1);
+	assert_eq!(sample.round, 1);
+}
+
+#[test]
+fn everyone_ages_by_the_step_after_each_round() {
+	let age_config = AgeConfig { step: 0.5, ..AgeConfig::default() };
+	let mut sample = Sample::generate(&SampleConfig::builder().population(50).complexity(3).age(age_config).seed(9).build().unwrap()).unwrap();
+	let ages: Vec<f32> = sample.population.iter().map(|individual| individual.age).collect();
+	assert!(ages.iter().all(|age| (18.0..=60.0).contains(age)));
+	
+	sample.run(4).unwrap();
+	
+	for (individual, age) in sample.population.iter().zip(ages) {
+		assert!((individual.age - (age + 2.0)).abs() < 1e-4);
+	}
+	
+	let statistics = sample.statistics();
+	assert!(statistics.by_age.len() > 1);
+	assert_eq!(statistics.by_age.values().map(|counts| counts.population).sum::<usize>(), 50);
+}
+
+#[test]
+fn churn_brings_arrivals_and_retires_matched_pairs() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(100).complexity(3).seed(10).build().unwrap()).unwrap();
+	sample.churn = ChurnConfig { arrivals_per_round: 10, exit_on_match_probability: 1.0, ..ChurnConfig::default() };
+	sample.matching_config.validate_rounds = true;
+	
+	let round_stats = sample.run(3).unwrap().round_stats;
+	
+	let retired: usize = round_stats.iter().map(|stats| stats.retired_pairs).sum();
+	assert!(retired > 0);
+	assert_eq!(sample.retired.len(), retired);
+	assert_eq!(sample.population.len(), 100 + 30 - 2 * retired);
+	// every pair leaves right after forming, nobody in the pool is matched
+	assert!(sample.pairs().is_empty());
+	
+	let statistics = sample.statistics();
+	assert_eq!(statistics.retired_pairs, retired);
+	assert_eq!(statistics.total_population, sample.population.len());
+	assert!(sample.validate().is_ok());
+}
+
+#[test]
+fn every_pair_breaks_up_and_forms_again_each_round() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(100).complexity(3).seed(11).build().unwrap()).unwrap();
+	sample.churn = ChurnConfig { breakup_probability: 1.0, ..ChurnConfig::default() };
+	sample.matching_config.validate_rounds = true;
+	
+	let mut pairs_before = 0;
+	let mut breakups = 0;
+	for _ in 0..4 {
+		let round_stats = sample.match_making().unwrap();
+		assert_eq!(round_stats.breakups, pairs_before);
+		
+		// nobody was matched when the round started, every pair formed again in it
+		let pairs = sample.pairs();
+		assert!(!pairs.is_empty());
+		assert!(pairs.iter().all(|pair| pair.matched_in_round == Some(sample.round)));
+		assert_eq!(round_stats.delta.new_matches, pairs.len());
+		assert_eq!(round_stats.delta.dissolved, 0);
+		
+		pairs_before = pairs.len();
+		breakups += round_stats.breakups;
+	}
+	assert!(breakups > 0);
+	assert_eq!(sample.breakups, breakups);
+	assert_eq!(sample.statistics().breakups, breakups);
+	
+	// with blacklisting the two who broke up never get back together
+	sample.churn.blacklist_on_breakup = true;
+	let broken_up = sample.pairs();
+	sample.match_making().unwrap();
+	for pair in &broken_up {
+		assert!(sample.get_by_id(pair.proposer_id).unwrap().extras().blacklist.contains_key(&pair.reviewer_id));
+		assert_ne!(sample.get_by_id(pair.proposer_id).unwrap().candidate, Some(pair.reviewer_id));
+	}
+}
+
+#[test]
+fn only_pairs_below_the_mean_divorce() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(200).complexity(3).seed(93).build().unwrap()).unwrap();
+	sample.churn = ChurnConfig { divorce_every_rounds: Some(2), divorce_below: 0.9, blacklist_on_divorce: true, ..ChurnConfig::default() };
+	sample.matching_config.validate_rounds = true;
+	
+	assert_eq!(sample.match_making().unwrap().divorces, 0);
+	assert_eq!(sample.match_making().unwrap().divorces, 0);
+	
+	let pairs = sample.pairs();
+	let mean_score = pairs.iter().map(|pair| pair.reviewer_score + pair.proposer_score).sum::<f32>() / (pairs.len() * 2) as f32;
+	let divorcing: Vec<_> = pairs
+		.iter()
+		.filter(|pair| pair.reviewer_score.min(pair.proposer_score) < 0.9 * mean_score)
+		.collect();
+	assert!(!divorcing.is_empty() && divorcing.len() < pairs.len());
+	
+	let round_stats = sample.match_making().unwrap();
+	assert_eq!(round_stats.divorces, divorcing.len());
+	for pair in &divorcing {
+		let proposer = sample.get_by_id(pair.proposer_id).unwrap();
+		assert_eq!(proposer.extras().divorced_in_round, Some(3));
+		assert_eq!(proposer.extras().blacklist.get(&pair.reviewer_id), Some(&3));
+		assert_ne!(proposer.candidate, Some(pair.reviewer_id));
+	}
+	// the pairs that were good enough stayed together
+	for pair in pairs.iter().filter(|pair| !divorcing.contains(pair)) {
+		assert!(sample.get_by_id(pair.proposer_id).unwrap().extras().divorced_in_round.is_none());
+	}
+	
+	// nobody looks again before round 4 is over
+	assert_eq!(sample.match_making().unwrap().divorces, 0);
+	let statistics = sample.statistics();
+	assert_eq!(statistics.divorces, divorcing.len());
+	assert!(statistics.divorced_rematch_rate.is_some_and(|rate| (0.0..=1.0).contains(&rate)));
+	assert!(Sample::generate(&SampleConfig::builder().population(20).seed(93).build().unwrap()).unwrap().statistics().divorced_rematch_rate.is_none());
+	
+	assert!(ChurnConfig { divorce_every_rounds: Some(0), ..ChurnConfig::default() }.validate().is_err());
+	assert!(ChurnConfig { divorce_below: -1.0, ..ChurnConfig::default() }.validate().is_err());
+}
+
+#[test]
+fn matched_pairs_have_children_who_join_once_they_come_of_age() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(120).complexity(3).seed(94).build().unwrap()).unwrap();
+	sample.reproduction = ReproductionConfig { births_per_pair: 2, every_rounds: 2, mutation_std_dev: 0.0, maturity_rounds: 1 };
+	
+	assert_eq!(sample.match_making().unwrap().births, 0);
+	let pairs = sample.pairs();
+	let round_stats = sample.match_making().unwrap();
+	let pairs_after = sample.pairs();
+	assert_eq!(round_stats.births, pairs_after.len() * 2);
+	assert_eq!(round_stats.juveniles, round_stats.births);
+	assert_eq!(round_stats.population, 120);
+	assert!(!pairs.is_empty());
+	
+	// without mutation a child has the mean ratings of its parents and the weights of one of them
+	for (pair, children) in pairs_after.iter().zip(sample.juveniles.chunks(2)) {
+		let (proposer, reviewer) = (sample.get_by_id(pair.proposer_id).unwrap(), sample.get_by_id(pair.reviewer_id).unwrap());
+		for juvenile in children {
+			let child = &juvenile.individual;
+			assert_eq!(juvenile.joins_in_round, 4);
+			assert_eq!(child.generation, 1);
+			for ((rating, proposer_rating), reviewer_rating) in child.ratings.iter().zip(&proposer.ratings).zip(&reviewer.ratings) {
+				assert!((rating - (proposer_rating + reviewer_rating) / 2.0).abs() < 1e-5);
+			}
+			assert!(child.preference_weights == proposer.preference_weights || child.preference_weights == reviewer.preference_weights);
+		}
+	}
+	
+	let births = round_stats.births;
+	assert_eq!(sample.match_making().unwrap().arrivals, 0);
+	let round_stats = sample.match_making().unwrap();
+	assert_eq!(round_stats.arrivals, births);
+	assert_eq!(round_stats.population, 120 + births);
+	assert_eq!(round_stats.juveniles, round_stats.births);
+	
+	let statistics = sample.statistics();
+	assert_eq!(statistics.by_generation[&1].population, births);
+	assert_eq!(statistics.by_generation[&0].population, 120);
+	assert_eq!(statistics.by_generation[&1].mean_ratings.len(), 3);
+	
+	// children are as likely to be of every gender as anyone drawn
+	let config = SampleConfig::builder()
+		.population(60)
+		.complexity(2)
+		.gender_distribution(GenderDistribution { male: 0.0, female: 1.0, non_binary: 0.0 })
+		.orientation_distribution(OrientationDistribution { heterosexual: 0.0, homosexual: 1.0, bisexual: 0.0 })
+		.seed(95)
+		.build()
+		.unwrap();
+	let mut sample = Sample::generate(&config).unwrap();
+	sample.reproduction = ReproductionConfig { births_per_pair: 1, mutation_std_dev: 0.5, ..ReproductionConfig::default() };
+	sample.match_making().unwrap();
+	assert!(!sample.juveniles.is_empty());
+	assert!(sample.juveniles.iter().all(|juvenile| juvenile.individual.gender == Gender::Female));
+	assert!(sample.juveniles.iter().all(|juvenile| juvenile.individual.ratings.iter().all(|rating| (1.0..=10.0).contains(rating))));
+	
+	assert!(ReproductionConfig { every_rounds: 0, ..ReproductionConfig::default() }.validate().is_err());
+	assert!(ReproductionConfig { mutation_std_dev: -0.1, ..ReproductionConfig::default() }.validate().is_err());
+}
+
+#[test]
+fn the_memory_estimate_adds_up_what_the_sample_holds() {
+	let sample = Sample::generate(&SampleConfig::builder().population(1000).complexity(20).seed(96).build().unwrap()).unwrap();
+	let report = sample.memory_estimate();
+	
+	assert_eq!(report.individuals, 1000);
+	assert_eq!(report.population, 1000 * std::mem::size_of::<Individual>());
+	assert_eq!(report.weights_and_ratings, 1000 * 2 * 20 * std::mem::size_of::<f32>());
+	assert_eq!(report.score_cache, 0);
+	// generated uuids are kept in the `PersonId`s, nothing is interned for them
+	assert_eq!(report.identities, 0);
+	// nobody drawn without categories, dealbreakers or an age gap allocates extras, empty ones change nothing
+	assert_eq!(report.other_attributes, 0);
+	let mut emptied = sample.population[0].clone();
+	emptied.extras_mut();
+	assert_eq!(emptied, sample.population[0]);
+	assert_eq!(serde_json::to_string(&emptied).unwrap(), serde_json::to_string(&sample.population[0]).unwrap());
+	assert!(report.per_individual() * 1000 <= report.total());
+	assert!(report.to_string().starts_with("Estimated memory of 1000 individuals"));
+	
+	let smaller = Sample::generate(&SampleConfig::builder().population(1000).complexity(2).seed(96).build().unwrap()).unwrap();
+	assert!(smaller.memory_estimate().total() < report.total());
+	
+	let mut cached = sample.clone();
+	cached.matching_config.score_cache = ScoreCacheMode::Lazy;
+	cached.match_making().unwrap();
+	assert!(cached.memory_estimate().score_cache >= 1000 * 1000 * std::mem::size_of::<f32>());
+	// those turned down keep their blacklist in their extras
+	assert!(cached.memory_estimate().other_attributes > 0);
+	
+	// pairs that leave the pool leave their room behind until the sample shrinks
+	let mut churning = smaller.clone();
+	churning.churn = ChurnConfig { exit_on_match_probability: 1.0, ..ChurnConfig::default() };
+	churning.match_making().unwrap();
+	let before = churning.memory_estimate();
+	assert!(before.individuals < 1000);
+	churning.shrink_to_fit();
+	let after = churning.memory_estimate();
+	assert_eq!(after.population, after.individuals * std::mem::size_of::<Individual>());
+	assert!(after.total() < before.total());
+}
+
+#[test]
+fn identities_keep_their_strings_and_order_whatever_their_kind() {
+	let identities = [
+		"3f2a91bc-0000-4000-8000-000000000001",
+		"03f2a91b-0000-4000-8000-000000000001",
+		"m0",
+		"ö-ünïcode",
+		"an identity longer than fifteen bytes",
+		// not the canonical form of a uuid, so kept as it was written
+		"3F2A91BC-0000-4000-8000-000000000001"
+	];
+	let ids: Vec<PersonId> = identities.iter().map(|identity| PersonId::new(identity)).collect();
+	for (id, identity) in ids.iter().zip(identities) {
+		assert_eq!(id.as_str(), identity);
+		assert_eq!(PersonId::get(identity), Some(*id));
+		assert_eq!(serde_json::to_string(id).unwrap(), format!("{:?}", identity));
+	}
+	assert_eq!(PersonId::from_uuid(uuid::Uuid::parse_str(identities[0]).unwrap()), ids[0]);
+	assert_ne!(ids[0], ids[5]);
+	assert_eq!(ids[0].short_id(), "3f2a91bc");
+	assert_eq!(ids[4].short_id(), "an ident");
+	
+	let mut by_id = ids.clone();
+	by_id.sort();
+	let mut by_string = identities.to_vec();
+	by_string.sort();
+	assert_eq!(by_id.iter().map(PersonId::to_string).collect::<Vec<String>>(), by_string);
+}
+
+#[test]
+fn long_identities_are_freed_with_the_last_sample_holding_them() {
+	// used by no other test, which could hold it meanwhile
+	let identity = "freed with the last sample holding it";
+	let people = || vec![
+		Individual::builder().identity(identity).gender(Gender::Male).build().unwrap(),
+		Individual::builder().identity("f0").gender(Gender::Female).build().unwrap()
+	];
+	let mut sample = Sample::from_individuals(people());
+	sample.match_making().unwrap();
+	let copy = sample.clone();
+	drop(sample);
+	assert_eq!(copy.population[0].identity.as_str(), identity);
+	assert_eq!(copy.get("f0").unwrap().candidate.unwrap().as_str(), identity);
+	
+	drop(copy);
+	assert_eq!(PersonId::get(identity), None);
+	
+	// the identity comes back with the next sample that has it
+	let again = Sample::from_individuals(people());
+	assert_eq!(PersonId::get(identity), Some(again.population[0].identity));
+	assert_eq!(again.population[0].identity.as_str(), identity);
+}
+
+#[test]
+fn generated_weights_and_ratings_stay_within_their_distributions() {
+	let schema = AttributeSchema {
+		attributes: vec![
+			AttributeSpec::new("uniform", RatingDistribution::Uniform { min: 2.0, max: 4.0 }),
+			AttributeSpec { min: 3.0, max: 8.0, ..AttributeSpec::new("normal", RatingDistribution::Normal { mean: 5.0, std_dev: 3.0 }) }
+		],
+		..AttributeSchema::default()
+	};
+	let config = SampleConfig::builder().population(5000).attribute_schema(schema).seed(97).build().unwrap();
+	let sample = Sample::generate(&config).unwrap();
+	
+	let column = |position: usize, values: fn(&Individual) -> &Vec<f32>| -> Vec<f32> {
+		return sample.population.iter().map(|individual| values(individual)[position]).collect();
+	};
+	let mean = |values: &[f32]| values.iter().sum::<f32>() / values.len() as f32;
+	for position in 0..2 {
+		let weights = column(position, |individual| &individual.preference_weights);
+		assert!(weights.iter().all(|weight| (0.0..1.0).contains(weight)));
+		assert!((mean(&weights) - 0.5).abs() < 0.02, "{}", mean(&weights));
+	}
+	let uniform = column(0, |individual| &individual.ratings);
+	assert!(uniform.iter().all(|rating| (2.0..=4.0).contains(rating)));
+	assert!((mean(&uniform) - 3.0).abs() < 0.03, "{}", mean(&uniform));
+	let normal = column(1, |individual| &individual.ratings);
+	assert!(normal.iter().all(|rating| (3.0..=8.0).contains(rating)));
+	assert!(normal.contains(&3.0) && normal.contains(&8.0));
+	
+	let ranged = SampleConfig { weight_distribution: WeightDistribution::UniformRange { min: -1.0, max: 0.5 }, ..config };
+	let weights: Vec<f32> = Sample::generate(&ranged).unwrap().population.iter().flat_map(|individual| individual.preference_weights.clone()).collect();
+	assert!(weights.iter().all(|weight| (-1.0..=0.5).contains(weight)));
+	assert!((mean(&weights) + 0.25).abs() < 0.02, "{}", mean(&weights));
+}
+
+#[test]
+fn builder_fills_in_deterministic_defaults() {
+	let individual = Individual::builder()
+		.ratings(vec![4.0, 6.0])
+		.candidate("f0", 3.5)
+		.blacklist(["f1"])
+		.build()
+		.unwrap();
+	
+	assert_eq!(individual.identity, "individual");
+	assert_eq!(individual.gender, Gender::Male);
+	assert_eq!(individual.preference_weights, vec![1.0, 1.0]);
+	assert_eq!(individual.candidate, Some(PersonId::from("f0")));
+	assert_eq!(individual.candidate_score, Some(3.5));
+	assert_eq!(individual.extras().blacklist.get(&PersonId::from("f1")), Some(&0));
+	assert_eq!(individual, Individual::builder().ratings(vec![4.0, 6.0]).candidate("f0", 3.5).blacklist(["f1"]).build().unwrap());
+}
+
+#[test]
+fn builder_rejects_weights_and_ratings_of_different_lengths() {
+	let result = Individual::builder()
+		.preference_weights(vec![1.0, 0.5])
+		.ratings(vec![4.0])
+		.build();
+	
+	assert_eq!(result, Err(SchemaError::WeightCountMismatch { attributes: 1, weights: 2 }));
+}
+
+#[test]
+fn from_individuals_indexes_the_population() {
+	let sample = Sample::from_individuals(vec![
+		Individual::builder().identity("m0").ratings(vec![5.0, 5.0]).candidate("f0", 5.0).build().unwrap(),
+		Individual::builder().identity("f0").gender(Gender::Female).ratings(vec![5.0, 5.0]).candidate("m0", 5.0).build().unwrap()
+	]);
+	
+	assert_eq!(sample.get("f0").unwrap().gender, Gender::Female);
+	assert_eq!(sample.attribute_schema.len(), 2);
+	assert_eq!(sample.pairs().len(), 1);
+	assert!(sample.validate().is_ok());
+}
+
+#[test]
+fn sample_config_builder_validates_on_build() {
+	assert!(SampleConfig::builder().population(0).build().is_err());
+	assert!(SampleConfig::builder().complexity(2).predefined_weights(vec![1.0, 0.5, 0.2]).build().is_err());
+	
+	let config = SampleConfig::builder().population(100).complexity(2).seed(42).build().unwrap();
+	assert_eq!(config.attribute_schema.len(), 2);
+	assert_eq!(config.seed, Some(42));
+}
+
+#[test]
+fn generated_sample_keeps_its_config() {
+	let config = SampleConfig::builder().population(30).complexity(2).build().unwrap();
+	let sample = Sample::generate(&config).unwrap();
+	
+	let kept = sample.sample_config.clone().unwrap();
+	assert_eq!(kept.seed, Some(sample.seed));
+	assert_eq!(kept.population_size, 30);
+	
+	// the kept config reproduces the population
+	assert_eq!(Sample::generate(&kept).unwrap().population, sample.population);
+	
+	#[allow(deprecated)]
+	let old = Sample::new(30, &config.attribute_schema, None, &GenderDistribution::default(), &OrientationDistribution::default(), &ThresholdDistribution::default(), &AgeConfig::default(), Some(sample.seed), false).unwrap();
+	assert_eq!(old.population, sample.population);
+}
+
+#[test]
+fn attribute_counts_at_the_boundaries() {
+	assert!(SampleConfig::builder().population(10).complexity(0).build().is_err());
+	
+	let mut rng = StdRng::seed_from_u64(1);
+	let empty = AttributeSchema::anonymous(0, RatingDistribution::default());
+	assert!(matches!(
+		Individual::new(&empty, None, Gender::Male, Orientation::Heterosexual, &ThresholdDistribution::default(), &AgeConfig::default(), &mut rng),
+		Err(SchemaError::Empty)
+	));
+	
+	for attributes in [1, 1024] {
+		let config = SampleConfig::builder().population(20).complexity(attributes).seed(3).build().unwrap();
+		let mut sample = Sample::generate(&config).unwrap();
+		assert!(sample.population.iter().all(|individual| individual.ratings.len() == attributes && individual.preference_weights.len() == attributes));
+		
+		sample.match_making().unwrap();
+		assert!(sample.validate().is_ok());
+	}
+}
+
+#[test]
+fn negative_sizes_are_rejected_by_the_config() {
+	assert!(toml::from_str::<SimulationConfig>("population_size = -5").is_err());
+	assert!(toml::from_str::<SimulationConfig>("preference_complexity = -1").is_err());
+	
+	let config: SimulationConfig = toml::from_str("preference_complexity = 300").unwrap();
+	assert_eq!(config.attribute_schema().len(), 300);
+	assert!(toml::from_str::<SimulationConfig>("preference_complexity = 0").unwrap().validate().is_err());
+}
+
+#[test]
+fn weights_and_ratings_of_different_lengths_are_a_typed_error() {
+	let error = SampleConfig::builder().complexity(2).predefined_weights(vec![1.0, 0.5, 0.2]).build().unwrap_err();
+	assert!(matches!(error, SimulationError::WeightRatingLengthMismatch { expected: 2, actual: 3 }), "{:?}", error);
+	
+	let male = Individual::builder().identity("m0").ratings(vec![5.0, 5.0]).build().unwrap();
+	let mut female = Individual::builder().identity("f0").gender(Gender::Female).ratings(vec![5.0, 5.0]).build().unwrap();
+	female.preference_weights.push(1.0);
+	
+	let mut sample = Sample::from_individuals(vec![male, female]);
+	let error = sample.match_making().unwrap_err();
+	assert!(matches!(error, SimulationError::WeightRatingLengthMismatch { expected: 2, actual: 3 }), "{:?}", error);
+}
+
+#[test]
+fn predefined_weights_are_shared_by_everyone() {
+	let config = SampleConfig::builder().population(50).complexity(3).predefined_weights(vec![0.2, 0.3, 0.5]).seed(8).build().unwrap();
+	let sample = Sample::generate(&config).unwrap();
+	
+	assert!(sample.population.iter().all(|individual| individual.preference_weights == [0.2, 0.3, 0.5]));
+}
+
+#[test]
+fn decaying_selectivity_lets_a_picky_population_settle() {
+	// rejections only last their round, otherwise everyone would be out of reach by the time standards drop
+	let matching = MatchingConfig { blacklist_duration_rounds: Some(1), ..MatchingConfig::default() };
+	let config = SimulationConfig {
+		population_size: 200,
+		seed: Some(57),
+		min_acceptable_score: ThresholdDistribution::OwnScore { factor: 1.0 },
+		selectivity: SelectivityConfig { initial: 20.0, decay: 1.0, floor: 0.0 },
+		matching: matching,
+		..SimulationConfig::default()
+	};
+	
+	let mut picky = config.generate_sample().unwrap();
+	picky.run(8).unwrap();
+	assert!(picky.pairs().is_empty());
+	assert_eq!(picky.statistics().mean_selectivity, Some(20.0));
+	
+	let mut settling = SimulationConfig {
+		selectivity: SelectivityConfig { initial: 20.0, decay: 0.5, floor: 0.5 },
+		..config
+	}.generate_sample().unwrap();
+	let mut mean_selectivities = Vec::new();
+	settling.run_with(8, |_, round_stats| mean_selectivities.push(round_stats.mean_selectivity.unwrap())).unwrap();
+	
+	assert!(!settling.pairs().is_empty());
+	assert!(mean_selectivities.windows(2).all(|window| window[1] <= window[0]), "{:?}", mean_selectivities);
+	assert!(settling.population.iter().all(|individual| individual.selectivity >= 0.5));
+	assert!(settling.statistics().selectivity_score_correlation.is_some());
+}
+
+#[test]
+fn drifting_weights_stay_within_their_bounds() {
+	let drift = PreferenceDrift { random_walk: 0.3, toward_rejecters: 0.5, min_weight: 0.1, max_weight: 0.6, ..PreferenceDrift::default() };
+	let config = SimulationConfig {
+		population_size: 120,
+		seed: Some(91),
+		drift: drift,
+		..SimulationConfig::default()
+	};
+	let mut sample = config.generate_sample().unwrap();
+	let weights_before: Vec<Vec<f32>> = sample.population.iter().map(|individual| individual.preference_weights.clone()).collect();
+	sample.run(5).unwrap();
+	
+	assert!(sample.population.iter().zip(&weights_before).any(|(individual, before)| individual.preference_weights != *before));
+	for individual in &sample.population {
+		assert!(individual.preference_weights.iter().all(|weight| (0.1..=0.6).contains(weight)), "{:?}", individual.preference_weights);
+	}
+	
+	// the cached scores of whoever drifted are dropped, so caching changes nothing
+	let mut cached = SimulationConfig {
+		matching: MatchingConfig { score_cache: ScoreCacheMode::Eager, ..MatchingConfig::default() },
+		..config
+	}.generate_sample().unwrap();
+	cached.run(5).unwrap();
+	assert_eq!(cached.pairs(), sample.pairs());
+	for (cached, uncached) in cached.population.iter().zip(&sample.population) {
+		assert_eq!(cached.preference_weights, uncached.preference_weights);
+	}
+	
+	let mut renormalized = SimulationConfig {
+		population_size: 120,
+		seed: Some(91),
+		drift: PreferenceDrift { random_walk: 0.05, renormalize: true, ..PreferenceDrift::default() },
+		..SimulationConfig::default()
+	}.generate_sample().unwrap();
+	renormalized.run(3).unwrap();
+	for individual in &renormalized.population {
+		assert!(individual.preference_weights.iter().all(|weight| (0.0..=1.0).contains(weight)));
+		assert!(individual.preference_weights.iter().sum::<f32>() <= 1.0 + 1e-5);
+	}
+	
+	assert!(SimulationConfig { drift: PreferenceDrift { toward_rejecters: 1.5, ..PreferenceDrift::default() }, ..SimulationConfig::default() }.validate().is_err());
+	assert!(SimulationConfig { drift: PreferenceDrift { min_weight: 1.0, max_weight: 0.0, ..PreferenceDrift::default() }, ..SimulationConfig::default() }.validate().is_err());
+}
+
+#[test]
+fn no_preference_update_runs_as_without_drift() {
+	let config = SimulationConfig { population_size: 150, seed: Some(92), ..SimulationConfig::default() };
+	let mut without = config.generate_sample().unwrap();
+	let mut with_no_op = config.generate_sample().unwrap();
+	with_no_op.set_preference_update(std::sync::Arc::new(NoOp));
+	
+	let report = without.run(4).unwrap();
+	assert_eq!(with_no_op.run(4).unwrap(), report);
+	assert_eq!(with_no_op.population, without.population);
+	assert_eq!(with_no_op.pairs(), without.pairs());
+}
+
+#[cfg(feature = "wasm")]
+#[test]
+fn the_wasm_bindings_run_a_round_of_200_people() {
+	use dating_simulation::wasm::WasmSample;
+	
+	let mut sample = WasmSample::from_config(r#"{"population_size": 200, "seed": 74}"#).unwrap();
+	let stats: serde_json::Value = serde_json::from_str(&sample.step_json().unwrap()).unwrap();
+	assert_eq!(stats["round"], 1);
+	
+	let pairs: Vec<(String, String, f32, f32)> = serde_json::from_str(&sample.pairs_to_json().unwrap()).unwrap();
+	assert_eq!(pairs.len(), sample.sample().pairs().len());
+	assert!(!pairs.is_empty());
+	
+	assert!(WasmSample::from_config(r#"{"population_size": 0}"#).is_err());
+}
+
+#[test]
+fn generated_individuals_get_names_that_follow_the_seed() {
+	let config = SampleConfig::builder().population(300).complexity(2).seed(79).build().unwrap();
+	let first = Sample::generate(&config).unwrap();
+	let second = Sample::generate(&config).unwrap();
+	
+	let names: Vec<Option<String>> = first.population.iter().map(|individual| individual.display_name.clone()).collect();
+	assert!(names.iter().all(|name| name.as_ref().is_some_and(|name| name.split(' ').count() == 2)));
+	assert_eq!(names, second.population.iter().map(|individual| individual.display_name.clone()).collect::<Vec<_>>());
+	
+	let individual = &first.population[0];
+	assert_eq!(individual.short_id(), &individual.identity.as_str()[..8]);
+	assert_eq!(individual.label(false), format!("{} ({})", individual.display_name.as_ref().unwrap(), individual.short_id()));
+	assert_eq!(individual.label(true), individual.identity.to_string());
+	
+	let unnamed = Individual::builder().identity("m0").build().unwrap();
+	assert_eq!(unnamed.label(false), "m0");
+	let named = Individual::builder().identity("3f2a91bc-0000").display_name("Alice Gray").build().unwrap();
+	assert_eq!(named.label(false), "Alice Gray (3f2a91bc)");
+}
+
+#[test]
+fn correlated_attributes_recover_the_correlation_they_were_drawn_with() {
+	let normal = RatingDistribution::Normal { mean: 5.5, std_dev: 1.5 };
+	let mut schema = AttributeSchema::anonymous(3, normal);
+	schema.correlation = Some(CorrelationMatrix::new(vec![
+		vec![1.0, 0.8, 0.0],
+		vec![0.8, 1.0, 0.0],
+		vec![0.0, 0.0, 1.0]
+	]).unwrap());
+	let sample = Sample::generate(&SampleConfig::builder().population(50_000).attribute_schema(schema).seed(85).build().unwrap()).unwrap();
+	
+	let correlation = |first: usize, second: usize| dating_simulation::pearson_correlation(
+		&sample.population.iter().map(|individual| (individual.ratings[first], individual.ratings[second])).collect::<Vec<(f32, f32)>>()
+	).unwrap();
+	assert!((correlation(0, 1) - 0.8).abs() < 0.02, "{}", correlation(0, 1));
+	assert!(correlation(0, 2).abs() < 0.02, "{}", correlation(0, 2));
+	
+	// through the config and a toml file, the uniform ratings keep their range
+	let config: SimulationConfig = toml::from_str("population_size = 20000\npreference_complexity = 2\nseed = 85\nattribute_correlation = [[1.0, 0.8], [0.8, 1.0]]").unwrap();
+	let sample = config.generate_sample().unwrap();
+	let pairs: Vec<(f32, f32)> = sample.population.iter().map(|individual| (individual.ratings[0], individual.ratings[1])).collect();
+	assert!((dating_simulation::pearson_correlation(&pairs).unwrap() - 0.8).abs() < 0.03);
+	assert!(pairs.iter().all(|&(first, second)| (1.0..=10.0).contains(&first) && (1.0..=10.0).contains(&second)));
+	assert_eq!(toml::from_str::<SimulationConfig>(&config.to_toml_string().unwrap()).unwrap(), config);
+	
+	let json = serde_json::to_string(&sample.attribute_schema).unwrap();
+	assert_eq!(serde_json::from_str::<AttributeSchema>(&json).unwrap(), sample.attribute_schema);
+}
+
+#[test]
+fn invalid_correlation_matrices_are_refused() {
+	let invalid = |rows: Vec<Vec<f64>>| matches!(CorrelationMatrix::new(rows), Err(SchemaError::InvalidCorrelation { .. }));
+	
+	assert!(invalid(vec![vec![1.0, 0.5], vec![0.4, 1.0]]));
+	assert!(invalid(vec![vec![1.0, 0.5], vec![0.5, 0.9]]));
+	assert!(invalid(vec![vec![1.0, 1.5], vec![1.5, 1.0]]));
+	assert!(invalid(vec![vec![1.0, 0.5]]));
+	assert!(invalid(vec![vec![1.0, 0.9, -0.9], vec![0.9, 1.0, 0.9], vec![-0.9, 0.9, 1.0]]));
+	// semi-definite, the second attribute copies the first
+	assert!(CorrelationMatrix::new(vec![vec![1.0, 1.0], vec![1.0, 1.0]]).is_ok());
+	
+	let config: SimulationConfig = toml::from_str("preference_complexity = 3\nattribute_correlation = [[1.0, 0.8], [0.8, 1.0]]").unwrap();
+	assert!(config.validate().is_err());
+	assert!(toml::from_str::<SimulationConfig>("attribute_correlation = [[1.0, 2.0], [2.0, 1.0]]").is_err());
+}
+
+#[test]
+fn dirichlet_weights_sum_to_one_and_follow_the_concentrations() {
+	let dirichlet = |concentration: Vec<f64>| SampleConfig::builder()
+		.population(20_000)
+		.complexity(concentration.len())
+		.weight_distribution(WeightDistribution::Dirichlet { concentration: concentration })
+		.seed(86)
+		.build()
+		.unwrap();
+	
+	let sample = Sample::generate(&dirichlet(vec![2.0, 1.0, 1.0])).unwrap();
+	assert!(sample.population.iter().all(|individual| (individual.preference_weights.iter().sum::<f32>() - 1.0).abs() < 1e-5));
+	for (position, expected) in [0.5, 0.25, 0.25].into_iter().enumerate() {
+		let mean = sample.population.iter().map(|individual| individual.preference_weights[position]).sum::<f32>() / sample.population.len() as f32;
+		assert!((mean - expected).abs() < 0.01, "mean weight {} of attribute {}", mean, position);
+	}
+	
+	// small concentrations put nearly all the weight on one attribute, large ones spread it evenly
+	let mean_largest = |sample: &Sample| sample.population
+		.iter()
+		.map(|individual| individual.preference_weights.iter().copied().fold(0.0, f32::max))
+		.sum::<f32>() / sample.population.len() as f32;
+	assert!(mean_largest(&Sample::generate(&dirichlet(vec![0.1; 3])).unwrap()) > 0.8);
+	assert!(mean_largest(&Sample::generate(&dirichlet(vec![100.0; 3])).unwrap()) < 0.4);
+	
+	assert_eq!("dirichlet:0.5,0.5".parse::<WeightDistribution>(), Ok(WeightDistribution::Dirichlet { concentration: vec![0.5, 0.5] }));
+	assert!("dirichlet".parse::<WeightDistribution>().is_err());
+	assert!(SampleConfig::builder().complexity(3).weight_distribution(WeightDistribution::Dirichlet { concentration: vec![1.0, 1.0] }).build().is_err());
+	assert!(SampleConfig::builder().complexity(2).weight_distribution(WeightDistribution::Dirichlet { concentration: vec![1.0, 0.0] }).build().is_err());
+	
+	let config: SimulationConfig = toml::from_str("preference_complexity = 2\n[weight_distribution]\nkind = \"dirichlet\"\nconcentration = [1.0, 3.0]").unwrap();
+	assert_eq!(config.sample_config().weight_distribution, WeightDistribution::Dirichlet { concentration: vec![1.0, 3.0] });
+	assert_eq!(toml::from_str::<SimulationConfig>(&config.to_toml_string().unwrap()).unwrap(), config);
+}
+
+#[test]
+fn presets_draw_the_populations_they_describe() {
+	let generate = |preset: Preset| Sample::generate(&SampleConfig {
+		population_size: 20_000,
+		seed: Some(87),
+		..SampleConfig::preset(preset)
+	}).unwrap();
+	let mean_weight = |sample: &Sample, position: usize| sample.population
+		.iter()
+		.map(|individual| individual.preference_weights[position])
+		.sum::<f32>() / sample.population.len() as f32;
+	let mean_largest = |sample: &Sample| sample.population
+		.iter()
+		.map(|individual| individual.preference_weights.iter().copied().fold(0.0, f32::max))
+		.sum::<f32>() / sample.population.len() as f32;
+	
+	let looks_dominated = generate(Preset::LooksDominated);
+	assert_eq!(looks_dominated.attribute_schema.attributes[0].name, "looks");
+	assert!(mean_weight(&looks_dominated, 0) > 0.6, "{}", mean_weight(&looks_dominated, 0));
+	
+	let balanced = generate(Preset::Balanced);
+	for position in 0..balanced.attribute_schema.len() {
+		assert!((mean_weight(&balanced, position) - 0.25).abs() < 0.01);
+	}
+	assert!(mean_largest(&balanced) < 0.35, "{}", mean_largest(&balanced));
+	
+	let polarized = generate(Preset::Polarized);
+	assert!(mean_largest(&polarized) > 0.8, "{}", mean_largest(&polarized));
+	
+	let halo_effect = generate(Preset::HaloEffect);
+	let ratings: Vec<(f32, f32)> = halo_effect.population.iter().map(|individual| (individual.ratings[0], individual.ratings[1])).collect();
+	assert!((dating_simulation::pearson_correlation(&ratings).unwrap() - 0.6).abs() < 0.05);
+	
+	for preset in Preset::ALL {
+		assert_eq!(preset.to_string().parse::<Preset>(), Ok(preset));
+		assert!(SampleConfig::preset(preset).validate().is_ok());
+	}
+	assert!("no-such-preset".parse::<Preset>().is_err());
+}
+
+#[test]
+fn a_categorical_mismatch_penalty_dominates_who_pairs_with_whom() {
+	let generate = |penalty: f32| {
+		let mut schema = AttributeSchema::anonymous(3, RatingDistribution::default());
+		schema.categorical = vec![
+			CategoricalAttribute::new("smoker", &["yes", "no"], CategoryPreference::SameCategoryBonus { bonus: 0.0, penalty: penalty })
+		];
+		let mut sample = Sample::generate(&SampleConfig::builder().population(2_000).attribute_schema(schema).seed(88).build().unwrap()).unwrap();
+		sample.run(5).unwrap();
+		assert!(sample.validate().is_ok());
+		
+		return sample;
+	};
+	let same_category = |sample: &Sample| {
+		let pairs = sample.pairs();
+		let same = pairs
+			.iter()
+			.filter(|pair| sample.get_by_id(pair.proposer_id).unwrap().extras().categories == sample.get_by_id(pair.reviewer_id).unwrap().extras().categories)
+			.count();
+		
+		return same as f64 / pairs.len() as f64;
+	};
+	
+	let indifferent = generate(0.0);
+	assert!((same_category(&indifferent) - 0.5).abs() < 0.1, "{}", same_category(&indifferent));
+	let dealbreaker = generate(100.0);
+	assert!(same_category(&dealbreaker) > 0.95, "{}", same_category(&dealbreaker));
+	assert!(dealbreaker.population.iter().all(|individual| individual.extras().categories.len() == 1 && individual.extras().categories[0] < 2));
+	
+	let smoker = dealbreaker.population.iter().find(|individual| individual.extras().categories == [0]).unwrap();
+	assert!(smoker.describe(&dealbreaker.attribute_schema).contains("Categories: smoker: yes"));
+	let json = serde_json::to_string(&dealbreaker).unwrap();
+	assert_eq!(Sample::from_json_reader(json.as_bytes()).unwrap().population, dealbreaker.population);
+}
+
+#[test]
+fn categorical_attributes_score_by_their_preference_and_load_from_toml() {
+	let religion = CategoricalAttribute::new("religion", &["none", "christian", "muslim"], CategoryPreference::Matrix {
+		compatibility: vec![vec![1.0, 0.0, -1.0], vec![0.5, 2.0, -2.0], vec![-1.0, -2.0, 2.0]]
+	});
+	assert_eq!(religion.score(1, 0), 0.5);
+	assert_eq!(religion.score(0, 2), -1.0);
+	assert_eq!(religion.score(5, 0), 0.0);
+	assert!(religion.validate().is_ok());
+	
+	let rater = Individual::builder().identity("rater").preference_weights(vec![1.0]).categories(vec![1]).build().unwrap();
+	let rated = Individual::builder().identity("rated").ratings(vec![5.0]).categories(vec![2]).build().unwrap();
+	let schema = AttributeSchema { categorical: vec![religion.clone()], ..AttributeSchema::anonymous(1, RatingDistribution::default()) };
+	assert_eq!(schema.category_score(&rater, &rated), -2.0);
+	
+	let invalid = |attribute: CategoricalAttribute| matches!(attribute.validate(), Err(SchemaError::InvalidCategories { .. }));
+	assert!(invalid(CategoricalAttribute::new("empty", &[], CategoryPreference::SameCategoryBonus { bonus: 1.0, penalty: 0.0 })));
+	assert!(invalid(CategoricalAttribute { frequencies: vec![1.0, 2.0], ..religion.clone() }));
+	assert!(invalid(CategoricalAttribute::new("religion", &["none", "christian", "muslim"], CategoryPreference::Matrix { compatibility: vec![vec![1.0; 3]; 2] })));
+	
+	let config: SimulationConfig = toml::from_str(r#"
+		population_size = 10000
+		seed = 88
+		
+		[[categorical_attributes]]
+		name = "smoker"
+		categories = ["yes", "no"]
+		frequencies = [1.0, 3.0]
+		preference = { kind = "same-category-bonus", bonus = 1.0, penalty = 5.0 }
+	"#).unwrap();
+	let sample = config.generate_sample().unwrap();
+	let smokers = sample.population.iter().filter(|individual| individual.extras().categories == [0]).count() as f64 / sample.population.len() as f64;
+	assert!((smokers - 0.25).abs() < 0.02, "{}", smokers);
+	assert_eq!(toml::from_str::<SimulationConfig>(&config.to_toml_string().unwrap()).unwrap(), config);
+	assert_eq!(serde_json::from_str::<AttributeSchema>(&serde_json::to_string(&sample.attribute_schema).unwrap()).unwrap(), sample.attribute_schema);
+}
+
+#[test]
+fn weights_can_be_drawn_from_a_range_with_negative_values() {
+	let config = SampleConfig::builder()
+		.population(5_000)
+		.weight_distribution(WeightDistribution::UniformRange { min: -1.0, max: 1.0 })
+		.seed(89)
+		.build()
+		.unwrap();
+	let sample = Sample::generate(&config).unwrap();
+	let weights: Vec<f32> = sample.population.iter().flat_map(|individual| individual.preference_weights.clone()).collect();
+	
+	assert!(weights.iter().all(|weight| (-1.0..=1.0).contains(weight)));
+	let negative = weights.iter().filter(|weight| **weight < 0.0).count() as f64 / weights.len() as f64;
+	assert!((negative - 0.5).abs() < 0.03, "{}", negative);
+	assert!(sample.population.iter().all(|individual| individual.min_acceptable_score.is_none()));
+	
+	assert_eq!("uniform:-1,1".parse::<WeightDistribution>(), Ok(WeightDistribution::UniformRange { min: -1.0, max: 1.0 }));
+	assert_eq!(WeightDistribution::UniformRange { min: -0.5, max: 2.0 }.to_string(), "uniform:-0.5,2");
+	assert!(SampleConfig::builder().weight_distribution(WeightDistribution::UniformRange { min: 1.0, max: -1.0 }).build().is_err());
+	
+	let config: SimulationConfig = toml::from_str("[weight_distribution]\nkind = \"uniform-range\"\nmin = -1.0\nmax = 1.0").unwrap();
+	assert_eq!(config.sample_config().weight_distribution, WeightDistribution::UniformRange { min: -1.0, max: 1.0 });
+}
+
+#[test]
+fn gender_counts_draw_exactly_that_many_of_each() {
+	for (males, females) in [(4000, 6000), (7, 7), (1, 0), (0, 3), (250, 1)] {
+		let config = SampleConfig::builder().gender_counts(males, females).complexity(3).seed(101).build().unwrap();
+		assert_eq!(config.population_size, males + females);
+		assert_eq!(config.gender_distribution, GenderDistribution { male: males as f64, female: females as f64, non_binary: 0.0 }, "arrivals keep the ratio");
+		let sample = Sample::generate(&config).unwrap();
+		
+		let statistics = sample.statistics();
+		assert_eq!((statistics.male_population, statistics.female_population), (males, females));
+		assert_eq!(statistics.non_binary_population, 0);
+		assert_eq!(sample, Sample::generate(&config).unwrap());
+	}
+	
+	// the two are mixed rather than one after the other
+	let config = SampleConfig::builder().gender_counts(4000, 6000).complexity(3).seed(101).build().unwrap();
+	let sample = Sample::generate(&config).unwrap();
+	assert!(sample.population[..4000].iter().any(|individual| individual.gender == Gender::Female));
+	
+	// from a config file they have to add up to the population
+	let mut config: SimulationConfig = toml::from_str("population_size = 100\n[gender_counts]\nmale = 40\nfemale = 60\n").unwrap();
+	assert_eq!(config.generate_sample().unwrap().statistics().male_population, 40);
+	config.population_size = 90;
+	assert!(matches!(config.validate(), Err(SimulationError::InvalidConfig(_))));
+}
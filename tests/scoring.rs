@@ -0,0 +1,136 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use std::sync::Arc;
+
+use common::individual;
+use dating_simulation::{
+	pearson_correlation, AttributeSchema, AttributeSpec, EuclideanSimilarity, Gender, Individual, MatchingAlgorithm, MinAttribute, PersonId, RatingDistribution,
+	RatingValues, Sample, SampleConfig, ScoreError, ScoringStrategy, WeightedSum
+};
+
+fn rater() -> Individual {
+	return individual("m0", Gender::Male, vec![0.5, 1.0], vec![5.0, 5.0]);
+}
+
+fn rated() -> Individual {
+	return individual("f0", Gender::Female, vec![1.0, 1.0], vec![4.0, 6.0]);
+}
+
+#[test]
+fn weighted_sum_is_the_dot_product() {
+	assert_eq!(WeightedSum.score(&rater(), &rated()), Ok(8.0));
+	assert_eq!(rater().score(&rated()), Ok(8.0));
+}
+
+#[test]
+fn the_score_breakdown_adds_up_to_the_score() {
+	assert_eq!(rater().score_breakdown(&rated()), Ok(vec![(0, 2.0), (1, 6.0)]));
+	assert_eq!(rater().top_attribute(&rated()), Ok(Some(1)));
+	
+	let rated = individual("f1", Gender::Female, vec![1.0], vec![4.0]);
+	assert_eq!(rater().score_breakdown(&rated), Err(rater().score(&rated).unwrap_err()));
+}
+
+#[test]
+fn euclidean_similarity_measures_the_distance_to_the_ideal() {
+	// the ideal is (5.5, 10.0), so the distance is the square root of 1.5^2 + 4^2
+	let score = EuclideanSimilarity.score(&rater(), &rated()).unwrap();
+	
+	assert!((score - 1.0 / (1.0 + 18.25_f32.sqrt())).abs() < 1e-6, "{}", score);
+}
+
+#[test]
+fn min_attribute_takes_the_weakest_weighted_rating() {
+	assert_eq!(MinAttribute.score(&rater(), &rated()), Ok(2.0));
+}
+
+#[test]
+fn mismatched_attributes_are_a_score_error() {
+	let rated = individual("f1", Gender::Female, vec![1.0], vec![4.0]);
+	
+	assert_eq!(
+		EuclideanSimilarity.score(&rater(), &rated),
+		Err(ScoreError::AttributeCountMismatch { rater: "m0".to_string(), rated: "f1".to_string(), weights: 2, ratings: 1 })
+	);
+}
+
+/// scores everyone by the second rating only
+#[derive(Debug)]
+struct SecondRating;
+
+impl ScoringStrategy for SecondRating {
+	fn score(&self, _rater: &Individual, rated: &Individual) -> Result<f32, ScoreError> {
+		return Ok(rated.ratings[1]);
+	}
+}
+
+#[test]
+fn match_making_uses_the_sample_strategy() {
+	// by weighted sum `f0` is the better match, by the second rating `f1`
+	let mut sample = Sample::from_individuals(vec![
+		individual("m0", Gender::Male, vec![1.0, 0.0], vec![5.0, 5.0]),
+		individual("f0", Gender::Female, vec![1.0, 1.0], vec![9.0, 1.0]),
+		individual("f1", Gender::Female, vec![1.0, 1.0], vec![1.0, 2.0])
+	]);
+	sample.matching_config.algorithm = MatchingAlgorithm::DeferredAcceptance;
+	sample.set_scoring_strategy(Arc::new(SecondRating));
+	
+	sample.match_making().unwrap();
+	
+	assert_eq!(sample.get("m0").unwrap().candidate, Some(PersonId::from("f1")));
+	assert_eq!(sample.get("m0").unwrap().candidate_score, Some(2.0));
+}
+
+#[test]
+fn percentile_ratings_rank_every_rating_among_the_population() {
+	let mut sample = Sample::from_individuals(vec![
+		individual("m0", Gender::Male, vec![1.0, 1.0], vec![1.0, 7.0]),
+		individual("m1", Gender::Male, vec![1.0, 1.0], vec![5.0, 7.0]),
+		individual("f0", Gender::Female, vec![1.0, 1.0], vec![5.0, 7.0]),
+		individual("f1", Gender::Female, vec![1.0, 1.0], vec![9.0, 7.0])
+	]);
+	sample.matching_config.rating_values = RatingValues::Percentile;
+	sample.match_making().unwrap();
+	
+	let percentiles = |identity: &str| sample.get(identity).unwrap().extras().percentile_ratings.clone();
+	assert_eq!(percentiles("m0"), vec![1.0, 5.5]);
+	assert_eq!(percentiles("m1"), vec![5.5, 5.5]);
+	assert_eq!(percentiles("f1"), vec![10.0, 5.5]);
+	assert_eq!(sample.get("m0").unwrap().ratings, vec![1.0, 7.0]);
+	
+	let strategy = sample.scoring_strategy();
+	assert_eq!(strategy.score(sample.get("m0").unwrap(), sample.get("f1").unwrap()).unwrap(), 15.5);
+	assert_eq!("percentile".parse::<RatingValues>(), Ok(RatingValues::Percentile));
+}
+
+#[test]
+fn percentile_scoring_weighs_a_narrow_attribute_like_a_wide_one() {
+	// everyone cares about both attributes alike, but the ratings of humor barely vary
+	let schema = AttributeSchema {
+		attributes: vec![
+			AttributeSpec::new("looks", RatingDistribution::Uniform { min: 1.0, max: 10.0 }),
+			AttributeSpec::new("humor", RatingDistribution::Normal { mean: 5.5, std_dev: 0.3 })
+		],
+		..AttributeSchema::default()
+	};
+	let config = SampleConfig::builder().population(1_000).attribute_schema(schema).predefined_weights(vec![0.5, 0.5]).seed(90).build().unwrap();
+	let humor_of_partners = |rating_values: RatingValues| {
+		let mut sample = Sample::generate(&config).unwrap();
+		sample.matching_config.algorithm = MatchingAlgorithm::DeferredAcceptance;
+		sample.matching_config.rating_values = rating_values;
+		sample.run(1).unwrap();
+		let pairs: Vec<(f32, f32)> = sample.pairs()
+			.iter()
+			.map(|pair| (sample.get_by_id(pair.proposer_id).unwrap().ratings[1], sample.get_by_id(pair.reviewer_id).unwrap().ratings[1]))
+			.collect();
+		
+		return pearson_correlation(&pairs).unwrap();
+	};
+	
+	let raw = humor_of_partners(RatingValues::Raw);
+	let percentile = humor_of_partners(RatingValues::Percentile);
+	assert!(raw < 0.15, "{}", raw);
+	assert!(percentile > 0.3, "{}", percentile);
+}
@@ -0,0 +1,214 @@
+#![allow(clippy::needless_return, clippy::redundant_field_names)]
+
+mod common;
+
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+use common::individual;
+use dating_simulation::{Gender, HttpRequest, Individual, Sample, ScoreError, ScoringStrategy, SimulationError, SimulationServer};
+
+fn json(body: &str) -> serde_json::Value {
+	return serde_json::from_str(body).unwrap();
+}
+
+#[test]
+fn a_small_simulation_runs_through_the_api() {
+	fn send<T: Send>() {}
+	send::<Sample>();
+
+	let server = SimulationServer::new();
+
+	let created = server.handle(&HttpRequest::new("POST", "/simulations", r#"{"population_size": 60, "seed": 75}"#));
+	assert_eq!(created.status, 201, "{}", created.body);
+	let id = json(&created.body)["id"].as_str().unwrap().to_string();
+
+	let rounds = server.handle(&HttpRequest::new("POST", &format!("/simulations/{}/rounds", id), r#"{"rounds": 3}"#));
+	assert_eq!(rounds.status, 200, "{}", rounds.body);
+	assert_eq!(json(&rounds.body)["round_stats"].as_array().unwrap().len(), 3);
+
+	let statistics = json(&server.handle(&HttpRequest::new("GET", &format!("/simulations/{}/statistics", id), "")).body);
+	assert_eq!(statistics["total_population"], 60);
+
+	let pairs = server.handle(&HttpRequest::new("GET", &format!("/simulations/{}/pairs?limit=2", id), ""));
+	assert_eq!(pairs.status, 200);
+	let pairs = json(&pairs.body);
+	assert_eq!(pairs.as_array().unwrap().len(), 2);
+
+	let identity = pairs[0]["proposer_id"].as_str().unwrap();
+	let individual = server.handle(&HttpRequest::new("GET", &format!("/simulations/{}/individuals/{}", id, identity), ""));
+	assert_eq!(individual.status, 200);
+	assert_eq!(json(&individual.body)["identity"], identity);
+
+	assert_eq!(server.handle(&HttpRequest::new("GET", &format!("/simulations/{}/individuals/nobody", id), "")).status, 404);
+	assert_eq!(server.handle(&HttpRequest::new("GET", "/simulations/not-an-id/statistics", "")).status, 404);
+	assert_eq!(server.handle(&HttpRequest::new("GET", "/simulations", "")).status, 405);
+	assert_eq!(server.handle(&HttpRequest::new("POST", "/simulations", r#"{"population_size": 0}"#)).status, 400);
+	assert_eq!(server.handle(&HttpRequest::new("POST", "/simulations", r#"{"no_such_key": 1}"#)).status, 400);
+}
+
+/// a server on a free port of the loopback, serving from its own thread
+fn listening() -> std::net::SocketAddr {
+	let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+	let address = listener.local_addr().unwrap();
+	std::thread::spawn(move || Arc::new(SimulationServer::new()).serve(listener));
+
+	return address;
+}
+
+/// the status line and body the server at `address` answers `request` with, on a connection of its own
+fn exchange(address: std::net::SocketAddr, request: &[u8]) -> (String, String) {
+	let mut stream = std::net::TcpStream::connect(address).unwrap();
+	stream.write_all(request).unwrap();
+	let mut response = String::new();
+	stream.read_to_string(&mut response).unwrap();
+
+	let (head, body) = response.split_once("\r\n\r\n").unwrap();
+	let status = head.lines().next().unwrap().to_string();
+
+	return (status, body.to_string());
+}
+
+/// `body` sent to `path` with its length
+fn post(path: &str, body: &str) -> Vec<u8> {
+	return format!("POST {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}", path, body.len(), body).into_bytes();
+}
+
+#[test]
+fn the_server_answers_over_tcp() {
+	let address = listening();
+
+	let (status, body) = exchange(address, &post("/simulations", r#"{"population_size": 20, "seed": 7}"#));
+	assert_eq!(status, "HTTP/1.1 201 Created", "{}", body);
+	let id = json(&body)["id"].as_str().unwrap().to_string();
+
+	let (status, body) = exchange(address, &post(&format!("/simulations/{}/rounds", id), r#"{"rounds": 2}"#));
+	assert_eq!(status, "HTTP/1.1 200 OK", "{}", body);
+	assert_eq!(json(&body)["round_stats"].as_array().unwrap().len(), 2);
+
+	let (status, body) = exchange(address, format!("GET /simulations/{}/statistics HTTP/1.1\r\n\r\n", id).as_bytes());
+	assert_eq!(status, "HTTP/1.1 200 OK", "{}", body);
+	assert_eq!(json(&body)["total_population"], 20);
+}
+
+#[test]
+fn malformed_requests_are_answered_over_tcp() {
+	let address = listening();
+
+	let (status, body) = exchange(address, b"GARBAGE\r\n\r\n");
+	assert_eq!(status, "HTTP/1.1 400 Bad Request");
+	assert!(body.contains("malformed request line"), "{}", body);
+
+	let (status, body) = exchange(address, b"POST /simulations HTTP/1.1\r\nContent-Length: lots\r\n\r\n");
+	assert_eq!(status, "HTTP/1.1 400 Bad Request");
+	assert!(body.contains("bad content-length"), "{}", body);
+
+	let (status, _) = exchange(address, &post("/simulations", "{not json"));
+	assert_eq!(status, "HTTP/1.1 400 Bad Request");
+
+	// none of them took the server down
+	let (status, body) = exchange(address, &post("/simulations", r#"{"population_size": 10}"#));
+	assert_eq!(status, "HTTP/1.1 201 Created", "{}", body);
+}
+
+/// what `HttpRequest::read_from` makes of `request`, the error as its message
+fn read(request: &[u8]) -> Result<Option<HttpRequest>, String> {
+	return HttpRequest::read_from(&mut &request[..]).map_err(|error| {
+		assert!(matches!(error, SimulationError::InvalidRequest(_)), "{:?}", error);
+
+		return error.to_string();
+	});
+}
+
+#[test]
+fn malformed_and_oversized_requests_are_refused() {
+	let request = read(b"POST /simulations HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}").unwrap().unwrap();
+	assert_eq!((request.method.as_str(), request.path.as_str(), request.body.as_slice()), ("POST", "/simulations", &b"{}"[..]));
+	assert_eq!(read(b""), Ok(None));
+
+	assert!(read(b"GARBAGE\r\n\r\n").unwrap_err().contains("malformed request line"));
+	assert!(read(b"POST /simulations HTTP/1.1\r\nContent-Length: lots\r\n\r\n").unwrap_err().contains("bad content-length"));
+	assert!(read(b"POST /simulations HTTP/1.1\r\nContent-Length: 10\r\n\r\n{}").unwrap_err().contains("ended before"));
+	assert!(read(b"GET /simulations\xff HTTP/1.1\r\n\r\n").unwrap_err().contains("utf-8"));
+
+	// the body is refused by its length, before it is read
+	let oversized = format!("POST /simulations HTTP/1.1\r\nContent-Length: {}\r\n\r\n", (1 << 20) + 1);
+	assert!(read(oversized.as_bytes()).unwrap_err().contains("over the limit"));
+	let endless_header = format!("GET /simulations HTTP/1.1\r\nX-Padding: {}\r\n\r\n", "a".repeat(20 << 10));
+	assert!(read(endless_header.as_bytes()).unwrap_err().contains("over the limit"));
+
+	let server = SimulationServer::new();
+	assert_eq!(server.handle(&HttpRequest::new("POST", "/simulations", "{not json")).status, 400);
+	assert_eq!(server.handle(&HttpRequest::new("POST", "/simulations", r#"{"population_size": -5}"#)).status, 400);
+	assert_eq!(server.handle(&HttpRequest::new("DELETE", "/simulations", "")).status, 405);
+	assert_eq!(server.handle(&HttpRequest::new("GET", "/nowhere", "")).status, 404);
+
+	let created = server.handle(&HttpRequest::new("POST", "/simulations", r#"{"population_size": 20, "seed": 3}"#));
+	let id = json(&created.body)["id"].as_str().unwrap().to_string();
+	let rounds = |body: &str| server.handle(&HttpRequest::new("POST", &format!("/simulations/{}/rounds", id), body)).status;
+	assert_eq!(rounds(r#"{"rounds": "three"}"#), 400);
+	assert_eq!(rounds(r#"{"rounds": 1, "seed": 1}"#), 400);
+	assert_eq!(rounds(r#""3""#), 400);
+	assert_eq!(server.handle(&HttpRequest::new("GET", &format!("/simulations/{}/pairs?limit=many", id), "")).status, 400);
+}
+
+#[test]
+fn oversized_bodies_are_answered_over_tcp() {
+	let address = listening();
+
+	let (status, body) = exchange(address, format!("POST /simulations HTTP/1.1\r\nContent-Length: {}\r\n\r\n", 1usize << 30).as_bytes());
+	assert_eq!(status, "HTTP/1.1 400 Bad Request");
+	assert!(body.contains("over the limit"), "{}", body);
+
+	// a body sent in full but over the limit is refused the same way
+	let (status, body) = exchange(address, &post("/simulations", &" ".repeat((1 << 20) + 1)));
+	assert_eq!(status, "HTTP/1.1 400 Bad Request");
+	assert!(body.contains("over the limit"), "{}", body);
+}
+
+#[test]
+fn populations_and_rounds_over_the_limits_are_refused() {
+	let mut server = SimulationServer::new();
+	assert_eq!((server.max_population, server.max_rounds), (SimulationServer::MAX_POPULATION, SimulationServer::MAX_ROUNDS));
+	server.max_population = 50;
+	server.max_rounds = 5;
+
+	let refused = server.handle(&HttpRequest::new("POST", "/simulations", r#"{"population_size": 51}"#));
+	assert_eq!(refused.status, 400);
+	assert!(json(&refused.body)["error"].as_str().unwrap().contains("over the limit of 50"));
+
+	let created = server.handle(&HttpRequest::new("POST", "/simulations", r#"{"population_size": 50, "seed": 4}"#));
+	assert_eq!(created.status, 201, "{}", created.body);
+	let id = json(&created.body)["id"].as_str().unwrap().to_string();
+	let rounds = |rounds: u32| server.handle(&HttpRequest::new("POST", &format!("/simulations/{}/rounds", id), &format!(r#"{{"rounds": {}}}"#, rounds)));
+	assert_eq!(rounds(6).status, 400);
+	assert_eq!(rounds(5).status, 200);
+}
+
+/// fails every round it scores in
+#[derive(Debug)]
+struct Panicking;
+
+impl ScoringStrategy for Panicking {
+	fn score(&self, _rater: &Individual, _rated: &Individual) -> Result<f32, ScoreError> {
+		panic!("the strategy broke");
+	}
+}
+
+#[test]
+fn a_simulation_whose_round_panicked_is_dropped() {
+	let server = SimulationServer::new();
+	let mut sample = Sample::from_individuals(vec![
+		individual("m0", Gender::Male, vec![1.0], vec![1.0]),
+		individual("f0", Gender::Female, vec![1.0], vec![1.0])
+	]);
+	sample.set_scoring_strategy(Arc::new(Panicking));
+	let id = server.insert(sample);
+	let individual = HttpRequest::new("GET", &format!("/simulations/{}/individuals/m0", id), "");
+	assert_eq!(server.handle(&individual).status, 200);
+
+	let panicked = server.handle(&HttpRequest::new("POST", &format!("/simulations/{}/rounds", id), ""));
+	assert_eq!(panicked.status, 500);
+	assert!(json(&panicked.body)["error"].as_str().unwrap().contains("dropped"));
+	assert_eq!(server.handle(&individual).status, 404);
+}
@@ -0,0 +1,590 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::{individual, with_candidate};
+use dating_simulation::{gini, pearson_correlation, AttributeSchema, EncounterOutcome, Estimate, Gender, Individual, MatchingAlgorithm, MonteCarlo, MonteCarloRun, PersonId, Sample, SampleConfig, ScoreDistribution, SimulationConfig, SimulationError, StopReason, SweepResult, SweepRunner, ThresholdDistribution, ValidationError};
+
+/// `m0` and `f0` are a pair, `m1` points at `f1` who does not point back and `f2` points at `m1`
+fn fixture() -> Sample {
+	let mut sample = Sample::from_individuals(vec![
+		with_candidate(individual("m0", Gender::Male, vec![1.0], vec![1.0]), "f0", 4.0),
+		with_candidate(individual("m1", Gender::Male, vec![1.0], vec![1.0]), "f1", 3.0),
+		with_candidate(individual("f0", Gender::Female, vec![1.0], vec![1.0]), "m0", 6.0),
+		individual("f1", Gender::Female, vec![1.0], vec![1.0]),
+		with_candidate(individual("f2", Gender::Female, vec![1.0], vec![1.0]), "m1", 2.0)
+	]);
+	sample.seed = 5;
+	
+	return sample;
+}
+
+#[test]
+fn statistics_only_count_mutual_pairs() {
+	let statistics = fixture().statistics();
+	
+	assert_eq!(statistics.total_population, 5);
+	assert_eq!(statistics.matched_males, 1);
+	assert_eq!(statistics.matched_females, 1);
+	assert_eq!(statistics.unmatched_males, 1);
+	assert_eq!(statistics.unmatched_females, 2);
+	assert_eq!(statistics.unmatched_percentage, 60.0);
+	assert_eq!(statistics.mean_candidate_score, Some(5.0));
+	assert_eq!(statistics.median_candidate_score, Some(5.0));
+	assert_eq!(statistics.gender_imbalance, -1);
+}
+
+#[test]
+fn statistics_display_keeps_the_report_layout() {
+	let report = fixture().statistics().to_string();
+	
+	assert!(report.starts_with("Statistics:\nSeed: 5\n"));
+	assert!(report.contains("Females that do not have a match: 2/3"));
+	assert!(report.contains("male population FELL SHORT OF that of female by 1"));
+	assert!(report.ends_with("60.00% of individuals were never matched."));
+}
+
+#[test]
+fn statistics_of_a_missing_side_read_n_a_rather_than_a_share() {
+	let report = |population: Vec<Individual>| Sample::from_individuals(population).statistics().to_string();
+	let female = || individual("f0", Gender::Female, vec![1.0], vec![1.0]);
+	let male = || individual("m0", Gender::Male, vec![1.0], vec![1.0]);
+	
+	let without_males = report(vec![female()]);
+	assert!(without_males.contains("Males that do not have a match: 0/0"));
+	assert!(without_males.contains("male population FELL SHORT OF that of female by 1"));
+	assert!(without_males.ends_with("100.00% of individuals were never matched."));
+	
+	let without_females = report(vec![male()]);
+	assert!(without_females.contains("male population EXCEEDED that of female by 1"));
+	assert!(without_females.ends_with("100.00% of individuals were never matched."));
+	
+	let empty = report(Vec::new());
+	assert!(empty.contains("male population EQUALED that of female\n"));
+	assert!(empty.ends_with("n/a of individuals were never matched."));
+	assert!(!empty.contains("NaN"));
+	
+	let equal = report(vec![male(), female()]);
+	assert!(equal.contains("male population EQUALED that of female\n"));
+	assert!(!equal.contains("by 0"));
+	// the empty deciles have no match rate
+	assert!(equal.lines().any(|line| line.starts_with("2 ") && line.ends_with("n/a")));
+}
+
+#[test]
+fn pairs_skip_stale_candidates() {
+	let mut sample = fixture();
+	sample.population.push(
+		with_candidate(individual("m2", Gender::Male, vec![1.0], vec![1.0]), "f1", 8.0)
+	);
+	sample.rebuild_index();
+	*sample.get_mut("f1").unwrap() = with_candidate(individual("f1", Gender::Female, vec![1.0], vec![1.0]), "m2", 7.0);
+	
+	let pairs = sample.pairs();
+	
+	assert_eq!(
+		pairs.iter().map(|pair| (pair.proposer_id, pair.reviewer_id)).collect::<Vec<_>>(),
+		vec![(PersonId::from("m2"), PersonId::from("f1")), (PersonId::from("m0"), PersonId::from("f0"))]
+	);
+	assert_eq!((pairs[0].reviewer_score, pairs[0].proposer_score), (7.0, 8.0));
+}
+
+#[test]
+fn validate_reports_every_violation() {
+	let mut sample = fixture();
+	sample.get_mut("f1").unwrap().candidate_score = Some(1.0);
+	sample.get_mut("f2").unwrap().extras_mut().blacklist.insert(PersonId::from("nobody"), 1);
+	sample.get_mut("m0").unwrap().ratings.push(2.0);
+	
+	let errors = sample.validate().unwrap_err();
+	
+	assert_eq!(errors, vec![
+		ValidationError::AttributeCountMismatch { identity: PersonId::from("m0"), expected: 1, weights: 1, ratings: 2 },
+		ValidationError::NotMutual { identity: PersonId::from("m1"), candidate: PersonId::from("f1") },
+		ValidationError::CandidateScoreMismatch { identity: PersonId::from("f1") },
+		ValidationError::NotMutual { identity: PersonId::from("f2"), candidate: PersonId::from("m1") },
+		ValidationError::UnknownBlacklisted { identity: PersonId::from("f2"), blacklisted: PersonId::from("nobody") }
+	]);
+}
+
+#[test]
+fn match_making_keeps_the_sample_valid() {
+	for algorithm in [MatchingAlgorithm::Greedy, MatchingAlgorithm::DeferredAcceptance, MatchingAlgorithm::ParallelProposal] {
+		for mutual_acceptance in [false, true] {
+			let mut sample = Sample::generate(&SampleConfig::builder().population(200).complexity(3).seed(18).build().unwrap()).unwrap();
+			sample.matching_config.algorithm = algorithm;
+			sample.matching_config.mutual_acceptance = mutual_acceptance;
+			sample.matching_config.validate_rounds = true;
+			
+			sample.run(5).unwrap();
+			
+			assert_eq!(sample.validate(), Ok(()), "{} with mutual acceptance {}", algorithm, mutual_acceptance);
+		}
+	}
+}
+
+#[test]
+fn rejections_are_averaged_by_match_status() {
+	let mut sample = fixture();
+	sample.get_mut("m0").unwrap().rejections_received = 2;
+	sample.get_mut("f0").unwrap().rejections_given = 4;
+	sample.get_mut("m1").unwrap().rejections_received = 3;
+	
+	let statistics = sample.statistics();
+	
+	assert_eq!(statistics.rejections_of_matched.received, Some(1.0));
+	assert_eq!(statistics.rejections_of_matched.given, Some(2.0));
+	assert_eq!(statistics.rejections_of_unmatched.received, Some(1.0));
+}
+
+#[test]
+fn score_distribution_covers_matched_scores_per_gender() {
+	let statistics = fixture().statistics();
+	
+	// only `m0` (4) and `f0` (6) are matched
+	let distribution = statistics.score_distribution.as_ref().unwrap();
+	assert_eq!((distribution.min, distribution.max, distribution.mean, distribution.std_dev), (4.0, 6.0, 5.0, 1.0));
+	assert_eq!(distribution.histogram.len(), ScoreDistribution::BUCKETS);
+	assert_eq!(distribution.histogram[0], 1);
+	assert_eq!(distribution.histogram[ScoreDistribution::BUCKETS - 1], 1);
+	
+	assert_eq!(statistics.score_distribution_by_gender[&Gender::Male].median, 4.0);
+	assert_eq!(statistics.score_distribution_by_gender[&Gender::Female].histogram[0], 1);
+	assert_eq!(statistics.render_histogram().lines().count(), 2 * (ScoreDistribution::BUCKETS + 1));
+}
+
+#[test]
+fn score_distribution_of_nobody_is_none() {
+	assert_eq!(ScoreDistribution::new(&[]), None);
+	assert_eq!(ScoreDistribution::new(&[f32::NAN]), None);
+	
+	let single = ScoreDistribution::new(&[3.0, f32::NAN]).unwrap();
+	assert_eq!((single.mean, single.std_dev, single.histogram[0]), (3.0, 0.0, 1));
+	
+	let mut sample = fixture();
+	sample.population.clear();
+	sample.rebuild_index();
+	let statistics = sample.statistics();
+	assert_eq!(statistics.score_distribution, None);
+	assert!(statistics.score_distribution_by_gender.is_empty());
+	assert_eq!(statistics.render_histogram(), "");
+	assert_eq!(statistics.unmatched_percentage, 0.0);
+}
+
+#[test]
+fn pearson_correlation_is_none_without_variance() {
+	assert_eq!(pearson_correlation(&[]), None);
+	assert_eq!(pearson_correlation(&[(1.0, 2.0)]), None);
+	assert_eq!(pearson_correlation(&[(1.0, 2.0), (1.0, 3.0)]), None);
+	assert!((pearson_correlation(&[(1.0, 2.0), (2.0, 4.0), (3.0, 6.0)]).unwrap() - 1.0).abs() < 1e-6);
+	assert!((pearson_correlation(&[(1.0, 3.0), (2.0, 2.0), (3.0, 1.0)]).unwrap() + 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn desirable_individuals_pair_with_each_other() {
+	let mut sample = Sample::from_individuals(vec![
+		with_candidate(individual("m0", Gender::Male, vec![1.0, 0.0], vec![2.0, 5.0]), "f0", 1.0),
+		with_candidate(individual("m1", Gender::Male, vec![1.0, 0.0], vec![5.0, 5.0]), "f1", 1.0),
+		with_candidate(individual("m2", Gender::Male, vec![0.0, 1.0], vec![9.0, 5.0]), "f2", 1.0),
+		with_candidate(individual("f0", Gender::Female, vec![0.0, 1.0], vec![1.0, 1.0]), "m0", 1.0),
+		with_candidate(individual("f1", Gender::Female, vec![1.0, 0.0], vec![4.0, 3.0]), "m1", 1.0),
+		with_candidate(individual("f2", Gender::Female, vec![1.0, 0.0], vec![8.0, 8.0]), "m2", 1.0)
+	]);
+	
+	let report = sample.desirability_report();
+	
+	assert_eq!(report.mean_weights, vec![4.0 / 6.0, 2.0 / 6.0]);
+	assert_eq!(report.individuals[3].identity, "f0");
+	assert_eq!(report.individuals[3].partner, Some(PersonId::from("m0")));
+	assert!((report.individuals[3].desirability - 1.0).abs() < 1e-6);
+	assert!(report.partner_correlation.unwrap() > 0.9);
+	assert_eq!(sample.statistics().partner_desirability_correlation, report.partner_correlation);
+	
+	sample.population.clear();
+	sample.rebuild_index();
+	let report = sample.desirability_report();
+	assert!(report.individuals.is_empty());
+	assert_eq!(report.partner_correlation, None);
+}
+
+#[test]
+fn estimates_carry_the_spread_and_a_confidence_interval() {
+	let estimate = Estimate::new(&[1.0, 2.0, 3.0, 4.0]).unwrap();
+	
+	assert!((estimate.mean - 2.5).abs() < 1e-9);
+	assert!((estimate.std_dev - 1.290_994).abs() < 1e-6);
+	// t quantile of 3 degrees of freedom
+	assert!((estimate.confidence_high - estimate.mean - 3.182 * 1.290_994 / 2.0).abs() < 1e-5);
+	assert!((estimate.mean - estimate.confidence_low - 3.182 * 1.290_994 / 2.0).abs() < 1e-5);
+	
+	assert_eq!(Estimate::new(&[0.5]).unwrap().confidence_low, 0.5);
+	assert_eq!(Estimate::new(&[]), None);
+}
+
+#[test]
+fn monte_carlo_runs_repeat_under_the_master_seed() {
+	let config = SimulationConfig {
+		population_size: 100,
+		rounds: 3,
+		seed: Some(21),
+		..SimulationConfig::default()
+	};
+	
+	let report = MonteCarlo::new(config.clone(), 4).run().unwrap();
+	
+	assert_eq!(report.runs.len(), 4);
+	assert_eq!(report.aggregate.runs, 4);
+	let seeds: Vec<u64> = report.runs.iter().map(|run| run.seed).collect();
+	assert_eq!(seeds, MonteCarlo::new(config.clone(), 4).seeds());
+	assert!(seeds.windows(2).all(|pair| pair[0] != pair[1]));
+	
+	let match_rates: Vec<f64> = report.runs.iter().map(|run| run.statistics.match_rate().unwrap()).collect();
+	assert_eq!(report.aggregate.match_rate, Estimate::new(&match_rates));
+	assert_eq!(MonteCarlo::new(config, 4).run().unwrap(), report);
+	
+	let mut rows: Vec<u8> = Vec::new();
+	report.runs[0].export_csv(&mut rows).unwrap();
+	let row = String::from_utf8(rows).unwrap();
+	assert_eq!(row.trim_end().split(',').count(), MonteCarloRun::CSV_HEADER.split(',').count());
+	assert!(row.starts_with(&format!("{},3,max-rounds,100,", seeds[0])));
+}
+
+#[test]
+fn an_unpairable_replicate_is_left_out_rather_than_failing_the_batch() {
+	// two individuals are of the same gender about every other draw
+	let config = SimulationConfig {
+		population_size: 2,
+		rounds: 2,
+		seed: Some(23),
+		..SimulationConfig::default()
+	};
+	
+	let report = MonteCarlo::new(config.clone(), 16).run().unwrap();
+	let unpairable = report.runs.iter().filter(|run| run.stop_reason == StopReason::Unpairable).count();
+	assert!(unpairable > 0 && unpairable < 16, "{} of 16 runs could not be paired", unpairable);
+	assert_eq!(report.aggregate.unpairable_runs, unpairable);
+	assert_eq!(report.aggregate.runs, 16 - unpairable);
+	assert_eq!(report.aggregate.match_rate.unwrap().mean, 1.0, "a pair of a male and a female always matches");
+	assert!(report.aggregate.to_string().contains(&format!("Left out {} runs", unpairable)));
+	
+	let mut runner = SweepRunner::new(config, Vec::new(), Vec::new());
+	runner.seeds_per_point = 16;
+	let results = runner.run().unwrap();
+	let unpairable: Vec<&SweepResult> = results.iter().filter(|result| result.report.stop_reason == StopReason::Unpairable).collect();
+	assert!(!unpairable.is_empty() && unpairable.len() < 16);
+	assert!(unpairable.iter().all(|result| result.report.rounds_executed == 0 && result.report.round_stats.is_empty()));
+}
+
+#[test]
+fn a_sweep_writes_a_row_per_point_and_round() {
+	let config = SimulationConfig {
+		rounds: 2,
+		seed: Some(22),
+		..SimulationConfig::default()
+	};
+	let mut runner = SweepRunner::new(config, vec![40, 80], vec![2, 3]);
+	runner.seeds_per_point = 2;
+	
+	let points = runner.points().unwrap();
+	let combinations: Vec<(usize, usize)> = points.iter().map(|point| (point.population, point.complexity)).collect();
+	assert_eq!(combinations, vec![(40, 2), (40, 2), (40, 3), (40, 3), (80, 2), (80, 2), (80, 3), (80, 3)]);
+	// every combination runs with the same seeds
+	assert_eq!(points[0].seed, points[6].seed);
+	assert_ne!(points[0].seed, points[1].seed);
+	
+	let results = runner.run().unwrap();
+	assert_eq!(results.len(), 8);
+	assert_eq!(results[7].statistics.total_population, 80);
+	assert_eq!(results[7].statistics.seed, results[7].point.seed);
+	
+	let mut output: Vec<u8> = Vec::new();
+	for result in &results {
+		result.export_csv(&mut output).unwrap();
+	}
+	let output = String::from_utf8(output).unwrap();
+	assert_eq!(output.lines().count(), 16);
+	assert!(output.lines().all(|row| row.split(',').count() == SweepResult::csv_header().split(',').count()));
+	assert!(output.lines().nth(3).unwrap().starts_with(&format!("40,2,{},2,", points[1].seed)));
+	
+	let mut named = runner.clone();
+	named.config.attributes = AttributeSchema::anonymous(2, Default::default());
+	assert!(matches!(named.points(), Err(SimulationError::InvalidConfig(_))));
+}
+
+#[test]
+fn a_blocking_pair_is_found_in_an_unstable_matching() {
+	// `m0` and `f1` both rate each other 9 and their partners 5
+	let sample = Sample::from_individuals(vec![
+		with_candidate(individual("m0", Gender::Male, vec![1.0], vec![9.0]), "f0", 5.0),
+		with_candidate(individual("m1", Gender::Male, vec![1.0], vec![5.0]), "f1", 9.0),
+		with_candidate(individual("f0", Gender::Female, vec![1.0], vec![5.0]), "m0", 9.0),
+		with_candidate(individual("f1", Gender::Female, vec![1.0], vec![9.0]), "m1", 5.0)
+	]);
+	
+	assert_eq!(sample.blocking_pairs(), vec![(PersonId::from("m0"), PersonId::from("f1"))]);
+	
+	let statistics = sample.statistics_with_blocking_pairs();
+	assert_eq!(statistics.blocking_pairs, Some(1.0));
+	assert!(!statistics.blocking_pairs_estimated);
+	// only counted when asked for
+	assert_eq!(sample.statistics().blocking_pairs, None);
+}
+
+#[test]
+fn unmatched_by_threshold_is_estimated_in_large_populations() {
+	let config = SampleConfig::builder()
+		.population(Sample::EXACT_UNMATCHED_BY_THRESHOLD_MAX + 1000)
+		.complexity(3)
+		.threshold_distribution(ThresholdDistribution::Uniform { min: 5.0, max: 25.0 })
+		.seed(25)
+		.build()
+		.unwrap();
+	let sample = Sample::generate(&config).unwrap();
+	// nobody is matched yet, everyone who scores all they are compatible with too low counts
+	let exact = sample.population
+		.iter()
+		.filter(|individual| {
+			let mut compatible = sample.population
+				.iter()
+				.filter(|other| other.identity != individual.identity && individual.compatible_with(other))
+				.peekable();
+			
+			return compatible.peek().is_some() && compatible.all(|other| individual.score(other).unwrap() < individual.demanded_score());
+		})
+		.count();
+	
+	let statistics = sample.statistics();
+	assert!(statistics.unmatched_by_threshold_estimated);
+	assert!(exact > 0);
+	assert!(
+		(statistics.unmatched_by_threshold as f64 - exact as f64).abs() <= exact as f64 * 0.25,
+		"estimated {} of {}", statistics.unmatched_by_threshold, exact
+	);
+	assert!(statistics.to_string().contains(&format!("minimum acceptable score: about {}", statistics.unmatched_by_threshold)));
+	
+	// at most the samples are checked one by one, exactly
+	let undemanding = Sample::generate(&SampleConfig { threshold_distribution: ThresholdDistribution::None, ..config }).unwrap();
+	let statistics = undemanding.statistics();
+	assert_eq!(statistics.unmatched_by_threshold, 0);
+	assert!(!statistics.unmatched_by_threshold_estimated);
+}
+
+#[test]
+fn the_blocking_pair_estimate_comes_close_to_the_count() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(300).complexity(3).seed(54).build().unwrap()).unwrap();
+	sample.match_making().unwrap();
+	
+	let exact = sample.blocking_pairs().len() as f64;
+	let estimate = sample.estimate_blocking_pairs(200_000, 1);
+	assert!(exact > 0.0);
+	assert!((estimate - exact).abs() < exact * 0.1, "{} {}", estimate, exact);
+	
+	// deferred acceptance leaves a stable matching
+	let mut stable = Sample::generate(&SampleConfig::builder().population(300).complexity(3).seed(54).build().unwrap()).unwrap();
+	stable.matching_config.algorithm = MatchingAlgorithm::DeferredAcceptance;
+	stable.match_making().unwrap();
+	assert!(stable.blocking_pairs().is_empty());
+}
+
+#[test]
+fn the_gini_coefficient_goes_from_equality_to_a_single_winner() {
+	assert_eq!(gini(&[3.0; 10]), 0.0);
+	assert_eq!(gini(&[]), 0.0);
+	assert_eq!(gini(&[5.0]), 0.0);
+	assert_eq!(gini(&[0.0, 0.0]), 0.0);
+	
+	let mut single_winner = vec![0.0; 999];
+	single_winner.push(1.0);
+	assert!((gini(&single_winner) - 0.999).abs() < 1e-4, "{}", gini(&single_winner));
+	
+	// the unmatched `f1` and `f2` count as 0 in the partner scores
+	let statistics = fixture().statistics();
+	assert!((statistics.matched_score_gini - gini(&[4.0, 0.0, 6.0, 0.0, 0.0])).abs() < 1e-6);
+}
+
+#[test]
+fn the_html_report_holds_the_key_numbers_of_the_run() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(60).complexity(3).seed(67).build().unwrap()).unwrap();
+	let run_report = sample.run(4).unwrap();
+	
+	let report = sample.report(&run_report);
+	let html = report.render();
+	let statistics = sample.statistics();
+	
+	assert!(html.contains("seed 67"));
+	assert!(html.contains(&format!("<td>{:.2}%</td>", statistics.match_rate().unwrap() * 100.0)), "{}", html);
+	assert!(html.contains(&format!("<td>{:.3}</td>", statistics.mean_candidate_score.unwrap())));
+	let last_round = run_report.round_stats.last().unwrap();
+	assert!(html.contains(&format!("<tr><td>{}</td><td>{}</td>", last_round.round, last_round.matched_males + last_round.matched_females + last_round.matched_non_binary)));
+	
+	// the best pair comes first, and nothing is loaded from elsewhere
+	assert!(report.top_pairs.len() <= 20 && !report.top_pairs.is_empty());
+	assert!(report.top_pairs.windows(2).all(|pair| pair[0].reviewer_score + pair[0].proposer_score >= pair[1].reviewer_score + pair[1].proposer_score));
+	assert!(html.contains(&*report.top_pairs[0].proposer_id.as_str()));
+	assert!(!html.contains("src=") && !html.contains("href="));
+}
+
+#[cfg(feature = "dashboard")]
+#[test]
+fn the_dashboard_shows_the_match_rates_the_scores_and_the_notable_events() {
+	use dating_simulation::{Dashboard, EventSink, SimEvent};
+	
+	let mut sample = Sample::generate(&SampleConfig::builder().population(40).complexity(2).seed(69).build().unwrap()).unwrap();
+	let run_report = sample.run(3).unwrap();
+	
+	let mut dashboard = Dashboard::new(3);
+	for stats in &run_report.round_stats {
+		dashboard.emit(&SimEvent::RoundStarted { round: stats.round, population: 40 }).unwrap();
+		let mut stats = stats.clone();
+		if stats.round == 2 {
+			dashboard.emit(&SimEvent::MatchFormed { round: 2, proposer: PersonId::new("m0"), reviewer: PersonId::new("f0"), reviewer_score: 9.0, proposer_score: 8.0 }).unwrap();
+			dashboard.emit(&SimEvent::MatchFormed { round: 2, proposer: PersonId::new("m1"), reviewer: PersonId::new("f1"), reviewer_score: 1.0, proposer_score: 1.0 }).unwrap();
+			stats.breakups = 2;
+		}
+		dashboard.emit(&SimEvent::RoundCompleted { round: stats.round, stats }).unwrap();
+	}
+	dashboard.finish().unwrap();
+	
+	let frame = dashboard.render(80);
+	let last = run_report.round_stats.last().unwrap();
+	let rate = last.matched_males as f64 / (last.matched_males + last.unmatched_males) as f64 * 100.0;
+	assert!(frame.starts_with("Dating simulation, round 3 of 3, population 40\n"));
+	assert!(frame.contains(&format!("{:>6.2}% matched", rate)));
+	assert_eq!(frame.lines().find(|line| line.starts_with("Mean score ")).unwrap().chars().filter(|c| ('▁'..='█').contains(c)).count(), 3);
+	
+	// only the best pair yet is logged, breakups with the round they happened in
+	assert!(frame.contains("round 2: best pair yet, m0 and f0 scoring each other 8.000 and 9.000"));
+	assert!(!frame.contains("m1 and f1"));
+	assert!(frame.contains("round 2: 2 pairs broke up"));
+	assert!(frame.lines().all(|line| console::measure_text_width(line) <= 80));
+}
+
+#[cfg(feature = "dashboard")]
+#[test]
+fn the_dashboard_draws_its_frames_on_the_alternate_screen_and_leaves_the_last_one() {
+	use std::sync::{Arc, Mutex};
+	
+	use dating_simulation::Dashboard;
+	
+	/// what the dashboard wrote, kept after the sample takes the dashboard
+	#[derive(Debug, Clone, Default)]
+	struct Output(Arc<Mutex<Vec<u8>>>);
+	
+	impl std::io::Write for Output {
+		fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+			return self.0.lock().unwrap().write(bytes);
+		}
+		
+		fn flush(&mut self) -> std::io::Result<()> {
+			return Ok(());
+		}
+	}
+	
+	let output = Output::default();
+	let mut sample = Sample::generate(&SampleConfig::builder().population(40).complexity(2).seed(69).build().unwrap()).unwrap();
+	sample.set_event_sink(Box::new(Dashboard::to_writer(3, output.clone(), 60)));
+	sample.run(3).unwrap();
+	
+	let written = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+	let (enter, leave) = ("\x1b[?1049h\x1b[?25l", "\x1b[?1049l\x1b[?25h");
+	assert!(written.starts_with(enter), "{:?}", written);
+	assert_eq!(written.matches(enter).count(), 1);
+	// the first frame is drawn as the first round starts, the next ones at most every 250ms
+	assert!(written[enter.len()..].starts_with("\x1b[H\x1b[JDating simulation, round 1 of 3, population 40\n"));
+	
+	// the terminal is given back before the last frame, which stays
+	let (frames, last) = written.rsplit_once(leave).unwrap();
+	assert!(!frames.contains("\x1b[?1049l"));
+	assert!(last.starts_with("Dating simulation, round 3 of 3, population 40\n"), "{:?}", last);
+	assert!(last.contains("% matched") && last.contains("Mean score "));
+	assert!(!last.contains('\x1b'));
+	assert!(last.lines().all(|line| console::measure_text_width(line) <= 60));
+}
+
+#[cfg(feature = "plots")]
+#[test]
+fn round_stats_plot_into_an_existing_directory() {
+	use dating_simulation::RoundStats;
+	
+	let mut sample = Sample::generate(&SampleConfig::builder().population(40).complexity(2).seed(68).build().unwrap()).unwrap();
+	let run_report = sample.run(5).unwrap();
+	
+	let directory = std::env::temp_dir().join("dating_simulation_plots");
+	std::fs::create_dir_all(&directory).unwrap();
+	let paths = RoundStats::plot(&run_report.round_stats, &directory).unwrap();
+	assert_eq!(paths.len(), 2);
+	for path in &paths {
+		assert!(std::fs::metadata(path).unwrap().len() > 0);
+		std::fs::remove_file(path).unwrap();
+	}
+	
+	let missing = directory.join("missing");
+	assert!(matches!(RoundStats::plot(&run_report.round_stats, &missing), Err(SimulationError::MissingDirectory(path)) if path == missing));
+}
+
+#[test]
+fn statistics_break_down_by_gender_and_desirability_decile() {
+	let mut sample = Sample::generate(&SampleConfig::builder().population(400).complexity(3).seed(80).build().unwrap()).unwrap();
+	sample.run(5).unwrap();
+	let statistics = sample.statistics();
+	
+	let males = statistics.by_gender[&Gender::Male];
+	assert_eq!((males.population, males.matched), (statistics.male_population, statistics.matched_males));
+	assert_eq!(males.mean_matched_score, statistics.mean_matched_score(Gender::Male));
+	assert!(males.mean_rejections.received.is_some());
+	
+	assert_eq!(statistics.by_decile.iter().map(|decile| decile.population).sum::<usize>(), 400);
+	assert!(statistics.by_decile.iter().all(|decile| decile.population == 40));
+	assert_eq!(statistics.by_decile.iter().map(|decile| decile.matched).sum::<usize>(), statistics.matched_males + statistics.matched_females + statistics.matched_non_binary);
+	assert!(statistics.by_decile.windows(2).all(|pair| pair[0].max_desirability <= pair[1].min_desirability));
+	
+	let rendered = statistics.to_string();
+	assert!(rendered.contains("Gender        Population   Matched  Match rate"), "{}", rendered);
+	assert!(rendered.contains("Decile          Desirability"));
+	
+	let json: serde_json::Value = serde_json::to_value(&statistics).unwrap();
+	assert_eq!(json["by_decile"].as_array().unwrap().len(), 10);
+	assert_eq!(json["by_gender"]["Male"]["population"], statistics.male_population);
+	
+	let mut tiny = Sample::generate(&SampleConfig::builder().population(3).complexity(1).seed(80).build().unwrap()).unwrap();
+	tiny.run(1).unwrap();
+	let deciles = tiny.statistics().by_decile;
+	assert_eq!(deciles.iter().filter(|decile| decile.population == 0).count(), 7);
+	assert!(deciles.iter().filter(|decile| decile.population == 0).all(|decile| decile.match_rate.is_none()));
+	tiny.statistics().to_string();
+}
+
+#[test]
+fn the_unmatched_report_the_best_score_a_rejection_gave_them() {
+	let config = SampleConfig::builder().population(300).complexity(3).seed(84).build().unwrap();
+	let mut sample = Sample::generate(&config).unwrap();
+	sample.matching_config.record_history = true;
+	sample.run(3).unwrap();
+	
+	// the best score of the proposals of the last round that were turned down
+	for individual in &sample.population {
+		let best = individual.extras().history
+			.iter()
+			.filter(|encounter| encounter.proposed && encounter.round == sample.round && encounter.outcome == EncounterOutcome::RejectedBelowCandidate)
+			.filter_map(|encounter| encounter.score)
+			.max_by(|a, b| a.total_cmp(b));
+		assert_eq!(individual.extras().best_rejected_score, best, "{}", individual.identity);
+	}
+	
+	let statistics = sample.statistics();
+	let pairs = sample.pairs();
+	let rejected: Vec<f32> = sample.population
+		.iter()
+		.filter(|individual| !pairs.iter().any(|pair| pair.proposer_id == individual.identity || pair.reviewer_id == individual.identity))
+		.filter_map(|individual| individual.extras().best_rejected_score)
+		.collect();
+	assert!(!rejected.is_empty());
+	let mean = rejected.iter().sum::<f32>() / rejected.len() as f32;
+	let best = statistics.mean_unmatched_best_rejected_score.unwrap();
+	assert!((best - mean).abs() < 1e-4, "{} against {}", best, mean);
+	assert_eq!(statistics.unmatched_score_gap, Some(statistics.mean_candidate_score.unwrap() - best));
+	assert!(statistics.to_string().contains("best rejected one of the unmatched"));
+	
+	sample.reset_matches();
+	assert!(sample.population.iter().all(|individual| individual.extras().best_rejected_score.is_none()));
+}